@@ -25,6 +25,11 @@
 //!
 //! This step is also sequencial but ises internal parallelization using [`rayon`].
 //!
+//! ### Optional: Re-execute the block.
+//!
+//! Sequencial too, as the block is executed on top of its parent. The receipts and state diff obtained by executing
+//! the transactions are compared against the block, see [`BlockImporter::re_execute`].
+//!
 //! ## Error handling
 //!
 //! When using p2p, validating a block could fail but that shouldn't close the whole app. This requires a retrying mechanism
@@ -33,23 +38,23 @@
 //!
 //! ## Future plans
 //!
-//! An optional sequencial step just before step 2 could be addded that executes the block to validate it: this will
-//! be useful for tendermint validator nodes in the future, and it should also be useful to test-execute a whole blockchain
-//! to check for errors.
 //! A signature verification mode should be added to allow the skipping of block validation entirely if the block is signed.
 
 use mc_db::{MadaraBackend, MadaraStorageError};
+use mc_exec::ContractClassCache;
 use mp_class::{class_hash::ComputeClassHashError, compile::ClassCompilationError};
 use starknet_core::types::Felt;
 use std::{borrow::Cow, sync::Arc};
 
 mod pre_validate;
 mod rayon;
+mod re_execute;
 mod types;
 mod verify_apply;
 
 pub use pre_validate::*;
 pub use rayon::*;
+pub use re_execute::*;
 pub use types::*;
 pub use verify_apply::*;
 
@@ -97,6 +102,11 @@ pub enum BlockImportError {
     #[error("Global state root mismatch: expected {expected:#x}, got {got:#x}")]
     GlobalStateRoot { got: Felt, expected: Felt },
 
+    #[error("Re-executing block #{block_n}: {error:#}")]
+    ReExecution { block_n: u64, error: mc_exec::Error },
+    #[error("Re-execution of block #{block_n} does not match the block: {mismatch}")]
+    ExecutionMismatch { block_n: u64, mismatch: ExecutionMismatch },
+
     /// Internal error, see [`BlockImportError::is_internal`].
     #[error("Internal database error while {context}: {error:#}")]
     InternalDb { context: Cow<'static, str>, error: MadaraStorageError },
@@ -112,6 +122,7 @@ impl BlockImportError {
     }
 }
 pub struct BlockImporter {
+    backend: Arc<MadaraBackend>,
     pool: Arc<RayonPool>,
     verify_apply: VerifyApply,
 }
@@ -119,7 +130,7 @@ pub struct BlockImporter {
 impl BlockImporter {
     pub fn new(backend: Arc<MadaraBackend>) -> Self {
         let pool = Arc::new(RayonPool::new());
-        Self { verify_apply: VerifyApply::new(Arc::clone(&backend), Arc::clone(&pool)), pool }
+        Self { verify_apply: VerifyApply::new(Arc::clone(&backend), Arc::clone(&pool)), pool, backend }
     }
 
     /// Perform [`BlockImporter::pre_validate`] followed by [`BlockImporter::verify_apply`] to import a block.
@@ -140,6 +151,23 @@ impl BlockImporter {
         pre_validate(&self.pool, block, validation).await
     }
 
+    /// Re-execute the transactions of the block and compare the results with its receipts and state diff. The parent of
+    /// the block must be the latest block in the database: this has to be called right before
+    /// [`BlockImporter::verify_apply`]. The block is returned as is.
+    pub async fn re_execute(
+        &self,
+        block: PreValidatedBlock,
+        class_cache: Arc<ContractClassCache>,
+    ) -> Result<PreValidatedBlock, BlockImportError> {
+        let backend = Arc::clone(&self.backend);
+        self.pool
+            .spawn_rayon_task(move || {
+                re_execute_inner(&backend, &class_cache, &block)?;
+                Ok(block)
+            })
+            .await
+    }
+
     pub async fn verify_apply(
         &self,
         block: PreValidatedBlock,
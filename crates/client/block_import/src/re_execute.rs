@@ -0,0 +1,353 @@
+//! Optional step between [`crate::pre_validate`] and [`crate::verify_apply`]: the transactions of the block are executed
+//! again on top of its parent, and the resulting receipts and state diff are checked against the block.
+//!
+//! This is how we catch execution regressions (blockifier, versioned constants) on real traffic, as the commitments
+//! only tell us that the block was not tampered with.
+
+use blockifier::execution::contract_class::ClassInfo;
+use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::transaction::transaction_execution::Transaction as BTransaction;
+use mc_db::MadaraBackend;
+use mc_exec::{ContractClassCache, ExecutionContext};
+use mp_block::{BlockId, BlockTag, Header, MadaraBlockInfo, MadaraMaybePendingBlockInfo};
+use mp_class::ConvertedClass;
+use mp_convert::ToFelt;
+use mp_receipt::{Event, ExecutionResult, FeePayment, MsgToL1, TransactionReceipt};
+use mp_state_update::StateDiff;
+use mp_transactions::Transaction;
+use starknet_api::transaction::{Fee, TransactionHash};
+use starknet_core::types::Felt;
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use crate::{BlockImportError, PreValidatedBlock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StateDiffEntry {
+    Storage { contract_address: Felt, key: Felt },
+    Nonce { contract_address: Felt },
+    ClassHash { contract_address: Felt },
+    CompiledClassHash { class_hash: Felt },
+}
+
+impl fmt::Display for StateDiffEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Storage { contract_address, key } => {
+                write!(f, "storage of contract {contract_address:#x} at {key:#x}")
+            }
+            Self::Nonce { contract_address } => write!(f, "nonce of contract {contract_address:#x}"),
+            Self::ClassHash { contract_address } => write!(f, "class hash of contract {contract_address:#x}"),
+            Self::CompiledClassHash { class_hash } => write!(f, "compiled class hash of class {class_hash:#x}"),
+        }
+    }
+}
+
+/// A difference between the block and the result of its re-execution.
+#[derive(Debug, thiserror::Error)]
+pub enum ExecutionMismatch {
+    #[error("Receipt count mismatch: expected {expected} receipts, got {got}")]
+    ReceiptCount { got: usize, expected: usize },
+    #[error("Fee mismatch for transaction #{index} ({tx_hash:#x}): expected {expected:?}, got {got:?}")]
+    Fee { index: usize, tx_hash: Felt, got: FeePayment, expected: FeePayment },
+    #[error("Execution result mismatch for transaction #{index} ({tx_hash:#x}): expected {expected:?}, got {got:?}")]
+    ExecutionResult { index: usize, tx_hash: Felt, got: ExecutionResult, expected: ExecutionResult },
+    #[error("Events mismatch for transaction #{index} ({tx_hash:#x}): expected {expected:?}, got {got:?}")]
+    Events { index: usize, tx_hash: Felt, got: Vec<Event>, expected: Vec<Event> },
+    #[error("L2 to L1 messages mismatch for transaction #{index} ({tx_hash:#x}): expected {expected:?}, got {got:?}")]
+    MessagesSent { index: usize, tx_hash: Felt, got: Vec<MsgToL1>, expected: Vec<MsgToL1> },
+    #[error("State diff mismatch for the {entry}: expected {expected:?}, got {got:?}")]
+    StateDiff { entry: StateDiffEntry, got: Option<Felt>, expected: Option<Felt> },
+}
+
+/// Re-executes the block on top of the latest block in the database, which must be its parent.
+///
+/// Blocks which cannot be executed by blockifier are skipped: blocks older than the first supported protocol version,
+/// and blocks with legacy `Deploy` transactions.
+pub fn re_execute_inner(
+    backend: &Arc<MadaraBackend>,
+    class_cache: &Arc<ContractClassCache>,
+    block: &PreValidatedBlock,
+) -> Result<(), BlockImportError> {
+    let latest_block_info = backend
+        .get_block_info(&BlockId::Tag(BlockTag::Latest))
+        .map_err(|error| BlockImportError::InternalDb { context: "getting latest block info".into(), error })?;
+    let (expected_block_n, latest_block_hash) = match latest_block_info.as_ref().and_then(|info| info.as_nonpending()) {
+        Some(info) => (info.header.block_number + 1, info.block_hash),
+        None => (0, Felt::ZERO),
+    };
+    let block_n = block.unverified_block_number.unwrap_or(expected_block_n);
+
+    // Executing on top of the wrong parent would report bogus mismatches: let verify_apply report the reorg.
+    if block.header.parent_block_hash.is_some_and(|parent_block_hash| parent_block_hash != latest_block_hash) {
+        log::debug!("Skipping the re-execution of block #{block_n}: it does not build on top of the latest block");
+        return Ok(());
+    }
+
+    if block.transactions.iter().any(|tx| matches!(tx, Transaction::Deploy(_))) {
+        log::debug!("Skipping the re-execution of block #{block_n}: deploy transactions cannot be executed");
+        return Ok(());
+    }
+
+    // Only the fields used for execution are needed: the commitments and block hash are not verified yet.
+    let block_info = MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo::new(
+        Header {
+            block_number: block_n,
+            sequencer_address: block.header.sequencer_address,
+            block_timestamp: block.header.block_timestamp,
+            protocol_version: block.header.protocol_version,
+            l1_gas_price: block.header.l1_gas_price.clone(),
+            l1_da_mode: block.header.l1_da_mode,
+            ..Default::default()
+        },
+        vec![],
+        Felt::ZERO,
+    ));
+    let exec_context = match ExecutionContext::new_in_block(Arc::clone(backend), Arc::clone(class_cache), &block_info) {
+        Ok(exec_context) => exec_context,
+        Err(mc_exec::Error::UnsupportedProtocolVersion(err)) => {
+            log::debug!("Skipping the re-execution of block #{block_n}: {err:#}");
+            return Ok(());
+        }
+        Err(error) => return Err(BlockImportError::ReExecution { block_n, error }),
+    };
+
+    // The transaction hashes are taken from the receipts.
+    if block.receipts.len() != block.transactions.len() {
+        let mismatch =
+            ExecutionMismatch::ReceiptCount { got: block.transactions.len(), expected: block.receipts.len() };
+        return Err(BlockImportError::ExecutionMismatch { block_n, mismatch });
+    }
+    let transactions = block
+        .transactions
+        .iter()
+        .zip(&block.receipts)
+        .map(|(tx, receipt)| to_blockifier_transaction(tx, receipt.transaction_hash(), &block.converted_classes))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (receipts, state_diff) = exec_context
+        .re_execute_block(transactions)
+        .map_err(|error| BlockImportError::ReExecution { block_n, error })?;
+
+    check_receipts(&receipts, &block.receipts)
+        .and_then(|_| check_state_diff(&state_diff, &block.state_diff))
+        .map_err(|mismatch| BlockImportError::ExecutionMismatch { block_n, mismatch })
+}
+
+fn to_blockifier_transaction(
+    tx: &Transaction,
+    tx_hash: Felt,
+    converted_classes: &[ConvertedClass],
+) -> Result<BTransaction, BlockImportError> {
+    let make_err = |context: &str| BlockImportError::Internal(format!("{context} for transaction {tx_hash:#x}").into());
+
+    let class_info = match tx {
+        Transaction::Declare(declare) => {
+            let class_hash = *declare.class_hash();
+            let class = converted_classes
+                .iter()
+                .find(|class| class.class_hash() == class_hash)
+                .ok_or_else(|| make_err("Declared class not found in block"))?;
+            let class_info = match class {
+                ConvertedClass::Legacy(legacy) => {
+                    let class = legacy
+                        .info
+                        .contract_class
+                        .to_blockifier_class()
+                        .map_err(|_| make_err("Converting legacy class to blockifier"))?;
+                    ClassInfo::new(&class, 0, 0)
+                }
+                ConvertedClass::Sierra(sierra) => {
+                    let class = sierra
+                        .compiled
+                        .to_blockifier_class()
+                        .map_err(|_| make_err("Converting class to blockifier"))?;
+                    ClassInfo::new(
+                        &class,
+                        sierra.info.contract_class.program_length(),
+                        sierra.info.contract_class.abi_length(),
+                    )
+                }
+            };
+            Some(class_info.map_err(|_| make_err("Mismatch between program length and class version"))?)
+        }
+        _ => None,
+    };
+    // The fee was paid on L1 and is not part of the block. It only needs to be enough to pay for the transaction.
+    let paid_fee_on_l1 = matches!(tx, Transaction::L1Handler(_)).then_some(Fee(u128::MAX));
+
+    let tx = tx.clone().try_into().map_err(|_| make_err("Converting transaction to starknet api"))?;
+    BTransaction::from_api(tx, TransactionHash(tx_hash), class_info, paid_fee_on_l1, None, false)
+        .map_err(|_| make_err("Converting transaction to blockifier"))
+}
+
+fn check_receipts(got: &[TransactionReceipt], expected: &[TransactionReceipt]) -> Result<(), ExecutionMismatch> {
+    if got.len() != expected.len() {
+        return Err(ExecutionMismatch::ReceiptCount { got: got.len(), expected: expected.len() });
+    }
+    for (index, (got, expected)) in got.iter().zip(expected).enumerate() {
+        let tx_hash = expected.transaction_hash();
+        if got.execution_result() != expected.execution_result() {
+            return Err(ExecutionMismatch::ExecutionResult {
+                index,
+                tx_hash,
+                got: got.execution_result(),
+                expected: expected.execution_result(),
+            });
+        }
+        if got.actual_fee() != expected.actual_fee() {
+            return Err(ExecutionMismatch::Fee {
+                index,
+                tx_hash,
+                got: got.actual_fee().clone(),
+                expected: expected.actual_fee().clone(),
+            });
+        }
+        if got.events() != expected.events() {
+            return Err(ExecutionMismatch::Events {
+                index,
+                tx_hash,
+                got: got.events().to_vec(),
+                expected: expected.events().to_vec(),
+            });
+        }
+        if got.messages_sent() != expected.messages_sent() {
+            return Err(ExecutionMismatch::MessagesSent {
+                index,
+                tx_hash,
+                got: got.messages_sent().to_vec(),
+                expected: expected.messages_sent().to_vec(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Legacy declared classes are not compared: they are not part of the blockifier state diff.
+fn check_state_diff(got: &CommitmentStateDiff, expected: &StateDiff) -> Result<(), ExecutionMismatch> {
+    let mut got_entries = BTreeMap::new();
+    for (contract_address, storage) in &got.storage_updates {
+        for (key, value) in storage {
+            let entry = StateDiffEntry::Storage { contract_address: contract_address.to_felt(), key: key.to_felt() };
+            got_entries.insert(entry, *value);
+        }
+    }
+    for (contract_address, nonce) in &got.address_to_nonce {
+        got_entries.insert(StateDiffEntry::Nonce { contract_address: contract_address.to_felt() }, nonce.to_felt());
+    }
+    for (contract_address, class_hash) in &got.address_to_class_hash {
+        got_entries
+            .insert(StateDiffEntry::ClassHash { contract_address: contract_address.to_felt() }, class_hash.to_felt());
+    }
+    for (class_hash, compiled_class_hash) in &got.class_hash_to_compiled_class_hash {
+        got_entries.insert(
+            StateDiffEntry::CompiledClassHash { class_hash: class_hash.to_felt() },
+            compiled_class_hash.to_felt(),
+        );
+    }
+
+    let mut expected_entries = BTreeMap::new();
+    for item in &expected.storage_diffs {
+        for entry in &item.storage_entries {
+            expected_entries
+                .insert(StateDiffEntry::Storage { contract_address: item.address, key: entry.key }, entry.value);
+        }
+    }
+    for item in &expected.nonces {
+        expected_entries.insert(StateDiffEntry::Nonce { contract_address: item.contract_address }, item.nonce);
+    }
+    for item in &expected.deployed_contracts {
+        expected_entries.insert(StateDiffEntry::ClassHash { contract_address: item.address }, item.class_hash);
+    }
+    for item in &expected.replaced_classes {
+        expected_entries.insert(StateDiffEntry::ClassHash { contract_address: item.contract_address }, item.class_hash);
+    }
+    for item in &expected.declared_classes {
+        expected_entries
+            .insert(StateDiffEntry::CompiledClassHash { class_hash: item.class_hash }, item.compiled_class_hash);
+    }
+
+    let entries = got_entries.keys().chain(expected_entries.keys());
+    for entry in entries {
+        let (got, expected) = (got_entries.get(entry).copied(), expected_entries.get(entry).copied());
+        if got != expected {
+            return Err(ExecutionMismatch::StateDiff { entry: *entry, got, expected });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mp_receipt::{InvokeTransactionReceipt, PriceUnit};
+    use mp_state_update::{ContractStorageDiffItem, NonceUpdate, StorageEntry};
+    use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
+    use starknet_api::state::StorageKey;
+
+    fn contract_address(felt: Felt) -> ContractAddress {
+        ContractAddress(PatriciaKey::try_from(felt).unwrap())
+    }
+
+    #[test]
+    fn test_check_state_diff() {
+        let mut got = CommitmentStateDiff {
+            address_to_class_hash: Default::default(),
+            address_to_nonce: [(contract_address(Felt::ONE), Nonce(Felt::TWO))].into_iter().collect(),
+            storage_updates: [(
+                contract_address(Felt::ONE),
+                [(StorageKey(PatriciaKey::try_from(Felt::THREE).unwrap()), Felt::from(42))].into_iter().collect(),
+            )]
+            .into_iter()
+            .collect(),
+            class_hash_to_compiled_class_hash: Default::default(),
+        };
+        let expected = StateDiff {
+            storage_diffs: vec![ContractStorageDiffItem {
+                address: Felt::ONE,
+                storage_entries: vec![StorageEntry { key: Felt::THREE, value: Felt::from(42) }],
+            }],
+            nonces: vec![NonceUpdate { contract_address: Felt::ONE, nonce: Felt::TWO }],
+            ..Default::default()
+        };
+        check_state_diff(&got, &expected).unwrap();
+
+        got.address_to_nonce.insert(contract_address(Felt::TWO), Nonce(Felt::ONE));
+        assert!(matches!(
+            check_state_diff(&got, &expected),
+            Err(ExecutionMismatch::StateDiff {
+                entry: StateDiffEntry::Nonce { contract_address },
+                got: Some(_),
+                expected: None
+            }) if contract_address == Felt::TWO
+        ));
+    }
+
+    #[test]
+    fn test_check_receipts() {
+        let expected = TransactionReceipt::Invoke(InvokeTransactionReceipt {
+            transaction_hash: Felt::ONE,
+            actual_fee: FeePayment { amount: Felt::from(100), unit: PriceUnit::Fri },
+            ..Default::default()
+        });
+        check_receipts(&[expected.clone()], &[expected.clone()]).unwrap();
+
+        let mut got = expected.clone();
+        let TransactionReceipt::Invoke(receipt) = &mut got else { unreachable!() };
+        receipt.actual_fee.amount = Felt::from(101);
+        assert!(matches!(check_receipts(&[got], &[expected.clone()]), Err(ExecutionMismatch::Fee { index: 0, .. })));
+
+        let mut got = expected.clone();
+        let TransactionReceipt::Invoke(receipt) = &mut got else { unreachable!() };
+        receipt.execution_result = ExecutionResult::Reverted { reason: "Out of gas".into() };
+        assert!(matches!(
+            check_receipts(&[got], &[expected.clone()]),
+            Err(ExecutionMismatch::ExecutionResult { index: 0, .. })
+        ));
+
+        // A transaction which was not executed must not go unnoticed.
+        assert!(matches!(
+            check_receipts(&[expected.clone()], &[expected.clone(), expected]),
+            Err(ExecutionMismatch::ReceiptCount { got: 1, expected: 2 })
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::db_block_id::{DbBlockId, DbBlockIdResolvable};
+use crate::notifications::BlockNotification;
 use crate::MadaraStorageError;
 use crate::{Column, DatabaseExt, MadaraBackend, WriteBatchWithTransaction};
 use anyhow::Context;
@@ -218,11 +219,16 @@ impl MadaraBackend {
         let mut writeopts = WriteOptions::default(); // todo move that in db
         writeopts.disable_wal(true);
         self.db.put_cf_opt(&col, ROW_L1_LAST_CONFIRMED_BLOCK, bincode::serialize(&l1_last)?, &writeopts)?;
+        self.notify_block(BlockNotification::L1Confirmed(l1_last));
         Ok(())
     }
 
     pub fn clear_last_confirmed_block(&self) -> Result<()> {
-        self.write_last_confirmed_block(0)
+        let col = self.db.get_column(Column::BlockStorageMeta);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.delete_cf_opt(&col, ROW_L1_LAST_CONFIRMED_BLOCK, &writeopts)?;
+        Ok(())
     }
 
     /// Also clears pending block
@@ -248,6 +254,8 @@ impl MadaraBackend {
         tx.put_cf(&block_n_to_block_inner, &block_n_encoded, bincode::serialize(&block.inner)?);
         tx.put_cf(&block_n_to_state_diff, &block_n_encoded, bincode::serialize(state_diff)?);
         tx.put_cf(&meta, ROW_SYNC_TIP, block_n_encoded);
+        self.events_db_store_block(&mut tx, block.info.header.block_number, &block.inner.receipts)?;
+        self.l1_db_store_block(&mut tx, block)?;
 
         // clear pending
         tx.delete_cf(&meta, ROW_PENDING_INFO);
@@ -260,6 +268,47 @@ impl MadaraBackend {
         Ok(())
     }
 
+    /// Adds the removal of block `block_n` to `batch`.
+    pub(crate) fn block_db_revert(&self, batch: &mut WriteBatchWithTransaction, block_n: u64) -> Result<()> {
+        let info = self
+            .get_block_info_from_block_n(block_n)?
+            .ok_or(MadaraStorageError::InconsistentStorage("Reverting a block that is not in the db".into()))?;
+
+        let tx_hash_to_block_n = self.db.get_column(Column::TxHashToBlockN);
+        let block_hash_to_block_n = self.db.get_column(Column::BlockHashToBlockN);
+        let block_n_to_block = self.db.get_column(Column::BlockNToBlockInfo);
+        let block_n_to_block_inner = self.db.get_column(Column::BlockNToBlockInner);
+        let block_n_to_state_diff = self.db.get_column(Column::BlockNToStateDiff);
+
+        let block_n_encoded = bincode::serialize(&block_n)?;
+
+        for hash in &info.tx_hashes {
+            batch.delete_cf(&tx_hash_to_block_n, bincode::serialize(hash)?);
+        }
+
+        batch.delete_cf(&block_hash_to_block_n, bincode::serialize(&info.block_hash)?);
+        batch.delete_cf(&block_n_to_block, &block_n_encoded);
+        batch.delete_cf(&block_n_to_block_inner, &block_n_encoded);
+        batch.delete_cf(&block_n_to_state_diff, &block_n_encoded);
+        Ok(())
+    }
+
+    /// Adds to `batch` the chain metadata updates of a revert to block `block_n`: it becomes the sync tip, the last
+    /// block confirmed on L1 is lowered to it, and the pending block is cleared.
+    pub(crate) fn block_db_revert_meta(&self, batch: &mut WriteBatchWithTransaction, block_n: u64) -> Result<()> {
+        let meta = self.db.get_column(Column::BlockStorageMeta);
+        batch.put_cf(&meta, ROW_SYNC_TIP, bincode::serialize(&block_n)?);
+        if self.get_l1_last_confirmed_block()?.is_some_and(|l1_last| l1_last > block_n) {
+            batch.put_cf(&meta, ROW_L1_LAST_CONFIRMED_BLOCK, bincode::serialize(&block_n)?);
+        }
+
+        // clear pending
+        batch.delete_cf(&meta, ROW_PENDING_INFO);
+        batch.delete_cf(&meta, ROW_PENDING_INNER);
+        batch.delete_cf(&meta, ROW_PENDING_STATE_UPDATE);
+        Ok(())
+    }
+
     // Convenience functions
 
     pub(crate) fn id_to_storage_type(&self, id: &BlockId) -> Result<Option<DbBlockId>> {
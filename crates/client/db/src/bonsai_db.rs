@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
 use bonsai_trie::id::BasicId;
 use bonsai_trie::{BonsaiDatabase, BonsaiPersistentDatabase, ByteVec, DatabaseKey};
 use rocksdb::{Direction, IteratorMode, WriteOptions};
@@ -22,19 +25,101 @@ impl DatabaseKeyMapping {
     }
 }
 
+/// Key of an entry in the in-memory overlay of a [`BonsaiDb`].
+type OverlayKey = (Column, Vec<u8>);
+/// Values of database entries, `None` meaning that the entry does not exist.
+type Overlay = BTreeMap<OverlayKey, Option<Vec<u8>>>;
+
+/// In-memory snapshots of the global tries, see [`crate::TrieLogConfig::snapshot_interval`].
+///
+/// A snapshot is the overlay of the values the entries of the tries had at its block, for every entry written since
+/// then: on top of the database, it gives the tries at that block. Views of the tries at a past block start from the
+/// closest snapshot after it, and only need the trie logs between the two blocks to be reverted.
+#[derive(Default)]
+pub(crate) struct TrieSnapshots(RwLock<BTreeMap<u64, Overlay>>);
+
+impl TrieSnapshots {
+    /// Starts a snapshot of the tries as they are in the database, and keeps the `max_saved_snapshots` most recent
+    /// ones starting at `earliest`.
+    pub(crate) fn take(&self, block_n: u64, earliest: u64, max_saved_snapshots: usize) {
+        let mut snapshots = self.0.write().expect("Poisoned lock");
+        snapshots.insert(block_n, Default::default());
+        snapshots.retain(|&snapshot_n, _| snapshot_n >= earliest);
+        while snapshots.len() > max_saved_snapshots {
+            snapshots.pop_first();
+        }
+    }
+
+    /// Drops the snapshots after `block_n`, when the blocks after it are reverted.
+    pub(crate) fn remove_after(&self, block_n: u64) {
+        self.0.write().expect("Poisoned lock").retain(|&snapshot_n, _| snapshot_n <= block_n);
+    }
+
+    /// The closest snapshot at or after `block_n`, restricted to the columns of a trie.
+    fn closest_after(&self, block_n: u64, column_mapping: &DatabaseKeyMapping) -> Option<(u64, Overlay)> {
+        let snapshots = self.0.read().expect("Poisoned lock");
+        let (&snapshot_n, overlay) = snapshots.range(block_n..).next()?;
+        let columns = [column_mapping.flat, column_mapping.trie, column_mapping.log];
+        let overlay = overlay
+            .iter()
+            .filter(|((column, _), _)| columns.contains(column))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        Some((snapshot_n, overlay))
+    }
+
+    /// Saves the value of an entry about to be written in every snapshot which does not have it yet.
+    fn record(&self, column: Column, key: &[u8], old_value: &Option<Vec<u8>>) {
+        let mut snapshots = self.0.write().expect("Poisoned lock");
+        for overlay in snapshots.values_mut() {
+            overlay.entry((column, key.to_vec())).or_insert_with(|| old_value.clone());
+        }
+    }
+}
+
 pub struct BonsaiDb<'db> {
     db: &'db DB,
     /// Mapping from `DatabaseKey` => rocksdb column name
     column_mapping: DatabaseKeyMapping,
-    // snapshots: BTreeMap<BasicId, SnapshotWithThreadMode<'db, DB>>,
+    /// Snapshots which are kept up to date with the writes to the database.
+    snapshots: &'db TrieSnapshots,
     write_opt: WriteOptions,
+    /// When set, writes are kept in memory instead of being written to the database, and reads see them on top of the
+    /// database content. This is used to build read-only views of the tries at a past block, by reverting them in
+    /// memory using the trie logs.
+    overlay: Option<Overlay>,
 }
 
 impl<'db> BonsaiDb<'db> {
-    pub(crate) fn new(db: &'db DB, column_mapping: DatabaseKeyMapping) -> Self {
+    pub(crate) fn new(db: &'db DB, snapshots: &'db TrieSnapshots, column_mapping: DatabaseKeyMapping) -> Self {
         let mut write_opt = WriteOptions::default();
         write_opt.disable_wal(true);
-        Self { db, column_mapping, write_opt }
+        Self { db, column_mapping, snapshots, write_opt, overlay: None }
+    }
+
+    /// A view of the database where all the writes stay in memory.
+    pub(crate) fn new_overlay(db: &'db DB, snapshots: &'db TrieSnapshots, column_mapping: DatabaseKeyMapping) -> Self {
+        Self { overlay: Some(Default::default()), ..Self::new(db, snapshots, column_mapping) }
+    }
+
+    /// A view of the database at the closest snapshot at or after `block_n`, where all the writes stay in memory.
+    /// Returns the block of the snapshot, or `None` when there is no such snapshot.
+    pub(crate) fn new_overlay_from_snapshot(
+        db: &'db DB,
+        snapshots: &'db TrieSnapshots,
+        column_mapping: DatabaseKeyMapping,
+        block_n: u64,
+    ) -> Option<(u64, Self)> {
+        let (snapshot_n, overlay) = snapshots.closest_after(block_n, &column_mapping)?;
+        Some((snapshot_n, Self { overlay: Some(overlay), ..Self::new(db, snapshots, column_mapping) }))
+    }
+
+    fn get_from_db(&self, column: Column, key: &[u8]) -> Result<Option<Vec<u8>>, DbError> {
+        if let Some(value) = self.overlay.as_ref().and_then(|overlay| overlay.get(&(column, key.to_vec()))) {
+            return Ok(value.clone());
+        }
+        let handle = self.db.get_column(column);
+        Ok(self.db.get_cf(&handle, key)?)
     }
 }
 
@@ -48,34 +133,47 @@ impl BonsaiDatabase for BonsaiDb<'_> {
 
     fn get(&self, key: &DatabaseKey) -> Result<Option<ByteVec>, Self::DatabaseError> {
         log::trace!("Getting from RocksDB: {:?}", key);
-        let handle = self.db.get_column(self.column_mapping.map(key));
-        Ok(self.db.get_cf(&handle, key.as_slice())?.map(Into::into))
+        Ok(self.get_from_db(self.column_mapping.map(key), key.as_slice())?.map(Into::into))
     }
 
     fn get_by_prefix(&self, prefix: &DatabaseKey) -> Result<Vec<(ByteVec, ByteVec)>, Self::DatabaseError> {
         log::trace!("Getting from RocksDB: {:?}", prefix);
-        let handle = self.db.get_column(self.column_mapping.map(prefix));
+        let column = self.column_mapping.map(prefix);
+        let handle = self.db.get_column(column);
         let iter = self.db.iterator_cf(&handle, IteratorMode::From(prefix.as_slice(), Direction::Forward));
-        Ok(iter
-            .map_while(|kv| {
-                if let Ok((key, value)) = kv {
-                    if key.starts_with(prefix.as_slice()) {
-                        // nb: to_vec on a Box<[u8]> is a noop conversion
-                        Some((key.to_vec().into(), value.to_vec().into()))
-                    } else {
-                        None
-                    }
+        let from_db = iter.map_while(|kv| {
+            if let Ok((key, value)) = kv {
+                if key.starts_with(prefix.as_slice()) {
+                    // nb: to_vec on a Box<[u8]> is a noop conversion
+                    Some((key.to_vec(), value.to_vec()))
                 } else {
                     None
                 }
-            })
-            .collect())
+            } else {
+                None
+            }
+        });
+
+        let Some(overlay) = &self.overlay else {
+            return Ok(from_db.map(|(key, value)| (key.into(), value.into())).collect());
+        };
+
+        let mut entries: BTreeMap<Vec<u8>, Vec<u8>> = from_db.collect();
+        let overlay_entries = overlay
+            .range((column, prefix.as_slice().to_vec())..)
+            .take_while(|((col, key), _)| *col == column && key.starts_with(prefix.as_slice()));
+        for ((_, key), value) in overlay_entries {
+            match value {
+                Some(value) => entries.insert(key.clone(), value.clone()),
+                None => entries.remove(key),
+            };
+        }
+        Ok(entries.into_iter().map(|(key, value)| (key.into(), value.into())).collect())
     }
 
     fn contains(&self, key: &DatabaseKey) -> Result<bool, Self::DatabaseError> {
         log::trace!("Checking if RocksDB contains: {:?}", key);
-        let handle = self.db.get_column(self.column_mapping.map(key));
-        Ok(self.db.get_cf(&handle, key.as_slice()).map(|value| value.is_some())?)
+        Ok(self.get_from_db(self.column_mapping.map(key), key.as_slice())?.is_some())
     }
 
     fn insert(
@@ -85,18 +183,20 @@ impl BonsaiDatabase for BonsaiDb<'_> {
         batch: Option<&mut Self::Batch>,
     ) -> Result<Option<ByteVec>, Self::DatabaseError> {
         log::trace!("Inserting into RocksDB: {:?} {:?}", key, value);
-        let handle = self.db.get_column(self.column_mapping.map(key));
-
-        // NB: we don't need old value as the trie log is not used :)
-        // this actually speeds up things quite a lot
-
-        // let old_value = self.db.get_cf(&handle, key.as_slice())?;
-        if let Some(batch) = batch {
-            batch.put_cf(&handle, key.as_slice(), value);
+        let column = self.column_mapping.map(key);
+
+        // NB: the old value is needed for the trie logs, which are used to revert the tries on reorgs.
+        let old_value = self.get_from_db(column, key.as_slice())?;
+        if let Some(overlay) = &mut self.overlay {
+            overlay.insert((column, key.as_slice().to_vec()), Some(value.to_vec()));
+        } else if let Some(batch) = batch {
+            self.snapshots.record(column, key.as_slice(), &old_value);
+            batch.put_cf(&self.db.get_column(column), key.as_slice(), value);
         } else {
-            self.db.put_cf_opt(&handle, key.as_slice(), value, &self.write_opt)?;
+            self.snapshots.record(column, key.as_slice(), &old_value);
+            self.db.put_cf_opt(&self.db.get_column(column), key.as_slice(), value, &self.write_opt)?;
         }
-        Ok(None)
+        Ok(old_value.map(Into::into))
     }
 
     fn remove(
@@ -105,24 +205,40 @@ impl BonsaiDatabase for BonsaiDb<'_> {
         batch: Option<&mut Self::Batch>,
     ) -> Result<Option<ByteVec>, Self::DatabaseError> {
         log::trace!("Removing from RocksDB: {:?}", key);
-        let handle = self.db.get_column(self.column_mapping.map(key));
-        // let old_value = self.db.get_cf(&handle, key.as_slice())?;
-        if let Some(batch) = batch {
-            batch.delete_cf(&handle, key.as_slice());
+        let column = self.column_mapping.map(key);
+        let old_value = self.get_from_db(column, key.as_slice())?;
+        if let Some(overlay) = &mut self.overlay {
+            overlay.insert((column, key.as_slice().to_vec()), None);
+        } else if let Some(batch) = batch {
+            self.snapshots.record(column, key.as_slice(), &old_value);
+            batch.delete_cf(&self.db.get_column(column), key.as_slice());
         } else {
-            self.db.delete_cf_opt(&handle, key.as_slice(), &self.write_opt)?;
+            self.snapshots.record(column, key.as_slice(), &old_value);
+            self.db.delete_cf_opt(&self.db.get_column(column), key.as_slice(), &self.write_opt)?;
         }
-        Ok(None)
+        Ok(old_value.map(Into::into))
     }
 
     fn remove_by_prefix(&mut self, prefix: &DatabaseKey) -> Result<(), Self::DatabaseError> {
         log::trace!("Getting from RocksDB: {:?}", prefix);
-        let handle = self.db.get_column(self.column_mapping.map(prefix));
+        if self.overlay.is_some() {
+            let column = self.column_mapping.map(prefix);
+            let keys = self.get_by_prefix(prefix)?;
+            let overlay = self.overlay.as_mut().expect("Overlay is set");
+            for (key, _) in keys {
+                overlay.insert((column, key.to_vec()), None);
+            }
+            return Ok(());
+        }
+
+        let column = self.column_mapping.map(prefix);
+        let handle = self.db.get_column(column);
         let iter = self.db.iterator_cf(&handle, IteratorMode::From(prefix.as_slice(), Direction::Forward));
         let mut batch = self.create_batch();
         for kv in iter {
-            if let Ok((key, _)) = kv {
+            if let Ok((key, value)) = kv {
                 if key.starts_with(prefix.as_slice()) {
+                    self.snapshots.record(column, &key, &Some(value.to_vec()));
                     batch.delete_cf(&handle, &key);
                 } else {
                     break;
@@ -137,6 +253,10 @@ impl BonsaiDatabase for BonsaiDb<'_> {
     }
 
     fn write_batch(&mut self, batch: Self::Batch) -> Result<(), Self::DatabaseError> {
+        // Nothing is ever written to the batches of an overlay.
+        if self.overlay.is_some() {
+            return Ok(());
+        }
         Ok(self.db.write_opt(batch, &self.write_opt)?)
     }
 }
@@ -252,16 +372,17 @@ where
     type Transaction = Self;
     type DatabaseError = DbError;
 
-    fn snapshot(&mut self, _id: BasicId) {
-        log::trace!("Generating RocksDB snapshot");
-        // let snapshot = self.db.snapshot();
-        // self.snapshots.insert(id, snapshot);
-    }
+    /// Snapshots are disabled in the bonsai config: they are taken by the backend once all the global tries are
+    /// committed for a block, see [`TrieSnapshots`].
+    fn snapshot(&mut self, _id: BasicId) {}
 
     fn transaction(&self, _id: BasicId) -> Option<Self::Transaction> {
         log::trace!("Generating RocksDB transaction");
         // TODO: we lie about supporting transactions here
-        Some(BonsaiDb::new(self.db, self.column_mapping.clone()))
+        Some(BonsaiDb {
+            overlay: self.overlay.clone(),
+            ..BonsaiDb::new(self.db, self.snapshots, self.column_mapping.clone())
+        })
         // if let Some(snapshot) = self.snapshots.get(&id) {
         //     let write_opts = WriteOptions::default();
         //     let mut txn_opts = OptimisticTransactionOptions::default();
@@ -1,4 +1,5 @@
 use mp_class::{ClassInfo, CompiledSierra, ConvertedClass};
+use mp_state_update::{DeclaredClassItem, StateDiff};
 use rayon::{iter::ParallelIterator, slice::ParallelSlice};
 use rocksdb::WriteOptions;
 use starknet_types_core::felt::Felt;
@@ -49,6 +50,15 @@ impl MadaraBackend {
         id: &impl DbBlockIdResolvable,
         class_hash: &Felt,
     ) -> Result<Option<ClassInfo>, MadaraStorageError> {
+        Ok(self.get_class_info_and_declared_at(id, class_hash)?.map(|(class_info, _)| class_info))
+    }
+
+    /// Same as [`MadaraBackend::get_class_info`], also returning the block in which the class was declared.
+    pub fn get_class_info_and_declared_at(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        class_hash: &Felt,
+    ) -> Result<Option<(ClassInfo, DbBlockId)>, MadaraStorageError> {
         let Some(requested_id) = id.resolve_db_block_id(self)? else { return Ok(None) };
 
         log::debug!("class info {requested_id:?} {class_hash:#x}");
@@ -75,7 +85,7 @@ impl MadaraBackend {
         }
         log::debug!("valid");
 
-        Ok(Some(info.class_info))
+        Ok(Some((info.class_info, info.block_id)))
     }
 
     pub fn contains_class(&self, id: &impl DbBlockIdResolvable, class_hash: &Felt) -> Result<bool, MadaraStorageError> {
@@ -183,6 +193,25 @@ impl MadaraBackend {
         )
     }
 
+    /// Removes the classes declared in a block, given the state diff of that block.
+    pub(crate) fn class_db_revert(
+        &self,
+        batch: &mut WriteBatchWithTransaction,
+        state_diff: &StateDiff,
+    ) -> Result<(), MadaraStorageError> {
+        let col_info = self.db.get_column(Column::ClassInfo);
+        let col_compiled = self.db.get_column(Column::ClassCompiled);
+
+        for class_hash in &state_diff.deprecated_declared_classes {
+            batch.delete_cf(&col_info, bincode::serialize(class_hash)?);
+        }
+        for DeclaredClassItem { class_hash, compiled_class_hash } in &state_diff.declared_classes {
+            batch.delete_cf(&col_info, bincode::serialize(class_hash)?);
+            batch.delete_cf(&col_compiled, bincode::serialize(compiled_class_hash)?);
+        }
+        Ok(())
+    }
+
     pub(crate) fn class_db_clear_pending(&self) -> Result<(), MadaraStorageError> {
         let mut writeopts = WriteOptions::new();
         writeopts.disable_wal(true);
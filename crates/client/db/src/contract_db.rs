@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use mp_state_update::{ContractStorageDiffItem, NonceUpdate, StateDiff, StorageEntry};
 use rayon::{iter::ParallelIterator, slice::ParallelSlice};
 use rocksdb::{BoundColumnFamily, IteratorMode, ReadOptions, WriteOptions};
 use serde::Serialize;
@@ -51,7 +52,10 @@ impl MadaraBackend {
                 let Some(block_n) = self.get_latest_block_n()? else { return Ok(None) };
                 block_n
             }
-            DbBlockId::BlockN(block_n) => block_n,
+            DbBlockId::BlockN(block_n) => {
+                self.check_not_pruned(block_n)?;
+                block_n
+            }
         };
 
         // We try to find history values.
@@ -236,6 +240,40 @@ impl MadaraBackend {
         Ok(())
     }
 
+    /// Removes the history entries written by block `block_number`. The keys to remove are given by the state diff
+    /// of that block.
+    pub(crate) fn contract_db_revert(
+        &self,
+        batch: &mut WriteBatchWithTransaction,
+        block_number: u64,
+        state_diff: &StateDiff,
+    ) -> Result<(), MadaraStorageError> {
+        let block_number = u32::try_from(block_number).map_err(|_| MadaraStorageError::InvalidBlockNumber)?;
+        let suffix = block_number.to_be_bytes();
+
+        let col_class_hashes = self.db.get_column(Column::ContractToClassHashes);
+        let col_nonces = self.db.get_column(Column::ContractToNonces);
+        let col_storage = self.db.get_column(Column::ContractStorage);
+
+        let class_updates = state_diff
+            .deployed_contracts
+            .iter()
+            .map(|item| item.address)
+            .chain(state_diff.replaced_classes.iter().map(|item| item.contract_address));
+        for address in class_updates {
+            batch.delete_cf(&col_class_hashes, [address.to_bytes_be().as_ref(), &suffix as &[u8]].concat());
+        }
+        for NonceUpdate { contract_address, .. } in &state_diff.nonces {
+            batch.delete_cf(&col_nonces, [contract_address.to_bytes_be().as_ref(), &suffix as &[u8]].concat());
+        }
+        for ContractStorageDiffItem { address, storage_entries } in &state_diff.storage_diffs {
+            for StorageEntry { key, .. } in storage_entries {
+                batch.delete_cf(&col_storage, [&make_storage_key_prefix(*address, *key) as &[u8], &suffix].concat());
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn contract_db_clear_pending(&self) -> Result<(), MadaraStorageError> {
         let mut writeopts = WriteOptions::new();
         writeopts.disable_wal(true);
@@ -21,6 +21,12 @@ pub enum MadaraStorageError {
     InconsistentStorage(Cow<'static, str>),
     #[error("Cannot create a pending block of the genesis block of a chain")]
     PendingCreationNoGenesis,
+    #[error("Cannot revert to block #{target}: latest block is #{latest} and the maximum reorg depth is {max_depth}")]
+    RevertTooDeep { target: u64, latest: u64, max_depth: u64 },
+    #[error("Cannot revert to block #{target}: the state history before block #{pruned_before} has been pruned")]
+    RevertBelowPruned { target: u64, pruned_before: u64 },
+    #[error("The state of block #{block_n} has been pruned, the earliest available block is #{earliest}")]
+    BlockPruned { block_n: u64, earliest: u64 },
 }
 
 impl From<bonsai_trie::BonsaiStorageError<DbError>> for MadaraStorageError {
@@ -43,7 +49,7 @@ pub enum DbError {
 
 impl bonsai_trie::DBError for DbError {}
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum TrieType {
     Contract,
     ContractStorage,
@@ -51,6 +57,9 @@ pub enum TrieType {
 }
 
 impl TrieType {
+    /// Global tries, in the order they are reverted.
+    pub const ALL: [TrieType; 3] = [TrieType::Contract, TrieType::ContractStorage, TrieType::Class];
+
     fn as_str(&self) -> &'static str {
         match self {
             TrieType::Contract => "contract",
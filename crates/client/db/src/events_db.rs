@@ -0,0 +1,266 @@
+//! Event index used to answer `starknet_getEvents` without loading every block in the requested range.
+//!
+//! For every closed block, we store:
+//! - one entry per distinct emitting contract in [`Column::EventsByAddress`], keyed by `from_address ++ block_n`.
+//! - one entry per distinct first event key in [`Column::EventsByFirstKey`], keyed by `first_key ++ block_n`.
+//! - a bloom filter summary of the addresses and the first few keys of every event in [`Column::EventBloom`].
+//!
+//! The index columns make it possible to jump straight to the blocks emitting events for an address or a first key,
+//! and the bloom filter is then used to discard the candidate blocks that cannot match the rest of the filter.
+
+use std::collections::{BTreeSet, HashSet};
+
+use mp_receipt::TransactionReceipt;
+use rocksdb::{IteratorMode, ReadOptions};
+use starknet_types_core::felt::Felt;
+
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError, WriteBatchWithTransaction};
+
+type Result<T, E = MadaraStorageError> = std::result::Result<T, E>;
+
+// NB: Columns cf needs prefix extractor of these length during creation
+pub(crate) const EVENTS_BY_ADDRESS_PREFIX_EXTRACTOR: usize = 32;
+pub(crate) const EVENTS_BY_FIRST_KEY_PREFIX_EXTRACTOR: usize = 32;
+
+/// First block that was stored with the event index. Blocks before it are not indexed and have to be scanned.
+const ROW_EVENTS_INDEX_START: &[u8] = b"events_index_start";
+
+/// Number of event key positions that are summarized in the bloom filter.
+const BLOOM_KEY_POSITIONS: usize = 4;
+const BLOOM_HASHES: u64 = 3;
+/// Bloom filters are sized for about 10 bits per item, which gives a false positive rate of about 2% with
+/// [`BLOOM_HASHES`] hashes.
+const BLOOM_BITS_PER_ITEM: usize = 10;
+const BLOOM_MIN_BYTES: usize = 256;
+
+/// Bloom filter summary of the events of a block. Its size grows with the number of distinct items of the block, so
+/// that busy blocks do not saturate it.
+///
+/// Items are tagged with their position in the event, so that a felt used as an address does not match a filter on a
+/// key and vice-versa.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EventBloom(Box<[u8]>);
+
+impl Default for EventBloom {
+    fn default() -> Self {
+        Self::with_capacity(0)
+    }
+}
+
+impl std::fmt::Debug for EventBloom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EventBloom({} bits set)", self.0.iter().map(|b| b.count_ones()).sum::<u32>())
+    }
+}
+
+impl EventBloom {
+    const ADDRESS_TAG: u8 = 0;
+
+    fn with_capacity(n_items: usize) -> Self {
+        let bytes = (n_items * BLOOM_BITS_PER_ITEM).div_ceil(8).next_power_of_two().max(BLOOM_MIN_BYTES);
+        Self(vec![0; bytes].into())
+    }
+
+    fn bit_indices(&self, tag: u8, felt: &Felt) -> impl Iterator<Item = usize> {
+        let n_bits = self.0.len() as u64 * 8;
+        // FNV-1a, split in two halves for double hashing.
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in std::iter::once(tag).chain(felt.to_bytes_be()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        let (h1, h2) = (hash & 0xFFFF_FFFF, (hash >> 32) | 1);
+        (0..BLOOM_HASHES).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % n_bits) as usize)
+    }
+
+    fn insert(&mut self, tag: u8, felt: &Felt) {
+        for bit in self.bit_indices(tag, felt) {
+            self.0[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    fn contains(&self, tag: u8, felt: &Felt) -> bool {
+        self.bit_indices(tag, felt).all(|bit| self.0[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+
+    pub fn from_receipts(receipts: &[TransactionReceipt]) -> Self {
+        let items: HashSet<(u8, Felt)> = receipts
+            .iter()
+            .flat_map(|receipt| receipt.events())
+            .flat_map(|event| {
+                std::iter::once((Self::ADDRESS_TAG, event.from_address))
+                    .chain(event.keys.iter().take(BLOOM_KEY_POSITIONS).enumerate().map(|(i, key)| (i as u8 + 1, *key)))
+            })
+            .collect();
+
+        let mut bloom = Self::with_capacity(items.len());
+        for (tag, felt) in &items {
+            bloom.insert(*tag, felt);
+        }
+        bloom
+    }
+
+    /// Returns `false` if no event summarized by this bloom filter can match the filter. False positives are possible.
+    pub fn may_match(&self, from_address: Option<&Felt>, keys: &[Vec<Felt>]) -> bool {
+        let address_match = from_address.map_or(true, |address| self.contains(Self::ADDRESS_TAG, address));
+        let keys_match = keys
+            .iter()
+            .take(BLOOM_KEY_POSITIONS)
+            .enumerate()
+            .all(|(i, keys)| keys.is_empty() || keys.iter().any(|key| self.contains(i as u8 + 1, key)));
+        address_match && keys_match
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        (!bytes.is_empty()).then(|| Self(bytes.into()))
+    }
+}
+
+fn make_index_key(felt: &Felt, block_n: u64) -> [u8; 40] {
+    let mut key = [0u8; 40];
+    key[..32].copy_from_slice(&felt.to_bytes_be());
+    key[32..].copy_from_slice(&block_n.to_be_bytes());
+    key
+}
+
+impl MadaraBackend {
+    /// Adds the event index of block `block_n` to `batch`.
+    pub(crate) fn events_db_store_block(
+        &self,
+        batch: &mut WriteBatchWithTransaction,
+        block_n: u64,
+        receipts: &[TransactionReceipt],
+    ) -> Result<()> {
+        let col_address = self.db.get_column(Column::EventsByAddress);
+        let col_first_key = self.db.get_column(Column::EventsByFirstKey);
+        let col_bloom = self.db.get_column(Column::EventBloom);
+        let meta = self.db.get_column(Column::BlockStorageMeta);
+
+        let events = receipts.iter().flat_map(|receipt| receipt.events());
+        let addresses: HashSet<Felt> = events.clone().map(|event| event.from_address).collect();
+        let first_keys: HashSet<Felt> = events.filter_map(|event| event.keys.first().copied()).collect();
+
+        for address in &addresses {
+            batch.put_cf(&col_address, make_index_key(address, block_n), b"");
+        }
+        for key in &first_keys {
+            batch.put_cf(&col_first_key, make_index_key(key, block_n), b"");
+        }
+        batch.put_cf(&col_bloom, block_n.to_be_bytes(), &*EventBloom::from_receipts(receipts).0);
+        if self.db.get_cf(&meta, ROW_EVENTS_INDEX_START)?.is_none() {
+            batch.put_cf(&meta, ROW_EVENTS_INDEX_START, bincode::serialize(&block_n)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn events_db_revert(
+        &self,
+        batch: &mut WriteBatchWithTransaction,
+        block_n: u64,
+        receipts: &[TransactionReceipt],
+    ) -> Result<()> {
+        let col_address = self.db.get_column(Column::EventsByAddress);
+        let col_first_key = self.db.get_column(Column::EventsByFirstKey);
+        let col_bloom = self.db.get_column(Column::EventBloom);
+
+        for event in receipts.iter().flat_map(|receipt| receipt.events()) {
+            batch.delete_cf(&col_address, make_index_key(&event.from_address, block_n));
+            if let Some(key) = event.keys.first() {
+                batch.delete_cf(&col_first_key, make_index_key(key, block_n));
+            }
+        }
+        batch.delete_cf(&col_bloom, block_n.to_be_bytes());
+        Ok(())
+    }
+
+    pub fn get_event_bloom(&self, block_n: u64) -> Result<Option<EventBloom>> {
+        let col = self.db.get_column(Column::EventBloom);
+        let Some(res) = self.db.get_pinned_cf(&col, block_n.to_be_bytes())? else { return Ok(None) };
+        Ok(Some(
+            EventBloom::from_bytes(res.as_ref())
+                .ok_or(MadaraStorageError::InconsistentStorage("Malformed event bloom filter".into()))?,
+        ))
+    }
+
+    fn events_index_start(&self) -> Result<Option<u64>> {
+        let col = self.db.get_column(Column::BlockStorageMeta);
+        let Some(res) = self.db.get_cf(&col, ROW_EVENTS_INDEX_START)? else { return Ok(None) };
+        Ok(Some(bincode::deserialize(&res)?))
+    }
+
+    /// Block numbers in `from_block..=to_block` that are indexed under `felt` in an index column.
+    fn events_index_scan(&self, col: Column, felt: &Felt, from_block: u64, to_block: u64) -> Result<BTreeSet<u64>> {
+        let col = self.db.get_column(col);
+        let mut options = ReadOptions::default();
+        options.set_prefix_same_as_start(true);
+        let start = make_index_key(felt, from_block);
+        let mode = IteratorMode::From(&start, rocksdb::Direction::Forward);
+
+        let mut blocks = BTreeSet::new();
+        for kv in self.db.iterator_cf_opt(&col, options, mode) {
+            let (key, _) = kv?;
+            let block_n = u64::from_be_bytes(
+                key[32..]
+                    .try_into()
+                    .map_err(|_| MadaraStorageError::InconsistentStorage("Malformed event index key".into()))?,
+            );
+            if block_n > to_block {
+                break;
+            }
+            blocks.insert(block_n);
+        }
+        Ok(blocks)
+    }
+
+    /// Returns the blocks in `from_block..=to_block` that may contain events matching the filter, in ascending order.
+    ///
+    /// The result can contain false positives: the events of the returned blocks still need to be filtered. However,
+    /// no block containing a matching event is ever left out.
+    pub fn get_events_candidate_blocks(
+        &self,
+        from_address: Option<Felt>,
+        keys: &[Vec<Felt>],
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<u64>> {
+        if from_block > to_block {
+            return Ok(vec![]);
+        }
+
+        // Blocks stored before the index was introduced have to be scanned.
+        let index_start = self.events_index_start()?.unwrap_or(u64::MAX).max(from_block);
+        let mut candidates: Vec<u64> = (from_block..index_start.min(to_block.saturating_add(1))).collect();
+        if index_start > to_block {
+            return Ok(candidates);
+        }
+
+        let by_address = from_address
+            .map(|address| self.events_index_scan(Column::EventsByAddress, &address, index_start, to_block))
+            .transpose()?;
+        let by_first_key = match keys.first() {
+            Some(first_keys) if !first_keys.is_empty() => {
+                let mut blocks = BTreeSet::new();
+                for key in first_keys {
+                    blocks.extend(self.events_index_scan(Column::EventsByFirstKey, key, index_start, to_block)?);
+                }
+                Some(blocks)
+            }
+            _ => None,
+        };
+
+        let indexed: Box<dyn Iterator<Item = u64>> = match (by_address, by_first_key) {
+            (Some(a), Some(b)) => Box::new(a.intersection(&b).copied().collect::<Vec<_>>().into_iter()),
+            (Some(blocks), None) | (None, Some(blocks)) => Box::new(blocks.into_iter()),
+            (None, None) => Box::new(index_start..=to_block),
+        };
+
+        for block_n in indexed {
+            match self.get_event_bloom(block_n)? {
+                Some(bloom) if !bloom.may_match(from_address.as_ref(), keys) => {}
+                _ => candidates.push(block_n),
+            }
+        }
+
+        Ok(candidates)
+    }
+}
@@ -1,25 +1,166 @@
-use rocksdb::WriteOptions;
+use std::fmt;
+
+use mp_block::{MadaraBlock, MadaraBlockInner};
+use mp_transactions::Transaction;
+use rocksdb::{Direction, IteratorMode, WriteOptions};
 use serde::{Deserialize, Serialize};
 use starknet_api::core::Nonce;
+use starknet_types_core::felt::Felt;
 
 use crate::error::DbError;
-use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError};
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError, WriteBatchWithTransaction};
 
 type Result<T, E = MadaraStorageError> = std::result::Result<T, E>;
 
 pub const LAST_SYNCED_L1_EVENT_BLOCK: &[u8] = b"LAST_SYNCED_L1_EVENT_BLOCK";
+pub const LAST_SYNCED_L1_EVENT_BLOCK_HASH: &[u8] = b"LAST_SYNCED_L1_EVENT_BLOCK_HASH";
 
 /// Struct to store block number and event_index where L1->L2 Message occured
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LastSyncedEventBlock {
     pub block_number: u64,
     pub event_index: u64,
+    /// Hash of the L1 block, used to detect when it is reorged out of the L1 chain. `None` when no L1 block was
+    /// synced yet.
+    pub block_hash: Option<[u8; 32]>,
 }
 
 impl LastSyncedEventBlock {
-    /// Create a new LastSyncedBlock with block number and event index
-    pub fn new(block_number: u64, event_index: u64) -> Self {
-        LastSyncedEventBlock { block_number, event_index }
+    /// Create a new LastSyncedBlock with block number, event index and block hash
+    pub fn new(block_number: u64, event_index: u64, block_hash: [u8; 32]) -> Self {
+        LastSyncedEventBlock { block_number, event_index, block_hash: Some(block_hash) }
+    }
+
+    fn position(&self) -> (u64, u64) {
+        (self.block_number, self.event_index)
+    }
+}
+
+/// Layout of the `LAST_SYNCED_L1_EVENT_BLOCK` row. The block hash is kept in the `LAST_SYNCED_L1_EVENT_BLOCK_HASH` row,
+/// so that the rows written before it was added can still be read.
+#[derive(Serialize, Deserialize)]
+struct LastSyncedEventBlockRow {
+    block_number: u64,
+    event_index: u64,
+}
+
+/// An L1 to L2 message which was submitted to the mempool, kept so that it can be rolled back if the L1 block which
+/// sent it is reorged out of the L1 chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L1MessageRecord {
+    pub nonce: Nonce,
+    pub l1_block_number: u64,
+    pub l1_block_hash: [u8; 32],
+    /// Index of the `LogMessageToL2` event in the L1 block.
+    pub log_index: u64,
+    pub l1_transaction_hash: [u8; 32],
+    /// Hash of the `L1Handler` transaction resulting from the message.
+    pub tx_hash: Felt,
+}
+
+impl L1MessageRecord {
+    /// Messages are stored by nonce. The core contract gives increasing nonces to the messages, so this is also the
+    /// order in which they were sent on L1.
+    fn key(&self) -> [u8; 32] {
+        self.nonce.0.to_bytes_be()
+    }
+
+    fn position(&self) -> (u64, u64) {
+        (self.l1_block_number, self.log_index)
+    }
+
+    fn last_synced_event_block(&self) -> LastSyncedEventBlock {
+        LastSyncedEventBlock::new(self.l1_block_number, self.log_index, self.l1_block_hash)
+    }
+}
+
+/// An L1 block, identified by its hash so that we can tell when it is reorged out of the L1 chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L1BlockRef {
+    pub block_number: u64,
+    pub block_hash: [u8; 32],
+}
+
+/// Last L1 block scanned for state updates, see [`MadaraBackend::get_l1_state_updates_checkpoint`].
+const ROW_L1_STATE_UPDATES_CHECKPOINT: &[u8] = b"l1_state_updates_checkpoint";
+
+/// A Starknet state update posted on L1, which settles the chain up to `block_number`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L1StateUpdateRecord {
+    pub block_number: u64,
+    pub block_hash: Felt,
+    pub global_root: Felt,
+    /// L1 block containing the `LogStateUpdate` event.
+    pub l1_block_number: u64,
+    pub l1_block_hash: [u8; 32],
+    pub l1_transaction_hash: [u8; 32],
+}
+
+/// An `updateState` transaction sent to L1.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementTransaction {
+    pub l1_transaction_hash: [u8; 32],
+    pub nonce: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    /// Unix time at which the transaction was sent, in seconds.
+    pub sent_at: u64,
+    /// Number of failed attempts at settling the block before this transaction.
+    pub previous_failures: u32,
+}
+
+/// Status of the settlement of a block on L1, when this node settles its own blocks by sending their state updates to
+/// the core contract.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementStatus {
+    /// The `updateState` transaction was sent, and is not in an L1 block yet. When it stays pending for too long, it is
+    /// replaced by a transaction with the same nonce and higher fees: `replaced` are the hashes of the earlier
+    /// transactions, which may still be included instead.
+    Submitted { transaction: SettlementTransaction, replaced: Vec<[u8; 32]> },
+    /// The `updateState` transaction succeeded.
+    Confirmed { l1_transaction_hash: [u8; 32], l1_block_number: u64 },
+    /// The `updateState` transaction could not be sent or reverted. `attempts` is the number of failures in a row for
+    /// this block, and `failed_at` the Unix time of the last one, in seconds. The state update is sent again after a
+    /// backoff, until too many attempts failed.
+    Failed { reason: String, attempts: u32, failed_at: u64 },
+}
+
+/// Result of checking the latest Starknet state update posted on L1 against the local chain. It is only kept in
+/// memory, and is checked again from the next state update after a restart.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum L1VerificationStatus {
+    /// No state update was checked yet.
+    #[default]
+    Unknown,
+    /// The block hash and global state root posted on L1 match the local chain.
+    Verified { block_number: u64 },
+    /// The block posted on L1 has not been synced yet. It is checked again when it is.
+    Deferred { block_number: u64 },
+    /// The block hash or global state root posted on L1 differ from the local chain. Blocks are not marked as
+    /// accepted on L1 until a later state update matches again.
+    Diverged {
+        block_number: u64,
+        l1_block_hash: Felt,
+        l1_global_root: Felt,
+        local_block_hash: Felt,
+        local_global_root: Felt,
+    },
+}
+
+impl fmt::Display for L1VerificationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "no L1 state update checked yet"),
+            Self::Verified { block_number } => write!(f, "block #{block_number} verified on L1"),
+            Self::Deferred { block_number } => write!(f, "block #{block_number} posted on L1 is not synced yet"),
+            Self::Diverged { block_number, l1_block_hash, l1_global_root, local_block_hash, local_global_root } => {
+                write!(
+                    f,
+                    "block #{block_number} diverges from L1: block hash {local_block_hash:#x} (L1: \
+                     {l1_block_hash:#x}), global state root {local_global_root:#x} (L1: {l1_global_root:#x})"
+                )
+            }
+        }
     }
 }
 
@@ -35,7 +176,7 @@ impl MadaraBackend {
     ///
     /// - `Ok(Some(LastSyncedEventBlock))` - If the last synced L1 block with a messaging event is found
     ///   and successfully deserialized.
-    /// - `Ok(Some(LastSyncedEventBlock::new(0, 0)))` - If no such block exists in the database.
+    /// - `Ok(Some(LastSyncedEventBlock::default()))` - If no such block exists in the database.
     /// - `Err(e)` - If there is an error accessing the database or deserializing the block.
     ///
     /// # Errors
@@ -61,10 +202,16 @@ impl MadaraBackend {
     pub fn messaging_last_synced_l1_block_with_event(&self) -> Result<Option<LastSyncedEventBlock>> {
         let messaging_column = self.db.get_column(Column::L1Messaging);
         let Some(res) = self.db.get_cf(&messaging_column, LAST_SYNCED_L1_EVENT_BLOCK)? else {
-            return Ok(Some(LastSyncedEventBlock::new(0, 0)));
+            return Ok(Some(LastSyncedEventBlock::default()));
         };
-        let res = bincode::deserialize(&res)?;
-        Ok(Some(res))
+        let LastSyncedEventBlockRow { block_number, event_index } = bincode::deserialize(&res)?;
+        // Databases written by older versions have no block hash: the checkpoint cannot be checked for L1 reorgs until
+        // it is written again.
+        let block_hash = match self.db.get_cf(&messaging_column, LAST_SYNCED_L1_EVENT_BLOCK_HASH)? {
+            Some(block_hash) => Some(bincode::deserialize(&block_hash)?),
+            None => None,
+        };
+        Ok(Some(LastSyncedEventBlock { block_number, event_index, block_hash }))
     }
 
     /// This function inserts a new block into the messaging column.
@@ -90,7 +237,7 @@ impl MadaraBackend {
     ///
     /// # Example
     ///
-    /// let block_sent = LastSyncedEventBlock::new(l1_block_number.unwrap(), event_index.unwrap());
+    /// let block_sent = LastSyncedEventBlock::new(l1_block_number, event_index, l1_block_hash);
     /// backend.messaging_update_last_synced_l1_block_with_event(block_sent)?;
     ///
     /// # Panics
@@ -99,16 +246,12 @@ impl MadaraBackend {
     pub fn messaging_update_last_synced_l1_block_with_event(
         &self,
         last_synced_event_block: LastSyncedEventBlock,
-    ) -> Result<(), DbError> {
-        let messaging_column = self.db.get_column(Column::L1Messaging);
+    ) -> Result<()> {
+        let mut batch = WriteBatchWithTransaction::default();
+        self.messaging_put_last_synced_l1_block_with_event(&mut batch, &last_synced_event_block)?;
         let mut writeopts = WriteOptions::default(); // todo move that in db
         writeopts.disable_wal(true);
-        self.db.put_cf_opt(
-            &messaging_column,
-            LAST_SYNCED_L1_EVENT_BLOCK,
-            bincode::serialize(&last_synced_event_block)?,
-            &writeopts,
-        )?;
+        self.db.write_opt(batch, &writeopts)?;
         Ok(())
     }
 
@@ -124,4 +267,307 @@ impl MadaraBackend {
         self.db.put_cf_opt(&nonce_column, bincode::serialize(&nonce)?, /* empty value */ [], &writeopts)?;
         Ok(())
     }
+
+    /// Hash of the `L1Handler` transaction which consumed the message with this nonce in a closed block. Returns
+    /// `None` for messages which were not executed yet, or which were cancelled on L1.
+    pub fn get_l1_messaging_nonce_tx_hash(&self, nonce: Nonce) -> Result<Option<Felt>> {
+        let nonce_column = self.db.get_column(Column::L1MessagingNonce);
+        let Some(res) = self.db.get_pinned_cf(&nonce_column, bincode::serialize(&nonce)?)? else { return Ok(None) };
+        if res.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(bincode::deserialize(&res)?))
+    }
+
+    /// Appends a message nonce to the messages sent by an L1 transaction.
+    pub fn add_l1_tx_messaging_nonce(&self, l1_tx_hash: &[u8; 32], nonce: Nonce) -> Result<()> {
+        let mut nonces = self.get_l1_tx_messaging_nonces(l1_tx_hash)?;
+        if nonces.contains(&nonce) {
+            return Ok(());
+        }
+        nonces.push(nonce);
+
+        let column = self.db.get_column(Column::L1TxHashToMessagingNonces);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.put_cf_opt(&column, l1_tx_hash, bincode::serialize(&nonces)?, &writeopts)?;
+        Ok(())
+    }
+
+    /// Removes a message nonce from the messages sent by an L1 transaction.
+    pub fn remove_l1_tx_messaging_nonce(&self, l1_tx_hash: &[u8; 32], nonce: Nonce) -> Result<()> {
+        let mut nonces = self.get_l1_tx_messaging_nonces(l1_tx_hash)?;
+        nonces.retain(|n| n != &nonce);
+
+        let column = self.db.get_column(Column::L1TxHashToMessagingNonces);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        if nonces.is_empty() {
+            self.db.delete_cf_opt(&column, l1_tx_hash, &writeopts)?;
+        } else {
+            self.db.put_cf_opt(&column, l1_tx_hash, bincode::serialize(&nonces)?, &writeopts)?;
+        }
+        Ok(())
+    }
+
+    /// Adds to `batch` the consumption of the L1 to L2 messages executed by the `L1Handler` transactions of a closed
+    /// block: their nonce is marked with the transaction hash, and the last synced event block is moved to the last
+    /// of these messages. Messages are only consumed once they are in a closed block, so that the ones still waiting
+    /// in the mempool or in the pending block are submitted again after a restart.
+    pub(crate) fn l1_db_store_block(&self, batch: &mut WriteBatchWithTransaction, block: &MadaraBlock) -> Result<()> {
+        let nonce_column = self.db.get_column(Column::L1MessagingNonce);
+        let mut last_message: Option<L1MessageRecord> = None;
+        for (tx, tx_hash) in block.inner.transactions.iter().zip(&block.info.tx_hashes) {
+            let Transaction::L1Handler(tx) = tx else { continue };
+            let nonce = Nonce(tx.nonce.into());
+            batch.put_cf(&nonce_column, bincode::serialize(&nonce)?, bincode::serialize(tx_hash)?);
+            // Full nodes do not scan L1 for messages, and have no record of them.
+            if let Some(message) = self.get_l1_message(nonce)? {
+                if last_message.as_ref().map_or(true, |last| message.position() > last.position()) {
+                    last_message = Some(message);
+                }
+            }
+        }
+
+        let Some(message) = last_message else { return Ok(()) };
+        let last_synced = self.messaging_last_synced_l1_block_with_event()?.unwrap_or_default();
+        if message.position() > last_synced.position() {
+            self.messaging_put_last_synced_l1_block_with_event(batch, &message.last_synced_event_block())?;
+        }
+        Ok(())
+    }
+
+    /// Adds to `batch` the revert of the L1 to L2 messages consumed by a reverted block: their nonce is unmarked, and
+    /// the last synced event block is moved back to the first of these messages so that they are submitted again the
+    /// next time the L1 messages are scanned from it.
+    pub(crate) fn l1_db_revert(&self, batch: &mut WriteBatchWithTransaction, inner: &MadaraBlockInner) -> Result<()> {
+        let nonce_column = self.db.get_column(Column::L1MessagingNonce);
+        let mut first_message: Option<L1MessageRecord> = None;
+        for tx in &inner.transactions {
+            let Transaction::L1Handler(tx) = tx else { continue };
+            let nonce = Nonce(tx.nonce.into());
+            batch.delete_cf(&nonce_column, bincode::serialize(&nonce)?);
+            if let Some(message) = self.get_l1_message(nonce)? {
+                if first_message.as_ref().map_or(true, |first| message.position() < first.position()) {
+                    first_message = Some(message);
+                }
+            }
+        }
+
+        let Some(message) = first_message else { return Ok(()) };
+        let last_synced = self.messaging_last_synced_l1_block_with_event()?.unwrap_or_default();
+        if message.position() <= last_synced.position() {
+            self.messaging_put_last_synced_l1_block_with_event(batch, &message.last_synced_event_block())?;
+        }
+        Ok(())
+    }
+
+    fn messaging_put_last_synced_l1_block_with_event(
+        &self,
+        batch: &mut WriteBatchWithTransaction,
+        last_synced_event_block: &LastSyncedEventBlock,
+    ) -> Result<()> {
+        let messaging_column = self.db.get_column(Column::L1Messaging);
+        let LastSyncedEventBlock { block_number, event_index, block_hash } = *last_synced_event_block;
+        batch.put_cf(
+            &messaging_column,
+            LAST_SYNCED_L1_EVENT_BLOCK,
+            bincode::serialize(&LastSyncedEventBlockRow { block_number, event_index })?,
+        );
+        match block_hash {
+            Some(block_hash) => {
+                batch.put_cf(&messaging_column, LAST_SYNCED_L1_EVENT_BLOCK_HASH, bincode::serialize(&block_hash)?)
+            }
+            None => batch.delete_cf(&messaging_column, LAST_SYNCED_L1_EVENT_BLOCK_HASH),
+        }
+        Ok(())
+    }
+
+    pub fn store_l1_message(&self, message: &L1MessageRecord) -> Result<()> {
+        let column = self.db.get_column(Column::L1Messages);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.put_cf_opt(&column, message.key(), bincode::serialize(message)?, &writeopts)?;
+        Ok(())
+    }
+
+    /// The L1 to L2 message with this nonce, if it was submitted to the mempool and was not rolled back.
+    pub fn get_l1_message(&self, nonce: Nonce) -> Result<Option<L1MessageRecord>> {
+        let column = self.db.get_column(Column::L1Messages);
+        let Some(res) = self.db.get_pinned_cf(&column, nonce.0.to_bytes_be())? else { return Ok(None) };
+        Ok(Some(bincode::deserialize(&res)?))
+    }
+
+    /// The L1 to L2 message with the highest nonce, which is the one sent in the most recent L1 block.
+    pub fn get_latest_l1_message(&self) -> Result<Option<L1MessageRecord>> {
+        let column = self.db.get_column(Column::L1Messages);
+        let mut iter = self.db.iterator_cf(&column, IteratorMode::End);
+        let Some(kv) = iter.next() else { return Ok(None) };
+        let (_, value) = kv?;
+        Ok(Some(bincode::deserialize(&value)?))
+    }
+
+    pub fn remove_l1_message(&self, message: &L1MessageRecord) -> Result<()> {
+        let column = self.db.get_column(Column::L1Messages);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.delete_cf_opt(&column, message.key(), &writeopts)?;
+        Ok(())
+    }
+
+    /// Nonces of the L1 to L2 messages sent by an L1 transaction, in the order they were sent. Empty if the
+    /// transaction is unknown.
+    pub fn get_l1_tx_messaging_nonces(&self, l1_tx_hash: &[u8; 32]) -> Result<Vec<Nonce>> {
+        let column = self.db.get_column(Column::L1TxHashToMessagingNonces);
+        let Some(res) = self.db.get_pinned_cf(&column, l1_tx_hash)? else { return Ok(vec![]) };
+        Ok(bincode::deserialize(&res)?)
+    }
+
+    /// Result of the last check of an L1 state update against the local chain, see [`L1VerificationStatus`].
+    pub fn get_l1_verification_status(&self) -> L1VerificationStatus {
+        self.l1_verification_status.read().expect("poisoned lock").clone()
+    }
+
+    pub fn set_l1_verification_status(&self, status: L1VerificationStatus) {
+        *self.l1_verification_status.write().expect("poisoned lock") = status;
+    }
+
+    /// Stores a state update posted on L1. Storing the same state update again is a no-op.
+    pub fn store_l1_state_update(&self, state_update: &L1StateUpdateRecord) -> Result<()> {
+        let column = self.db.get_column(Column::L1StateUpdates);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.put_cf_opt(
+            &column,
+            state_update.block_number.to_be_bytes(),
+            bincode::serialize(state_update)?,
+            &writeopts,
+        )?;
+        Ok(())
+    }
+
+    /// The state update posted on L1 which settled block `block_n`, that is the first one settling the chain up to
+    /// that block or a later one. Returns `None` when the block has not been settled on L1 yet.
+    pub fn get_l1_state_update(&self, block_n: u64) -> Result<Option<L1StateUpdateRecord>> {
+        let column = self.db.get_column(Column::L1StateUpdates);
+        let start = block_n.to_be_bytes();
+        let mut iter = self.db.iterator_cf(&column, IteratorMode::From(&start, Direction::Forward));
+        let Some(kv) = iter.next() else { return Ok(None) };
+        let (_, value) = kv?;
+        Ok(Some(bincode::deserialize(&value)?))
+    }
+
+    /// The state update posted on L1 which settles the most recent block.
+    pub fn get_latest_l1_state_update(&self) -> Result<Option<L1StateUpdateRecord>> {
+        let column = self.db.get_column(Column::L1StateUpdates);
+        let mut iter = self.db.iterator_cf(&column, IteratorMode::End);
+        let Some(kv) = iter.next() else { return Ok(None) };
+        let (_, value) = kv?;
+        Ok(Some(bincode::deserialize(&value)?))
+    }
+
+    /// Removes the state update settling the chain up to `block_n`, when the L1 block it was posted in is reorged out.
+    pub fn remove_l1_state_update(&self, block_n: u64) -> Result<()> {
+        let column = self.db.get_column(Column::L1StateUpdates);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.delete_cf_opt(&column, block_n.to_be_bytes(), &writeopts)?;
+        Ok(())
+    }
+
+    /// Last L1 block scanned for state updates. All the state updates posted up to this block are stored. Returns
+    /// `None` when no L1 block was scanned yet.
+    pub fn get_l1_state_updates_checkpoint(&self) -> Result<Option<L1BlockRef>> {
+        let column = self.db.get_column(Column::BlockStorageMeta);
+        let Some(res) = self.db.get_cf(&column, ROW_L1_STATE_UPDATES_CHECKPOINT)? else { return Ok(None) };
+        Ok(Some(bincode::deserialize(&res)?))
+    }
+
+    pub fn write_l1_state_updates_checkpoint(&self, l1_block: &L1BlockRef) -> Result<()> {
+        let column = self.db.get_column(Column::BlockStorageMeta);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.put_cf_opt(&column, ROW_L1_STATE_UPDATES_CHECKPOINT, bincode::serialize(l1_block)?, &writeopts)?;
+        Ok(())
+    }
+
+    pub fn clear_l1_state_updates_checkpoint(&self) -> Result<()> {
+        let column = self.db.get_column(Column::BlockStorageMeta);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.delete_cf_opt(&column, ROW_L1_STATE_UPDATES_CHECKPOINT, &writeopts)?;
+        Ok(())
+    }
+
+    pub fn get_settlement_status(&self, block_n: u64) -> Result<Option<SettlementStatus>> {
+        let column = self.db.get_column(Column::L1Settlement);
+        let Some(res) = self.db.get_pinned_cf(&column, block_n.to_be_bytes())? else { return Ok(None) };
+        Ok(Some(bincode::deserialize(&res)?))
+    }
+
+    pub fn set_settlement_status(&self, block_n: u64, status: &SettlementStatus) -> Result<()> {
+        let column = self.db.get_column(Column::L1Settlement);
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.put_cf_opt(&column, block_n.to_be_bytes(), bincode::serialize(status)?, &writeopts)?;
+        Ok(())
+    }
+
+    /// The most recent block with a settlement status. Blocks are settled in order, so all the blocks before it are
+    /// settled.
+    pub fn get_latest_settlement_status(&self) -> Result<Option<(u64, SettlementStatus)>> {
+        let column = self.db.get_column(Column::L1Settlement);
+        let mut iter = self.db.iterator_cf(&column, IteratorMode::End);
+        let Some(kv) = iter.next() else { return Ok(None) };
+        let (key, value) = kv?;
+        let block_n = u64::from_be_bytes(
+            (*key).try_into().map_err(|_| MadaraStorageError::InconsistentStorage("Invalid settlement key".into()))?,
+        );
+        Ok(Some((block_n, bincode::deserialize(&value)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mp_chain_config::ChainConfig;
+    use mp_utils::tests_common::*;
+    use rstest::*;
+    use std::sync::Arc;
+
+    /// Rows written before the block hash was added are still read, without a block hash.
+    #[rstest]
+    fn test_last_synced_event_block_without_hash(_set_workdir: ()) {
+        #[derive(Serialize)]
+        struct OldLastSyncedEventBlock {
+            block_number: u64,
+            event_index: u64,
+        }
+
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::test_config().unwrap()));
+        let column = backend.db.get_column(Column::L1Messaging);
+        let old = bincode::serialize(&OldLastSyncedEventBlock { block_number: 12, event_index: 3 }).unwrap();
+        backend.db.put_cf(&column, LAST_SYNCED_L1_EVENT_BLOCK, old).unwrap();
+        assert_eq!(
+            backend.messaging_last_synced_l1_block_with_event().unwrap(),
+            Some(LastSyncedEventBlock { block_number: 12, event_index: 3, block_hash: None })
+        );
+
+        backend.messaging_update_last_synced_l1_block_with_event(LastSyncedEventBlock::new(13, 0, [4; 32])).unwrap();
+        assert_eq!(
+            backend.messaging_last_synced_l1_block_with_event().unwrap(),
+            Some(LastSyncedEventBlock::new(13, 0, [4; 32]))
+        );
+        backend
+            .messaging_update_last_synced_l1_block_with_event(LastSyncedEventBlock {
+                block_number: 14,
+                event_index: 1,
+                block_hash: None,
+            })
+            .unwrap();
+        assert_eq!(
+            backend.messaging_last_synced_l1_block_with_event().unwrap(),
+            Some(LastSyncedEventBlock { block_number: 14, event_index: 1, block_hash: None })
+        );
+    }
 }
@@ -1,17 +1,19 @@
 //! Madara database
 
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 use std::{fmt, fs};
 
 use anyhow::{Context, Result};
-use bonsai_db::{BonsaiDb, DatabaseKeyMapping};
+use bonsai_db::{BonsaiDb, DatabaseKeyMapping, TrieSnapshots};
 use bonsai_trie::id::BasicId;
 use bonsai_trie::{BonsaiStorage, BonsaiStorageConfig};
 use db_metrics::DbMetrics;
 use mp_chain_config::ChainConfig;
 use mp_utils::service::Service;
+use pruning::PruningMode;
 use rocksdb::backup::{BackupEngine, BackupEngineOptions};
 
 pub mod block_db;
@@ -26,12 +28,16 @@ pub mod contract_db;
 pub mod db_block_id;
 pub mod db_metrics;
 pub mod devnet_db;
+pub mod events_db;
 pub mod l1_db;
+pub mod notifications;
+pub mod pruning;
 pub mod storage_updates;
 
 pub use error::{MadaraStorageError, TrieType};
 use starknet_types_core::hash::{Pedersen, Poseidon, StarkHash};
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinSet;
 
 pub type DB = DBWithThreadMode<MultiThreaded>;
 
@@ -40,6 +46,31 @@ pub type WriteBatchWithTransaction = rocksdb::WriteBatchWithTransaction<false>;
 
 const DB_UPDATES_BATCH_SIZE: usize = 1024;
 
+/// Maximum number of blocks that can be reverted with [`MadaraBackend::revert_to`]. We keep the bonsai trie logs
+/// for at least this many blocks, as they are needed to roll back the global tries.
+pub const MAX_REORG_DEPTH: u64 = 64;
+
+/// Retention of the bonsai trie history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieLogConfig {
+    /// Number of blocks for which the trie logs are kept. They are used to revert the tries on reorgs and to open
+    /// views of the tries at past blocks, see [`MadaraBackend::contract_trie_at`]. This cannot be lower than
+    /// [`MAX_REORG_DEPTH`].
+    pub max_saved_trie_logs: u64,
+    /// Maximum number of in-memory snapshots of the global tries. Views of the tries at a past block are opened from
+    /// the closest snapshot after that block instead of the latest block, which saves reverting the trie logs in
+    /// between. Snapshots are not persisted, and use memory for every trie entry written since their block.
+    pub max_saved_snapshots: usize,
+    /// Number of blocks between two snapshots of the global tries. Cannot be 0.
+    pub snapshot_interval: u64,
+}
+
+impl Default for TrieLogConfig {
+    fn default() -> Self {
+        Self { max_saved_trie_logs: MAX_REORG_DEPTH, max_saved_snapshots: 0, snapshot_interval: MAX_REORG_DEPTH }
+    }
+}
+
 pub fn open_rocksdb(path: &Path, create: bool) -> Result<Arc<DB>> {
     let mut opts = Options::default();
     opts.set_report_bg_io_stats(true);
@@ -108,7 +139,7 @@ fn spawn_backup_db_task(
     Ok(())
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Column {
     // Blocks storage
     // block_n => Block info
@@ -167,6 +198,22 @@ pub enum Column {
 
     L1Messaging,
     L1MessagingNonce,
+    /// L1 transaction hash => nonces of the L1 to L2 messages it sent
+    L1TxHashToMessagingNonces,
+    /// block_n => state update posted on L1 which settled the chain up to that block
+    L1StateUpdates,
+    /// L1 block_n ++ log_index => L1 to L2 message, kept to roll it back if its L1 block is reorged out
+    L1Messages,
+    /// block_n => status of the settlement of the block on L1, when this node settles its own blocks
+    L1Settlement,
+
+    // Event index
+    // from_address ++ block_n => ()
+    EventsByAddress,
+    // first_key ++ block_n => ()
+    EventsByFirstKey,
+    /// block_n => bloom filter of the events of the block
+    EventBloom,
 
     /// Devnet: stores the private keys for the devnet predeployed contracts
     Devnet,
@@ -214,9 +261,16 @@ impl Column {
             BonsaiClassesLog,
             L1Messaging,
             L1MessagingNonce,
+            L1TxHashToMessagingNonces,
+            L1StateUpdates,
+            L1Messages,
+            L1Settlement,
             PendingContractToClassHashes,
             PendingContractToNonces,
             PendingContractStorage,
+            EventsByAddress,
+            EventsByFirstKey,
+            EventBloom,
             Devnet,
         ]
     };
@@ -251,9 +305,16 @@ impl Column {
             ContractStorage => "contract_storage",
             L1Messaging => "l1_messaging",
             L1MessagingNonce => "l1_messaging_nonce",
+            L1TxHashToMessagingNonces => "l1_tx_hash_to_messaging_nonces",
+            L1StateUpdates => "l1_state_updates",
+            L1Messages => "l1_messages",
+            L1Settlement => "l1_settlement",
             PendingContractToClassHashes => "pending_contract_to_class_hashes",
             PendingContractToNonces => "pending_contract_to_nonces",
             PendingContractStorage => "pending_contract_storage",
+            EventsByAddress => "events_by_address",
+            EventsByFirstKey => "events_by_first_key",
+            EventBloom => "event_bloom",
             Devnet => "devnet",
         }
     }
@@ -278,6 +339,16 @@ impl Column {
                     contract_db::CONTRACT_NONCES_PREFIX_EXTRACTOR,
                 ));
             }
+            Column::EventsByAddress => {
+                opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(
+                    events_db::EVENTS_BY_ADDRESS_PREFIX_EXTRACTOR,
+                ));
+            }
+            Column::EventsByFirstKey => {
+                opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(
+                    events_db::EVENTS_BY_FIRST_KEY_PREFIX_EXTRACTOR,
+                ));
+            }
             _ => {}
         }
         opts
@@ -305,12 +376,23 @@ pub struct MadaraBackend {
     db: Arc<DB>,
     last_flush_time: Mutex<Option<Instant>>,
     chain_config: Arc<ChainConfig>,
+    /// See [`MadaraBackend::get_pruned_before`].
+    pruned_before: AtomicU64,
+    /// See [`MadaraBackend::get_revert_count`].
+    revert_count: AtomicU64,
+    /// See [`MadaraBackend::get_l1_verification_status`].
+    l1_verification_status: RwLock<l1_db::L1VerificationStatus>,
+    trie_log_config: TrieLogConfig,
+    trie_snapshots: TrieSnapshots,
+    /// See [`MadaraBackend::subscribe_block_notifications`].
+    block_notifications: broadcast::Sender<notifications::BlockNotification>,
     #[cfg(feature = "testing")]
     _temp_dir: Option<tempfile::TempDir>,
 }
 
 pub struct DatabaseService {
     handle: Arc<MadaraBackend>,
+    pruning_mode: PruningMode,
 }
 
 impl DatabaseService {
@@ -322,6 +404,8 @@ impl DatabaseService {
     /// * `backup_dir` - Optional path to the backup directory.
     /// * `restore_from_latest_backup` - Whether to restore the database from the latest backup.
     /// * `chain_config` - The chain configuration.
+    /// * `pruning_mode` - Whether to keep the whole state history, or only the last blocks.
+    /// * `trie_log_config` - Retention of the global tries history.
     ///
     /// # Returns
     ///
@@ -332,14 +416,21 @@ impl DatabaseService {
         backup_dir: Option<PathBuf>,
         restore_from_latest_backup: bool,
         chain_config: Arc<ChainConfig>,
+        pruning_mode: PruningMode,
+        trie_log_config: TrieLogConfig,
     ) -> anyhow::Result<Self> {
         log::info!("💾 Opening database at: {}", base_path.display());
 
-        let handle =
-            MadaraBackend::open(base_path.to_owned(), backup_dir.clone(), restore_from_latest_backup, chain_config)
-                .await?;
+        let handle = MadaraBackend::open(
+            base_path.to_owned(),
+            backup_dir.clone(),
+            restore_from_latest_backup,
+            chain_config,
+            trie_log_config,
+        )
+        .await?;
 
-        Ok(Self { handle })
+        Ok(Self { handle, pruning_mode })
     }
 
     pub fn backend(&self) -> &Arc<MadaraBackend> {
@@ -347,7 +438,16 @@ impl DatabaseService {
     }
 }
 
-impl Service for DatabaseService {}
+#[async_trait::async_trait]
+impl Service for DatabaseService {
+    async fn start(&mut self, join_set: &mut JoinSet<anyhow::Result<()>>) -> anyhow::Result<()> {
+        if let PruningMode::Blocks(keep_blocks) = self.pruning_mode {
+            log::info!("✂️  State pruning is enabled, keeping the last {keep_blocks} blocks of state history");
+            join_set.spawn(pruning::pruning_task(Arc::clone(&self.handle), keep_blocks));
+        }
+        Ok(())
+    }
+}
 
 struct BackupRequest {
     callback: oneshot::Sender<()>,
@@ -365,7 +465,7 @@ impl MadaraBackend {
         &self.chain_config
     }
 
-    #[cfg(feature = "testing")]
+    #[cfg(any(test, feature = "testing"))]
     pub fn open_for_testing(chain_config: Arc<ChainConfig>) -> Arc<MadaraBackend> {
         let temp_dir = tempfile::TempDir::with_prefix("madara-test").unwrap();
         Arc::new(Self {
@@ -373,6 +473,12 @@ impl MadaraBackend {
             db: open_rocksdb(temp_dir.as_ref(), true).unwrap(),
             last_flush_time: Default::default(),
             chain_config,
+            pruned_before: Default::default(),
+            revert_count: Default::default(),
+            l1_verification_status: Default::default(),
+            trie_log_config: Default::default(),
+            trie_snapshots: Default::default(),
+            block_notifications: broadcast::channel(notifications::BLOCK_NOTIFICATIONS_CAPACITY).0,
             _temp_dir: Some(temp_dir),
         })
     }
@@ -383,7 +489,13 @@ impl MadaraBackend {
         backup_dir: Option<PathBuf>,
         restore_from_latest_backup: bool,
         chain_config: Arc<ChainConfig>,
+        trie_log_config: TrieLogConfig,
     ) -> Result<Arc<MadaraBackend>> {
+        anyhow::ensure!(
+            trie_log_config.max_saved_trie_logs >= MAX_REORG_DEPTH,
+            "Trie logs must be kept for at least {MAX_REORG_DEPTH} blocks"
+        );
+        anyhow::ensure!(trie_log_config.snapshot_interval > 0, "The trie snapshot interval cannot be 0");
         let db_path = db_config_dir.join("db");
 
         // when backups are enabled, a thread is spawned that owns the rocksdb BackupEngine (it is not thread safe) and it receives backup requests using a mpsc channel
@@ -414,10 +526,18 @@ impl MadaraBackend {
             db,
             last_flush_time: Default::default(),
             chain_config: Arc::clone(&chain_config),
+            pruned_before: Default::default(),
+            revert_count: Default::default(),
+            l1_verification_status: Default::default(),
+            trie_log_config,
+            trie_snapshots: Default::default(),
+            block_notifications: broadcast::channel(notifications::BLOCK_NOTIFICATIONS_CAPACITY).0,
             #[cfg(feature = "testing")]
             _temp_dir: None,
         });
         backend.check_configuration()?;
+        backend.load_pruned_before()?;
+        backend.resume_revert()?;
         Ok(backend)
     }
 
@@ -430,12 +550,7 @@ impl MadaraBackend {
             };
         if should_flush {
             log::debug!("doing a db flush");
-            let mut opts = FlushOptions::default();
-            opts.set_wait(true);
-            // we have to collect twice here :/
-            let columns = Column::ALL.iter().map(|e| self.db.get_column(*e)).collect::<Vec<_>>();
-            let columns = columns.iter().collect::<Vec<_>>();
-            self.db.flush_cfs_opt(&columns, &opts).context("Flushing database")?;
+            self.flush_all_columns().context("Flushing database")?;
 
             *inst = Some(Instant::now());
         }
@@ -443,6 +558,18 @@ impl MadaraBackend {
         Ok(should_flush)
     }
 
+    /// Flushes the memtables of every column to disk. Writes are done without the WAL, and atomic flush is enabled:
+    /// the columns are flushed together, so the db never holds a write of one column without the writes made before
+    /// it in the others.
+    pub(crate) fn flush_all_columns(&self) -> Result<(), rocksdb::Error> {
+        let mut opts = FlushOptions::default();
+        opts.set_wait(true);
+        // we have to collect twice here :/
+        let columns = Column::ALL.iter().map(|e| self.db.get_column(*e)).collect::<Vec<_>>();
+        let columns = columns.iter().collect::<Vec<_>>();
+        self.db.flush_cfs_opt(&columns, &opts)
+    }
+
     pub async fn backup(&self) -> Result<()> {
         let (callback_sender, callback_recv) = oneshot::channel();
         let _res = self
@@ -456,24 +583,56 @@ impl MadaraBackend {
 
     // tries
 
+    pub fn trie_log_config(&self) -> &TrieLogConfig {
+        &self.trie_log_config
+    }
+
+    fn bonsai_config(&self) -> BonsaiStorageConfig {
+        BonsaiStorageConfig {
+            max_saved_trie_logs: Some(self.trie_log_config.max_saved_trie_logs as usize),
+            // Snapshots are taken once all the tries are committed for a block, see [`TrieSnapshots`].
+            max_saved_snapshots: Some(0),
+            snapshot_interval: u64::MAX,
+        }
+    }
+
     pub(crate) fn get_bonsai<H: StarkHash + Send + Sync>(
         &self,
         map: DatabaseKeyMapping,
     ) -> BonsaiStorage<BasicId, BonsaiDb<'_>, H> {
-        let bonsai = BonsaiStorage::new(
-            BonsaiDb::new(&self.db, map),
-            BonsaiStorageConfig {
-                max_saved_trie_logs: Some(0),
-                max_saved_snapshots: Some(0),
-                snapshot_interval: u64::MAX,
-            },
-        )
-        // TODO(bonsai-trie): change upstream to reflect that.
-        .expect("New bonsai storage can never error");
+        let bonsai = BonsaiStorage::new(BonsaiDb::new(&self.db, &self.trie_snapshots, map), self.bonsai_config())
+            // TODO(bonsai-trie): change upstream to reflect that.
+            .expect("New bonsai storage can never error");
 
         bonsai
     }
 
+    /// Opens a view of a trie at block `block_n`. The trie is reverted in memory using the trie logs, from the closest
+    /// snapshot after `block_n` or from the latest block, which means nothing is ever written back to the database:
+    /// the view is read-only.
+    fn get_bonsai_at<H: StarkHash + Send + Sync>(
+        &self,
+        map: DatabaseKeyMapping,
+        block_n: u64,
+    ) -> Result<BonsaiStorage<BasicId, BonsaiDb<'_>, H>, MadaraStorageError> {
+        let latest = self.get_latest_block_n()?.ok_or(MadaraStorageError::InvalidBlockNumber)?;
+        if block_n > latest {
+            return Err(MadaraStorageError::InvalidBlockNumber);
+        }
+        let earliest = latest.saturating_sub(self.trie_log_config.max_saved_trie_logs);
+        if block_n < earliest {
+            return Err(MadaraStorageError::BlockPruned { block_n, earliest });
+        }
+
+        let (from, db) = BonsaiDb::new_overlay_from_snapshot(&self.db, &self.trie_snapshots, map.clone(), block_n)
+            .unwrap_or_else(|| (latest, BonsaiDb::new_overlay(&self.db, &self.trie_snapshots, map)));
+        let mut bonsai = BonsaiStorage::new(db, self.bonsai_config()).expect("New bonsai storage can never error");
+        if block_n < from {
+            bonsai.revert_to(BasicId::new(block_n), BasicId::new(from))?;
+        }
+        Ok(bonsai)
+    }
+
     pub fn contract_trie(&self) -> BonsaiStorage<BasicId, BonsaiDb<'_>, Pedersen> {
         self.get_bonsai(DatabaseKeyMapping {
             flat: Column::BonsaiContractsFlat,
@@ -498,6 +657,87 @@ impl MadaraBackend {
         })
     }
 
+    /// Read-only view of the contract trie at block `block_n`.
+    pub fn contract_trie_at(
+        &self,
+        block_n: u64,
+    ) -> Result<BonsaiStorage<BasicId, BonsaiDb<'_>, Pedersen>, MadaraStorageError> {
+        self.get_bonsai_at(
+            DatabaseKeyMapping {
+                flat: Column::BonsaiContractsFlat,
+                trie: Column::BonsaiContractsTrie,
+                log: Column::BonsaiContractsLog,
+            },
+            block_n,
+        )
+    }
+
+    /// Read-only view of the contract storage tries at block `block_n`.
+    pub fn contract_storage_trie_at(
+        &self,
+        block_n: u64,
+    ) -> Result<BonsaiStorage<BasicId, BonsaiDb<'_>, Pedersen>, MadaraStorageError> {
+        self.get_bonsai_at(
+            DatabaseKeyMapping {
+                flat: Column::BonsaiContractsStorageFlat,
+                trie: Column::BonsaiContractsStorageTrie,
+                log: Column::BonsaiContractsStorageLog,
+            },
+            block_n,
+        )
+    }
+
+    /// Read-only view of the class trie at block `block_n`.
+    pub fn class_trie_at(
+        &self,
+        block_n: u64,
+    ) -> Result<BonsaiStorage<BasicId, BonsaiDb<'_>, Poseidon>, MadaraStorageError> {
+        self.get_bonsai_at(
+            DatabaseKeyMapping {
+                flat: Column::BonsaiClassesFlat,
+                trie: Column::BonsaiClassesTrie,
+                log: Column::BonsaiClassesLog,
+            },
+            block_n,
+        )
+    }
+
+    /// Revert a global trie from the `current` block to the `target` block, using the trie logs.
+    pub(crate) fn trie_revert_to(&self, trie: TrieType, target: u64, current: u64) -> Result<(), MadaraStorageError> {
+        let (target, current) = (BasicId::new(target), BasicId::new(current));
+        match trie {
+            TrieType::Contract => self.contract_trie().revert_to(target, current)?,
+            TrieType::ContractStorage => self.contract_storage_trie().revert_to(target, current)?,
+            TrieType::Class => self.class_trie().revert_to(target, current)?,
+        }
+        Ok(())
+    }
+
+    /// Remove the trie logs that are older than [`TrieLogConfig::max_saved_trie_logs`] blocks relative to `block_n`.
+    /// Bonsai only prunes the logs it has committed during the lifetime of a storage instance, and we create a new
+    /// one for every block.
+    pub(crate) fn tries_prune_logs(&self, block_n: u64) -> Result<(), MadaraStorageError> {
+        let Some(keep_from) = block_n.checked_sub(self.trie_log_config.max_saved_trie_logs) else { return Ok(()) };
+        let mut writeopts = rocksdb::WriteOptions::new();
+        writeopts.disable_wal(true);
+        // Trie log keys are prefixed with the big endian commit id, which is the block number.
+        let end = keep_from.to_be_bytes();
+        for col in [Column::BonsaiContractsLog, Column::BonsaiContractsStorageLog, Column::BonsaiClassesLog] {
+            self.db.delete_range_cf_opt(&self.db.get_column(col), &[] as &[u8], end, &writeopts)?;
+        }
+        Ok(())
+    }
+
+    /// Takes a snapshot of the global tries every [`TrieLogConfig::snapshot_interval`] blocks, once they are committed
+    /// for block `block_n`. Snapshots older than the trie logs are dropped, as no view can be opened at their block.
+    pub(crate) fn tries_take_snapshot(&self, block_n: u64) {
+        let TrieLogConfig { max_saved_trie_logs, max_saved_snapshots, snapshot_interval } = self.trie_log_config;
+        if max_saved_snapshots == 0 || block_n % snapshot_interval != 0 {
+            return;
+        }
+        self.trie_snapshots.take(block_n, block_n.saturating_sub(max_saved_trie_logs), max_saved_snapshots);
+    }
+
     pub fn get_storage_size(&self, db_metrics: &DbMetrics) -> u64 {
         let mut storage_size = 0;
 
@@ -0,0 +1,54 @@
+//! Notifications of chain updates, for consumers that need to follow the chain as it is imported (RPC subscriptions).
+
+use std::sync::Arc;
+
+use mp_block::{MadaraBlock, MadaraPendingBlock};
+use starknet_types_core::felt::Felt;
+use tokio::sync::broadcast;
+
+use crate::MadaraBackend;
+
+/// Number of notifications kept for slow subscribers. A subscriber that falls further behind will receive a
+/// [`broadcast::error::RecvError::Lagged`] error, and should fetch the blocks it missed from the db.
+pub(crate) const BLOCK_NOTIFICATIONS_CAPACITY: usize = 128;
+
+/// Range of blocks removed from the chain by [`MadaraBackend::revert_to`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevertedBlocks {
+    /// First block removed from the chain.
+    pub starting_block_number: u64,
+    pub starting_block_hash: Felt,
+    /// Last block removed from the chain, which was the latest block before the revert.
+    pub ending_block_number: u64,
+    pub ending_block_hash: Felt,
+}
+
+#[derive(Debug, Clone)]
+pub enum BlockNotification {
+    /// A new block was added to the chain.
+    Closed(Arc<MadaraBlock>),
+    /// The pending block was updated.
+    Pending(Arc<MadaraPendingBlock>),
+    /// Blocks were removed from the chain.
+    Reverted(RevertedBlocks),
+    /// The last block confirmed on L1 changed, see [`MadaraBackend::write_last_confirmed_block`].
+    L1Confirmed(u64),
+}
+
+impl MadaraBackend {
+    /// Subscribe to the updates of the chain. A notification is sent every time a block is stored or reverted, or when
+    /// the last block confirmed on L1 changes, once the update is visible in the db.
+    pub fn subscribe_block_notifications(&self) -> broadcast::Receiver<BlockNotification> {
+        self.block_notifications.subscribe()
+    }
+
+    /// Whether anyone is listening to the notifications. Used to avoid cloning blocks for nothing.
+    pub(crate) fn has_block_subscribers(&self) -> bool {
+        self.block_notifications.receiver_count() > 0
+    }
+
+    pub(crate) fn notify_block(&self, notification: BlockNotification) {
+        // An error only means that there are no subscribers left.
+        let _ = self.block_notifications.send(notification);
+    }
+}
@@ -0,0 +1,166 @@
+//! State history pruning.
+//!
+//! The history columns of the contract db ([`Column::ContractStorage`], [`Column::ContractToNonces`] and
+//! [`Column::ContractToClassHashes`]) keep one entry per (key, block_n) where the value was updated. When pruning up
+//! to block `before`, every entry of a key that is older than `before` is removed, except for the most recent of them:
+//! that one still holds the value of the key at block `before`, and at every later block until the next update.
+//!
+//! State queries on blocks older than `before` are rejected with [`MadaraStorageError::BlockPruned`].
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mp_utils::wait_or_graceful_shutdown;
+use rocksdb::{IteratorMode, ReadOptions, WriteOptions};
+
+use crate::contract_db::{
+    CONTRACT_CLASS_HASH_PREFIX_EXTRACTOR, CONTRACT_NONCES_PREFIX_EXTRACTOR, CONTRACT_STORAGE_PREFIX_EXTRACTOR,
+};
+use crate::{
+    Column, DatabaseExt, MadaraBackend, MadaraStorageError, WriteBatchWithTransaction, DB_UPDATES_BATCH_SIZE,
+    MAX_REORG_DEPTH,
+};
+
+type Result<T, E = MadaraStorageError> = std::result::Result<T, E>;
+
+/// First block for which the state history is available.
+const ROW_PRUNED_BEFORE: &[u8] = b"pruned_before";
+
+/// Only start a pruning pass once the retention window has moved by this many blocks, as every pass needs to go over
+/// the whole history columns.
+const PRUNING_STEP_BLOCKS: u64 = 1000;
+const PRUNING_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PruningMode {
+    /// Keep the whole state history.
+    #[default]
+    Archive,
+    /// Only keep the state history of the last `n` blocks.
+    Blocks(u64),
+}
+
+impl FromStr for PruningMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "archive" {
+            return Ok(Self::Archive);
+        }
+        let n: u64 = s.parse().map_err(|_| format!("Expected `archive` or a number of blocks, got `{s}`"))?;
+        if n < MAX_REORG_DEPTH {
+            return Err(format!(
+                "Cannot keep less than {MAX_REORG_DEPTH} blocks of state history, as they are needed for reorgs"
+            ));
+        }
+        Ok(Self::Blocks(n))
+    }
+}
+
+impl fmt::Display for PruningMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Archive => write!(f, "archive"),
+            Self::Blocks(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+impl MadaraBackend {
+    /// First block for which the historical state is still available. This is zero for archive nodes.
+    pub fn get_pruned_before(&self) -> u64 {
+        self.pruned_before.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn load_pruned_before(&self) -> Result<()> {
+        let col = self.db.get_column(Column::BlockStorageMeta);
+        if let Some(res) = self.db.get_cf(&col, ROW_PRUNED_BEFORE)? {
+            self.pruned_before.store(bincode::deserialize(&res)?, Ordering::Release);
+        }
+        Ok(())
+    }
+
+    /// Returns [`MadaraStorageError::BlockPruned`] if the state history of `block_n` has been pruned.
+    pub fn check_not_pruned(&self, block_n: u64) -> Result<()> {
+        let earliest = self.get_pruned_before();
+        if block_n < earliest {
+            return Err(MadaraStorageError::BlockPruned { block_n, earliest });
+        }
+        Ok(())
+    }
+
+    /// Removes the state history that is not needed to answer queries at block `before` and later blocks.
+    pub fn prune_history(&self, before: u64) -> Result<()> {
+        if before <= self.get_pruned_before() {
+            return Ok(());
+        }
+        let before_u32 = u32::try_from(before).map_err(|_| MadaraStorageError::InvalidBlockNumber)?;
+
+        // Mark the blocks as pruned first, so that no query can observe a partially pruned state.
+        let col = self.db.get_column(Column::BlockStorageMeta);
+        self.db.put_cf(&col, ROW_PRUNED_BEFORE, bincode::serialize(&before)?)?;
+        self.pruned_before.store(before, Ordering::Release);
+
+        self.prune_history_column(Column::ContractStorage, CONTRACT_STORAGE_PREFIX_EXTRACTOR, before_u32)?;
+        self.prune_history_column(Column::ContractToNonces, CONTRACT_NONCES_PREFIX_EXTRACTOR, before_u32)?;
+        self.prune_history_column(Column::ContractToClassHashes, CONTRACT_CLASS_HASH_PREFIX_EXTRACTOR, before_u32)?;
+        Ok(())
+    }
+
+    fn prune_history_column(&self, col: Column, prefix_len: usize, before: u32) -> Result<()> {
+        let handle = self.db.get_column(col);
+        let mut writeopts = WriteOptions::new();
+        writeopts.disable_wal(true);
+        let mut options = ReadOptions::default();
+        options.set_total_order_seek(true);
+
+        let mut batch = WriteBatchWithTransaction::default();
+        // Latest entry older than `before` for the key we are currently looking at.
+        let mut prev: Option<Box<[u8]>> = None;
+        for kv in self.db.iterator_cf_opt(&handle, options, IteratorMode::Start) {
+            let (key, _) = kv?;
+            let block_n = u32::from_be_bytes(
+                key[prefix_len..]
+                    .try_into()
+                    .map_err(|_| MadaraStorageError::InconsistentStorage("Malformed history key".into()))?,
+            );
+
+            if let Some(prev_key) = prev.take() {
+                if block_n < before && prev_key[..prefix_len] == key[..prefix_len] {
+                    // Superseded by a more recent entry which is still older than `before`.
+                    batch.delete_cf(&handle, prev_key);
+                    if batch.len() >= DB_UPDATES_BATCH_SIZE {
+                        self.db.write_opt(std::mem::take(&mut batch), &writeopts)?;
+                    }
+                }
+            }
+            if block_n < before {
+                prev = Some(key);
+            }
+        }
+        self.db.write_opt(batch, &writeopts)?;
+        Ok(())
+    }
+}
+
+/// Background task that periodically prunes the state history to keep only the last `keep_blocks` blocks.
+pub(crate) async fn pruning_task(backend: Arc<MadaraBackend>, keep_blocks: u64) -> anyhow::Result<()> {
+    let mut interval = tokio::time::interval(PRUNING_POLL_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    while wait_or_graceful_shutdown(interval.tick()).await.is_some() {
+        let Some(latest) = backend.get_latest_block_n()? else { continue };
+        let before = (latest + 1).saturating_sub(keep_blocks);
+        if before < backend.get_pruned_before() + PRUNING_STEP_BLOCKS {
+            continue;
+        }
+
+        log::info!("✂️  Pruning state history before block #{before}");
+        let backend_ = Arc::clone(&backend);
+        tokio::task::spawn_blocking(move || backend_.prune_history(before)).await??;
+        log::debug!("State history pruning done");
+    }
+    Ok(())
+}
@@ -1,12 +1,27 @@
-use crate::MadaraBackend;
-use crate::MadaraStorageError;
+use crate::db_block_id::DbBlockId;
+use crate::notifications::{BlockNotification, RevertedBlocks};
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError, TrieType, WriteBatchWithTransaction};
 use mp_block::{MadaraBlock, MadaraMaybePendingBlock, MadaraMaybePendingBlockInfo, MadaraPendingBlock};
 use mp_class::ConvertedClass;
 use mp_state_update::{
     ContractStorageDiffItem, DeployedContractItem, NonceUpdate, ReplacedClassItem, StateDiff, StorageEntry,
 };
+use rocksdb::WriteOptions;
 use starknet_types_core::felt::Felt;
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+const ROW_REVERT_PROGRESS: &[u8] = b"revert_progress";
+
+/// A revert which may not be finished.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RevertProgress {
+    target: u64,
+    latest: u64,
+    /// Number of global tries already reverted, in the order of [`TrieType::ALL`].
+    tries_reverted: usize,
+}
 
 impl MadaraBackend {
     /// NB: This functions needs to run on the rayon thread pool
@@ -18,6 +33,7 @@ impl MadaraBackend {
     ) -> Result<(), MadaraStorageError> {
         let block_n = block.info.block_n();
         let state_diff_cpy = state_diff.clone();
+        let notification_block = self.has_block_subscribers().then(|| block.clone());
 
         let task_block_db = || match block.info {
             MadaraMaybePendingBlockInfo::Pending(info) => {
@@ -75,7 +91,134 @@ impl MadaraBackend {
 
         let ((r1, r2), r3) = rayon::join(|| rayon::join(task_block_db, task_contract_db), task_class_db);
 
-        r1.and(r2).and(r3)
+        r1.and(r2).and(r3)?;
+
+        if let Some(block_n) = block_n {
+            self.tries_prune_logs(block_n)?;
+            self.tries_take_snapshot(block_n);
+        }
+
+        if let Some(block) = notification_block {
+            self.notify_block(match block.info {
+                MadaraMaybePendingBlockInfo::Pending(info) => {
+                    BlockNotification::Pending(Arc::new(MadaraPendingBlock { info, inner: block.inner }))
+                }
+                MadaraMaybePendingBlockInfo::NotPending(info) => {
+                    BlockNotification::Closed(Arc::new(MadaraBlock { info, inner: block.inner }))
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Reverts the chain to `block_n`, which becomes the new latest block. Every block after it is removed from the
+    /// db along with its state, and the global tries are rolled back using the trie logs. The pending block is
+    /// cleared.
+    ///
+    /// Blocks can only be reverted as far back as the trie logs go, see [`crate::TrieLogConfig::max_saved_trie_logs`],
+    /// and not before the state history was pruned.
+    pub fn revert_to(&self, block_n: u64) -> Result<(), MadaraStorageError> {
+        let latest = self.get_latest_block_n()?.ok_or(MadaraStorageError::InvalidBlockNumber)?;
+        if block_n > latest {
+            return Err(MadaraStorageError::InvalidBlockNumber);
+        }
+        let max_depth = self.trie_log_config().max_saved_trie_logs;
+        if latest - block_n > max_depth {
+            return Err(MadaraStorageError::RevertTooDeep { target: block_n, latest, max_depth });
+        }
+        // The history entries needed to restore the state of older blocks are gone.
+        let pruned_before = self.get_pruned_before();
+        if block_n < pruned_before {
+            return Err(MadaraStorageError::RevertBelowPruned { target: block_n, pruned_before });
+        }
+
+        self.clear_pending_block()?;
+        if block_n == latest {
+            return Ok(());
+        }
+
+        log::info!("⏪ Reverting chain from block #{latest} to block #{block_n}");
+
+        let block_hash = |b: u64| {
+            self.get_block_hash(&DbBlockId::BlockN(b))?
+                .ok_or(MadaraStorageError::InconsistentStorage("Missing block hash for a block to revert".into()))
+        };
+        let reverted = RevertedBlocks {
+            starting_block_number: block_n + 1,
+            starting_block_hash: block_hash(block_n + 1)?,
+            ending_block_number: latest,
+            ending_block_hash: block_hash(latest)?,
+        };
+
+        self.revert_blocks(RevertProgress { target: block_n, latest, tries_reverted: 0 })?;
+
+        self.revert_count.fetch_add(1, Ordering::Release);
+        self.notify_block(BlockNotification::Reverted(reverted));
+        Ok(())
+    }
+
+    /// Reverts the global tries one after the other, saving the progress in the db, then removes the blocks from
+    /// every column in a single write batch which also clears the progress. This way, a revert interrupted by a crash
+    /// is finished by [`Self::resume_revert`] when the db is opened again.
+    ///
+    /// Writes skip the WAL, so every column is flushed after each step: the progress row on disk always matches the
+    /// state of the tries.
+    fn revert_blocks(&self, mut progress: RevertProgress) -> Result<(), MadaraStorageError> {
+        let meta = self.db.get_column(Column::BlockStorageMeta);
+        let mut writeopts = WriteOptions::new();
+        writeopts.disable_wal(true);
+
+        self.db.put_cf_opt(&meta, ROW_REVERT_PROGRESS, bincode::serialize(&progress)?, &writeopts)?;
+        self.flush_all_columns()?;
+        self.trie_snapshots.remove_after(progress.target);
+        for trie in TrieType::ALL.into_iter().skip(progress.tries_reverted) {
+            self.trie_revert_to(trie, progress.target, progress.latest)?;
+            progress.tries_reverted += 1;
+            self.db.put_cf_opt(&meta, ROW_REVERT_PROGRESS, bincode::serialize(&progress)?, &writeopts)?;
+            self.flush_all_columns()?;
+        }
+
+        let mut batch = WriteBatchWithTransaction::default();
+        for b in (progress.target + 1..=progress.latest).rev() {
+            let state_diff = self
+                .get_block_state_diff(&DbBlockId::BlockN(b))?
+                .ok_or(MadaraStorageError::InconsistentStorage("Missing state diff for a block to revert".into()))?;
+            let inner = self
+                .get_block_inner(&DbBlockId::BlockN(b))?
+                .ok_or(MadaraStorageError::InconsistentStorage("Missing block inner for a block to revert".into()))?;
+
+            self.contract_db_revert(&mut batch, b, &state_diff)?;
+            self.class_db_revert(&mut batch, &state_diff)?;
+            self.events_db_revert(&mut batch, b, &inner.receipts)?;
+            self.l1_db_revert(&mut batch, &inner)?;
+            self.block_db_revert(&mut batch, b)?;
+        }
+        self.block_db_revert_meta(&mut batch, progress.target)?;
+        batch.delete_cf(&meta, ROW_REVERT_PROGRESS);
+        self.db.write_opt(batch, &writeopts)?;
+        self.flush_all_columns()?;
+        Ok(())
+    }
+
+    /// Finishes a revert which was interrupted, see [`Self::revert_blocks`].
+    pub(crate) fn resume_revert(&self) -> Result<(), MadaraStorageError> {
+        let meta = self.db.get_column(Column::BlockStorageMeta);
+        let Some(res) = self.db.get_cf(&meta, ROW_REVERT_PROGRESS)? else { return Ok(()) };
+        let progress: RevertProgress = bincode::deserialize(&res)?;
+        log::warn!(
+            "⏪ Resuming the interrupted revert of the chain from block #{} to block #{}",
+            progress.latest,
+            progress.target
+        );
+        self.revert_blocks(progress)?;
+        self.revert_count.fetch_add(1, Ordering::Release);
+        Ok(())
+    }
+
+    /// Number of times blocks were reverted since the db was opened. In-memory caches of chain data can compare it
+    /// with the value they were filled at to know when they may be stale.
+    pub fn get_revert_count(&self) -> u64 {
+        self.revert_count.load(Ordering::Acquire)
     }
 
     pub fn clear_pending_block(&self) -> Result<(), MadaraStorageError> {
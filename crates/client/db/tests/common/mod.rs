@@ -5,5 +5,7 @@ use tempfile::TempDir;
 pub async fn temp_db() -> DatabaseService {
     let temp_dir = TempDir::new().unwrap();
     let chain_config = std::sync::Arc::new(ChainConfig::test_config().expect("failed to retrieve test chain config"));
-    DatabaseService::new(temp_dir.path(), None, false, chain_config).await.unwrap()
+    DatabaseService::new(temp_dir.path(), None, false, chain_config, Default::default(), Default::default())
+        .await
+        .unwrap()
 }
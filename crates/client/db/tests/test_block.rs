@@ -2,6 +2,7 @@ mod common;
 
 use common::*;
 use mc_db::db_block_id::DbBlockIdResolvable;
+use mc_db::notifications::{BlockNotification, RevertedBlocks};
 use mc_db::{block_db::TxIndex, db_block_id::DbBlockId};
 use mp_block::BlockId;
 use mp_block::{
@@ -149,6 +150,68 @@ async fn test_store_latest_block(_set_workdir: ()) {
     assert_eq!(backend.get_latest_block_n().unwrap().unwrap(), 1);
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_revert_to(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    backend.store_block(finalized_block_zero(), finalized_state_diff_zero(), vec![]).unwrap();
+    let block_one = finalized_block_one();
+    backend.store_block(block_one.clone(), finalized_state_diff_one(), vec![]).unwrap();
+    backend.store_block(pending_block_two(), pending_state_diff_two(), vec![]).unwrap();
+    assert_eq!(backend.get_revert_count(), 0);
+
+    backend.revert_to(0).unwrap();
+
+    assert_eq!(backend.get_revert_count(), 1);
+    assert_eq!(backend.get_latest_block_n().unwrap().unwrap(), 0);
+    assert!(backend.get_block(&DbBlockId::BlockN(1)).unwrap().is_none());
+    assert!(backend.resolve_block_id(&BlockId::Hash(block_one.info.block_hash().unwrap())).unwrap().is_none());
+    assert!(backend.find_tx_hash_block_info(&block_one.info.tx_hashes()[1]).unwrap().is_none());
+    assert!(backend.get_block(&DbBlockId::Pending).unwrap().unwrap().inner.transactions.is_empty());
+
+    // Block 0 is left untouched
+    assert_eq!(backend.get_block(&DbBlockId::BlockN(0)).unwrap().unwrap(), finalized_block_zero());
+    // Cannot revert to a block in the future
+    assert!(backend.revert_to(1).is_err());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_block_notifications(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+    let mut notifications = backend.subscribe_block_notifications();
+
+    let block_zero = finalized_block_zero();
+    backend.store_block(block_zero.clone(), finalized_state_diff_zero(), vec![]).unwrap();
+    let block_one = finalized_block_one();
+    backend.store_block(block_one.clone(), finalized_state_diff_one(), vec![]).unwrap();
+    backend.store_block(pending_block_two(), pending_state_diff_two(), vec![]).unwrap();
+    backend.revert_to(0).unwrap();
+
+    let BlockNotification::Closed(block) = notifications.try_recv().unwrap() else { panic!("Expected a closed block") };
+    assert_eq!(MadaraMaybePendingBlock::from((*block).clone()), block_zero);
+    let BlockNotification::Closed(block) = notifications.try_recv().unwrap() else { panic!("Expected a closed block") };
+    assert_eq!(block.info.header.block_number, 1);
+    let BlockNotification::Pending(block) = notifications.try_recv().unwrap() else {
+        panic!("Expected a pending block")
+    };
+    assert_eq!(block.info.tx_hashes, pending_block_two().info.tx_hashes());
+    let BlockNotification::Reverted(reverted) = notifications.try_recv().unwrap() else { panic!("Expected a revert") };
+    assert_eq!(
+        reverted,
+        RevertedBlocks {
+            starting_block_number: 1,
+            starting_block_hash: block_one.info.block_hash().unwrap(),
+            ending_block_number: 1,
+            ending_block_hash: block_one.info.block_hash().unwrap(),
+        }
+    );
+    assert!(notifications.try_recv().is_err());
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_latest_confirmed_block(_set_workdir: ()) {
@@ -0,0 +1,92 @@
+mod common;
+
+use common::*;
+use mc_db::events_db::EventBloom;
+use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+use mp_receipt::{Event, InvokeTransactionReceipt, TransactionReceipt};
+use mp_state_update::StateDiff;
+use mp_transactions::InvokeTransactionV0;
+use mp_utils::tests_common::*;
+use rstest::*;
+use starknet_types_core::felt::Felt;
+
+fn block_with_events(block_number: u64, events: Vec<Event>) -> MadaraMaybePendingBlock {
+    let receipt = InvokeTransactionReceipt { events, ..Default::default() };
+    let block_inner = MadaraBlockInner::new(vec![InvokeTransactionV0::default().into()], vec![receipt.into()]);
+    let header = Header { block_number, ..Default::default() };
+    let block_info = MadaraBlockInfo::new(header, vec![Felt::from(block_number)], Felt::from(block_number));
+
+    MadaraMaybePendingBlock { info: block_info.into(), inner: block_inner }
+}
+
+fn event(from_address: u64, keys: &[u64]) -> Event {
+    Event { from_address: Felt::from(from_address), keys: keys.iter().map(|k| Felt::from(*k)).collect(), data: vec![] }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_events_candidate_blocks(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    backend.store_block(block_with_events(0, vec![event(1, &[10, 11])]), StateDiff::default(), vec![]).unwrap();
+    backend.store_block(block_with_events(1, vec![event(2, &[20])]), StateDiff::default(), vec![]).unwrap();
+    backend.store_block(block_with_events(2, vec![]), StateDiff::default(), vec![]).unwrap();
+    backend.store_block(block_with_events(3, vec![event(1, &[20, 12])]), StateDiff::default(), vec![]).unwrap();
+
+    let candidates = |address: Option<u64>, keys: &[&[u64]], from: u64, to: u64| {
+        let keys: Vec<Vec<Felt>> = keys.iter().map(|keys| keys.iter().map(|k| Felt::from(*k)).collect()).collect();
+        backend.get_events_candidate_blocks(address.map(Felt::from), &keys, from, to).unwrap()
+    };
+
+    assert_eq!(candidates(None, &[], 0, 3), vec![0, 1, 2, 3]);
+    assert_eq!(candidates(Some(1), &[], 0, 3), vec![0, 3]);
+    assert_eq!(candidates(Some(1), &[], 1, 2), Vec::<u64>::new());
+    assert_eq!(candidates(None, &[&[20]], 0, 3), vec![1, 3]);
+    assert_eq!(candidates(None, &[&[10, 20]], 0, 3), vec![0, 1, 3]);
+    assert_eq!(candidates(Some(1), &[&[20]], 0, 3), vec![3]);
+    assert_eq!(candidates(Some(2), &[&[10]], 0, 3), Vec::<u64>::new());
+    // Second key position is only filtered by the bloom filter
+    assert_eq!(candidates(None, &[&[], &[12]], 0, 3), vec![3]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_events_revert(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    backend.store_block(block_with_events(0, vec![event(1, &[10])]), StateDiff::default(), vec![]).unwrap();
+    backend.store_block(block_with_events(1, vec![event(1, &[10])]), StateDiff::default(), vec![]).unwrap();
+
+    backend.revert_to(0).unwrap();
+
+    assert!(backend.get_event_bloom(1).unwrap().is_none());
+    assert_eq!(backend.get_events_candidate_blocks(Some(Felt::from(1)), &[], 0, 1).unwrap(), vec![0]);
+}
+
+#[test]
+fn test_event_bloom() {
+    let receipts: Vec<TransactionReceipt> =
+        vec![InvokeTransactionReceipt { events: vec![event(1, &[10, 11])], ..Default::default() }.into()];
+    let bloom = EventBloom::from_receipts(&receipts);
+
+    assert!(bloom.may_match(None, &[]));
+    assert!(bloom.may_match(Some(&Felt::from(1)), &[vec![Felt::from(10)]]));
+    assert!(bloom.may_match(None, &[vec![], vec![Felt::from(11)]]));
+    // Items are tagged with their position
+    assert!(!bloom.may_match(Some(&Felt::from(10)), &[]));
+    assert!(!bloom.may_match(None, &[vec![Felt::from(11)]]));
+}
+
+#[test]
+fn test_event_bloom_busy_block() {
+    let events = (0..5000).map(|i| event(i, &[i + 100_000])).collect();
+    let receipts: Vec<TransactionReceipt> = vec![InvokeTransactionReceipt { events, ..Default::default() }.into()];
+    let bloom = EventBloom::from_receipts(&receipts);
+
+    assert!((0..5000).all(|i| bloom.may_match(Some(&Felt::from(i)), &[])));
+    // The bloom filter grows with the block, so it keeps discarding most of the blocks that do not match
+    let false_positives = (10_000..11_000).filter(|i| bloom.may_match(Some(&Felt::from(*i)), &[])).count();
+    assert!(false_positives < 50, "{false_positives} false positives");
+}
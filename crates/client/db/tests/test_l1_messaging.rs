@@ -0,0 +1,139 @@
+mod common;
+
+use common::*;
+use mc_db::l1_db::{L1MessageRecord, LastSyncedEventBlock};
+use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+use mp_receipt::L1HandlerTransactionReceipt;
+use mp_state_update::StateDiff;
+use mp_transactions::L1HandlerTransaction;
+use mp_utils::tests_common::*;
+use rstest::*;
+use starknet_api::core::Nonce;
+use starknet_types_core::felt::Felt;
+
+fn block_with_l1_handlers(block_number: u64, nonces: &[u64]) -> MadaraMaybePendingBlock {
+    let transactions = nonces.iter().map(|nonce| L1HandlerTransaction { nonce: *nonce, ..Default::default() }.into());
+    let receipts = nonces.iter().map(|_| L1HandlerTransactionReceipt::default().into());
+    let block_inner = MadaraBlockInner::new(transactions.collect(), receipts.collect());
+    let tx_hashes = nonces.iter().map(|nonce| Felt::from(0x100 + nonce)).collect();
+    let header = Header { block_number, ..Default::default() };
+    let block_info = MadaraBlockInfo::new(header, tx_hashes, Felt::from(block_number));
+
+    MadaraMaybePendingBlock { info: block_info.into(), inner: block_inner }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_l1_messaging_nonce_cancelled(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let (cancelled, unknown) = (Nonce(Felt::TWO), Nonce(Felt::THREE));
+    backend.set_l1_messaging_nonce(cancelled).unwrap();
+
+    assert!(backend.has_l1_messaging_nonce(cancelled).unwrap());
+    assert_eq!(backend.get_l1_messaging_nonce_tx_hash(cancelled).unwrap(), None);
+    assert!(!backend.has_l1_messaging_nonce(unknown).unwrap());
+    assert_eq!(backend.get_l1_messaging_nonce_tx_hash(unknown).unwrap(), None);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_l1_messages_consumed_by_closed_blocks(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    backend.store_l1_message(&message(1, 10, 2)).unwrap();
+    backend.store_l1_message(&message(2, 11, 0)).unwrap();
+    // Submitted to the mempool, but not in a closed block yet.
+    backend.store_l1_message(&message(3, 11, 4)).unwrap();
+    let (nonce_1, nonce_2, nonce_3) = (Nonce(Felt::ONE), Nonce(Felt::TWO), Nonce(Felt::THREE));
+
+    backend.store_block(block_with_l1_handlers(0, &[]), StateDiff::default(), vec![]).unwrap();
+    backend.store_block(block_with_l1_handlers(1, &[1]), StateDiff::default(), vec![]).unwrap();
+    backend.store_block(block_with_l1_handlers(2, &[2]), StateDiff::default(), vec![]).unwrap();
+
+    assert_eq!(backend.get_l1_messaging_nonce_tx_hash(nonce_1).unwrap(), Some(Felt::from(0x101)));
+    assert_eq!(backend.get_l1_messaging_nonce_tx_hash(nonce_2).unwrap(), Some(Felt::from(0x102)));
+    assert!(!backend.has_l1_messaging_nonce(nonce_3).unwrap());
+    assert_eq!(
+        backend.messaging_last_synced_l1_block_with_event().unwrap(),
+        Some(LastSyncedEventBlock::new(11, 0, [11; 32]))
+    );
+
+    // The messages of the reverted blocks are unmarked, and scanned again from the first of them.
+    backend.revert_to(0).unwrap();
+
+    assert!(!backend.has_l1_messaging_nonce(nonce_1).unwrap());
+    assert!(!backend.has_l1_messaging_nonce(nonce_2).unwrap());
+    assert_eq!(
+        backend.messaging_last_synced_l1_block_with_event().unwrap(),
+        Some(LastSyncedEventBlock::new(10, 2, [10; 32]))
+    );
+    assert_eq!(backend.get_l1_message(nonce_3).unwrap(), Some(message(3, 11, 4)));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_l1_tx_messaging_nonces(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let l1_tx_hash = [1u8; 32];
+    assert_eq!(backend.get_l1_tx_messaging_nonces(&l1_tx_hash).unwrap(), vec![]);
+
+    backend.add_l1_tx_messaging_nonce(&l1_tx_hash, Nonce(Felt::TWO)).unwrap();
+    backend.add_l1_tx_messaging_nonce(&l1_tx_hash, Nonce(Felt::ONE)).unwrap();
+    // Adding a nonce twice is a no-op.
+    backend.add_l1_tx_messaging_nonce(&l1_tx_hash, Nonce(Felt::TWO)).unwrap();
+
+    assert_eq!(backend.get_l1_tx_messaging_nonces(&l1_tx_hash).unwrap(), vec![Nonce(Felt::TWO), Nonce(Felt::ONE)]);
+    assert_eq!(backend.get_l1_tx_messaging_nonces(&[2u8; 32]).unwrap(), vec![]);
+
+    backend.remove_l1_tx_messaging_nonce(&l1_tx_hash, Nonce(Felt::TWO)).unwrap();
+    assert_eq!(backend.get_l1_tx_messaging_nonces(&l1_tx_hash).unwrap(), vec![Nonce(Felt::ONE)]);
+    backend.remove_l1_tx_messaging_nonce(&l1_tx_hash, Nonce(Felt::ONE)).unwrap();
+    assert_eq!(backend.get_l1_tx_messaging_nonces(&l1_tx_hash).unwrap(), vec![]);
+}
+
+fn message(nonce: u64, l1_block_number: u64, log_index: u64) -> L1MessageRecord {
+    L1MessageRecord {
+        nonce: Nonce(Felt::from(nonce)),
+        l1_block_number,
+        l1_block_hash: [l1_block_number as u8; 32],
+        log_index,
+        l1_transaction_hash: [nonce as u8; 32],
+        tx_hash: Felt::from(0x100 + nonce),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_l1_messages(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    assert_eq!(backend.get_latest_l1_message().unwrap(), None);
+    backend.store_l1_message(&message(2, 10, 3)).unwrap();
+    backend.store_l1_message(&message(1, 10, 0)).unwrap();
+    backend.store_l1_message(&message(0, 9, 7)).unwrap();
+
+    // Ordered by nonce, which is also the order in which the messages were sent on L1.
+    assert_eq!(backend.get_latest_l1_message().unwrap(), Some(message(2, 10, 3)));
+    backend.remove_l1_message(&message(2, 10, 3)).unwrap();
+    assert_eq!(backend.get_latest_l1_message().unwrap(), Some(message(1, 10, 0)));
+    backend.remove_l1_message(&message(1, 10, 0)).unwrap();
+    assert_eq!(backend.get_latest_l1_message().unwrap(), Some(message(0, 9, 7)));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_last_synced_event_block(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    assert_eq!(backend.messaging_last_synced_l1_block_with_event().unwrap(), Some(LastSyncedEventBlock::default()));
+    let block = LastSyncedEventBlock::new(12, 3, [4; 32]);
+    backend.messaging_update_last_synced_l1_block_with_event(block.clone()).unwrap();
+    assert_eq!(backend.messaging_last_synced_l1_block_with_event().unwrap(), Some(block));
+}
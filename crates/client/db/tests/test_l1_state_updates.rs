@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+use mc_db::l1_db::{L1BlockRef, L1StateUpdateRecord};
+use mp_utils::tests_common::*;
+use rstest::*;
+use starknet_types_core::felt::Felt;
+
+fn state_update(block_number: u64, l1_block_number: u64) -> L1StateUpdateRecord {
+    L1StateUpdateRecord {
+        block_number,
+        block_hash: Felt::from(block_number),
+        global_root: Felt::from(block_number + 0x100),
+        l1_block_number,
+        l1_block_hash: [l1_block_number as u8 + 1; 32],
+        l1_transaction_hash: [l1_block_number as u8; 32],
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_l1_state_updates(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    backend.store_l1_state_update(&state_update(3, 10)).unwrap();
+    backend.store_l1_state_update(&state_update(7, 12)).unwrap();
+    // Storing the same state update again is a no-op.
+    backend.store_l1_state_update(&state_update(7, 12)).unwrap();
+
+    // A block is settled by the first state update which includes it.
+    assert_eq!(backend.get_l1_state_update(0).unwrap(), Some(state_update(3, 10)));
+    assert_eq!(backend.get_l1_state_update(3).unwrap(), Some(state_update(3, 10)));
+    assert_eq!(backend.get_l1_state_update(4).unwrap(), Some(state_update(7, 12)));
+    assert_eq!(backend.get_l1_state_update(7).unwrap(), Some(state_update(7, 12)));
+    assert_eq!(backend.get_l1_state_update(8).unwrap(), None);
+    assert_eq!(backend.get_latest_l1_state_update().unwrap(), Some(state_update(7, 12)));
+
+    // Rolled back when its L1 block is reorged out.
+    backend.remove_l1_state_update(7).unwrap();
+    assert_eq!(backend.get_l1_state_update(4).unwrap(), None);
+    assert_eq!(backend.get_latest_l1_state_update().unwrap(), Some(state_update(3, 10)));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_l1_state_updates_checkpoint(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    assert_eq!(backend.get_l1_state_updates_checkpoint().unwrap(), None);
+    let checkpoint = L1BlockRef { block_number: 1234, block_hash: [1; 32] };
+    backend.write_l1_state_updates_checkpoint(&checkpoint).unwrap();
+    assert_eq!(backend.get_l1_state_updates_checkpoint().unwrap(), Some(checkpoint));
+    backend.clear_l1_state_updates_checkpoint().unwrap();
+    assert_eq!(backend.get_l1_state_updates_checkpoint().unwrap(), None);
+}
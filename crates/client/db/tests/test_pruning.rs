@@ -0,0 +1,93 @@
+mod common;
+
+use common::*;
+use mc_db::pruning::PruningMode;
+use mc_db::MadaraStorageError;
+use mp_block::{BlockId, Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+use mp_state_update::{ContractStorageDiffItem, NonceUpdate, StateDiff, StorageEntry};
+use mp_utils::tests_common::*;
+use rstest::*;
+use starknet_types_core::felt::Felt;
+
+const CONTRACT: Felt = Felt::from_hex_unchecked("0x1");
+const KEY: Felt = Felt::from_hex_unchecked("0x10");
+
+fn empty_block(block_number: u64) -> MadaraMaybePendingBlock {
+    let header = Header { block_number, ..Default::default() };
+    let block_info = MadaraBlockInfo::new(header, vec![], Felt::from(block_number));
+    MadaraMaybePendingBlock { info: block_info.into(), inner: MadaraBlockInner::new(vec![], vec![]) }
+}
+
+fn state_diff(value: u64) -> StateDiff {
+    StateDiff {
+        storage_diffs: vec![ContractStorageDiffItem {
+            address: CONTRACT,
+            storage_entries: vec![StorageEntry { key: KEY, value: Felt::from(value) }],
+        }],
+        nonces: vec![NonceUpdate { contract_address: CONTRACT, nonce: Felt::from(value) }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_pruning_mode_from_str() {
+    assert_eq!("archive".parse::<PruningMode>().unwrap(), PruningMode::Archive);
+    assert_eq!("1000".parse::<PruningMode>().unwrap(), PruningMode::Blocks(1000));
+    assert!("1".parse::<PruningMode>().is_err());
+    assert!("full".parse::<PruningMode>().is_err());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_prune_history(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    // Value is updated at blocks 0, 1 and 3, and left untouched at blocks 2 and 4.
+    for (block_n, value) in [(0, Some(10)), (1, Some(11)), (2, None), (3, Some(13)), (4, None)] {
+        let diff = value.map(state_diff).unwrap_or_default();
+        backend.store_block(empty_block(block_n), diff, vec![]).unwrap();
+    }
+
+    backend.prune_history(3).unwrap();
+    assert_eq!(backend.get_pruned_before(), 3);
+
+    assert!(matches!(
+        backend.get_contract_storage_at(&BlockId::Number(2), &CONTRACT, &KEY),
+        Err(MadaraStorageError::BlockPruned { block_n: 2, earliest: 3 })
+    ));
+    assert!(matches!(
+        backend.get_contract_nonce_at(&BlockId::Number(0), &CONTRACT),
+        Err(MadaraStorageError::BlockPruned { block_n: 0, earliest: 3 })
+    ));
+
+    // Values are still there for the blocks that are kept.
+    assert_eq!(backend.get_contract_storage_at(&BlockId::Number(3), &CONTRACT, &KEY).unwrap(), Some(Felt::from(13)));
+    assert_eq!(backend.get_contract_storage_at(&BlockId::Number(4), &CONTRACT, &KEY).unwrap(), Some(Felt::from(13)));
+    assert_eq!(backend.get_contract_nonce_at(&BlockId::Number(4), &CONTRACT).unwrap(), Some(Felt::from(13)));
+
+    // Latest value older than the pruning point is kept.
+    backend.prune_history(4).unwrap();
+    assert_eq!(backend.get_contract_storage_at(&BlockId::Number(4), &CONTRACT, &KEY).unwrap(), Some(Felt::from(13)));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_revert_below_pruned(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    for block_n in 0..5 {
+        backend.store_block(empty_block(block_n), state_diff(10 + block_n), vec![]).unwrap();
+    }
+    backend.prune_history(3).unwrap();
+
+    assert!(matches!(backend.revert_to(2), Err(MadaraStorageError::RevertBelowPruned { target: 2, pruned_before: 3 })));
+    // Nothing was reverted
+    assert_eq!(backend.get_latest_block_n().unwrap(), Some(4));
+    assert_eq!(backend.get_contract_storage_at(&BlockId::Number(4), &CONTRACT, &KEY).unwrap(), Some(Felt::from(14)));
+
+    backend.revert_to(3).unwrap();
+    assert_eq!(backend.get_latest_block_n().unwrap(), Some(3));
+    assert_eq!(backend.get_contract_storage_at(&BlockId::Number(3), &CONTRACT, &KEY).unwrap(), Some(Felt::from(13)));
+}
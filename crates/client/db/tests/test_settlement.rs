@@ -0,0 +1,39 @@
+mod common;
+
+use common::*;
+use mc_db::l1_db::{SettlementStatus, SettlementTransaction};
+use mp_utils::tests_common::*;
+use rstest::*;
+
+#[rstest]
+#[tokio::test]
+async fn test_settlement_status(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    assert_eq!(backend.get_latest_settlement_status().unwrap(), None);
+    assert_eq!(backend.get_settlement_status(0).unwrap(), None);
+
+    let confirmed = SettlementStatus::Confirmed { l1_transaction_hash: [1; 32], l1_block_number: 10 };
+    let submitted = SettlementStatus::Submitted {
+        transaction: SettlementTransaction {
+            l1_transaction_hash: [2; 32],
+            nonce: 3,
+            max_fee_per_gas: 100,
+            max_priority_fee_per_gas: 10,
+            sent_at: 1_700_000_000,
+            previous_failures: 1,
+        },
+        replaced: vec![[1; 32]],
+    };
+    backend.set_settlement_status(0, &confirmed).unwrap();
+    backend.set_settlement_status(1, &submitted).unwrap();
+
+    assert_eq!(backend.get_settlement_status(0).unwrap(), Some(confirmed));
+    assert_eq!(backend.get_settlement_status(1).unwrap(), Some(submitted.clone()));
+    assert_eq!(backend.get_latest_settlement_status().unwrap(), Some((1, submitted)));
+
+    let failed = SettlementStatus::Failed { reason: "reverted".into(), attempts: 2, failed_at: 1_700_000_100 };
+    backend.set_settlement_status(1, &failed).unwrap();
+    assert_eq!(backend.get_latest_settlement_status().unwrap(), Some((1, failed)));
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use bitvec::order::Msb0;
+use bitvec::vec::BitVec;
+use bitvec::view::AsBits;
+use bonsai_trie::id::BasicId;
+use common::*;
+use mc_db::{bonsai_identifier, DatabaseService, MadaraStorageError, TrieLogConfig};
+use mp_block::{BlockId, BlockTag, Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+use mp_chain_config::ChainConfig;
+use mp_state_update::{ContractStorageDiffItem, NonceUpdate, StateDiff, StorageEntry};
+use mp_utils::tests_common::*;
+use rstest::*;
+use starknet_types_core::felt::Felt;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn empty_block(block_number: u64) -> MadaraMaybePendingBlock {
+    let header = Header { block_number, ..Default::default() };
+    let block_info = MadaraBlockInfo::new(header, vec![], Felt::from(block_number));
+    MadaraMaybePendingBlock { info: block_info.into(), inner: MadaraBlockInner::new(vec![], vec![]) }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_trie_view_at_past_block(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let key: BitVec<u8, Msb0> = Felt::ONE.to_bytes_be().as_bits()[5..].to_owned();
+    let mut roots = vec![];
+    for block_n in 0..3 {
+        let mut trie = backend.contract_trie();
+        trie.insert(bonsai_identifier::CONTRACT, &key, &Felt::from(block_n + 1)).unwrap();
+        trie.commit(BasicId::new(block_n)).unwrap();
+        roots.push(trie.root_hash(bonsai_identifier::CONTRACT).unwrap());
+        backend.store_block(empty_block(block_n), StateDiff::default(), vec![]).unwrap();
+    }
+
+    for (block_n, root) in roots.iter().enumerate() {
+        let view = backend.contract_trie_at(block_n as u64).unwrap();
+        assert_eq!(view.root_hash(bonsai_identifier::CONTRACT).unwrap(), *root);
+    }
+
+    // Opening a view does not modify the tries.
+    assert_eq!(backend.contract_trie().root_hash(bonsai_identifier::CONTRACT).unwrap(), roots[2]);
+    assert!(matches!(backend.contract_trie_at(3), Err(MadaraStorageError::InvalidBlockNumber)));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_trie_view_from_snapshots(_set_workdir: ()) {
+    let temp_dir = TempDir::new().unwrap();
+    let chain_config = Arc::new(ChainConfig::test_config().unwrap());
+    let trie_log_config = TrieLogConfig { max_saved_snapshots: 2, snapshot_interval: 2, ..Default::default() };
+    let db = DatabaseService::new(temp_dir.path(), None, false, chain_config, Default::default(), trie_log_config)
+        .await
+        .unwrap();
+    let backend = db.backend();
+
+    let key: BitVec<u8, Msb0> = Felt::ONE.to_bytes_be().as_bits()[5..].to_owned();
+    let other_key: BitVec<u8, Msb0> = Felt::TWO.to_bytes_be().as_bits()[5..].to_owned();
+    let mut roots = vec![];
+    for block_n in 0..7 {
+        let mut trie = backend.contract_trie();
+        trie.insert(bonsai_identifier::CONTRACT, &key, &Felt::from(block_n + 1)).unwrap();
+        if block_n % 3 == 0 {
+            trie.insert(bonsai_identifier::CONTRACT, &other_key, &Felt::from(block_n + 1)).unwrap();
+        }
+        trie.commit(BasicId::new(block_n)).unwrap();
+        roots.push(trie.root_hash(bonsai_identifier::CONTRACT).unwrap());
+        backend.store_block(empty_block(block_n), StateDiff::default(), vec![]).unwrap();
+    }
+
+    // Snapshots are kept at blocks 4 and 6: views up to block 4 are opened from the snapshot of block 4.
+    for (block_n, root) in roots.iter().enumerate() {
+        let view = backend.contract_trie_at(block_n as u64).unwrap();
+        assert_eq!(view.root_hash(bonsai_identifier::CONTRACT).unwrap(), *root, "Root of block #{block_n}");
+    }
+
+    // Snapshots after the new latest block are dropped on reverts, and the others are kept up to date.
+    backend.revert_to(5).unwrap();
+    for (block_n, root) in roots.iter().enumerate().take(6) {
+        let view = backend.contract_trie_at(block_n as u64).unwrap();
+        assert_eq!(view.root_hash(bonsai_identifier::CONTRACT).unwrap(), *root, "Root of block #{block_n}");
+    }
+    assert_eq!(backend.contract_trie().root_hash(bonsai_identifier::CONTRACT).unwrap(), roots[5]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_revert_to_restores_state(_set_workdir: ()) {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let (contract, storage_key) = (Felt::ONE, Felt::TWO);
+    let key: BitVec<u8, Msb0> = contract.to_bytes_be().as_bits()[5..].to_owned();
+    let mut roots = vec![];
+    for block_n in 0..3 {
+        let mut trie = backend.contract_trie();
+        trie.insert(bonsai_identifier::CONTRACT, &key, &Felt::from(block_n + 1)).unwrap();
+        trie.commit(BasicId::new(block_n)).unwrap();
+        roots.push(trie.root_hash(bonsai_identifier::CONTRACT).unwrap());
+
+        let state_diff = StateDiff {
+            storage_diffs: vec![ContractStorageDiffItem {
+                address: contract,
+                storage_entries: vec![StorageEntry { key: storage_key, value: Felt::from(10 + block_n) }],
+            }],
+            nonces: vec![NonceUpdate { contract_address: contract, nonce: Felt::from(block_n) }],
+            ..Default::default()
+        };
+        backend.store_block(empty_block(block_n), state_diff, vec![]).unwrap();
+    }
+    backend.write_last_confirmed_block(2).unwrap();
+
+    backend.revert_to(0).unwrap();
+
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(backend.get_latest_block_n().unwrap(), Some(0));
+    assert_eq!(backend.contract_trie().root_hash(bonsai_identifier::CONTRACT).unwrap(), roots[0]);
+    assert_eq!(backend.get_contract_storage_at(&latest, &contract, &storage_key).unwrap(), Some(Felt::from(10)));
+    assert_eq!(backend.get_contract_nonce_at(&latest, &contract).unwrap(), Some(Felt::ZERO));
+    assert_eq!(backend.get_l1_last_confirmed_block().unwrap(), Some(0));
+}
@@ -185,8 +185,13 @@ impl ChainGenesisDescription {
 mod tests {
     use super::*;
     use assert_matches::assert_matches;
-    use mc_block_import::{BlockImporter, BlockValidationContext};
+    use blockifier::blockifier::config::ConcurrencyConfig;
+    use mc_block_import::{
+        BlockImportError, BlockImporter, BlockValidationContext, ExecutionMismatch, UnverifiedFullBlock,
+        UnverifiedHeader,
+    };
     use mc_db::MadaraBackend;
+    use mc_exec::ContractClassCache;
     use mc_mempool::block_production::BlockProductionTask;
     use mc_mempool::MempoolProvider;
     use mc_mempool::{transaction_hash, L1DataProvider, Mempool, MockL1DataProvider};
@@ -293,6 +298,10 @@ mod tests {
 
     #[fixture]
     fn chain() -> DevnetForTesting {
+        make_chain(ConcurrencyConfig::default())
+    }
+
+    fn make_chain(concurrency_config: ConcurrencyConfig) -> DevnetForTesting {
         let _ = env_logger::builder().is_test(true).try_init();
 
         let mut g = ChainGenesisDescription::base_config().unwrap();
@@ -325,14 +334,18 @@ mod tests {
             strk_l1_data_gas_price: 128,
         });
         let l1_data_provider = Arc::new(l1_data_provider) as Arc<dyn L1DataProvider>;
-        let mempool = Arc::new(Mempool::new(Arc::clone(&backend), Arc::clone(&l1_data_provider)));
+        let class_cache = Arc::new(ContractClassCache::for_testing());
+        let mempool =
+            Arc::new(Mempool::new(Arc::clone(&backend), Arc::clone(&l1_data_provider), Arc::clone(&class_cache)));
         let block_production = BlockProductionTask::new(
             Arc::clone(&backend),
             Arc::clone(&importer),
             Arc::clone(&mempool),
             Arc::clone(&l1_data_provider),
+            class_cache,
         )
-        .unwrap();
+        .unwrap()
+        .with_concurrency(concurrency_config);
 
         DevnetForTesting { backend, contracts, block_production, mempool }
     }
@@ -602,4 +615,143 @@ mod tests {
             }
         }
     }
+
+    fn transfer_tx(
+        from: &DevnetPredeployedContract,
+        to: Felt,
+        amount: u128,
+        nonce: u64,
+    ) -> BroadcastedInvokeTransaction {
+        BroadcastedInvokeTransaction::V3(BroadcastedInvokeTransactionV3 {
+            sender_address: from.address,
+            calldata: Multicall::default()
+                .with(Call {
+                    to: ERC20_STRK_CONTRACT_ADDRESS,
+                    selector: Selector::from("transfer"),
+                    calldata: vec![to, amount.into(), Felt::ZERO],
+                })
+                .flatten()
+                .collect(),
+            signature: vec![], // Signature is filled in by `sign_and_add_invoke_tx`.
+            nonce: nonce.into(),
+            resource_bounds: ResourceBoundsMapping {
+                l1_gas: ResourceBounds { max_amount: 60000, max_price_per_unit: 10000 },
+                l2_gas: ResourceBounds { max_amount: 60000, max_price_per_unit: 10000 },
+            },
+            tip: 0,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            is_query: false,
+        })
+    }
+
+    /// Concurrent execution must not change the result of block production: the same transactions executed on top of
+    /// the devnet genesis give the same receipts and state diff.
+    #[rstest]
+    fn test_concurrent_execution_matches_sequential(_set_workdir: ()) {
+        let mut sequential = make_chain(ConcurrencyConfig::default());
+        let mut concurrent = make_chain(ConcurrencyConfig { enabled: true, n_workers: 4, chunk_size: 8 });
+
+        for chain in [&mut sequential, &mut concurrent] {
+            // Every transfer touches the balance of the sequencer and of another account, so that the transactions
+            // conflict with each other. The last transfer of each account is more than its balance and is reverted.
+            for (nonce, amount) in [(0u64, 1_000), (1, 1_000), (2, 20_000)] {
+                for (i, from) in chain.contracts.0.iter().enumerate() {
+                    let to = chain.contracts.0[(i + 1) % chain.contracts.0.len()].address;
+                    chain.sign_and_add_invoke_tx(transfer_tx(from, to, amount * STRK_FRI_DECIMALS, nonce), from);
+                }
+            }
+
+            // Use the whole block capacity.
+            let n_pending_ticks_per_block = chain.backend.chain_config().n_pending_ticks_per_block();
+            chain.block_production.set_current_pending_tick(n_pending_ticks_per_block);
+            chain.block_production.on_pending_time_tick().unwrap();
+        }
+
+        let pending = BlockId::Tag(BlockTag::Pending);
+        let sequential_block = sequential.backend.get_block(&pending).unwrap().unwrap();
+        let concurrent_block = concurrent.backend.get_block(&pending).unwrap().unwrap();
+        assert_eq!(sequential_block.inner.transactions.len(), 30);
+        assert!(sequential_block
+            .inner
+            .receipts
+            .iter()
+            .any(|receipt| matches!(receipt.execution_result(), ExecutionResult::Reverted { .. })));
+
+        assert_eq!(concurrent_block.info.tx_hashes(), sequential_block.info.tx_hashes());
+        assert_eq!(concurrent_block.inner.receipts, sequential_block.inner.receipts);
+        assert_eq!(
+            concurrent.backend.get_block_state_diff(&pending).unwrap(),
+            sequential.backend.get_block_state_diff(&pending).unwrap()
+        );
+    }
+
+    /// Full nodes syncing with `--verify-execution` re-execute the blocks produced by the sequencer: re-executing a
+    /// produced block on top of its parent must give back its receipts and state diff, and any difference must be
+    /// reported.
+    #[rstest]
+    fn test_re_execute_produced_block(_set_workdir: (), mut chain: DevnetForTesting) {
+        for (i, from) in chain.contracts.0.iter().take(3).enumerate() {
+            let to = chain.contracts.0[i + 1].address;
+            chain.sign_and_add_invoke_tx(transfer_tx(from, to, 1_000 * STRK_FRI_DECIMALS, 0), from);
+        }
+        chain.block_production.set_current_pending_tick(1);
+        chain.block_production.on_pending_time_tick().unwrap();
+
+        // The pending block is built on top of the latest block, which is the genesis block.
+        let pending = BlockId::Tag(BlockTag::Pending);
+        let block = chain.backend.get_block(&pending).unwrap().unwrap();
+        let state_diff = chain.backend.get_block_state_diff(&pending).unwrap().unwrap();
+        let header = block.info.as_pending().unwrap().header.clone();
+        assert_eq!(block.inner.transactions.len(), 3);
+
+        let chain_id = chain.backend.chain_config().chain_id.clone();
+        let validation = BlockValidationContext::new(chain_id).trust_transaction_hashes(true);
+        let importer = BlockImporter::new(Arc::clone(&chain.backend));
+        let class_cache = Arc::new(ContractClassCache::for_testing());
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let block = rt
+            .block_on(importer.pre_validate(
+                UnverifiedFullBlock {
+                    unverified_block_number: Some(1),
+                    header: UnverifiedHeader {
+                        parent_block_hash: Some(header.parent_block_hash),
+                        sequencer_address: header.sequencer_address,
+                        block_timestamp: header.block_timestamp,
+                        protocol_version: header.protocol_version,
+                        l1_gas_price: header.l1_gas_price,
+                        l1_da_mode: header.l1_da_mode,
+                    },
+                    state_diff,
+                    transactions: block.inner.transactions,
+                    receipts: block.inner.receipts,
+                    declared_classes: vec![],
+                    commitments: Default::default(),
+                },
+                validation,
+            ))
+            .unwrap();
+
+        rt.block_on(importer.re_execute(block.clone(), Arc::clone(&class_cache))).unwrap();
+
+        let mut tampered = block.clone();
+        let TransactionReceipt::Invoke(receipt) = &mut tampered.receipts[1] else { unreachable!() };
+        receipt.actual_fee.amount += Felt::ONE;
+        assert_matches!(
+            rt.block_on(importer.re_execute(tampered, Arc::clone(&class_cache))),
+            Err(BlockImportError::ExecutionMismatch { block_n: 1, mismatch: ExecutionMismatch::Fee { index: 1, .. } })
+        );
+
+        let mut tampered = block;
+        tampered.receipts.pop();
+        assert_matches!(
+            rt.block_on(importer.re_execute(tampered, class_cache)),
+            Err(BlockImportError::ExecutionMismatch {
+                block_n: 1,
+                mismatch: ExecutionMismatch::ReceiptCount { got: 3, expected: 2 }
+            })
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use crate::client::StarknetCoreContract::StarknetCoreContractInstance;
 use crate::utils::u256_to_felt;
+use alloy::eips::BlockNumberOrTag;
 use alloy::sol_types::SolEvent;
 use alloy::{
     primitives::Address,
@@ -13,8 +14,28 @@ use bitvec::macros::internal::funty::Fundamental;
 use mc_metrics::{Gauge, MetricsRegistry, PrometheusError, F64};
 use starknet_types_core::felt::Felt;
 use std::sync::Arc;
+use std::time::Duration;
 use url::Url;
 
+/// Number of L1 blocks queried at once when scanning for events.
+pub(crate) const EVENTS_QUERY_RANGE: u64 = 10_000;
+
+/// Splits the L1 blocks `from_block..=to_block` into the ranges queried when scanning for events.
+pub(crate) fn events_query_ranges(from_block: u64, to_block: u64) -> impl Iterator<Item = (u64, u64)> {
+    (from_block..=to_block)
+        .step_by(EVENTS_QUERY_RANGE as usize)
+        .map(move |from_block| (from_block, to_block.min(from_block + EVENTS_QUERY_RANGE - 1)))
+}
+
+/// How the L1 events (state updates and L1 to L2 messages) are read. Events are only processed once their L1 block
+/// is finalized, or `confirmations` blocks deep when it is set. L1 reorgs which replace processed blocks are rolled
+/// back when they are detected.
+#[derive(Clone, Debug)]
+pub struct L1EventsConfig {
+    pub confirmations: Option<u64>,
+    pub poll_interval: Duration,
+}
+
 #[derive(Clone, Debug)]
 pub struct L1BlockMetrics {
     // L1 network metrics
@@ -22,6 +43,8 @@ pub struct L1BlockMetrics {
     // gas price is also define in sync/metrics/block_metrics.rs but this would be the price from l1
     pub l1_gas_price_wei: Gauge<F64>,
     pub l1_gas_price_strk: Gauge<F64>,
+    /// 1 when the last state update posted on L1 does not match the local chain.
+    pub l1_state_diverged: Gauge<F64>,
 }
 
 impl L1BlockMetrics {
@@ -33,6 +56,10 @@ impl L1BlockMetrics {
             l1_gas_price_wei: registry.register(Gauge::new("madara_l1_gas_price", "Gauge for madara L1 gas price")?)?,
             l1_gas_price_strk: registry
                 .register(Gauge::new("madara_l1_gas_price_strk", "Gauge for madara L1 gas price in strk")?)?,
+            l1_state_diverged: registry.register(Gauge::new(
+                "madara_l1_state_diverged",
+                "Whether the block hash or state root of the last state update posted on L1 differ from the local chain",
+            )?)?,
         })
     }
 }
@@ -92,6 +119,28 @@ impl EthereumClient {
         Ok(block_number)
     }
 
+    /// Most recent L1 block with at least `confirmations` blocks on top of it, or the finalized L1 block when
+    /// `confirmations` is `None`. Returns `None` when there is no such block yet.
+    pub async fn get_confirmed_block_number(&self, confirmations: Option<u64>) -> anyhow::Result<Option<u64>> {
+        match confirmations {
+            Some(confirmations) => Ok(self.get_latest_block_number().await?.checked_sub(confirmations)),
+            None => {
+                let block = self.provider.get_block_by_number(BlockNumberOrTag::Finalized, false).await?;
+                Ok(block.and_then(|block| block.header.number))
+            }
+        }
+    }
+
+    /// Hash of the L1 block with this number in the current L1 chain. Returns `None` when there is no such block,
+    /// which happens when a reorg made the chain shorter.
+    pub async fn get_block_hash(&self, block_number: u64) -> anyhow::Result<Option<[u8; 32]>> {
+        let Some(block) = self.provider.get_block_by_number(block_number.into(), false).await? else {
+            return Ok(None);
+        };
+        let block_hash = block.header.hash.context("L1 block without a hash")?;
+        Ok(Some(block_hash.0))
+    }
+
     /// Get the block number of the last occurrence of a given event.
     pub async fn get_last_event_block_number<T: SolEvent>(&self) -> anyhow::Result<u64> {
         let latest_block: u64 = self.get_latest_block_number().await?;
@@ -1,4 +1,5 @@
 use crate::client::EthereumClient;
+use crate::oracle::Oracle;
 use alloy::eips::BlockNumberOrTag;
 use alloy::providers::Provider;
 use anyhow::Context;
@@ -8,12 +9,16 @@ use std::time::{Duration, UNIX_EPOCH};
 use mp_utils::wait_or_graceful_shutdown;
 use std::time::SystemTime;
 
+/// The STRK gas prices are only updated when an `oracle` is given. See [`mp_chain_config::L1GasPriceConfig`] for
+/// `ema_blocks`.
 pub async fn gas_price_worker_once(
     eth_client: &EthereumClient,
     l1_gas_provider: GasPriceProvider,
+    oracle: Option<&dyn Oracle>,
+    ema_blocks: Option<u64>,
     gas_price_poll_ms: Duration,
 ) -> anyhow::Result<()> {
-    match update_gas_price(eth_client, l1_gas_provider.clone()).await {
+    match update_gas_price(eth_client, l1_gas_provider.clone(), oracle, ema_blocks).await {
         Ok(_) => log::trace!("Updated gas prices"),
         Err(e) => log::error!("Failed to update gas prices: {:?}", e),
     }
@@ -35,33 +40,72 @@ pub async fn gas_price_worker_once(
 pub async fn gas_price_worker(
     eth_client: &EthereumClient,
     l1_gas_provider: GasPriceProvider,
+    oracle: Option<&dyn Oracle>,
+    ema_blocks: Option<u64>,
     gas_price_poll_ms: Duration,
 ) -> anyhow::Result<()> {
     l1_gas_provider.update_last_update_timestamp();
     let mut interval = tokio::time::interval(gas_price_poll_ms);
     interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
     while wait_or_graceful_shutdown(interval.tick()).await.is_some() {
-        gas_price_worker_once(eth_client, l1_gas_provider.clone(), gas_price_poll_ms).await?;
+        gas_price_worker_once(eth_client, l1_gas_provider.clone(), oracle, ema_blocks, gas_price_poll_ms).await?;
     }
     Ok(())
 }
 
-async fn update_gas_price(eth_client: &EthereumClient, l1_gas_provider: GasPriceProvider) -> anyhow::Result<()> {
+async fn update_gas_price(
+    eth_client: &EthereumClient,
+    l1_gas_provider: GasPriceProvider,
+    oracle: Option<&dyn Oracle>,
+    ema_blocks: Option<u64>,
+) -> anyhow::Result<()> {
     let block_number = eth_client.get_latest_block_number().await?;
     let fee_history = eth_client.provider.get_fee_history(300, BlockNumberOrTag::Number(block_number), &[]).await?;
 
-    // The RPC responds with 301 elements for some reason. It's also just safer to manually
-    // take the last 300. We choose 300 to get average gas caprice for last one hour (300 * 12 sec block
-    // time).
-    let (_, blob_fee_history_one_hour) =
-        fee_history.base_fee_per_blob_gas.split_at(fee_history.base_fee_per_blob_gas.len().max(300) - 300);
-
-    let avg_blob_base_fee = blob_fee_history_one_hour.iter().sum::<u128>() / blob_fee_history_one_hour.len() as u128;
-
-    let eth_gas_price = fee_history.base_fee_per_gas.last().context("Getting eth gas price")?;
+    let (eth_gas_price, avg_blob_base_fee) = match ema_blocks {
+        Some(n) => (
+            ema(&fee_history.base_fee_per_gas, n).context("Getting eth gas price")?,
+            ema(&fee_history.base_fee_per_blob_gas, n).context("Getting eth data gas price")?,
+        ),
+        None => {
+            // The RPC responds with 301 elements for some reason. It's also just safer to manually
+            // take the last 300. We choose 300 to get average gas caprice for last one hour (300 * 12 sec block
+            // time).
+            let (_, blob_fee_history_one_hour) =
+                fee_history.base_fee_per_blob_gas.split_at(fee_history.base_fee_per_blob_gas.len().max(300) - 300);
+
+            let avg_blob_base_fee =
+                blob_fee_history_one_hour.iter().sum::<u128>() / blob_fee_history_one_hour.len() as u128;
+
+            (*fee_history.base_fee_per_gas.last().context("Getting eth gas price")?, avg_blob_base_fee)
+        }
+    };
+
+    // A failing or stale oracle must not stop the ETH prices from being updated: the last STRK prices are kept
+    // instead, until the oracle recovers.
+    let strk_prices = match oracle {
+        Some(oracle) => {
+            let strk_prices = async {
+                let rate = oracle.fetch_eth_strk_rate().await.context("Getting the ETH/STRK rate")?;
+                anyhow::Ok((rate.wei_to_fri(eth_gas_price)?, rate.wei_to_fri(avg_blob_base_fee)?))
+            };
+            match strk_prices.await {
+                Ok(strk_prices) => Some(strk_prices),
+                Err(err) => {
+                    log::warn!("Keeping the last STRK gas prices: {err:#}");
+                    None
+                }
+            }
+        }
+        None => None,
+    };
 
-    l1_gas_provider.update_eth_l1_gas_price(*eth_gas_price);
+    l1_gas_provider.update_eth_l1_gas_price(eth_gas_price);
     l1_gas_provider.update_eth_l1_data_gas_price(avg_blob_base_fee);
+    if let Some((strk_gas_price, strk_data_gas_price)) = strk_prices {
+        l1_gas_provider.update_strk_l1_gas_price(strk_gas_price);
+        l1_gas_provider.update_strk_l1_data_gas_price(strk_data_gas_price);
+    }
 
     l1_gas_provider.update_last_update_timestamp();
 
@@ -71,6 +115,15 @@ async fn update_gas_price(eth_client: &EthereumClient, l1_gas_provider: GasPrice
     Ok(())
 }
 
+/// Exponential moving average of the last `n` values, oldest first, with a smoothing factor of `2 / (n + 1)`.
+fn ema(values: &[u128], n: u64) -> Option<u128> {
+    let n = n.max(1);
+    let window = &values[values.len().saturating_sub(n as usize)..];
+    let (first, rest) = window.split_first()?;
+    let n = n as u128;
+    Some(rest.iter().fold(*first, |ema, value| (value * 2 + ema * (n - 1)) / (n + 1)))
+}
+
 async fn update_l1_block_metrics(eth_client: &EthereumClient, l1_gas_provider: GasPriceProvider) -> anyhow::Result<()> {
     // Get the latest block number
     let latest_block_number = eth_client.get_latest_block_number().await?;
@@ -78,12 +131,12 @@ async fn update_l1_block_metrics(eth_client: &EthereumClient, l1_gas_provider: G
     // Get the current gas price
     let current_gas_price = l1_gas_provider.get_gas_prices();
     let eth_gas_price = current_gas_price.eth_l1_gas_price;
+    let strk_gas_price = current_gas_price.strk_l1_gas_price;
 
     // Update the metrics
     eth_client.l1_block_metrics.l1_block_number.set(latest_block_number as f64);
     eth_client.l1_block_metrics.l1_gas_price_wei.set(eth_gas_price as f64);
-
-    // We're ignoring l1_gas_price_strk
+    eth_client.l1_block_metrics.l1_gas_price_strk.set(strk_gas_price as f64);
 
     Ok(())
 }
@@ -92,6 +145,7 @@ async fn update_l1_block_metrics(eth_client: &EthereumClient, l1_gas_provider: G
 mod eth_client_gas_price_worker_test {
     use super::*;
     use crate::client::eth_client_getter_test::create_ethereum_client;
+    use crate::oracle::FixedRateOracle;
     use alloy::node_bindings::Anvil;
     use httpmock::{MockServer, Regex};
     use mc_mempool::GasPriceProvider;
@@ -119,7 +173,7 @@ mod eth_client_gas_price_worker_test {
         let worker_handle: JoinHandle<anyhow::Result<()>> = tokio::spawn({
             let eth_client = eth_client.clone();
             let l1_gas_provider = l1_gas_provider.clone();
-            async move { gas_price_worker(&eth_client, l1_gas_provider, Duration::from_millis(200)).await }
+            async move { gas_price_worker(&eth_client, l1_gas_provider, None, None, Duration::from_millis(200)).await }
         });
 
         // Wait for a short duration to allow the worker to run
@@ -157,7 +211,8 @@ mod eth_client_gas_price_worker_test {
         let l1_gas_provider = GasPriceProvider::new();
 
         // Run the worker for a short time
-        let worker_handle = gas_price_worker_once(&eth_client, l1_gas_provider.clone(), Duration::from_millis(200));
+        let worker_handle =
+            gas_price_worker_once(&eth_client, l1_gas_provider.clone(), None, None, Duration::from_millis(200));
 
         // Wait for the worker to complete
         worker_handle.await.expect("issue with the gas worker");
@@ -205,7 +260,7 @@ mod eth_client_gas_price_worker_test {
 
         let result = timeout(
             timeout_duration,
-            gas_price_worker(&eth_client, l1_gas_provider.clone(), Duration::from_millis(200)),
+            gas_price_worker(&eth_client, l1_gas_provider.clone(), None, None, Duration::from_millis(200)),
         )
         .await;
 
@@ -242,7 +297,7 @@ mod eth_client_gas_price_worker_test {
         l1_gas_provider.update_last_update_timestamp();
 
         // Update gas prices
-        update_gas_price(&eth_client, l1_gas_provider.clone()).await.expect("Failed to update gas prices");
+        update_gas_price(&eth_client, l1_gas_provider.clone(), None, None).await.expect("Failed to update gas prices");
 
         // Access the updated gas prices
         let updated_prices = l1_gas_provider.get_gas_prices();
@@ -263,4 +318,73 @@ mod eth_client_gas_price_worker_test {
 
         assert!(time_since_last_update.as_secs() < 60, "Last update timestamp should be within the last minute");
     }
+
+    #[serial]
+    #[tokio::test]
+    async fn update_gas_price_with_oracle_works() {
+        let anvil = Anvil::new()
+            .fork(FORK_URL)
+            .fork_block_number(L1_BLOCK_NUMBER)
+            .port(ANOTHER_ANVIL_PORT)
+            .try_spawn()
+            .expect("issue while forking for the anvil");
+        let eth_client = create_ethereum_client(Some(anvil.endpoint().as_str()));
+        let l1_gas_provider = GasPriceProvider::new();
+        let oracle = FixedRateOracle("2500.5".parse().unwrap());
+
+        update_gas_price(&eth_client, l1_gas_provider.clone(), Some(&oracle), None)
+            .await
+            .expect("Failed to update gas prices");
+
+        let updated_prices = l1_gas_provider.get_gas_prices();
+        assert_eq!(updated_prices.eth_l1_gas_price, 948082986);
+        assert_eq!(updated_prices.strk_l1_gas_price, 2370681506493);
+        assert_eq!(updated_prices.strk_l1_data_gas_price, 2500);
+    }
+
+    struct FailingOracle;
+
+    #[async_trait::async_trait]
+    impl Oracle for FailingOracle {
+        async fn fetch_eth_strk_rate(&self) -> anyhow::Result<crate::oracle::EthStrkRate> {
+            anyhow::bail!("ETH/STRK rate is stale")
+        }
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn update_gas_price_with_failing_oracle_keeps_strk_prices() {
+        let anvil = Anvil::new()
+            .fork(FORK_URL)
+            .fork_block_number(L1_BLOCK_NUMBER)
+            .port(ANOTHER_ANVIL_PORT)
+            .try_spawn()
+            .expect("issue while forking for the anvil");
+        let eth_client = create_ethereum_client(Some(anvil.endpoint().as_str()));
+        let l1_gas_provider = GasPriceProvider::new();
+        l1_gas_provider.update_strk_l1_gas_price(1234);
+        l1_gas_provider.update_strk_l1_data_gas_price(5678);
+        let before_update = SystemTime::now();
+
+        update_gas_price(&eth_client, l1_gas_provider.clone(), Some(&FailingOracle), None)
+            .await
+            .expect("The oracle failure should not fail the update");
+
+        let updated_prices = l1_gas_provider.get_gas_prices();
+        assert_eq!(updated_prices.eth_l1_gas_price, 948082986);
+        assert_eq!(updated_prices.eth_l1_data_gas_price, 1);
+        assert_eq!(updated_prices.strk_l1_gas_price, 1234);
+        assert_eq!(updated_prices.strk_l1_data_gas_price, 5678);
+        assert!(l1_gas_provider.get_gas_prices_last_update() >= before_update);
+    }
+
+    #[test]
+    fn ema_works() {
+        assert_eq!(ema(&[], 10), None);
+        assert_eq!(ema(&[100, 200, 300], 1), Some(300));
+        // alpha = 2 / 4: 100 -> 150 -> 225
+        assert_eq!(ema(&[7, 100, 200, 300], 3), Some(225));
+        // A spike in the last block only moves the average by 2 / (n + 1).
+        assert_eq!(ema(&[100; 20].into_iter().chain([1000]).collect::<Vec<_>>(), 19), Some(190));
+    }
 }
@@ -1,16 +1,17 @@
-use alloy::eips::BlockNumberOrTag;
 use anyhow::Context;
-use futures::StreamExt;
 use std::sync::Arc;
 
 use crate::client::StarknetCoreContract::LogMessageToL2;
-use crate::client::{EthereumClient, StarknetCoreContract};
+use crate::client::{events_query_ranges, EthereumClient, L1EventsConfig, StarknetCoreContract};
 use crate::utils::u256_to_felt;
 use alloy::primitives::{keccak256, FixedBytes, U256};
+use alloy::rpc::types::Log;
 use alloy::sol_types::SolValue;
 use blockifier::transaction::transactions::L1HandlerTransaction as BlockifierL1HandlerTransaction;
-use mc_db::{l1_db::LastSyncedEventBlock, MadaraBackend};
-use mp_utils::channel_wait_or_graceful_shutdown;
+use mc_db::l1_db::{L1BlockRef, L1MessageRecord, LastSyncedEventBlock};
+use mc_db::MadaraBackend;
+use mc_mempool::{Mempool, MempoolProvider, SubmittedTransactionStatus};
+use mp_utils::wait_or_graceful_shutdown;
 use starknet_api::core::{ChainId, ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::transaction::{
     Calldata, Fee, L1HandlerTransaction, Transaction, TransactionHash, TransactionVersion,
@@ -39,103 +40,209 @@ impl EthereumClient {
     }
 }
 
-pub async fn sync(backend: &MadaraBackend, client: &EthereumClient, chain_id: &ChainId) -> anyhow::Result<()> {
+pub async fn sync(
+    backend: &MadaraBackend,
+    client: &EthereumClient,
+    chain_id: &ChainId,
+    mempool: Arc<Mempool>,
+    config: &L1EventsConfig,
+) -> anyhow::Result<()> {
     tracing::info!("⟠ Starting L1 Messages Syncing...");
 
-    let last_synced_event_block = match backend.messaging_last_synced_l1_block_with_event() {
-        Ok(Some(blk)) => blk,
-        Ok(None) => {
-            unreachable!("Should never be None")
+    // Last L1 block scanned for messages. After a restart, the scan starts again from the last message executed in a
+    // closed block, and the messages after it which were not executed yet are submitted again.
+    let mut last_scanned = None;
+    loop {
+        if let Err(e) =
+            poll_messages(backend, client, chain_id, &mempool, config.confirmations, &mut last_scanned).await
+        {
+            tracing::error!("⟠ Failed to get the L1 Messages, retrying on the next poll: {:#}", e);
         }
-        Err(e) => {
-            tracing::error!("⟠ Madara Messaging DB unavailable: {:?}", e);
-            return Err(e.into());
+        if wait_or_graceful_shutdown(tokio::time::sleep(config.poll_interval)).await.is_none() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Rolls back the L1 reorgs, and processes the messages sent in the new confirmed L1 blocks (see
+/// [`EthereumClient::get_confirmed_block_number`]).
+async fn poll_messages(
+    backend: &MadaraBackend,
+    client: &EthereumClient,
+    chain_id: &ChainId,
+    mempool: &Mempool,
+    confirmations: Option<u64>,
+    last_scanned: &mut Option<L1BlockRef>,
+) -> anyhow::Result<()> {
+    let last_synced_event_block = backend.messaging_last_synced_l1_block_with_event()?.unwrap_or_default();
+    let anchor = last_scanned.or(last_synced_event_block
+        .block_hash
+        .map(|block_hash| L1BlockRef { block_number: last_synced_event_block.block_number, block_hash }));
+    if let Some(anchor) = anchor {
+        if client.get_block_hash(anchor.block_number).await? != Some(anchor.block_hash) {
+            rollback_reorged_messages(backend, client, anchor).await.context("Rolling back L1 reorg")?;
+            *last_scanned = None;
         }
+    }
+
+    // No message can be sent before the core contract is deployed.
+    let from_block = match last_scanned {
+        Some(block) => block.block_number + 1,
+        None => match backend.messaging_last_synced_l1_block_with_event()? {
+            Some(block) => block.block_number,
+            None => backend.chain_config().eth_core_contract_deployment_block,
+        },
     };
-    let event_filter = client.l1_core_contract.event_filter::<StarknetCoreContract::LogMessageToL2>();
-    let mut event_stream = event_filter
-        .from_block(last_synced_event_block.block_number)
-        .select(BlockNumberOrTag::Finalized)
-        .watch()
-        .await
-        .context("Failed to watch event filter")?
-        .into_stream();
-
-    while let Some(event_result) = channel_wait_or_graceful_shutdown(event_stream.next()).await {
-        if let Ok((event, meta)) = event_result {
+    let Some(target_l1_block) = client.get_confirmed_block_number(confirmations).await? else { return Ok(()) };
+
+    for (from_block, to_block) in events_query_ranges(from_block, target_l1_block) {
+        let event_filter = client
+            .l1_core_contract
+            .event_filter::<StarknetCoreContract::LogMessageToL2>()
+            .from_block(from_block)
+            .to_block(to_block);
+        let Some(logs) = wait_or_graceful_shutdown(event_filter.query()).await else { return Ok(()) };
+        let logs = logs.with_context(|| format!("Getting the messages of L1 blocks #{from_block} to #{to_block}"))?;
+
+        for (event, meta) in logs {
+            handle_message_event(backend, client, chain_id, mempool, &event, &meta).await?;
+        }
+
+        let block_hash = client
+            .get_block_hash(to_block)
+            .await?
+            .with_context(|| format!("L1 block #{to_block} is not in the L1 chain anymore"))?;
+        *last_scanned = Some(L1BlockRef { block_number: to_block, block_hash });
+    }
+
+    Ok(())
+}
+
+async fn handle_message_event(
+    backend: &MadaraBackend,
+    client: &EthereumClient,
+    chain_id: &ChainId,
+    mempool: &Mempool,
+    event: &LogMessageToL2,
+    meta: &Log,
+) -> anyhow::Result<()> {
+    tracing::info!(
+        "⟠ Processing L1 Message from block: {:?}, transaction_hash: {:?}, log_index: {:?}, fromAddress: {:?}",
+        meta.block_number,
+        meta.transaction_hash,
+        meta.log_index,
+        event.fromAddress
+    );
+
+    // Check if cancellation was initiated
+    let event_hash = get_l1_to_l2_msg_hash(event)?;
+    tracing::info!("⟠ Checking for cancelation, event hash : {:?}", event_hash);
+    let cancellation_timestamp = client.get_l1_to_l2_message_cancellations(event_hash).await?;
+    if cancellation_timestamp != Felt::ZERO {
+        tracing::info!("⟠ L1 Message was cancelled in block at timestamp : {:?}", cancellation_timestamp);
+        let tx_nonce = Nonce(u256_to_felt(event.nonce)?);
+        // cancelled message nonce should be inserted to avoid reprocessing
+        match backend.has_l1_messaging_nonce(tx_nonce) {
+            Ok(false) => {
+                backend.set_l1_messaging_nonce(tx_nonce)?;
+            }
+            Ok(true) => {}
+            Err(e) => {
+                tracing::error!("⟠ Unexpected DB error: {:?}", e);
+                return Err(e.into());
+            }
+        };
+        return Ok(());
+    }
+
+    match process_l1_message(backend, event, meta, chain_id, mempool).await {
+        Ok(Some(tx_hash)) => {
             tracing::info!(
-                "⟠ Processing L1 Message from block: {:?}, transaction_hash: {:?}, log_index: {:?}, fromAddress: {:?}",
+                "⟠ L1 Message from block: {:?}, transaction_hash: {:?}, log_index: {:?} submitted, \
+                transaction hash on L2: {:?}",
                 meta.block_number,
                 meta.transaction_hash,
                 meta.log_index,
-                event.fromAddress
+                tx_hash
             );
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!(
+                "⟠ Unexpected error while processing L1 Message from block: {:?}, transaction_hash: {:?}, \
+            log_index: {:?}, error: {:?}",
+                meta.block_number,
+                meta.transaction_hash,
+                meta.log_index,
+                e
+            )
+        }
+    }
 
-            // Check if cancellation was initiated
-            let event_hash = get_l1_to_l2_msg_hash(&event)?;
-            tracing::info!("⟠ Checking for cancelation, event hash : {:?}", event_hash);
-            let cancellation_timestamp = client.get_l1_to_l2_message_cancellations(event_hash).await?;
-            if cancellation_timestamp != Felt::ZERO {
-                tracing::info!("⟠ L1 Message was cancelled in block at timestamp : {:?}", cancellation_timestamp);
-                let tx_nonce = Nonce(u256_to_felt(event.nonce)?);
-                // cancelled message nonce should be inserted to avoid reprocessing
-                match backend.has_l1_messaging_nonce(tx_nonce) {
-                    Ok(false) => {
-                        backend.set_l1_messaging_nonce(tx_nonce)?;
-                    }
-                    Ok(true) => {}
-                    Err(e) => {
-                        tracing::error!("⟠ Unexpected DB error: {:?}", e);
-                        return Err(e.into());
-                    }
-                };
-                continue;
-            }
+    Ok(())
+}
 
-            match process_l1_message(backend, &event, &meta.block_number, &meta.log_index, chain_id).await {
-                Ok(Some(tx_hash)) => {
-                    tracing::info!(
-                        "⟠ L1 Message from block: {:?}, transaction_hash: {:?}, log_index: {:?} submitted, \
-                        transaction hash on L2: {:?}",
-                        meta.block_number,
-                        meta.transaction_hash,
-                        meta.log_index,
-                        tx_hash
-                    );
-                }
-                Ok(None) => {}
-                Err(e) => {
-                    tracing::error!(
-                        "⟠ Unexpected error while processing L1 Message from block: {:?}, transaction_hash: {:?}, \
-                    log_index: {:?}, error: {:?}",
-                        meta.block_number,
-                        meta.transaction_hash,
-                        meta.log_index,
-                        e
-                    )
-                }
-            }
+/// Rolls back the messages sent in L1 blocks which were reorged out, from the most recent one, and moves the last
+/// synced event block back to the last message which is still in the L1 chain when it was past it.
+async fn rollback_reorged_messages(
+    backend: &MadaraBackend,
+    client: &EthereumClient,
+    reorged_block: L1BlockRef,
+) -> anyhow::Result<()> {
+    tracing::warn!("⟠ L1 reorg detected: L1 block #{} is not in the L1 chain anymore", reorged_block.block_number);
+
+    while let Some(message) = backend.get_latest_l1_message()? {
+        if client.get_block_hash(message.l1_block_number).await? == Some(message.l1_block_hash) {
+            break;
         }
+        rollback_message(backend, &message)?;
     }
 
+    let last_synced_event_block = backend.messaging_last_synced_l1_block_with_event()?.unwrap_or_default();
+    let last_synced_position = (last_synced_event_block.block_number, last_synced_event_block.event_index);
+    let new_last_synced_event_block = match backend.get_latest_l1_message()? {
+        Some(message) if (message.l1_block_number, message.log_index) < last_synced_position => {
+            LastSyncedEventBlock::new(message.l1_block_number, message.log_index, message.l1_block_hash)
+        }
+        Some(_) => last_synced_event_block,
+        None => LastSyncedEventBlock::default(),
+    };
+    backend.messaging_update_last_synced_l1_block_with_event(new_last_synced_event_block)?;
+    Ok(())
+}
+
+/// The message record is removed so that the message is processed again if the new L1 chain sends it again. Its
+/// `L1Handler` transaction is dropped by block production if it is still in the mempool. A transaction which is
+/// already in a closed block cannot be reverted.
+fn rollback_message(backend: &MadaraBackend, message: &L1MessageRecord) -> anyhow::Result<()> {
+    if backend.get_l1_messaging_nonce_tx_hash(message.nonce)?.is_some() {
+        tracing::error!(
+            "⟠ L1 Message with nonce {:?} was reorged out of L1, but its L1 handler transaction is already in a block",
+            message.nonce
+        );
+    } else {
+        tracing::warn!("⟠ L1 Message with nonce {:?} was reorged out of L1, rolling it back", message.nonce);
+        backend.remove_l1_tx_messaging_nonce(&message.l1_transaction_hash, message.nonce)?;
+    }
+    backend.remove_l1_message(message)?;
     Ok(())
 }
 
 async fn process_l1_message(
     backend: &MadaraBackend,
     event: &LogMessageToL2,
-    l1_block_number: &Option<u64>,
-    event_index: &Option<u64>,
+    meta: &Log,
     chain_id: &ChainId,
+    mempool: &Mempool,
 ) -> anyhow::Result<Option<TransactionHash>> {
     let transaction = parse_handle_l1_message_transaction(event)?;
     let tx_nonce = transaction.nonce;
 
-    // Ensure that L1 message has not been executed
+    // Ensure that L1 message has not been executed in a closed block
     match backend.has_l1_messaging_nonce(tx_nonce) {
-        Ok(false) => {
-            backend.set_l1_messaging_nonce(tx_nonce)?;
-        }
+        Ok(false) => {}
         Ok(true) => {
             tracing::debug!("⟠ Event already processed: {:?}", transaction);
             return Ok(None);
@@ -146,18 +253,51 @@ async fn process_l1_message(
         }
     };
 
+    let l1_block_number = meta.block_number.context("LogMessageToL2 event without a block number")?;
+    let l1_block_hash = meta.block_hash.context("LogMessageToL2 event without a block hash")?;
+    let event_index = meta.log_index.context("LogMessageToL2 event without a log index")?;
+    let l1_transaction_hash = meta.transaction_hash.context("LogMessageToL2 event without a transaction hash")?;
+
     let tx_hash = get_transaction_hash(&Transaction::L1Handler(transaction.clone()), chain_id, &transaction.version)?;
     let blockifier_transaction: BlockifierL1HandlerTransaction =
         BlockifierL1HandlerTransaction { tx: transaction.clone(), tx_hash, paid_fee_on_l1: Fee(event.fee.try_into()?) };
 
-    // TODO: submit tx to mempool
+    // The message is already waiting in the mempool, or it was rolled back by an L1 reorg and sent again in the new
+    // L1 chain while its transaction was still in the mempool.
+    let in_mempool = mempool
+        .get_submitted_transaction(&tx_hash.0)
+        .is_some_and(|tx| matches!(tx.status, SubmittedTransactionStatus::Received));
+    if in_mempool && backend.get_l1_message(tx_nonce)?.is_some() {
+        tracing::debug!("⟠ Event already submitted: {:?}", transaction);
+        return Ok(None);
+    }
 
-    // TODO: remove unwraps
-    let block_sent = LastSyncedEventBlock::new(l1_block_number.unwrap(), event_index.unwrap());
-    backend.messaging_update_last_synced_l1_block_with_event(block_sent)?;
+    // The message is recorded before the transaction is added to the mempool, as block production drops the
+    // `L1Handler` transactions of messages which have no record. Its nonce is only marked, and the last synced event
+    // block only moves past it, once the transaction is in a closed block: a message which is still in the mempool
+    // or in the pending block is submitted again after a restart.
+    let message = L1MessageRecord {
+        nonce: tx_nonce,
+        l1_block_number,
+        l1_block_hash: l1_block_hash.0,
+        log_index: event_index,
+        l1_transaction_hash: l1_transaction_hash.0,
+        tx_hash: tx_hash.0,
+    };
+    // Used by `starknet_getMessagesStatus`.
+    backend.add_l1_tx_messaging_nonce(&l1_transaction_hash.0, tx_nonce)?;
+    backend.store_l1_message(&message)?;
 
-    // TODO: replace by tx hash from mempool
-    Ok(Some(blockifier_transaction.tx_hash))
+    if in_mempool {
+        return Ok(None);
+    }
+    if let Err(e) = mempool.accept_l1_handler_tx(blockifier_transaction) {
+        backend.remove_l1_message(&message)?;
+        backend.remove_l1_tx_messaging_nonce(&l1_transaction_hash.0, tx_nonce)?;
+        return Err(e.into());
+    }
+
+    Ok(Some(tx_hash))
 }
 
 pub fn parse_handle_l1_message_transaction(event: &LogMessageToL2) -> anyhow::Result<L1HandlerTransaction> {
@@ -214,7 +354,7 @@ mod l1_messaging_tests {
     use super::Felt;
     use crate::{
         client::{
-            EthereumClient, L1BlockMetrics,
+            EthereumClient, L1BlockMetrics, L1EventsConfig,
             StarknetCoreContract::{self, LogMessageToL2},
         },
         l1_messaging::get_l1_to_l2_msg_hash,
@@ -224,13 +364,19 @@ mod l1_messaging_tests {
         hex::FromHex,
         node_bindings::{Anvil, AnvilInstance},
         primitives::{Address, U256},
-        providers::{ProviderBuilder, RootProvider},
+        providers::{Provider, ProviderBuilder, RootProvider},
         sol,
         transports::http::{Client, Http},
     };
-    use mc_db::DatabaseService;
+    use mc_db::{DatabaseService, MadaraBackend};
+    use mc_exec::ContractClassCache;
+    use mc_mempool::{GasPriceProvider, Mempool, MempoolProvider, MempoolTransaction};
     use mc_metrics::MetricsService;
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
     use mp_chain_config::ChainConfig;
+    use mp_receipt::L1HandlerTransactionReceipt;
+    use mp_state_update::StateDiff;
+    use mp_transactions::TransactionWithHash;
     use mp_utils::tests_common::*;
     use rstest::*;
     use starknet_api::core::Nonce;
@@ -242,11 +388,27 @@ mod l1_messaging_tests {
 
     use self::DummyContract::DummyContractInstance;
 
+    fn events_config() -> L1EventsConfig {
+        L1EventsConfig { confirmations: Some(0), poll_interval: Duration::from_millis(100) }
+    }
+
+    /// Stores a closed block with the `L1Handler` transaction of a message, as block production would.
+    fn store_closed_block(backend: &MadaraBackend, block_number: u64, tx: MempoolTransaction) {
+        let tx = TransactionWithHash::from(tx.tx);
+        let block_inner =
+            MadaraBlockInner::new(vec![tx.transaction], vec![L1HandlerTransactionReceipt::default().into()]);
+        let header = Header { block_number, ..Default::default() };
+        let block_info = MadaraBlockInfo::new(header, vec![tx.hash], Felt::from(block_number));
+        let block = MadaraMaybePendingBlock { info: block_info.into(), inner: block_inner };
+        backend.store_block(block, StateDiff::default(), vec![]).expect("Failed to store block");
+    }
+
     struct TestRunner {
         #[allow(dead_code)]
         anvil: AnvilInstance, // Not used but needs to stay in scope otherwise it will be dropped
         chain_config: Arc<ChainConfig>,
         db_service: Arc<DatabaseService>,
+        mempool: Arc<Mempool>,
         dummy_contract: DummyContractInstance<Http<Client>, RootProvider<Http<Client>>>,
         eth_client: EthereumClient,
     }
@@ -325,7 +487,7 @@ mod l1_messaging_tests {
     /// 4. Waits for event to be processed
     /// 5. Assert that the worker handle the event with correct data
     /// 6. Assert that the hash computed by the worker is correct
-    /// 7. TODO : Assert that the tx is succesfully submited to the mempool
+    /// 7. Assert that the tx is succesfully submited to the mempool
     /// 8. Assert that the event is successfully pushed to the db
     /// 9. TODO : Assert that the tx was correctly executed
     #[fixture]
@@ -344,11 +506,24 @@ mod l1_messaging_tests {
 
         // Initialize database service
         let db = Arc::new(
-            DatabaseService::new(&base_path, backup_dir, false, chain_config.clone())
-                .await
-                .expect("Failed to create database service"),
+            DatabaseService::new(
+                &base_path,
+                backup_dir,
+                false,
+                chain_config.clone(),
+                Default::default(),
+                Default::default(),
+            )
+            .await
+            .expect("Failed to create database service"),
         );
 
+        let mempool = Arc::new(Mempool::new(
+            Arc::clone(db.backend()),
+            Arc::new(GasPriceProvider::new()),
+            Arc::new(ContractClassCache::for_testing()),
+        ));
+
         // Set up metrics service
         let prometheus_service = MetricsService::new(true, false, 9615).unwrap();
         let l1_block_metrics = L1BlockMetrics::register(&prometheus_service.registry()).unwrap();
@@ -368,7 +543,7 @@ mod l1_messaging_tests {
             l1_block_metrics: l1_block_metrics.clone(),
         };
 
-        TestRunner { anvil, chain_config, db_service: db, dummy_contract: contract, eth_client }
+        TestRunner { anvil, chain_config, db_service: db, mempool, dummy_contract: contract, eth_client }
     }
 
     /// Test the basic workflow of l1 -> l2 messaging
@@ -380,25 +555,28 @@ mod l1_messaging_tests {
     /// 4. Waits for event to be processed
     /// 5. Assert that the worker handle the event with correct data
     /// 6. Assert that the hash computed by the worker is correct
-    /// 7. TODO : Assert that the tx is succesfully submited to the mempool
+    /// 7. Assert that the tx is succesfully submited to the mempool
     /// 8. Assert that the event is successfully pushed to the db
     /// 9. TODO : Assert that the tx was correctly executed
     #[rstest]
     #[traced_test]
     #[tokio::test]
     async fn e2e_test_basic_workflow(#[future] setup_test_env: TestRunner, _set_workdir: ()) {
-        let TestRunner { chain_config, db_service: db, dummy_contract: contract, eth_client, anvil: _anvil } =
+        let TestRunner { chain_config, db_service: db, mempool, dummy_contract: contract, eth_client, anvil: _anvil } =
             setup_test_env.await;
 
         // Start worker
         let worker_handle = {
             let db = Arc::clone(&db);
-            tokio::spawn(async move { sync(db.backend(), &eth_client, &chain_config.chain_id).await })
+            let mempool = Arc::clone(&mempool);
+            tokio::spawn(async move {
+                sync(db.backend(), &eth_client, &chain_config.chain_id, mempool, &events_config()).await
+            })
         };
 
         let _ = contract.setIsCanceled(false).send().await;
         // Send a Event and wait for processing, Panic if fail
-        let _ = contract.fireEvent().send().await.expect("Failed to fire event");
+        let l1_tx_hash = *contract.fireEvent().send().await.expect("Failed to fire event").tx_hash();
         tokio::time::sleep(Duration::from_secs(5)).await;
 
         // Assert that event was caught by the worker with correct data
@@ -411,27 +589,92 @@ mod l1_messaging_tests {
                 .as_str()
         ));
 
-        // TODO : Assert that the tx has been included in the mempool
-
-        // Assert that the event is well stored in db
+        // Assert that the message is stored in db. It is only consumed once its transaction is in a closed block.
         let last_block =
             db.backend().messaging_last_synced_l1_block_with_event().expect("failed to retrieve block").unwrap();
-        assert_ne!(last_block.block_number, 0);
+        assert_eq!(last_block.block_number, 0);
         let nonce = Nonce(Felt::from_dec_str("10000000000000000").expect("failed to parse nonce string"));
-        assert!(db.backend().has_l1_messaging_nonce(nonce).unwrap());
+        assert!(!db.backend().has_l1_messaging_nonce(nonce).unwrap());
+
+        // Assert that the tx has been included in the mempool
+        let mempool_tx = mempool.take_tx().expect("L1 handler transaction should be in the mempool");
+        assert_eq!(mempool_tx.nonce(), nonce);
+        assert!(mempool.take_tx().is_none());
+
+        // Assert that the message can be found from the L1 transaction hash
+        assert_eq!(db.backend().get_l1_tx_messaging_nonces(&l1_tx_hash.0).unwrap(), vec![nonce]);
+        let message = db.backend().get_l1_message(nonce).unwrap().expect("L1 message should be stored");
+        assert_eq!(message.tx_hash, *mempool_tx.tx_hash());
+        assert_ne!(message.l1_block_number, 0);
         // TODO : Assert that the tx was correctly executed
 
         // Explicitly cancel the listen task, else it would be running in the background
         worker_handle.abort();
     }
 
+    /// Test that a message sent in an L1 block which is reorged out is rolled back
+    ///
+    /// This test performs the following steps:
+    /// 1. Sets up test environemment and starts worker
+    /// 2. Fires a Message event from the dummy contract, after taking an L1 snapshot
+    /// 3. Reverts the L1 chain to the snapshot, which drops the L1 block with the event
+    /// 4. Assert that the message is removed, so that the mempool transaction is dropped by block production
+    /// 5. Fires the Message again in the new L1 chain
+    /// 6. Assert that the message is stored again, and that the mempool transaction is not duplicated
+    #[rstest]
+    #[traced_test]
+    #[tokio::test]
+    async fn e2e_test_l1_reorg(#[future] setup_test_env: TestRunner, _set_workdir: ()) {
+        let TestRunner { chain_config, db_service: db, mempool, dummy_contract: contract, eth_client, anvil: _anvil } =
+            setup_test_env.await;
+        let provider = eth_client.provider.clone();
+
+        // Start worker
+        let worker_handle = {
+            let db = Arc::clone(&db);
+            let mempool = Arc::clone(&mempool);
+            tokio::spawn(async move {
+                sync(db.backend(), &eth_client, &chain_config.chain_id, mempool, &events_config()).await
+            })
+        };
+
+        let snapshot: U256 = provider.raw_request("evm_snapshot".into(), ()).await.unwrap();
+        let l1_tx_hash = *contract.fireEvent().send().await.expect("Failed to fire event").tx_hash();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        let nonce = Nonce(Felt::from_dec_str("10000000000000000").expect("failed to parse nonce string"));
+        assert!(db.backend().get_l1_message(nonce).unwrap().is_some());
+
+        // Drop the L1 block with the message
+        let reverted: bool = provider.raw_request("evm_revert".into(), (snapshot,)).await.unwrap();
+        assert!(reverted);
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        assert!(logs_contain("L1 reorg detected"));
+        assert_eq!(db.backend().get_l1_message(nonce).unwrap(), None);
+        assert_eq!(db.backend().get_l1_tx_messaging_nonces(&l1_tx_hash.0).unwrap(), vec![]);
+        assert_eq!(db.backend().get_latest_l1_message().unwrap(), None);
+        let last_block =
+            db.backend().messaging_last_synced_l1_block_with_event().expect("failed to retrieve block").unwrap();
+        assert_eq!(last_block.block_number, 0);
+
+        // The message is sent again in the new L1 chain
+        contract.fireEvent().send().await.expect("Failed to fire event");
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert!(db.backend().get_l1_message(nonce).unwrap().is_some());
+        let mempool_tx = mempool.take_tx().expect("L1 handler transaction should be in the mempool");
+        assert_eq!(mempool_tx.nonce(), nonce);
+        assert!(mempool.take_tx().is_none());
+
+        worker_handle.abort();
+    }
+
     /// Test the workflow of l1 -> l2 messaging with duplicate event
     ///
     /// This test performs the following steps:
     /// 1. Sets up test environemment
     /// 2. Starts worker
     /// 3. Fires a Message event from the dummy contract
-    /// 4. Waits for event to be processed
+    /// 4. Waits for event to be processed, and stores its transaction in a closed block
     /// 5. Assert that the event is well stored in db
     /// 6. Fires a Message with the same event from the dummy contract
     /// 7. Assert that the last event stored is the first one
@@ -439,18 +682,23 @@ mod l1_messaging_tests {
     #[traced_test]
     #[tokio::test]
     async fn e2e_test_already_processed_event(#[future] setup_test_env: TestRunner, _set_workdir: ()) {
-        let TestRunner { chain_config, db_service: db, dummy_contract: contract, eth_client, anvil: _anvil } =
+        let TestRunner { chain_config, db_service: db, mempool, dummy_contract: contract, eth_client, anvil: _anvil } =
             setup_test_env.await;
 
         // Start worker
         let worker_handle = {
             let db = Arc::clone(&db);
-            tokio::spawn(async move { sync(db.backend(), &eth_client, &chain_config.chain_id).await })
+            let mempool = Arc::clone(&mempool);
+            tokio::spawn(async move {
+                sync(db.backend(), &eth_client, &chain_config.chain_id, mempool, &events_config()).await
+            })
         };
 
         let _ = contract.setIsCanceled(false).send().await;
         let _ = contract.fireEvent().send().await.expect("Failed to fire event");
         tokio::time::sleep(Duration::from_secs(5)).await;
+        let mempool_tx = mempool.take_tx().expect("L1 handler transaction should be in the mempool");
+        store_closed_block(db.backend(), 0, mempool_tx);
         let last_block =
             db.backend().messaging_last_synced_l1_block_with_event().expect("failed to retrieve block").unwrap();
         assert_ne!(last_block.block_number, 0);
@@ -460,6 +708,7 @@ mod l1_messaging_tests {
         // Send the event a second time
         let _ = contract.fireEvent().send().await.expect("Failed to fire event");
         tokio::time::sleep(Duration::from_secs(5)).await;
+        assert!(mempool.take_tx().is_none());
         // Assert that the last event in db is still the same as it is already processed (same nonce)
         assert_eq!(
             last_block.block_number,
@@ -474,6 +723,69 @@ mod l1_messaging_tests {
         worker_handle.abort();
     }
 
+    /// Test that a message is delivered again after a restart until its transaction is in a closed block
+    ///
+    /// This test performs the following steps:
+    /// 1. Sets up test environemment and starts worker
+    /// 2. Fires a Message event from the dummy contract, and stops the worker while the message is in the mempool
+    /// 3. Restarts the worker with an empty mempool, as after a node restart
+    /// 4. Assert that the message is submitted to the mempool again
+    /// 5. Stores the transaction in a closed block, and restarts the worker with an empty mempool
+    /// 6. Assert that the message is not submitted again, and that the last synced event block moved past it
+    #[rstest]
+    #[traced_test]
+    #[tokio::test]
+    async fn e2e_test_message_delivered_after_restart(#[future] setup_test_env: TestRunner, _set_workdir: ()) {
+        let TestRunner { chain_config, db_service: db, mempool, dummy_contract: contract, eth_client, anvil: _anvil } =
+            setup_test_env.await;
+        let eth_client = Arc::new(eth_client);
+        let new_mempool = || {
+            Arc::new(Mempool::new(
+                Arc::clone(db.backend()),
+                Arc::new(GasPriceProvider::new()),
+                Arc::new(ContractClassCache::for_testing()),
+            ))
+        };
+        let start_worker = |mempool: Arc<Mempool>| {
+            let (db, eth_client, chain_config) = (Arc::clone(&db), Arc::clone(&eth_client), Arc::clone(&chain_config));
+            tokio::spawn(async move {
+                sync(db.backend(), &eth_client, &chain_config.chain_id, mempool, &events_config()).await
+            })
+        };
+        let nonce = Nonce(Felt::from_dec_str("10000000000000000").expect("failed to parse nonce string"));
+
+        let worker_handle = start_worker(Arc::clone(&mempool));
+        let _ = contract.setIsCanceled(false).send().await;
+        let _ = contract.fireEvent().send().await.expect("Failed to fire event");
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        worker_handle.abort();
+        let first_tx = mempool.take_tx().expect("L1 handler transaction should be in the mempool");
+        assert_eq!(first_tx.nonce(), nonce);
+
+        // The node restarts before the transaction is in a closed block: the mempool is lost
+        let mempool = new_mempool();
+        let worker_handle = start_worker(Arc::clone(&mempool));
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        worker_handle.abort();
+        let mempool_tx = mempool.take_tx().expect("L1 handler transaction should be submitted again");
+        assert_eq!(mempool_tx.tx_hash(), first_tx.tx_hash());
+        assert!(mempool.take_tx().is_none());
+
+        // The transaction is in a closed block: the message is consumed
+        store_closed_block(db.backend(), 0, mempool_tx);
+        assert!(db.backend().has_l1_messaging_nonce(nonce).unwrap());
+        let last_block =
+            db.backend().messaging_last_synced_l1_block_with_event().expect("failed to retrieve block").unwrap();
+        assert_ne!(last_block.block_number, 0);
+
+        let mempool = new_mempool();
+        let worker_handle = start_worker(Arc::clone(&mempool));
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        worker_handle.abort();
+        assert!(mempool.take_tx().is_none());
+        assert!(logs_contain("Event already processed"));
+    }
+
     /// Test the workflow of l1 -> l2 messaging with message cancelled
     ///
     /// This test performs the following steps:
@@ -486,13 +798,16 @@ mod l1_messaging_tests {
     #[traced_test]
     #[tokio::test]
     async fn e2e_test_message_canceled(#[future] setup_test_env: TestRunner, _set_workdir: ()) {
-        let TestRunner { chain_config, db_service: db, dummy_contract: contract, eth_client, anvil: _anvil } =
+        let TestRunner { chain_config, db_service: db, mempool, dummy_contract: contract, eth_client, anvil: _anvil } =
             setup_test_env.await;
 
         // Start worker
         let worker_handle = {
             let db = Arc::clone(&db);
-            tokio::spawn(async move { sync(db.backend(), &eth_client, &chain_config.chain_id).await })
+            let mempool = Arc::clone(&mempool);
+            tokio::spawn(async move {
+                sync(db.backend(), &eth_client, &chain_config.chain_id, mempool, &events_config()).await
+            })
         };
 
         // Mock cancelled message
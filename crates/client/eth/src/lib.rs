@@ -2,6 +2,8 @@ pub mod client;
 pub mod error;
 pub mod l1_gas_price;
 pub mod l1_messaging;
+pub mod oracle;
+pub mod settlement;
 pub mod state_update;
 pub mod sync;
 pub mod utils;
@@ -0,0 +1,284 @@
+//! Price oracles used to convert the L1 gas prices, which are in wei, to fri. Both ETH and STRK have 18 decimals, so
+//! a price in wei converts to fri with the ETH/STRK exchange rate.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::{Address, U256};
+use alloy::providers::ReqwestProvider;
+use alloy::sol;
+use anyhow::{bail, Context};
+use serde::Deserialize;
+use url::Url;
+
+/// Price of one ETH in STRK, as a fixed point number: `price / 10^decimals`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthStrkRate {
+    pub price: u128,
+    pub decimals: u32,
+}
+
+impl EthStrkRate {
+    /// Rates with more decimals than this are rejected: `10^decimals` would not fit in a `u128`.
+    pub const MAX_DECIMALS: u32 = 38;
+
+    pub fn new(price: u128, decimals: u32) -> anyhow::Result<Self> {
+        if decimals > Self::MAX_DECIMALS {
+            bail!("ETH/STRK rate has {decimals} decimals, the maximum is {}", Self::MAX_DECIMALS);
+        }
+        Ok(Self { price, decimals })
+    }
+
+    pub fn wei_to_fri(&self, wei: u128) -> anyhow::Result<u128> {
+        let fri = U256::from(wei) * U256::from(self.price) / U256::from(10u8).pow(U256::from(self.decimals));
+        fri.try_into().with_context(|| format!("Converting {wei} wei to fri overflows"))
+    }
+}
+
+/// Parses a decimal number such as `2500` or `2512.75`.
+impl FromStr for EthStrkRate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if integer.is_empty() || !(integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())) {
+            bail!("Invalid ETH/STRK rate {s:?}");
+        }
+        let price =
+            format!("{integer}{fraction}").parse().with_context(|| format!("ETH/STRK rate {s:?} is too large"))?;
+        Self::new(price, fraction.len() as u32)
+    }
+}
+
+impl fmt::Display for EthStrkRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.decimals == 0 {
+            return write!(f, "{}", self.price);
+        }
+        let scale = 10u128.pow(self.decimals);
+        write!(f, "{}.{:0width$}", self.price / scale, self.price % scale, width = self.decimals as usize)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait Oracle: Send + Sync {
+    /// Latest ETH/STRK rate. Oracles returning a rate older than their maximum age return an error instead.
+    async fn fetch_eth_strk_rate(&self) -> anyhow::Result<EthStrkRate>;
+}
+
+fn check_fresh(updated_at: SystemTime, max_age: Duration) -> anyhow::Result<()> {
+    let age = SystemTime::now().duration_since(updated_at).unwrap_or_default();
+    if age > max_age {
+        bail!("ETH/STRK rate is stale: it was last updated {}s ago", age.as_secs());
+    }
+    Ok(())
+}
+
+/// Always returns the same rate, for devnets and chains where STRK is not traded.
+pub struct FixedRateOracle(pub EthStrkRate);
+
+#[async_trait::async_trait]
+impl Oracle for FixedRateOracle {
+    async fn fetch_eth_strk_rate(&self) -> anyhow::Result<EthStrkRate> {
+        Ok(self.0)
+    }
+}
+
+/// Reads the ETH/STRK price from an HTTP price feed returning
+/// `{"price": "<hex>", "decimals": <decimals>, "timestamp": <unix time in ms>}`, which is the format of the
+/// [Pragma](https://docs.pragma.build) offchain API.
+pub struct HttpOracle {
+    client: reqwest::Client,
+    url: Url,
+    api_key: Option<String>,
+    max_age: Duration,
+}
+
+#[derive(Deserialize)]
+struct HttpOracleResponse {
+    price: String,
+    decimals: u32,
+    timestamp: u64,
+}
+
+impl HttpOracle {
+    /// The API key is sent in the `x-api-key` header.
+    pub fn new(url: Url, api_key: Option<String>, max_age: Duration) -> Self {
+        Self { client: reqwest::Client::new(), url, api_key, max_age }
+    }
+}
+
+#[async_trait::async_trait]
+impl Oracle for HttpOracle {
+    async fn fetch_eth_strk_rate(&self) -> anyhow::Result<EthStrkRate> {
+        let mut request = self.client.get(self.url.clone());
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-api-key", api_key);
+        }
+        let response: HttpOracleResponse = request
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .context("Querying the ETH/STRK price feed")?
+            .json()
+            .await
+            .context("Parsing the ETH/STRK price feed response")?;
+
+        check_fresh(UNIX_EPOCH + Duration::from_millis(response.timestamp), self.max_age)?;
+        let price = u128::from_str_radix(response.price.trim_start_matches("0x"), 16)
+            .with_context(|| format!("Invalid ETH/STRK price {:?}", response.price))?;
+        EthStrkRate::new(price, response.decimals)
+    }
+}
+
+sol!(
+    #[sol(rpc)]
+    interface AggregatorV3Interface {
+        function decimals() external view returns (uint8);
+        function latestRoundData() external view returns (
+            uint80 roundId,
+            int256 answer,
+            uint256 startedAt,
+            uint256 updatedAt,
+            uint80 answeredInRound
+        );
+    }
+);
+
+/// Reads the ETH/STRK price from a Chainlink-compatible aggregator contract on L1.
+pub struct L1ContractOracle {
+    contract: AggregatorV3Interface::AggregatorV3InterfaceInstance<
+        alloy::transports::http::Http<alloy::transports::http::Client>,
+        ReqwestProvider,
+    >,
+    max_age: Duration,
+}
+
+impl L1ContractOracle {
+    pub fn new(provider: ReqwestProvider, address: Address, max_age: Duration) -> Self {
+        Self { contract: AggregatorV3Interface::new(address, provider), max_age }
+    }
+}
+
+#[async_trait::async_trait]
+impl Oracle for L1ContractOracle {
+    async fn fetch_eth_strk_rate(&self) -> anyhow::Result<EthStrkRate> {
+        let decimals = self.contract.decimals().call().await.context("Getting the ETH/STRK oracle decimals")?._0;
+        let round = self.contract.latestRoundData().call().await.context("Getting the ETH/STRK oracle price")?;
+
+        let updated_at: u64 = round.updatedAt.try_into().context("Invalid ETH/STRK oracle timestamp")?;
+        check_fresh(UNIX_EPOCH + Duration::from_secs(updated_at), self.max_age)?;
+        if round.answer.is_negative() {
+            bail!("Negative ETH/STRK price {}", round.answer);
+        }
+        let price = round.answer.into_raw().try_into().context("ETH/STRK price overflows")?;
+        EthStrkRate::new(price, decimals.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::MockServer;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("2500", EthStrkRate { price: 2500, decimals: 0 })]
+    #[case("2512.75", EthStrkRate { price: 251275, decimals: 2 })]
+    #[case("0.5", EthStrkRate { price: 5, decimals: 1 })]
+    fn test_parse_rate(#[case] s: &str, #[case] expected: EthStrkRate) {
+        let rate: EthStrkRate = s.parse().unwrap();
+        assert_eq!(rate, expected);
+        assert_eq!(rate.to_string(), s);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case(".5")]
+    #[case("12a")]
+    #[case("-3")]
+    #[case("0.000000000000000000000000000000000000001")]
+    fn test_parse_invalid_rate(#[case] s: &str) {
+        assert!(s.parse::<EthStrkRate>().is_err());
+    }
+
+    #[test]
+    fn test_wei_to_fri() {
+        let rate = EthStrkRate { price: 251275, decimals: 2 };
+        assert_eq!(rate.wei_to_fri(1_000_000_000).unwrap(), 2_512_750_000_000);
+        assert!(EthStrkRate { price: u128::MAX, decimals: 0 }.wei_to_fri(2).is_err());
+    }
+
+    fn now_ms() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+    }
+
+    #[tokio::test]
+    async fn test_http_oracle() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method("GET").path("/eth/strk").header("x-api-key", "secret");
+            then.status(200).json_body(serde_json::json!({
+                "num_sources_aggregated": 3,
+                "pair_id": "ETH/STRK",
+                "price": "0x878678326eac900000",
+                "timestamp": now_ms(),
+                "decimals": 18
+            }));
+        });
+
+        let oracle =
+            HttpOracle::new(server.url("/eth/strk").parse().unwrap(), Some("secret".into()), Duration::from_secs(60));
+        let rate = oracle.fetch_eth_strk_rate().await.unwrap();
+        assert_eq!(rate, EthStrkRate { price: 2_500_000_000_000_000_000_000, decimals: 18 });
+        assert_eq!(rate.wei_to_fri(10).unwrap(), 25_000);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_http_oracle_stale() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method("GET");
+            then.status(200).json_body(serde_json::json!({
+                "price": "0x9c4",
+                "timestamp": now_ms() - 120_000,
+                "decimals": 0
+            }));
+        });
+
+        let oracle = HttpOracle::new(server.url("/").parse().unwrap(), None, Duration::from_secs(60));
+        let err = oracle.fetch_eth_strk_rate().await.unwrap_err();
+        assert!(err.to_string().contains("stale"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn test_http_oracle_too_many_decimals() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method("GET");
+            then.status(200).json_body(serde_json::json!({
+                "price": "0x9c4",
+                "timestamp": now_ms(),
+                "decimals": 78
+            }));
+        });
+
+        let oracle = HttpOracle::new(server.url("/").parse().unwrap(), None, Duration::from_secs(60));
+        let err = oracle.fetch_eth_strk_rate().await.unwrap_err();
+        assert!(err.to_string().contains("decimals"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn test_http_oracle_error() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method("GET");
+            then.status(503);
+        });
+
+        let oracle = HttpOracle::new(server.url("/").parse().unwrap(), None, Duration::from_secs(60));
+        assert!(oracle.fetch_eth_strk_rate().await.is_err());
+    }
+}
@@ -0,0 +1,559 @@
+//! Settlement of the blocks produced by this node, for appchains: the state update of every closed block is sent to
+//! the `updateState` function of the core contract, in order.
+//!
+//! No proof is sent: the core contract must accept the state updates of the sequencer as they are, which is the case
+//! of the core contracts deployed for development.
+
+use std::collections::BTreeSet;
+use std::iter;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use alloy::eips::BlockNumberOrTag;
+use alloy::network::EthereumWallet;
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::transports::http::{Client, Http};
+use anyhow::Context;
+use mc_db::db_block_id::DbBlockId;
+use mc_db::l1_db::{SettlementStatus, SettlementTransaction};
+use mc_db::MadaraBackend;
+use mp_block::{MadaraBlock, MadaraBlockInfo};
+use mp_state_update::da::encode_state_diff;
+use mp_state_update::{NonceUpdate, StateDiff};
+use mp_transactions::Transaction;
+use mp_utils::wait_or_graceful_shutdown;
+use starknet_types_core::felt::Felt;
+use url::Url;
+
+use crate::client::StarknetCoreContract::{self, StarknetCoreContractInstance};
+use crate::utils::felt_to_u256;
+
+#[derive(Clone)]
+pub struct SettlementConfig {
+    pub l1_endpoint: Url,
+    /// Core contract the state updates are sent to.
+    pub core_contract: Address,
+    /// Account sending the `updateState` transactions.
+    pub signer: PrivateKeySigner,
+    pub poll_interval: Duration,
+    /// A state update which is still not in an L1 block after this time is sent again with higher fees.
+    pub resubmit_after: Duration,
+    /// Time to wait before sending a state update which failed again. It doubles after every failure.
+    pub retry_backoff: Duration,
+    /// Settlement stops once the state update of a block failed this many times in a row.
+    pub max_attempts: u32,
+}
+
+pub async fn settlement_worker(backend: &MadaraBackend, config: &SettlementConfig) -> anyhow::Result<()> {
+    log::info!("⟠ Settling blocks on the L1 core contract {}", config.core_contract);
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(EthereumWallet::from(config.signer.clone()))
+        .on_http(config.l1_endpoint.clone());
+    let core_contract = StarknetCoreContract::new(config.core_contract, provider);
+    let sender = config.signer.address();
+
+    loop {
+        if let Some((block_n, SettlementStatus::Failed { reason, attempts, .. })) =
+            backend.get_latest_settlement_status()?
+        {
+            if attempts >= config.max_attempts {
+                anyhow::bail!("The state update of block #{block_n} failed {attempts} times, giving up: {reason}");
+            }
+        }
+        if let Err(err) = settle_blocks(backend, &core_contract, sender, config).await {
+            log::warn!("⟠ Failed to settle blocks on L1, retrying on the next poll: {err:#}");
+        }
+        if wait_or_graceful_shutdown(tokio::time::sleep(config.poll_interval)).await.is_none() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends the state update of the next block to settle, once the state update of the previous block succeeded on L1.
+/// A state update which stays pending for longer than [`SettlementConfig::resubmit_after`] is sent again with higher
+/// fees, and a state update which failed is sent again after [`SettlementConfig::retry_backoff`].
+async fn settle_blocks<P: Provider<Http<Client>>>(
+    backend: &MadaraBackend,
+    core_contract: &StarknetCoreContractInstance<Http<Client>, P>,
+    sender: Address,
+    config: &SettlementConfig,
+) -> anyhow::Result<()> {
+    loop {
+        let (block_n, previous_failures) = match backend.get_latest_settlement_status()? {
+            None => (0, 0),
+            Some((block_n, SettlementStatus::Confirmed { .. })) => (block_n + 1, 0),
+            Some((block_n, SettlementStatus::Failed { attempts, failed_at, .. })) => {
+                // The worker stops once too many attempts failed.
+                let retry_at = failed_at.saturating_add(retry_delay(config.retry_backoff, attempts).as_secs());
+                if attempts >= config.max_attempts || unix_now() < retry_at {
+                    return Ok(());
+                }
+                (block_n, attempts)
+            }
+            Some((block_n, SettlementStatus::Submitted { transaction, replaced })) => {
+                // Any of the transactions sent with this nonce may be the one which was included.
+                let mut receipt = None;
+                for tx_hash in iter::once(transaction.l1_transaction_hash).chain(replaced.iter().copied()) {
+                    receipt = core_contract.provider().get_transaction_receipt(B256::from(tx_hash)).await?;
+                    if receipt.is_some() {
+                        break;
+                    }
+                }
+                let Some(receipt) = receipt else {
+                    if unix_now() >= transaction.sent_at.saturating_add(config.resubmit_after.as_secs()) {
+                        log::warn!(
+                            "⟠ The state update of block #{block_n} is still pending on L1, sending it again with \
+                             higher fees"
+                        );
+                        let previous_failures = transaction.previous_failures;
+                        let replacing = Some((transaction, replaced));
+                        submit_block(backend, core_contract, sender, block_n, previous_failures, replacing).await?;
+                    }
+                    return Ok(());
+                };
+                let l1_transaction_hash = receipt.transaction_hash.0;
+                let status = if receipt.status() {
+                    let l1_block_number = receipt.block_number.context("Receipt without a block number")?;
+                    log::info!("⟠ Block #{block_n} settled on L1 in L1 block #{l1_block_number}");
+                    SettlementStatus::Confirmed { l1_transaction_hash, l1_block_number }
+                } else {
+                    log::warn!("⟠ The state update of block #{block_n} reverted on L1");
+                    SettlementStatus::Failed {
+                        reason: "The updateState transaction reverted".into(),
+                        attempts: transaction.previous_failures + 1,
+                        failed_at: unix_now(),
+                    }
+                };
+                backend.set_settlement_status(block_n, &status)?;
+                continue;
+            }
+        };
+
+        submit_block(backend, core_contract, sender, block_n, previous_failures, None).await?;
+        return Ok(());
+    }
+}
+
+/// Sends the state update of a closed block. Nothing is sent when the block is not closed yet. `previous_failures` is
+/// the number of failed attempts at settling the block, and `replacing` is the pending transaction to replace along
+/// with the transactions it replaced: the new transaction reuses its nonce.
+async fn submit_block<P: Provider<Http<Client>>>(
+    backend: &MadaraBackend,
+    core_contract: &StarknetCoreContractInstance<Http<Client>, P>,
+    sender: Address,
+    block_n: u64,
+    previous_failures: u32,
+    replacing: Option<(SettlementTransaction, Vec<[u8; 32]>)>,
+) -> anyhow::Result<()> {
+    let Some(block) = backend.get_block(&DbBlockId::BlockN(block_n))? else { return Ok(()) };
+    let block = MadaraBlock::try_from(block).context("Block to settle is pending")?;
+    let parent = match block_n.checked_sub(1) {
+        Some(parent_n) => Some(
+            backend
+                .get_block_info(&DbBlockId::BlockN(parent_n))?
+                .and_then(|info| info.as_nonpending().cloned())
+                .context("Getting the parent of the block to settle")?,
+        ),
+        None => None,
+    };
+    let state_diff =
+        backend.get_block_state_diff(&DbBlockId::BlockN(block_n))?.context("Getting the state diff of the block")?;
+
+    let da = encode_state_diff(&with_current_nonces(backend, block_n, state_diff)?)?;
+    let program_output = program_output(&block, parent.as_ref(), &da);
+    let da_hash = keccak256(da.iter().flat_map(|felt| felt.to_bytes_be()).collect::<Vec<_>>());
+
+    let provider = core_contract.provider();
+    let fees = provider.estimate_eip1559_fees(None).await.context("Estimating the L1 fees")?;
+    let (nonce, max_fee_per_gas, max_priority_fee_per_gas, replaced) = match replacing {
+        Some((previous, mut replaced)) => {
+            replaced.push(previous.l1_transaction_hash);
+            (
+                previous.nonce,
+                fees.max_fee_per_gas.max(bump_fee(previous.max_fee_per_gas)),
+                fees.max_priority_fee_per_gas.max(bump_fee(previous.max_priority_fee_per_gas)),
+                replaced,
+            )
+        }
+        None => {
+            let nonce = provider
+                .get_transaction_count(sender)
+                .block_id(BlockNumberOrTag::Pending.into())
+                .await
+                .context("Getting the nonce of the settlement account")?;
+            (nonce, fees.max_fee_per_gas, fees.max_priority_fee_per_gas, vec![])
+        }
+    };
+    let is_replacement = !replaced.is_empty();
+
+    let call = core_contract
+        .updateState(
+            program_output.into_iter().map(felt_to_u256).collect(),
+            U256::from_be_bytes(da_hash.0),
+            U256::from(da.len()),
+        )
+        .nonce(nonce)
+        .max_fee_per_gas(max_fee_per_gas)
+        .max_priority_fee_per_gas(max_priority_fee_per_gas);
+    match call.send().await {
+        Ok(pending) => {
+            log::info!("⟠ Sent the state update of block #{block_n} to L1 in transaction {}", pending.tx_hash());
+            let transaction = SettlementTransaction {
+                l1_transaction_hash: pending.tx_hash().0,
+                nonce,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                sent_at: unix_now(),
+                previous_failures,
+            };
+            backend.set_settlement_status(block_n, &SettlementStatus::Submitted { transaction, replaced })?;
+            Ok(())
+        }
+        // The transactions sent before are still pending: they are checked again on the next poll.
+        Err(err) if is_replacement => {
+            Err(err).with_context(|| format!("Sending the state update of block #{block_n} again"))
+        }
+        Err(err) => {
+            let status = SettlementStatus::Failed {
+                reason: format!("{err:#}"),
+                attempts: previous_failures + 1,
+                failed_at: unix_now(),
+            };
+            backend.set_settlement_status(block_n, &status)?;
+            Err(err).with_context(|| format!("Sending the state update of block #{block_n}"))
+        }
+    }
+}
+
+/// Raises a fee by 25%. L1 nodes only accept a transaction replacing a pending one when both its fees are at least 10%
+/// higher, the rest is a margin for the base fee to rise.
+fn bump_fee(fee: u128) -> u128 {
+    fee.saturating_add(fee / 4).saturating_add(1)
+}
+
+/// Time to wait before sending a state update again after `attempts` failures in a row.
+fn retry_delay(backoff: Duration, attempts: u32) -> Duration {
+    backoff.saturating_mul(1 << attempts.saturating_sub(1).min(16))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// The data availability contains the nonce of every updated contract: the current nonces of the contracts with
+/// storage or class updates which did not send a transaction in the block are added to the state diff.
+fn with_current_nonces(backend: &MadaraBackend, block_n: u64, mut state_diff: StateDiff) -> anyhow::Result<StateDiff> {
+    let mut missing: BTreeSet<Felt> = state_diff
+        .storage_diffs
+        .iter()
+        .map(|diff| diff.address)
+        .chain(state_diff.deployed_contracts.iter().map(|deployed| deployed.address))
+        .chain(state_diff.replaced_classes.iter().map(|replaced| replaced.contract_address))
+        .collect();
+    for nonce in &state_diff.nonces {
+        missing.remove(&nonce.contract_address);
+    }
+
+    for contract_address in missing {
+        let nonce =
+            backend.get_contract_nonce_at(&DbBlockId::BlockN(block_n), &contract_address)?.unwrap_or(Felt::ZERO);
+        state_diff.nonces.push(NonceUpdate { contract_address, nonce });
+    }
+    Ok(state_diff)
+}
+
+/// Starknet OS program output of a block, with the layout the core contract expects: the header, the messages to L1
+/// and the messages to L2. As no proof is sent, the OS program hash and config hash are zero. The data availability
+/// `da` is sent as calldata after the messages.
+pub fn program_output(block: &MadaraBlock, parent: Option<&MadaraBlockInfo>, da: &[Felt]) -> Vec<Felt> {
+    let header = &block.info.header;
+    let (prev_root, prev_block_hash) =
+        parent.map(|parent| (parent.header.global_state_root, parent.block_hash)).unwrap_or_default();
+
+    let messages_to_l1: Vec<Felt> = block
+        .inner
+        .receipts
+        .iter()
+        .flat_map(|receipt| receipt.messages_sent())
+        .flat_map(|message| {
+            [message.from_address, message.to_address, Felt::from(message.payload.len() as u64)]
+                .into_iter()
+                .chain(message.payload.iter().copied())
+        })
+        .collect();
+    let messages_to_l2: Vec<Felt> = block
+        .inner
+        .transactions
+        .iter()
+        .filter_map(|tx| match tx {
+            Transaction::L1Handler(tx) => Some(tx),
+            _ => None,
+        })
+        .flat_map(|tx| {
+            // The first calldata element of an L1 handler is the L1 sender.
+            let (from_address, payload) =
+                tx.calldata.split_first().map(|(from, payload)| (*from, payload)).unwrap_or_default();
+            [
+                from_address,
+                tx.contract_address,
+                Felt::from(tx.nonce),
+                tx.entry_point_selector,
+                Felt::from(payload.len() as u64),
+            ]
+            .into_iter()
+            .chain(payload.iter().copied())
+        })
+        .collect();
+
+    let mut output = vec![
+        prev_root,
+        header.global_state_root,
+        // -1 for the genesis block
+        Felt::from(header.block_number) - Felt::ONE,
+        Felt::from(header.block_number),
+        prev_block_hash,
+        block.info.block_hash,
+        // OS program hash and config hash
+        Felt::ZERO,
+        Felt::ZERO,
+        // use_kzg_da and full_output
+        Felt::ZERO,
+        Felt::ZERO,
+    ];
+    output.push(Felt::from(messages_to_l1.len() as u64));
+    output.extend(messages_to_l1);
+    output.push(Felt::from(messages_to_l2.len() as u64));
+    output.extend(messages_to_l2);
+    output.extend_from_slice(da);
+    output
+}
+
+#[cfg(test)]
+mod settlement_tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use alloy::node_bindings::{Anvil, AnvilInstance};
+    use alloy::primitives::Bytes;
+    use alloy::sol_types::SolCall;
+    use mp_block::{Header, MadaraBlockInner};
+    use mp_chain_config::ChainConfig;
+    use mp_state_update::{ContractStorageDiffItem, StorageEntry};
+    use rstest::*;
+
+    /// First account of anvil.
+    const ANVIL_PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const CORE_CONTRACT: Address = Address::repeat_byte(0x42);
+
+    /// Mock core contract implementing `updateState`. The header of the program output must follow the last state
+    /// update: its previous state root, block number and previous block hash are checked against the storage, which
+    /// holds the state root in slot 0, the next block number in slot 1 and the block hash in slot 2. The new state is
+    /// then stored. It reverts otherwise, and for every other function.
+    fn mock_core_contract_code() -> Bytes {
+        let selector = alloy::hex::encode(StarknetCoreContract::updateStateCall::SELECTOR);
+        let code = [
+            // Dispatch on the selector
+            "60003560e01c63",
+            &selector,
+            "14601357600080fd",
+            // The program output starts at 4 + calldataload(4), and has at least the 12 header elements
+            "5b6004356004018035600c11606557",
+            // output[0] == sload(0), output[3] == sload(1) and output[4] == sload(2)
+            "80602001356000541415606557",
+            "80608001356001541415606557",
+            "8060a001356002541415606557",
+            // sstore(0, output[1]), sstore(1, output[3] + 1) and sstore(2, output[5])
+            "8060400135600055",
+            "8060800135600101600155",
+            "8060c00135600255",
+            "00",
+            // Failed check
+            "5b600080fd",
+        ]
+        .concat();
+        alloy::hex::decode(code).unwrap().into()
+    }
+
+    async fn deploy_mock_core_contract(anvil: &AnvilInstance) -> impl Provider<Http<Client>> {
+        let provider = ProviderBuilder::new().on_http(anvil.endpoint().parse().unwrap());
+        let () =
+            provider.raw_request("anvil_setCode".into(), (CORE_CONTRACT, mock_core_contract_code())).await.unwrap();
+        provider
+    }
+
+    fn settlement_config(anvil: &AnvilInstance, resubmit_after: Duration) -> SettlementConfig {
+        SettlementConfig {
+            l1_endpoint: anvil.endpoint().parse().unwrap(),
+            core_contract: CORE_CONTRACT,
+            signer: ANVIL_PRIVATE_KEY.parse().unwrap(),
+            poll_interval: Duration::from_millis(100),
+            resubmit_after,
+            retry_backoff: Duration::from_secs(60),
+            max_attempts: 5,
+        }
+    }
+
+    fn store_block(backend: &MadaraBackend, block_number: u64, state_diff: StateDiff) {
+        let header = Header { block_number, global_state_root: Felt::from(0x100 + block_number), ..Default::default() };
+        let block = MadaraBlock::new(
+            MadaraBlockInfo::new(header, vec![], Felt::from(0x200 + block_number)),
+            MadaraBlockInner::new(vec![], vec![]),
+        );
+        backend.store_block(block.into(), state_diff, vec![]).unwrap();
+    }
+
+    async fn storage<P: Provider<Http<Client>>>(provider: &P, slot: u64) -> U256 {
+        provider.get_storage_at(CORE_CONTRACT, U256::from(slot)).await.unwrap()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn settlement_worker_settles_closed_blocks() {
+        let anvil = Anvil::new().try_spawn().expect("failed to spawn anvil instance");
+        let provider = deploy_mock_core_contract(&anvil).await;
+
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::test_config().unwrap()));
+        let state_diff = StateDiff {
+            storage_diffs: vec![ContractStorageDiffItem {
+                address: Felt::ONE,
+                storage_entries: vec![StorageEntry { key: Felt::TWO, value: Felt::THREE }],
+            }],
+            ..Default::default()
+        };
+        store_block(&backend, 0, state_diff);
+        store_block(&backend, 1, StateDiff::default());
+
+        let config = settlement_config(&anvil, Duration::from_secs(300));
+        let worker_handle = {
+            let backend = Arc::clone(&backend);
+            tokio::spawn(async move { settlement_worker(&backend, &config).await })
+        };
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        worker_handle.abort();
+
+        let Some(SettlementStatus::Confirmed { l1_transaction_hash, .. }) = backend.get_settlement_status(0).unwrap()
+        else {
+            panic!("Block #0 should be settled");
+        };
+        assert!(matches!(backend.get_settlement_status(1).unwrap(), Some(SettlementStatus::Confirmed { .. })));
+        assert_eq!(backend.get_settlement_status(2).unwrap(), None);
+
+        // The mock core contract accepted both state updates, in order.
+        assert_eq!(storage(&provider, 0).await, U256::from(0x101));
+        assert_eq!(storage(&provider, 1).await, U256::from(2));
+        assert_eq!(storage(&provider, 2).await, U256::from(0x201));
+
+        let tx = provider.get_transaction_by_hash(B256::from(l1_transaction_hash)).await.unwrap().unwrap();
+        assert_eq!(tx.to, Some(CORE_CONTRACT));
+        let call = StarknetCoreContract::updateStateCall::abi_decode(&tx.input, true).unwrap();
+        let program_output: Vec<Felt> =
+            call.programOutput.iter().map(|value| Felt::from_bytes_be(&value.to_be_bytes())).collect();
+        // One contract with nonce 0 and one storage update, and no declared classes
+        let da = [Felt::ONE, Felt::ONE, Felt::ONE, Felt::TWO, Felt::THREE, Felt::ZERO];
+        #[rustfmt::skip]
+        let expected = [
+            Felt::ZERO, Felt::from(0x100), Felt::MAX, Felt::ZERO, Felt::ZERO, Felt::from(0x200),
+            Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ZERO,
+            // no messages
+            Felt::ZERO, Felt::ZERO,
+        ];
+        assert_eq!(program_output, [expected.as_slice(), &da].concat());
+        assert_eq!(call.onchainDataSize, U256::from(da.len()));
+
+        // The state update of block #0 does not follow the state of the core contract anymore.
+        let core_contract = StarknetCoreContract::new(CORE_CONTRACT, &provider);
+        let replay = core_contract.updateState(call.programOutput, call.onchainDataHash, call.onchainDataSize);
+        assert!(replay.call().await.is_err());
+    }
+
+    /// A state update which stays pending is replaced by a transaction with the same nonce and higher fees, and the
+    /// block is settled by whichever gets included.
+    #[rstest]
+    #[tokio::test]
+    async fn settlement_worker_resubmits_pending_state_updates() {
+        let anvil = Anvil::new().arg("--no-mining").try_spawn().expect("failed to spawn anvil instance");
+        let provider = deploy_mock_core_contract(&anvil).await;
+
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::test_config().unwrap()));
+        store_block(&backend, 0, StateDiff::default());
+
+        let config = settlement_config(&anvil, Duration::from_secs(1));
+        let worker_handle = {
+            let backend = Arc::clone(&backend);
+            tokio::spawn(async move { settlement_worker(&backend, &config).await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let Some(SettlementStatus::Submitted { transaction: first, replaced }) =
+            backend.get_settlement_status(0).unwrap()
+        else {
+            panic!("The state update of block #0 should be pending");
+        };
+        assert_eq!(replaced, vec![]);
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let Some(SettlementStatus::Submitted { transaction: replacement, replaced }) =
+            backend.get_settlement_status(0).unwrap()
+        else {
+            panic!("The state update of block #0 should still be pending");
+        };
+        assert_eq!(replaced.first(), Some(&first.l1_transaction_hash));
+        assert_eq!(replacement.nonce, first.nonce);
+        assert!(replacement.max_fee_per_gas > first.max_fee_per_gas);
+        assert!(replacement.max_priority_fee_per_gas > first.max_priority_fee_per_gas);
+
+        let () = provider.raw_request("anvil_mine".into(), (U256::from(1), Option::<U256>::None)).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        worker_handle.abort();
+
+        let Some(SettlementStatus::Confirmed { l1_transaction_hash, .. }) = backend.get_settlement_status(0).unwrap()
+        else {
+            panic!("Block #0 should be settled");
+        };
+        assert_ne!(l1_transaction_hash, first.l1_transaction_hash);
+        assert_eq!(storage(&provider, 0).await, U256::from(0x100));
+    }
+
+    /// A state update rejected by the core contract is sent again a bounded number of times, then settlement stops.
+    #[rstest]
+    #[tokio::test]
+    async fn settlement_worker_gives_up_after_max_attempts() {
+        let anvil = Anvil::new().try_spawn().expect("failed to spawn anvil instance");
+        let provider = deploy_mock_core_contract(&anvil).await;
+        // The core contract expects the state update of block #5 next.
+        let () = provider
+            .raw_request("anvil_setStorageAt".into(), (CORE_CONTRACT, U256::from(1), B256::from(U256::from(5))))
+            .await
+            .unwrap();
+
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::test_config().unwrap()));
+        store_block(&backend, 0, StateDiff::default());
+
+        let config = SettlementConfig {
+            retry_backoff: Duration::ZERO,
+            max_attempts: 3,
+            ..settlement_config(&anvil, Duration::from_secs(300))
+        };
+        let res = tokio::time::timeout(Duration::from_secs(5), settlement_worker(&backend, &config)).await;
+        let err = res.expect("Settlement should stop").unwrap_err();
+        assert!(err.to_string().contains("giving up"), "{err:#}");
+
+        let Some(SettlementStatus::Failed { attempts, .. }) = backend.get_settlement_status(0).unwrap() else {
+            panic!("The state update of block #0 should have failed");
+        };
+        assert_eq!(attempts, 3);
+        assert_eq!(storage(&provider, 0).await, U256::ZERO);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let backoff = Duration::from_secs(60);
+        assert_eq!(retry_delay(backoff, 1), Duration::from_secs(60));
+        assert_eq!(retry_delay(backoff, 3), Duration::from_secs(240));
+        assert_eq!(retry_delay(Duration::MAX, 10), Duration::MAX);
+    }
+}
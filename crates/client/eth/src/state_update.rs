@@ -1,15 +1,17 @@
-use crate::client::{L1BlockMetrics, StarknetCoreContract};
+use crate::client::{events_query_ranges, L1BlockMetrics, L1EventsConfig, StarknetCoreContract, EVENTS_QUERY_RANGE};
 use crate::{
     client::EthereumClient,
     utils::{convert_log_state_update, trim_hash},
 };
+use alloy::rpc::types::Log;
 use anyhow::Context;
-use futures::StreamExt;
+use mc_db::db_block_id::DbBlockId;
+use mc_db::l1_db::{L1BlockRef, L1StateUpdateRecord, L1VerificationStatus};
 use mc_db::MadaraBackend;
-use mp_transactions::MAIN_CHAIN_ID;
-use mp_utils::channel_wait_or_graceful_shutdown;
+use mp_utils::wait_or_graceful_shutdown;
 use serde::Deserialize;
 use starknet_types_core::felt::Felt;
+use tokio::sync::broadcast::error::RecvError;
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct L1StateUpdate {
@@ -18,6 +20,12 @@ pub struct L1StateUpdate {
     pub block_hash: Felt,
 }
 
+impl From<L1StateUpdateRecord> for L1StateUpdate {
+    fn from(record: L1StateUpdateRecord) -> Self {
+        Self { block_number: record.block_number, global_root: record.global_root, block_hash: record.block_hash }
+    }
+}
+
 /// Get the last Starknet state update verified on the L1
 pub async fn get_initial_state(client: &EthereumClient) -> anyhow::Result<L1StateUpdate> {
     let block_number = client.get_last_verified_block_number().await?;
@@ -27,75 +35,269 @@ pub async fn get_initial_state(client: &EthereumClient) -> anyhow::Result<L1Stat
     Ok(L1StateUpdate { global_root, block_number, block_hash })
 }
 
-/// Subscribes to the LogStateUpdate event from the Starknet core contract and store latest
-/// verified state
+/// Stores a `LogStateUpdate` event, along with the L1 block and transaction it was emitted in.
+fn store_state_update(
+    backend: &MadaraBackend,
+    event: StarknetCoreContract::LogStateUpdate,
+    log: &Log,
+) -> anyhow::Result<()> {
+    let state_update = convert_log_state_update(event).context("formatting event into an L1StateUpdate")?;
+    let l1_block_number = log.block_number.context("LogStateUpdate event without a block number")?;
+    let l1_block_hash = log.block_hash.context("LogStateUpdate event without a block hash")?;
+    let l1_transaction_hash = log.transaction_hash.context("LogStateUpdate event without a transaction hash")?;
+
+    backend
+        .store_l1_state_update(&L1StateUpdateRecord {
+            block_number: state_update.block_number,
+            block_hash: state_update.block_hash,
+            global_root: state_update.global_root,
+            l1_block_number,
+            l1_block_hash: l1_block_hash.0,
+            l1_transaction_hash: l1_transaction_hash.0,
+        })
+        .context("Storing L1 state update")?;
+    Ok(())
+}
+
+/// Stores all the state updates posted on L1 since the last checkpoint, up to the last confirmed L1 block (see
+/// [`EthereumClient::get_confirmed_block_number`]), so that every block can be linked to the L1 transaction which settled it. The L1 blocks are
+/// scanned in ranges, and the scan resumes from the checkpoint after a restart.
+///
+/// Returns whether new state updates were stored.
+pub async fn backfill_state_updates(
+    eth_client: &EthereumClient,
+    backend: &MadaraBackend,
+    confirmations: Option<u64>,
+) -> anyhow::Result<bool> {
+    let Some(target_l1_block) = eth_client.get_confirmed_block_number(confirmations).await? else { return Ok(false) };
+    let from_block = first_block_to_scan(
+        backend.get_l1_state_updates_checkpoint()?,
+        backend.chain_config().eth_core_contract_deployment_block,
+    );
+    if target_l1_block.saturating_sub(from_block) >= EVENTS_QUERY_RANGE {
+        log::info!("⟠ Backfilling L1 state updates from L1 block #{from_block} to #{target_l1_block}");
+    }
+
+    let mut stored = false;
+    for (from_block, to_block) in events_query_ranges(from_block, target_l1_block) {
+        let event_filter = eth_client
+            .l1_core_contract
+            .event_filter::<StarknetCoreContract::LogStateUpdate>()
+            .from_block(from_block)
+            .to_block(to_block);
+        let Some(logs) = wait_or_graceful_shutdown(event_filter.query()).await else { return Ok(stored) };
+        let logs =
+            logs.with_context(|| format!("Getting the state updates of L1 blocks #{from_block} to #{to_block}"))?;
+
+        for (event, log) in logs {
+            store_state_update(backend, event, &log)?;
+            stored = true;
+        }
+        // The checkpoint hash is checked on every poll, so that the scanned blocks are scanned again if they are
+        // reorged out.
+        let block_hash = eth_client
+            .get_block_hash(to_block)
+            .await?
+            .with_context(|| format!("L1 block #{to_block} is not in the L1 chain anymore"))?;
+        backend.write_l1_state_updates_checkpoint(&L1BlockRef { block_number: to_block, block_hash })?;
+    }
+
+    Ok(stored)
+}
+
+/// The scan resumes after the checkpoint, and starts from the block the core contract was deployed in otherwise.
+fn first_block_to_scan(checkpoint: Option<L1BlockRef>, deployment_block: u64) -> u64 {
+    checkpoint.map_or(deployment_block, |block| block.block_number + 1)
+}
+
+/// Rolls back the state updates posted in L1 blocks which were reorged out: they are removed, the blocks they
+/// settled are not accepted on L1 anymore, and the L1 blocks after the last remaining state update are scanned
+/// again.
+///
+/// Returns whether an L1 reorg was detected.
+pub async fn rollback_reorged_state_updates(
+    eth_client: &EthereumClient,
+    backend: &MadaraBackend,
+) -> anyhow::Result<bool> {
+    let Some(checkpoint) = backend.get_l1_state_updates_checkpoint()? else { return Ok(false) };
+    if eth_client.get_block_hash(checkpoint.block_number).await? == Some(checkpoint.block_hash) {
+        return Ok(false);
+    }
+    log::warn!("⟠ L1 reorg detected: L1 block #{} is not in the L1 chain anymore", checkpoint.block_number);
+
+    let mut last_canonical = None;
+    while let Some(state_update) = backend.get_latest_l1_state_update()? {
+        if eth_client.get_block_hash(state_update.l1_block_number).await? == Some(state_update.l1_block_hash) {
+            last_canonical = Some(state_update);
+            break;
+        }
+        log::warn!(
+            "⟠ State update for block #{} was posted in L1 block #{} which was reorged out, rolling it back",
+            state_update.block_number,
+            state_update.l1_block_number
+        );
+        backend.remove_l1_state_update(state_update.block_number)?;
+    }
+
+    let last_confirmed = backend.get_l1_last_confirmed_block()?;
+    match &last_canonical {
+        Some(state_update) => {
+            backend.write_l1_state_updates_checkpoint(&L1BlockRef {
+                block_number: state_update.l1_block_number,
+                block_hash: state_update.l1_block_hash,
+            })?;
+            if last_confirmed.is_some_and(|block_n| block_n > state_update.block_number) {
+                backend.write_last_confirmed_block(state_update.block_number)?;
+            }
+        }
+        None => {
+            backend.clear_l1_state_updates_checkpoint()?;
+            backend.clear_last_confirmed_block()?;
+            backend.set_l1_verification_status(L1VerificationStatus::Unknown);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Polls the L1 for state updates, and checks the latest one against the local chain. The state updates posted while
+/// the node was not running are backfilled on the first poll.
+///
+/// `deferred` is a state update for a block we have not synced yet: it is checked again every time a block is added
+/// to the chain, until a newer state update replaces it.
 pub async fn listen_and_update_state(
     eth_client: &EthereumClient,
     backend: &MadaraBackend,
     block_metrics: &L1BlockMetrics,
-    chain_id: Felt,
+    config: &L1EventsConfig,
+    mut deferred: Option<L1StateUpdate>,
 ) -> anyhow::Result<()> {
-    let event_filter = eth_client.l1_core_contract.event_filter::<StarknetCoreContract::LogStateUpdate>();
+    let mut notifications = backend.subscribe_block_notifications();
 
-    let mut event_stream = event_filter.watch().await.context("Failed to watch event filter")?.into_stream();
+    loop {
+        match poll_state_updates(eth_client, backend, config).await {
+            Ok(Some(state_update)) => deferred = verify_state_update(backend, state_update, block_metrics)?,
+            Ok(None) => {}
+            Err(err) => log::warn!("⟠ Failed to get the L1 state updates, retrying on the next poll: {err:#}"),
+        }
 
-    while let Some(event_result) = channel_wait_or_graceful_shutdown(event_stream.next()).await {
-        let log = event_result.context("listening for events")?;
-        let format_event: L1StateUpdate =
-            convert_log_state_update(log.0.clone()).context("formatting event into an L1StateUpdate")?;
-        update_l1(backend, format_event, block_metrics, chain_id)?;
+        let wait = async {
+            tokio::select! {
+                _ = tokio::time::sleep(config.poll_interval) => Some(false),
+                notification = notifications.recv(), if deferred.is_some() => {
+                    // Lagging is fine, the block is read from the db.
+                    (!matches!(notification, Err(RecvError::Closed))).then_some(true)
+                }
+            }
+        };
+        let Some(Some(block_added)) = wait_or_graceful_shutdown(wait).await else { break };
+        if block_added {
+            if let Some(state_update) = deferred.take() {
+                deferred = verify_state_update(backend, state_update, block_metrics)?;
+            }
+        }
     }
 
     Ok(())
 }
 
-pub fn update_l1(
+/// Rolls back the L1 reorgs and scans the new L1 blocks. Returns the state update to check against the local chain
+/// when it changed.
+async fn poll_state_updates(
+    eth_client: &EthereumClient,
+    backend: &MadaraBackend,
+    config: &L1EventsConfig,
+) -> anyhow::Result<Option<L1StateUpdate>> {
+    let reorged = rollback_reorged_state_updates(eth_client, backend).await.context("Rolling back L1 reorg")?;
+    let stored = backfill_state_updates(eth_client, backend, config.confirmations)
+        .await
+        .context("Getting the L1 state updates")?;
+    if !reorged && !stored {
+        return Ok(None);
+    }
+    Ok(backend.get_latest_l1_state_update()?.map(Into::into))
+}
+
+/// Returns the state update again when it has to be checked once its block is synced.
+fn verify_state_update(
     backend: &MadaraBackend,
     state_update: L1StateUpdate,
     block_metrics: &L1BlockMetrics,
-    chain_id: Felt,
-) -> anyhow::Result<()> {
-    // This is a provisory check to avoid updating the state with an L1StateUpdate that should not have been detected
-    //
-    // TODO: Remove this check when the L1StateUpdate is properly verified
-    if state_update.block_number > 500000u64 || chain_id == MAIN_CHAIN_ID {
-        log::info!(
-            "🔄 Updated L1 head #{} ({}) with state root ({})",
-            state_update.block_number,
-            trim_hash(&state_update.block_hash),
-            trim_hash(&state_update.global_root)
-        );
-
-        block_metrics.l1_block_number.set(state_update.block_number as f64);
+) -> anyhow::Result<Option<L1StateUpdate>> {
+    Ok(match update_l1(backend, state_update.clone(), block_metrics)? {
+        L1VerificationStatus::Deferred { .. } => Some(state_update),
+        _ => None,
+    })
+}
 
-        backend
-            .write_last_confirmed_block(state_update.block_number)
-            .context("Setting l1 last confirmed block number")?;
-        log::debug!("update_l1: wrote last confirmed block number");
-    }
+/// Checks a state update posted on L1 against the block we have locally, and marks the chain as accepted on L1 up to
+/// that block when the block hash and global state root match.
+pub fn update_l1(
+    backend: &MadaraBackend,
+    state_update: L1StateUpdate,
+    block_metrics: &L1BlockMetrics,
+) -> anyhow::Result<L1VerificationStatus> {
+    let L1StateUpdate { block_number, global_root, block_hash } = state_update;
+    block_metrics.l1_block_number.set(block_number as f64);
+
+    let local_block = backend
+        .get_block_info(&DbBlockId::BlockN(block_number))
+        .context("Getting the block posted on L1")?
+        .and_then(|info| info.as_nonpending().map(|info| (info.block_hash, info.header.global_state_root)));
+
+    let status = match local_block {
+        None => {
+            log::debug!("L1 state update for block #{block_number} is ahead of the local chain, deferring it");
+            L1VerificationStatus::Deferred { block_number }
+        }
+        Some((local_block_hash, local_global_root))
+            if local_block_hash == block_hash && local_global_root == global_root =>
+        {
+            log::info!(
+                "🔄 Updated L1 head #{} ({}) with state root ({})",
+                block_number,
+                trim_hash(&block_hash),
+                trim_hash(&global_root)
+            );
+            backend.write_last_confirmed_block(block_number).context("Setting l1 last confirmed block number")?;
+            log::debug!("update_l1: wrote last confirmed block number");
+            block_metrics.l1_state_diverged.set(0.0);
+            L1VerificationStatus::Verified { block_number }
+        }
+        Some((local_block_hash, local_global_root)) => {
+            let status = L1VerificationStatus::Diverged {
+                block_number,
+                l1_block_hash: block_hash,
+                l1_global_root: global_root,
+                local_block_hash,
+                local_global_root,
+            };
+            log::error!("❗ L1 state update does not match the local chain: {status}");
+            block_metrics.l1_state_diverged.set(1.0);
+            status
+        }
+    };
 
-    Ok(())
+    backend.set_l1_verification_status(status.clone());
+    Ok(status)
 }
 
 pub async fn state_update_worker(
     backend: &MadaraBackend,
     eth_client: &EthereumClient,
-    chain_id: Felt,
+    config: &L1EventsConfig,
 ) -> anyhow::Result<()> {
-    // Clear L1 confirmed block at startup
-    backend.clear_last_confirmed_block().context("Clearing l1 last confirmed block number")?;
-    log::debug!("update_l1: cleared confirmed block number");
-
     log::info!("🚀 Subscribed to L1 state verification");
-    // ideally here there would be one service which will update the l1 gas prices and another one for messages and one that's already present is state update
-    // Get and store the latest verified state
-    let initial_state = get_initial_state(eth_client).await.context("Getting initial ethereum state")?;
-    update_l1(backend, initial_state, &eth_client.l1_block_metrics, chain_id)?;
-
-    // Listen to LogStateUpdate (0x77552641) update and send changes continusly
-    listen_and_update_state(eth_client, backend, &eth_client.l1_block_metrics, chain_id)
+    // The latest state update stored by a previous run is checked again, as the local chain may have changed since.
+    let deferred = match backend.get_latest_l1_state_update()? {
+        Some(state_update) => verify_state_update(backend, state_update.into(), &eth_client.l1_block_metrics)?,
+        None => None,
+    };
+
+    // Poll LogStateUpdate (0x77552641) events and check them against the local chain continuously
+    listen_and_update_state(eth_client, backend, &eth_client.l1_block_metrics, config, deferred)
         .await
-        .context("Subscribing to the LogStateUpdate event")?;
+        .context("Listening to the LogStateUpdate event")?;
 
     Ok(())
 }
@@ -105,11 +307,17 @@ mod eth_client_event_subscription_test {
     use super::*;
     use std::{sync::Arc, time::Duration};
 
-    use alloy::{node_bindings::Anvil, providers::ProviderBuilder, sol};
+    use alloy::{
+        node_bindings::Anvil,
+        primitives::U256,
+        providers::{Provider, ProviderBuilder},
+        sol,
+    };
     use mc_db::DatabaseService;
-    use mc_metrics::MetricsService;
+    use mc_metrics::{MetricsRegistry, MetricsService};
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
     use mp_chain_config::ChainConfig;
-    use mp_convert::ToFelt;
+    use mp_state_update::StateDiff;
     use rstest::*;
     use tempfile::TempDir;
     use url::Url;
@@ -133,6 +341,10 @@ mod eth_client_event_subscription_test {
     const ANOTHER_ANVIL_PORT: u16 = 8548;
     const EVENT_PROCESSING_TIME: u64 = 2; // Time to allow for event processing in seconds
 
+    fn events_config() -> L1EventsConfig {
+        L1EventsConfig { confirmations: Some(0), poll_interval: Duration::from_millis(100) }
+    }
+
     /// Test the event subscription and state update functionality
     ///
     /// This test performs the following steps:
@@ -141,7 +353,7 @@ mod eth_client_event_subscription_test {
     /// 3. Deploys a dummy contract and sets up an Ethereum client
     /// 4. Starts listening for state updates
     /// 5. Fires an event from the dummy contract
-    /// 6. Waits for event processing and verifies the block number
+    /// 6. Waits for event processing and verifies that the update is stored, and deferred as the block is not synced
     #[rstest]
     #[tokio::test]
     async fn listen_and_update_state_when_event_fired_works() {
@@ -164,9 +376,16 @@ mod eth_client_event_subscription_test {
 
         // Initialize database service
         let db = Arc::new(
-            DatabaseService::new(&base_path, backup_dir, false, chain_info.clone())
-                .await
-                .expect("Failed to create database service"),
+            DatabaseService::new(
+                &base_path,
+                backup_dir,
+                false,
+                chain_info.clone(),
+                Default::default(),
+                Default::default(),
+            )
+            .await
+            .expect("Failed to create database service"),
         );
 
         // Set up metrics service
@@ -186,27 +405,153 @@ mod eth_client_event_subscription_test {
         let listen_handle = {
             let db = Arc::clone(&db);
             tokio::spawn(async move {
-                listen_and_update_state(
-                    &eth_client,
-                    db.backend(),
-                    &eth_client.l1_block_metrics,
-                    chain_info.chain_id.clone().to_felt(),
-                )
-                .await
+                listen_and_update_state(&eth_client, db.backend(), &eth_client.l1_block_metrics, &events_config(), None)
+                    .await
             })
         };
 
-        let _ = contract.fireEvent().send().await.expect("Failed to fire event");
+        let pending_tx = contract.fireEvent().send().await.expect("Failed to fire event");
+        let l1_tx_hash = *pending_tx.tx_hash();
 
         // Wait for event processing
         tokio::time::sleep(Duration::from_secs(EVENT_PROCESSING_TIME)).await;
 
-        // Verify the block number
+        // The block was not synced, so it cannot be marked as accepted on L1 yet
         let block_in_db =
             db.backend().get_l1_last_confirmed_block().expect("Failed to get L1 last confirmed block number");
+        let status = db.backend().get_l1_verification_status();
+        let stored = db.backend().get_l1_state_update(L2_BLOCK_NUMBER).expect("Failed to get L1 state update");
 
         // Explicitly cancel the listen task, else it would be running in the background
         listen_handle.abort();
-        assert_eq!(block_in_db, Some(L2_BLOCK_NUMBER), "Block in DB does not match expected L2 block number");
+        assert_eq!(block_in_db, None, "Block should not be confirmed before it is synced");
+        assert_eq!(status, L1VerificationStatus::Deferred { block_number: L2_BLOCK_NUMBER });
+        let stored = stored.expect("The state update should be stored");
+        assert_eq!(stored.block_number, L2_BLOCK_NUMBER);
+        assert_eq!(stored.l1_transaction_hash, l1_tx_hash.0);
+    }
+
+    /// State updates posted in L1 blocks which are reorged out are rolled back: the state update is removed, and the
+    /// blocks it settled are not accepted on L1 anymore.
+    #[rstest]
+    #[tokio::test]
+    async fn listen_and_update_state_rolls_back_l1_reorgs() {
+        let anvil = Anvil::new().block_time(1).try_spawn().expect("failed to spawn anvil instance");
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::test_config().unwrap()));
+        let provider = ProviderBuilder::new().on_http(anvil.endpoint().parse().unwrap());
+        let contract = DummyContract::deploy(provider.clone()).await.unwrap();
+        let eth_client = EthereumClient {
+            provider: Arc::new(provider.clone()),
+            l1_core_contract: StarknetCoreContract::new(*contract.address(), provider.clone()),
+            l1_block_metrics: L1BlockMetrics::register(&MetricsRegistry::dummy()).unwrap(),
+        };
+
+        let listen_handle = {
+            let backend = Arc::clone(&backend);
+            tokio::spawn(async move {
+                listen_and_update_state(&eth_client, &backend, &eth_client.l1_block_metrics, &events_config(), None)
+                    .await
+            })
+        };
+
+        let snapshot: U256 = provider.raw_request("evm_snapshot".into(), ()).await.unwrap();
+        contract.fireEvent().send().await.expect("Failed to fire event");
+        tokio::time::sleep(Duration::from_secs(EVENT_PROCESSING_TIME)).await;
+        assert!(backend.get_l1_state_update(L2_BLOCK_NUMBER).unwrap().is_some());
+        // Pretend the block was confirmed
+        backend.write_last_confirmed_block(L2_BLOCK_NUMBER).unwrap();
+
+        // Drop the L1 block with the state update: the blocks mined after the revert have a different hash
+        let reverted: bool = provider.raw_request("evm_revert".into(), (snapshot,)).await.unwrap();
+        assert!(reverted);
+        tokio::time::sleep(Duration::from_secs(EVENT_PROCESSING_TIME + 1)).await;
+
+        let stored = backend.get_l1_state_update(L2_BLOCK_NUMBER).unwrap();
+        let last_confirmed = backend.get_l1_last_confirmed_block().unwrap();
+        listen_handle.abort();
+        assert_eq!(stored, None, "The state update should be rolled back");
+        assert_eq!(last_confirmed, None);
+    }
+
+    fn store_block(backend: &MadaraBackend, block_hash: Felt, global_state_root: Felt) {
+        let header = Header { block_number: 0, global_state_root, ..Default::default() };
+        let block = MadaraMaybePendingBlock {
+            info: MadaraBlockInfo::new(header, vec![], block_hash).into(),
+            inner: MadaraBlockInner::new(vec![], vec![]),
+        };
+        backend.store_block(block, StateDiff::default(), vec![]).unwrap();
+    }
+
+    #[rstest]
+    #[case::matching(Felt::from(0x12), Felt::from(0x34), L1VerificationStatus::Verified { block_number: 0 }, Some(0))]
+    #[case::wrong_block_hash(Felt::from(0x13), Felt::from(0x34), L1VerificationStatus::Diverged {
+        block_number: 0,
+        l1_block_hash: Felt::from(0x13),
+        l1_global_root: Felt::from(0x34),
+        local_block_hash: Felt::from(0x12),
+        local_global_root: Felt::from(0x34),
+    }, None)]
+    #[case::wrong_global_root(Felt::from(0x12), Felt::from(0x35), L1VerificationStatus::Diverged {
+        block_number: 0,
+        l1_block_hash: Felt::from(0x12),
+        l1_global_root: Felt::from(0x35),
+        local_block_hash: Felt::from(0x12),
+        local_global_root: Felt::from(0x34),
+    }, None)]
+    fn update_l1_verifies_state_update(
+        #[case] block_hash: Felt,
+        #[case] global_root: Felt,
+        #[case] expected: L1VerificationStatus,
+        #[case] expected_confirmed: Option<u64>,
+    ) {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::test_config().unwrap()));
+        let metrics = L1BlockMetrics::register(&MetricsRegistry::dummy()).unwrap();
+        store_block(&backend, Felt::from(0x12), Felt::from(0x34));
+
+        let status = update_l1(&backend, L1StateUpdate { block_number: 0, global_root, block_hash }, &metrics).unwrap();
+
+        assert_eq!(status, expected);
+        assert_eq!(backend.get_l1_verification_status(), expected);
+        assert_eq!(backend.get_l1_last_confirmed_block().unwrap(), expected_confirmed);
+        let diverged = matches!(expected, L1VerificationStatus::Diverged { .. });
+        assert_eq!(metrics.l1_state_diverged.get(), if diverged { 1.0 } else { 0.0 });
+    }
+
+    #[test]
+    fn backfill_resumes_from_checkpoint() {
+        let checkpoint = |block_number| Some(L1BlockRef { block_number, block_hash: [0; 32] });
+        assert_eq!(first_block_to_scan(None, 0), 0);
+        assert_eq!(first_block_to_scan(None, 13_000_000), 13_000_000);
+        assert_eq!(first_block_to_scan(checkpoint(13_004_999), 13_000_000), 13_005_000);
+
+        let ranges = |from_block, to_block| events_query_ranges(from_block, to_block).collect::<Vec<_>>();
+        assert_eq!(ranges(100, 99), vec![]);
+        assert_eq!(ranges(100, 100), vec![(100, 100)]);
+        assert_eq!(
+            ranges(13_000_000, 13_025_000),
+            vec![(13_000_000, 13_009_999), (13_010_000, 13_019_999), (13_020_000, 13_025_000)]
+        );
+        // Resuming from the checkpoint written after the first range.
+        assert_eq!(
+            ranges(first_block_to_scan(checkpoint(13_009_999), 13_000_000), 13_025_000),
+            vec![(13_010_000, 13_019_999), (13_020_000, 13_025_000)]
+        );
+    }
+
+    #[rstest]
+    fn update_l1_defers_blocks_not_synced_yet() {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::test_config().unwrap()));
+        let metrics = L1BlockMetrics::register(&MetricsRegistry::dummy()).unwrap();
+        let state_update =
+            L1StateUpdate { block_number: 0, global_root: Felt::from(0x34), block_hash: Felt::from(0x12) };
+
+        let status = update_l1(&backend, state_update.clone(), &metrics).unwrap();
+        assert_eq!(status, L1VerificationStatus::Deferred { block_number: 0 });
+        assert_eq!(backend.get_l1_last_confirmed_block().unwrap(), None);
+
+        store_block(&backend, Felt::from(0x12), Felt::from(0x34));
+        let status = update_l1(&backend, state_update, &metrics).unwrap();
+        assert_eq!(status, L1VerificationStatus::Verified { block_number: 0 });
+        assert_eq!(backend.get_l1_last_confirmed_block().unwrap(), Some(0));
     }
 }
@@ -1,26 +1,44 @@
-use crate::client::EthereumClient;
+use crate::client::{EthereumClient, L1EventsConfig};
 use crate::l1_gas_price::gas_price_worker;
+use crate::l1_messaging::sync;
+use crate::oracle::Oracle;
 use crate::state_update::state_update_worker;
-use mc_mempool::GasPriceProvider;
-use starknet_types_core::felt::Felt;
+use mc_mempool::{GasPriceProvider, Mempool};
+use starknet_api::core::ChainId;
+use std::sync::Arc;
 use std::time::Duration;
 
 use mc_db::MadaraBackend;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn l1_sync_worker(
     backend: &MadaraBackend,
     eth_client: &EthereumClient,
-    chain_id: Felt,
+    events_config: &L1EventsConfig,
+    chain_id: &ChainId,
     l1_gas_provider: GasPriceProvider,
+    oracle: Option<Arc<dyn Oracle>>,
     gas_price_sync_disabled: bool,
     gas_price_poll_ms: Duration,
+    mempool: Option<Arc<Mempool>>,
 ) -> anyhow::Result<()> {
-    tokio::try_join!(state_update_worker(backend, eth_client, chain_id), async {
-        if !gas_price_sync_disabled {
-            gas_price_worker(eth_client, l1_gas_provider, gas_price_poll_ms).await?;
+    tokio::try_join!(
+        state_update_worker(backend, eth_client, events_config),
+        async {
+            if !gas_price_sync_disabled {
+                let ema_blocks = backend.chain_config().l1_gas_price.ema_blocks;
+                gas_price_worker(eth_client, l1_gas_provider, oracle.as_deref(), ema_blocks, gas_price_poll_ms).await?;
+            }
+            Ok(())
+        },
+        async {
+            // L1 to L2 messages are only consumed when producing blocks.
+            if let Some(mempool) = mempool {
+                sync(backend, eth_client, chain_id, mempool, events_config).await?;
+            }
+            Ok(())
         }
-        Ok(())
-    })?;
+    )?;
 
     Ok(())
 }
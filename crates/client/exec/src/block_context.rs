@@ -1,4 +1,4 @@
-use crate::{blockifier_state_adapter::BlockifierStateAdapter, Error};
+use crate::{blockifier_state_adapter::BlockifierStateAdapter, ContractClassCache, Error};
 use blockifier::{
     blockifier::{
         config::TransactionExecutorConfig, stateful_validator::StatefulValidator,
@@ -14,6 +14,7 @@ use std::sync::Arc;
 
 pub struct ExecutionContext {
     pub(crate) backend: Arc<MadaraBackend>,
+    pub(crate) class_cache: Arc<ContractClassCache>,
     pub(crate) block_context: BlockContext,
     pub(crate) db_id: DbBlockId,
 }
@@ -23,7 +24,7 @@ impl ExecutionContext {
         TransactionExecutor::new(
             self.init_cached_state(),
             self.block_context.clone(),
-            // No concurrency yet.
+            // Transactions are executed sequentially, concurrency can be enabled through the `config` field.
             TransactionExecutorConfig { concurrency_config: Default::default() },
         )
     }
@@ -49,13 +50,18 @@ impl ExecutionContext {
 
         CachedState::new(BlockifierStateAdapter::new(
             Arc::clone(&self.backend),
+            Arc::clone(&self.class_cache),
             self.block_context.block_info().block_number.0,
             on_top_of,
         ))
     }
 
     /// Create an execution context for executing transactions **within** that block.
-    pub fn new_in_block(backend: Arc<MadaraBackend>, block_info: &MadaraMaybePendingBlockInfo) -> Result<Self, Error> {
+    pub fn new_in_block(
+        backend: Arc<MadaraBackend>,
+        class_cache: Arc<ContractClassCache>,
+        block_info: &MadaraMaybePendingBlockInfo,
+    ) -> Result<Self, Error> {
         let (db_id, protocol_version, block_number, block_timestamp, sequencer_address, l1_gas_price, l1_da_mode) =
             match block_info {
                 MadaraMaybePendingBlockInfo::Pending(block) => (
@@ -107,6 +113,7 @@ impl ExecutionContext {
             ),
             db_id,
             backend,
+            class_cache,
         })
     }
 }
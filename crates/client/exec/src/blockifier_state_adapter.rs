@@ -1,3 +1,4 @@
+use crate::ContractClassCache;
 use blockifier::execution::contract_class::ContractClass;
 use blockifier::state::errors::StateError;
 use blockifier::state::state_api::{StateReader, StateResult};
@@ -16,14 +17,20 @@ use std::sync::Arc;
 /// changes in db are evaluated at the end only from the produced state diff.
 pub struct BlockifierStateAdapter {
     backend: Arc<MadaraBackend>,
+    class_cache: Arc<ContractClassCache>,
     /// When this value is None, we are executing the genesis block.
     pub on_top_of_block_id: Option<DbBlockId>,
     pub block_number: u64,
 }
 
 impl BlockifierStateAdapter {
-    pub fn new(backend: Arc<MadaraBackend>, block_number: u64, on_top_of_block_id: Option<DbBlockId>) -> Self {
-        Self { backend, on_top_of_block_id, block_number }
+    pub fn new(
+        backend: Arc<MadaraBackend>,
+        class_cache: Arc<ContractClassCache>,
+        block_number: u64,
+        on_top_of_block_id: Option<DbBlockId>,
+    ) -> Self {
+        Self { backend, class_cache, on_top_of_block_id, block_number }
     }
 }
 
@@ -121,8 +128,12 @@ impl StateReader for BlockifierStateAdapter {
             return Err(StateError::UndeclaredClassHash(class_hash));
         };
 
-        let Some(class_info) =
-            self.backend.get_class_info(&on_top_of_block_id, &class_hash.to_felt()).map_err(|err| {
+        if let Some(class) = self.class_cache.get(&self.backend, &class_hash, &on_top_of_block_id) {
+            return Ok(class);
+        }
+
+        let Some((class_info, declared_at)) =
+            self.backend.get_class_info_and_declared_at(&on_top_of_block_id, &class_hash.to_felt()).map_err(|err| {
                 log::warn!("Failed to retrieve class {class_hash:#}: {err:#}");
                 StateError::StateReadError(format!("Failed to retrieve class {class_hash:#}"))
             })?
@@ -130,7 +141,7 @@ impl StateReader for BlockifierStateAdapter {
             return Err(StateError::UndeclaredClassHash(class_hash));
         };
 
-        match class_info {
+        let class = match class_info {
             ClassInfo::Sierra(info) => {
                 let compiled_class = self
                     .backend
@@ -143,13 +154,19 @@ impl StateReader for BlockifierStateAdapter {
                         "Inconsistent state: compiled sierra class {class_hash:#} not found"
                     )))?;
                 // TODO: convert ClassCompilationError to StateError
-                Ok(compiled_class.to_blockifier_class().map_err(|e| StateError::StateReadError(e.to_string()))?)
+                compiled_class.to_blockifier_class().map_err(|e| StateError::StateReadError(e.to_string()))?
             }
             ClassInfo::Legacy(info) => {
                 // TODO: convert ClassCompilationError to StateError
-                Ok(info.contract_class.to_blockifier_class().map_err(|e| StateError::StateReadError(e.to_string()))?)
+                info.contract_class.to_blockifier_class().map_err(|e| StateError::StateReadError(e.to_string()))?
             }
+        };
+
+        // Classes of the pending block are not cached, as the pending block can be discarded.
+        if let DbBlockId::BlockN(declared_at) = declared_at {
+            self.class_cache.insert(&self.backend, class_hash, declared_at, class.clone());
         }
+        Ok(class)
     }
 
     fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
@@ -0,0 +1,123 @@
+use blockifier::execution::contract_class::ContractClass;
+use lru::LruCache;
+use mc_db::db_block_id::DbBlockId;
+use mc_db::MadaraBackend;
+use mc_metrics::{Counter, MetricsRegistry, PrometheusError, U64};
+use starknet_api::core::ClassHash;
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Clone, Debug)]
+pub struct ClassCacheMetrics {
+    pub hits: Counter<U64>,
+    pub misses: Counter<U64>,
+}
+
+impl ClassCacheMetrics {
+    pub fn register(registry: &MetricsRegistry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            hits: registry.register(Counter::new(
+                "madara_class_cache_hits",
+                "Number of contract classes for execution found in the class cache",
+            )?)?,
+            misses: registry.register(Counter::new(
+                "madara_class_cache_misses",
+                "Number of contract classes for execution which had to be loaded from the db",
+            )?)?,
+        })
+    }
+}
+
+struct CachedClass {
+    /// The class is not visible when executing on top of an earlier block.
+    declared_at: u64,
+    class: ContractClass,
+}
+
+struct ClassCacheInner {
+    classes: LruCache<ClassHash, CachedClass>,
+    /// See [`MadaraBackend::get_revert_count`]: the block a class was declared in is not valid anymore after a revert.
+    revert_count: u64,
+}
+
+/// Contract classes converted to their blockifier representation, which is expensive to build: the CASM of sierra
+/// classes is parsed from JSON, and the program of legacy classes is decompressed and parsed.
+///
+/// Only classes declared in a closed block are cached, classes in the pending block can still be removed. The cache is
+/// shared by everything executing transactions, and holds at most `capacity` classes.
+pub struct ContractClassCache {
+    inner: Mutex<ClassCacheInner>,
+    metrics: ClassCacheMetrics,
+}
+
+impl ContractClassCache {
+    pub const DEFAULT_CAPACITY: usize = 512;
+
+    pub fn new(capacity: usize, metrics: ClassCacheMetrics) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self { inner: Mutex::new(ClassCacheInner { classes: LruCache::new(capacity), revert_count: 0 }), metrics }
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub fn for_testing() -> Self {
+        let metrics = ClassCacheMetrics::register(&MetricsRegistry::dummy()).expect("Registering class cache metrics");
+        Self::new(Self::DEFAULT_CAPACITY, metrics)
+    }
+
+    /// Returns the class if it is cached and declared on top of `on_top_of`.
+    pub fn get(&self, backend: &MadaraBackend, class_hash: &ClassHash, on_top_of: &DbBlockId) -> Option<ContractClass> {
+        let mut inner = self.lock(backend);
+        let class = inner.classes.get(class_hash).filter(|cached| match on_top_of {
+            DbBlockId::Pending => true,
+            DbBlockId::BlockN(block_n) => cached.declared_at <= *block_n,
+        });
+
+        if let Some(cached) = class {
+            self.metrics.hits.inc();
+            Some(cached.class.clone())
+        } else {
+            self.metrics.misses.inc();
+            None
+        }
+    }
+
+    pub fn insert(&self, backend: &MadaraBackend, class_hash: ClassHash, declared_at: u64, class: ContractClass) {
+        self.lock(backend).classes.put(class_hash, CachedClass { declared_at, class });
+    }
+
+    fn lock(&self, backend: &MadaraBackend) -> MutexGuard<'_, ClassCacheInner> {
+        let mut inner = self.inner.lock().expect("Poisoned lock");
+        let revert_count = backend.get_revert_count();
+        if inner.revert_count != revert_count {
+            inner.classes.clear();
+            inner.revert_count = revert_count;
+        }
+        inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockifier::execution::contract_class::ContractClassV0;
+    use mp_chain_config::ChainConfig;
+    use starknet_types_core::felt::Felt;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_class_cache_visibility() {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::test_config().unwrap()));
+        let cache = ContractClassCache::for_testing();
+        let class_hash = ClassHash(Felt::ONE);
+        let class = ContractClass::V0(ContractClassV0::default());
+
+        assert_eq!(cache.get(&backend, &class_hash, &DbBlockId::Pending), None);
+        cache.insert(&backend, class_hash, 5, class.clone());
+
+        assert_eq!(cache.get(&backend, &class_hash, &DbBlockId::BlockN(4)), None);
+        assert_eq!(cache.get(&backend, &class_hash, &DbBlockId::BlockN(5)), Some(class.clone()));
+        assert_eq!(cache.get(&backend, &class_hash, &DbBlockId::Pending), Some(class));
+        assert_eq!(cache.metrics.hits.get(), 2);
+        assert_eq!(cache.metrics.misses.get(), 2);
+    }
+}
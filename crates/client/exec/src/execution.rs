@@ -1,12 +1,13 @@
 use blockifier::fee::fee_utils::get_fee_by_gas_vector;
 use blockifier::fee::gas_usage::estimate_minimal_gas_vector;
-use blockifier::state::cached_state::TransactionalState;
+use blockifier::state::cached_state::{CommitmentStateDiff, TransactionalState};
 use blockifier::transaction::account_transaction::AccountTransaction;
 use blockifier::transaction::errors::TransactionExecutionError;
 use blockifier::transaction::objects::{FeeType, HasRelatedFeeType, TransactionExecutionInfo};
 use blockifier::transaction::transaction_execution::Transaction;
 use blockifier::transaction::transaction_types::TransactionType;
 use blockifier::transaction::transactions::{ExecutableTransaction, ExecutionFlags};
+use mp_receipt::{from_blockifier_execution_info, TransactionReceipt};
 use starknet_api::transaction::TransactionHash;
 
 use crate::{Error, ExecutionContext, ExecutionResult, TxFeeEstimationError, TxReexecError};
@@ -98,6 +99,36 @@ impl ExecutionContext {
     }
 }
 
+impl ExecutionContext {
+    /// Execute all of the transactions of the block, in order and charging fees, the way the sequencer did when it
+    /// produced the block. Unlike [`ExecutionContext::re_execute_transactions`], the state diff is that of the whole
+    /// block: a storage slot written by several transactions appears once, and not at all if the block leaves it
+    /// unchanged.
+    pub fn re_execute_block(
+        &self,
+        transactions: impl IntoIterator<Item = Transaction>,
+    ) -> Result<(Vec<TransactionReceipt>, CommitmentStateDiff), Error> {
+        let mut cached_state = self.init_cached_state();
+
+        let receipts = transactions
+            .into_iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                let hash = tx.tx_hash();
+                log::debug!("executing {hash:#} (block re-execution)");
+                let charge_fee = true;
+                let validate = true;
+                let execution_info = tx
+                    .execute(&mut cached_state, &self.block_context, charge_fee, validate)
+                    .map_err(|err| TxReexecError { block_n: self.db_id, hash, index, err })?;
+                Ok(from_blockifier_execution_info(&execution_info, &tx))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok((receipts, cached_state.to_state_diff()?.into()))
+    }
+}
+
 pub trait TxInfo {
     fn tx_hash(&self) -> TransactionHash;
     fn tx_type(&self) -> TransactionType;
@@ -1,5 +1,5 @@
 use blockifier::{
-    state::cached_state::CommitmentStateDiff,
+    state::{cached_state::CommitmentStateDiff, errors::StateError},
     transaction::{
         errors::TransactionExecutionError,
         objects::{FeeType, GasVector, TransactionExecutionInfo},
@@ -13,12 +13,14 @@ use starknet_types_core::felt::Felt;
 mod block_context;
 mod blockifier_state_adapter;
 mod call;
+mod class_cache;
 mod execution;
 mod fee;
 mod trace;
 
 pub use block_context::ExecutionContext;
 pub use blockifier_state_adapter::BlockifierStateAdapter;
+pub use class_cache::{ClassCacheMetrics, ContractClassCache};
 pub use trace::execution_result_to_tx_trace;
 
 #[derive(thiserror::Error, Debug)]
@@ -37,6 +39,8 @@ pub enum Error {
     Storage(#[from] MadaraStorageError),
     #[error("Invalid sequencer address: {0:#x}")]
     InvalidSequencerAddress(Felt),
+    #[error("State error: {0:#}")]
+    State(#[from] StateError),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -0,0 +1,142 @@
+use hyper::{header, Body, Response, StatusCode};
+use jsonrpsee::types::ErrorObjectOwned;
+use mc_db::MadaraStorageError;
+use serde::Serialize;
+
+use crate::types::UserTransactionError;
+
+/// Error codes returned by the Starknet sequencer gateway, which clients such as `starknet-providers` match on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StarknetErrorCode {
+    #[serde(rename = "StarknetErrorCode.BLOCK_NOT_FOUND")]
+    BlockNotFound,
+    #[serde(rename = "StarknetErrorCode.UNDECLARED_CLASS")]
+    UndeclaredClass,
+    #[serde(rename = "StarknetErrorCode.MALFORMED_REQUEST")]
+    MalformedRequest,
+    #[serde(rename = "StarknetErrorCode.INVALID_TRANSACTION_VERSION")]
+    InvalidTransactionVersion,
+    #[serde(rename = "StarknetErrorCode.INVALID_CONTRACT_CLASS")]
+    InvalidContractClass,
+    #[serde(rename = "StarknetErrorCode.CLASS_ALREADY_DECLARED")]
+    ClassAlreadyDeclared,
+    #[serde(rename = "StarknetErrorCode.INVALID_TRANSACTION_NONCE")]
+    InvalidTransactionNonce,
+    #[serde(rename = "StarknetErrorCode.INSUFFICIENT_MAX_FEE")]
+    InsufficientMaxFee,
+    #[serde(rename = "StarknetErrorCode.INSUFFICIENT_ACCOUNT_BALANCE")]
+    InsufficientAccountBalance,
+    #[serde(rename = "StarknetErrorCode.VALIDATE_FAILURE")]
+    ValidateFailure,
+    #[serde(rename = "StarknetErrorCode.COMPILATION_FAILED")]
+    CompilationFailed,
+    #[serde(rename = "StarknetErrorCode.CONTRACT_CLASS_OBJECT_SIZE_TOO_LARGE")]
+    ContractClassObjectSizeTooLarge,
+    #[serde(rename = "StarknetErrorCode.DUPLICATED_TRANSACTION")]
+    DuplicatedTransaction,
+    #[serde(rename = "StarknetErrorCode.INVALID_COMPILED_CLASS_HASH")]
+    InvalidCompiledClassHash,
+}
+
+impl StarknetErrorCode {
+    /// The gateway equivalent of a JSON-RPC error code, for the errors returned when adding a transaction.
+    fn from_rpc_code(code: i32) -> Option<Self> {
+        match code {
+            50 => Some(Self::InvalidContractClass),
+            51 => Some(Self::ClassAlreadyDeclared),
+            52 => Some(Self::InvalidTransactionNonce),
+            53 => Some(Self::InsufficientMaxFee),
+            54 => Some(Self::InsufficientAccountBalance),
+            55 => Some(Self::ValidateFailure),
+            56 => Some(Self::CompilationFailed),
+            57 => Some(Self::ContractClassObjectSizeTooLarge),
+            59 => Some(Self::DuplicatedTransaction),
+            60 => Some(Self::InvalidCompiledClassHash),
+            61 => Some(Self::InvalidTransactionVersion),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayError {
+    #[error("{message}")]
+    StarknetError { code: StarknetErrorCode, message: String },
+    #[error("Not found")]
+    NotFound,
+    #[error("Request body is larger than {max_size} bytes")]
+    PayloadTooLarge { max_size: usize },
+    #[error("Storage error: {0:#}")]
+    Storage(#[from] MadaraStorageError),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<ErrorObjectOwned> for GatewayError {
+    fn from(err: ErrorObjectOwned) -> Self {
+        let message = match err.data() {
+            Some(data) => format!("{}: {}", err.message(), data.get()),
+            None => err.message().to_string(),
+        };
+        match StarknetErrorCode::from_rpc_code(err.code()) {
+            Some(code) => Self::StarknetError { code, message },
+            None => Self::Internal(anyhow::anyhow!("Adding transaction: {message}")),
+        }
+    }
+}
+
+impl From<UserTransactionError> for GatewayError {
+    fn from(err: UserTransactionError) -> Self {
+        let code = match err {
+            UserTransactionError::UnsupportedVersion(_) => StarknetErrorCode::InvalidTransactionVersion,
+            UserTransactionError::InvalidSierraProgram(_) => StarknetErrorCode::InvalidContractClass,
+            UserTransactionError::SierraProgramTooLarge => StarknetErrorCode::ContractClassObjectSizeTooLarge,
+            _ => StarknetErrorCode::MalformedRequest,
+        };
+        Self::StarknetError { code, message: err.to_string() }
+    }
+}
+
+#[derive(Serialize)]
+struct StarknetErrorBody<'a> {
+    code: StarknetErrorCode,
+    message: &'a str,
+}
+
+impl GatewayError {
+    pub fn block_not_found() -> Self {
+        Self::StarknetError { code: StarknetErrorCode::BlockNotFound, message: "Block not found".into() }
+    }
+
+    pub fn malformed_request(message: impl Into<String>) -> Self {
+        Self::StarknetError { code: StarknetErrorCode::MalformedRequest, message: message.into() }
+    }
+
+    pub fn into_response(self) -> Response<Body> {
+        match self {
+            Self::StarknetError { code, message } => {
+                let body = serde_json::to_vec(&StarknetErrorBody { code, message: &message })
+                    .expect("Error body should serialize");
+                json_response(StatusCode::BAD_REQUEST, body)
+            }
+            Self::NotFound => response(StatusCode::NOT_FOUND, "Not found."),
+            Self::PayloadTooLarge { .. } => response(StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
+            Self::Storage(_) | Self::Internal(_) => {
+                log::error!("Error when handling feeder gateway request: {self:#}");
+                response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+            }
+        }
+    }
+}
+
+pub(crate) fn response(status: StatusCode, body: impl Into<Body>) -> Response<Body> {
+    let mut response = Response::new(body.into());
+    *response.status_mut() = status;
+    response
+}
+
+pub(crate) fn json_response(status: StatusCode, body: Vec<u8>) -> Response<Body> {
+    let mut response = response(status, body);
+    response.headers_mut().insert(header::CONTENT_TYPE, header::HeaderValue::from_static("application/json"));
+    response
+}
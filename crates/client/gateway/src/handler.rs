@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use hyper::body::HttpBody;
+use hyper::{header, Body, Method, Request, Response, StatusCode};
+use mc_db::db_block_id::{DbBlockId, DbBlockIdResolvable};
+use mc_db::l1_db::L1StateUpdateRecord;
+use mc_db::MadaraBackend;
+use mc_rpc::providers::AddTransactionProvider;
+use mp_block::{BlockId, BlockTag, MadaraMaybePendingBlockInfo};
+use mp_class::ClassInfo;
+use serde::Serialize;
+use starknet_types_core::felt::Felt;
+
+use crate::error::{json_response, GatewayError, StarknetErrorCode};
+use crate::types::{
+    AddTransactionCode, AddTransactionResult, ProviderBlock, ProviderStateUpdate, ProviderStateUpdateWithBlock,
+    UserTransaction,
+};
+
+type Query = HashMap<String, String>;
+
+/// `add_transaction_provider` is only set when the `gateway/add_transaction` endpoint is enabled. Request bodies larger
+/// than `max_request_size` bytes are rejected.
+pub(crate) async fn handle_request(
+    req: Request<Body>,
+    backend: Arc<MadaraBackend>,
+    add_transaction_provider: Option<Arc<dyn AddTransactionProvider>>,
+    max_request_size: usize,
+) -> Response<Body> {
+    let query: Query =
+        req.uri().query().map(|q| url::form_urlencoded::parse(q.as_bytes()).into_owned().collect()).unwrap_or_default();
+
+    let method = req.method().clone();
+    let path = req.uri().path().trim_end_matches('/').to_owned();
+
+    let res = match (&method, path.as_str()) {
+        (&Method::GET, "/feeder_gateway/get_block") => handle_get_block(&backend, &query),
+        (&Method::GET, "/feeder_gateway/get_state_update") => handle_get_state_update(&backend, &query),
+        (&Method::GET, "/feeder_gateway/get_class_by_hash") => handle_get_class_by_hash(&backend, &query),
+        (&Method::POST, "/gateway/add_transaction") => match add_transaction_provider {
+            Some(provider) => handle_add_transaction(req, provider.as_ref(), max_request_size).await,
+            None => Err(GatewayError::NotFound),
+        },
+        _ => Err(GatewayError::NotFound),
+    };
+
+    res.unwrap_or_else(GatewayError::into_response)
+}
+
+fn handle_get_block(backend: &MadaraBackend, query: &Query) -> Result<Response<Body>, GatewayError> {
+    let block_id = block_id_from_query(query, BlockTag::Latest)?;
+    let block = backend.get_block(&block_id)?.ok_or_else(GatewayError::block_not_found)?;
+    let l1_last_confirmed_block = backend.get_l1_last_confirmed_block()?;
+    let l1_state_update = l1_state_update(backend, &block.info)?;
+
+    ok_json(&ProviderBlock::new(block.info, block.inner, l1_last_confirmed_block, l1_state_update))
+}
+
+fn handle_get_state_update(backend: &MadaraBackend, query: &Query) -> Result<Response<Body>, GatewayError> {
+    let block_id = block_id_from_query(query, BlockTag::Latest)?;
+    let include_block = query.get("includeBlock").is_some_and(|value| value == "true");
+
+    let resolved_block_id = backend.resolve_block_id(&block_id)?.ok_or_else(GatewayError::block_not_found)?;
+    let block_info = backend.get_block_info(&resolved_block_id)?.ok_or_else(GatewayError::block_not_found)?;
+    let state_diff = backend.get_block_state_diff(&resolved_block_id)?.ok_or_else(GatewayError::block_not_found)?;
+
+    let state_update = match &block_info {
+        MadaraMaybePendingBlockInfo::Pending(_) => ProviderStateUpdate {
+            block_hash: None,
+            new_root: None,
+            old_root: global_state_root(backend, &BlockId::Tag(BlockTag::Latest))?,
+            state_diff: state_diff.into(),
+        },
+        MadaraMaybePendingBlockInfo::NotPending(info) => ProviderStateUpdate {
+            block_hash: Some(info.block_hash),
+            new_root: Some(info.header.global_state_root),
+            old_root: match info.header.block_number.checked_sub(1) {
+                Some(parent_n) => global_state_root(backend, &DbBlockId::BlockN(parent_n))?,
+                None => Felt::ZERO,
+            },
+            state_diff: state_diff.into(),
+        },
+    };
+
+    if !include_block {
+        return ok_json(&state_update);
+    }
+
+    let inner = backend.get_block_inner(&resolved_block_id)?.ok_or_else(GatewayError::block_not_found)?;
+    let l1_last_confirmed_block = backend.get_l1_last_confirmed_block()?;
+    let l1_state_update = l1_state_update(backend, &block_info)?;
+    ok_json(&ProviderStateUpdateWithBlock {
+        state_update,
+        block: ProviderBlock::new(block_info, inner, l1_last_confirmed_block, l1_state_update),
+    })
+}
+
+fn handle_get_class_by_hash(backend: &MadaraBackend, query: &Query) -> Result<Response<Body>, GatewayError> {
+    let class_hash = query.get("classHash").ok_or_else(|| GatewayError::malformed_request("Missing classHash"))?;
+    let class_hash = Felt::from_hex(class_hash).map_err(|_| GatewayError::malformed_request("Invalid classHash"))?;
+    // Classes declared in the pending block are visible by default, as on the Starknet feeder gateway.
+    let block_id = block_id_from_query(query, BlockTag::Pending)?;
+
+    let class_info = backend.get_class_info(&block_id, &class_hash)?.ok_or_else(|| GatewayError::StarknetError {
+        code: StarknetErrorCode::UndeclaredClass,
+        message: format!("Class with hash {class_hash:#x} is not declared"),
+    })?;
+
+    match class_info {
+        ClassInfo::Sierra(info) => {
+            ok_json(&starknet_core::types::FlattenedSierraClass::from((*info.contract_class).clone()))
+        }
+        ClassInfo::Legacy(info) => {
+            let program = info.contract_class.decompress_program().map_err(anyhow::Error::from)?;
+            let class = starknet_core::types::CompressedLegacyContractClass::from((*info.contract_class).clone());
+            ok_json(&serde_json::json!({
+                "program": program,
+                "entry_points_by_type": class.entry_points_by_type,
+                "abi": class.abi,
+            }))
+        }
+    }
+}
+
+async fn handle_add_transaction(
+    req: Request<Body>,
+    provider: &dyn AddTransactionProvider,
+    max_request_size: usize,
+) -> Result<Response<Body>, GatewayError> {
+    let body = read_body(req, max_request_size).await?;
+    let transaction: UserTransaction = serde_json::from_slice(&body)
+        .map_err(|err| GatewayError::malformed_request(format!("Invalid transaction: {err}")))?;
+
+    let result = match transaction {
+        UserTransaction::Invoke(tx) => {
+            let res = provider.add_invoke_transaction(tx.try_into()?).await?;
+            AddTransactionResult {
+                code: AddTransactionCode::TransactionReceived,
+                transaction_hash: res.transaction_hash,
+                address: None,
+                class_hash: None,
+            }
+        }
+        UserTransaction::Declare(tx) => {
+            let res = provider.add_declare_transaction(tx.try_into()?).await?;
+            AddTransactionResult {
+                code: AddTransactionCode::TransactionReceived,
+                transaction_hash: res.transaction_hash,
+                address: None,
+                class_hash: Some(res.class_hash),
+            }
+        }
+        UserTransaction::DeployAccount(tx) => {
+            let res = provider.add_deploy_account_transaction(tx.try_into()?).await?;
+            AddTransactionResult {
+                code: AddTransactionCode::TransactionReceived,
+                transaction_hash: res.transaction_hash,
+                address: Some(res.contract_address),
+                class_hash: None,
+            }
+        }
+    };
+
+    ok_json(&result)
+}
+
+/// The `Content-Length` header is checked first, but chunked bodies do not have one, so the size is also checked while
+/// reading.
+async fn read_body(req: Request<Body>, max_size: usize) -> Result<Vec<u8>, GatewayError> {
+    let content_length = req.headers().get(header::CONTENT_LENGTH).and_then(|value| value.to_str().ok()?.parse().ok());
+    if content_length.is_some_and(|len: usize| len > max_size) {
+        return Err(GatewayError::PayloadTooLarge { max_size });
+    }
+
+    let mut body = req.into_body();
+    let mut bytes = Vec::with_capacity(content_length.unwrap_or_default());
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(anyhow::Error::from)?;
+        if bytes.len() + chunk.len() > max_size {
+            return Err(GatewayError::PayloadTooLarge { max_size });
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// `blockNumber` can be a number, `latest` or `pending`. It takes precedence over `blockHash`.
+fn block_id_from_query(query: &Query, default: BlockTag) -> Result<BlockId, GatewayError> {
+    if let Some(block_number) = query.get("blockNumber") {
+        return match block_number.as_str() {
+            "latest" => Ok(BlockId::Tag(BlockTag::Latest)),
+            "pending" => Ok(BlockId::Tag(BlockTag::Pending)),
+            number => number
+                .parse()
+                .map(BlockId::Number)
+                .map_err(|_| GatewayError::malformed_request(format!("Invalid blockNumber {number:?}"))),
+        };
+    }
+    if let Some(block_hash) = query.get("blockHash") {
+        return Felt::from_hex(block_hash)
+            .map(BlockId::Hash)
+            .map_err(|_| GatewayError::malformed_request(format!("Invalid blockHash {block_hash:?}")));
+    }
+    Ok(BlockId::Tag(default))
+}
+
+/// Zero when there is no such block, which is the case for the parent of the genesis block.
+fn global_state_root(backend: &MadaraBackend, block_id: &impl DbBlockIdResolvable) -> Result<Felt, GatewayError> {
+    Ok(backend
+        .get_block_info(block_id)?
+        .and_then(|info| info.as_nonpending().map(|info| info.header.global_state_root))
+        .unwrap_or(Felt::ZERO))
+}
+
+/// The state update posted on L1 which settled a closed block.
+fn l1_state_update(
+    backend: &MadaraBackend,
+    block_info: &MadaraMaybePendingBlockInfo,
+) -> Result<Option<L1StateUpdateRecord>, GatewayError> {
+    match block_info.block_n() {
+        Some(block_n) => Ok(backend.get_l1_state_update(block_n)?),
+        None => Ok(None),
+    }
+}
+
+fn ok_json<T: Serialize>(value: &T) -> Result<Response<Body>, GatewayError> {
+    let body = serde_json::to_vec(value).map_err(anyhow::Error::from)?;
+    Ok(json_response(StatusCode::OK, body))
+}
@@ -0,0 +1,19 @@
+//! Feeder gateway server. It serves the blocks, state updates and classes stored in the database using the same
+//! endpoints and JSON format as the Starknet sequencer, so that other nodes can sync from Madara with
+//! `starknet-providers`' `SequencerGatewayProvider`.
+//!
+//! Supported endpoints:
+//! - `/feeder_gateway/get_block`
+//! - `/feeder_gateway/get_state_update`, with `includeBlock=true`
+//! - `/feeder_gateway/get_class_by_hash`
+//! - `/gateway/add_transaction`, when enabled. Transactions are handed to an
+//!   [`AddTransactionProvider`](mc_rpc::providers::AddTransactionProvider), which lets full nodes forward their
+//!   transactions to a Madara sequencer.
+
+mod error;
+mod handler;
+mod server;
+pub mod types;
+
+pub use error::{GatewayError, StarknetErrorCode};
+pub use server::start_server;
@@ -0,0 +1,469 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Context;
+use hyper::server::conn::AddrIncoming;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Server};
+use mc_db::MadaraBackend;
+use mc_rpc::providers::AddTransactionProvider;
+use mp_utils::wait_or_graceful_shutdown;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::handler::handle_request;
+
+/// Serves the feeder gateway until `stop_recv` fires or a graceful shutdown is requested. The
+/// `gateway/add_transaction` endpoint is only served when an `add_transaction_provider` is given, and rejects request
+/// bodies larger than `max_request_size` bytes.
+pub async fn start_server(
+    backend: Arc<MadaraBackend>,
+    add_transaction_provider: Option<Arc<dyn AddTransactionProvider>>,
+    max_request_size: usize,
+    addr: SocketAddr,
+    stop_recv: oneshot::Receiver<()>,
+) -> anyhow::Result<()> {
+    let socket = TcpListener::bind(addr).await.with_context(|| format!("Opening socket server at {addr}"))?;
+    serve(backend, add_transaction_provider, max_request_size, socket, stop_recv).await
+}
+
+pub(crate) async fn serve(
+    backend: Arc<MadaraBackend>,
+    add_transaction_provider: Option<Arc<dyn AddTransactionProvider>>,
+    max_request_size: usize,
+    socket: TcpListener,
+    stop_recv: oneshot::Receiver<()>,
+) -> anyhow::Result<()> {
+    let listener = AddrIncoming::from_listener(socket).context("Opening feeder gateway socket")?;
+    log::info!("🌐 Gateway endpoint started at {}", listener.local_addr());
+
+    let service = make_service_fn(move |_| {
+        let backend = Arc::clone(&backend);
+        let add_transaction_provider = add_transaction_provider.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let backend = Arc::clone(&backend);
+                let add_transaction_provider = add_transaction_provider.clone();
+                async move {
+                    Ok::<_, Infallible>(handle_request(req, backend, add_transaction_provider, max_request_size).await)
+                }
+            }))
+        }
+    });
+
+    Server::builder(listener)
+        .serve(service)
+        .with_graceful_shutdown(async {
+            wait_or_graceful_shutdown(stop_recv).await;
+        })
+        .await
+        .context("Running feeder gateway server")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use jsonrpsee::core::{async_trait, RpcResult};
+    use jsonrpsee::types::ErrorObjectOwned;
+    use mc_db::l1_db::L1StateUpdateRecord;
+    use mc_mempool::{SubmittedTransaction, TransactionStatusTable};
+    use mp_block::header::GasPrices;
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+    use mp_chain_config::{ChainConfig, StarknetVersion};
+    use mp_receipt::{
+        DeployAccountTransactionReceipt, Event, ExecutionResources, FeePayment, InvokeTransactionReceipt, MsgToL1,
+        PriceUnit, TransactionReceipt,
+    };
+    use mp_state_update::{ContractStorageDiffItem, DeployedContractItem, NonceUpdate, StateDiff, StorageEntry};
+    use mp_transactions::{
+        DataAvailabilityMode, DeployAccountTransactionV3, InvokeTransactionV1, ResourceBounds, ResourceBoundsMapping,
+        Transaction,
+    };
+    use mp_utils::tests_common::*;
+    use rstest::*;
+    use starknet_core::types::{
+        BlockId, BlockTag, BroadcastedDeclareTransaction, BroadcastedDeclareTransactionV2,
+        BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV3,
+        DeclareTransactionResult, DeployAccountTransactionResult, EntryPointsByType, FlattenedSierraClass,
+        InvokeTransactionResult, StarknetError,
+    };
+    use starknet_providers::{Provider, ProviderError, SequencerGatewayProvider};
+    use starknet_types_core::felt::Felt;
+    use tokio::sync::broadcast;
+    use url::Url;
+
+    const TEST_MAX_REQUEST_SIZE: usize = 1024 * 1024;
+
+    struct TestServer {
+        provider: SequencerGatewayProvider,
+        _stop_send: oneshot::Sender<()>,
+    }
+
+    #[fixture]
+    fn backend(_set_workdir: ()) -> Arc<MadaraBackend> {
+        MadaraBackend::open_for_testing(Arc::new(ChainConfig::test_config().unwrap()))
+    }
+
+    async fn start_test_server(
+        backend: Arc<MadaraBackend>,
+        add_transaction_provider: Option<Arc<dyn AddTransactionProvider>>,
+    ) -> TestServer {
+        let socket = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", socket.local_addr().unwrap());
+        let (stop_send, stop_recv) = oneshot::channel();
+        tokio::spawn(serve(backend, add_transaction_provider, TEST_MAX_REQUEST_SIZE, socket, stop_recv));
+
+        let provider = SequencerGatewayProvider::new(
+            Url::parse(&format!("{base_url}/gateway")).unwrap(),
+            Url::parse(&format!("{base_url}/feeder_gateway")).unwrap(),
+            Felt::from_hex_unchecked("0x4d41444152415f54455354"),
+        );
+        TestServer { provider, _stop_send: stop_send }
+    }
+
+    /// Records the transactions it receives, or rejects them all with `reject_with` when set.
+    #[derive(Default)]
+    struct RecordingProvider {
+        invoke: Mutex<Vec<BroadcastedInvokeTransaction>>,
+        declare: Mutex<Vec<BroadcastedDeclareTransaction>>,
+        reject_with: Option<i32>,
+        submitted: TransactionStatusTable,
+    }
+
+    impl RecordingProvider {
+        fn check_rejected(&self) -> RpcResult<()> {
+            match self.reject_with {
+                Some(code) => Err(ErrorObjectOwned::owned(code, "Rejected", None::<()>)),
+                None => Ok(()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AddTransactionProvider for RecordingProvider {
+        async fn add_declare_transaction(
+            &self,
+            declare_transaction: BroadcastedDeclareTransaction,
+        ) -> RpcResult<DeclareTransactionResult> {
+            self.check_rejected()?;
+            self.declare.lock().unwrap().push(declare_transaction);
+            Ok(DeclareTransactionResult { transaction_hash: Felt::from(0x20), class_hash: Felt::from(0x21) })
+        }
+
+        async fn add_deploy_account_transaction(
+            &self,
+            _deploy_account_transaction: BroadcastedDeployAccountTransaction,
+        ) -> RpcResult<DeployAccountTransactionResult> {
+            self.check_rejected()?;
+            Ok(DeployAccountTransactionResult {
+                transaction_hash: Felt::from(0x30),
+                contract_address: Felt::from(0x31),
+            })
+        }
+
+        async fn add_invoke_transaction(
+            &self,
+            invoke_transaction: BroadcastedInvokeTransaction,
+        ) -> RpcResult<InvokeTransactionResult> {
+            self.check_rejected()?;
+            self.invoke.lock().unwrap().push(invoke_transaction);
+            Ok(InvokeTransactionResult { transaction_hash: Felt::from(0x10) })
+        }
+
+        fn get_submitted_transaction(&self, _tx_hash: &Felt) -> Option<SubmittedTransaction> {
+            None
+        }
+
+        fn subscribe_submitted_transactions(&self) -> broadcast::Receiver<Felt> {
+            self.submitted.subscribe()
+        }
+    }
+
+    fn invoke_v3() -> BroadcastedInvokeTransaction {
+        BroadcastedInvokeTransaction::V3(BroadcastedInvokeTransactionV3 {
+            sender_address: Felt::from(0x100),
+            calldata: vec![Felt::ONE, Felt::TWO],
+            signature: vec![Felt::THREE],
+            nonce: Felt::ONE,
+            resource_bounds: starknet_core::types::ResourceBoundsMapping {
+                l1_gas: starknet_core::types::ResourceBounds { max_amount: 0x100, max_price_per_unit: 0x3bf1322e5 },
+                l2_gas: starknet_core::types::ResourceBounds { max_amount: 0, max_price_per_unit: 0 },
+            },
+            tip: 0,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: starknet_core::types::DataAvailabilityMode::L1,
+            fee_data_availability_mode: starknet_core::types::DataAvailabilityMode::L1,
+            is_query: false,
+        })
+    }
+
+    fn block_zero() -> (MadaraMaybePendingBlock, StateDiff) {
+        let transactions: Vec<Transaction> = vec![
+            InvokeTransactionV1 {
+                sender_address: Felt::from(0x100),
+                calldata: vec![Felt::ONE, Felt::TWO],
+                max_fee: Felt::from(0x1000),
+                signature: vec![Felt::THREE],
+                nonce: Felt::ZERO,
+            }
+            .into(),
+            DeployAccountTransactionV3 {
+                signature: vec![Felt::THREE],
+                nonce: Felt::ZERO,
+                contract_address_salt: Felt::from(0x42),
+                constructor_calldata: vec![Felt::ONE],
+                class_hash: Felt::from(0x200),
+                resource_bounds: ResourceBoundsMapping {
+                    l1_gas: ResourceBounds { max_amount: 0x100, max_price_per_unit: 0x3bf1322e5 },
+                    l2_gas: ResourceBounds::default(),
+                },
+                tip: 0,
+                paymaster_data: vec![],
+                nonce_data_availability_mode: DataAvailabilityMode::L1,
+                fee_data_availability_mode: DataAvailabilityMode::L1,
+            }
+            .into(),
+        ];
+        let execution_resources = ExecutionResources {
+            steps: 1234,
+            memory_holes: Some(5),
+            pedersen_builtin_applications: Some(2),
+            ..Default::default()
+        };
+        let receipts: Vec<TransactionReceipt> = vec![
+            InvokeTransactionReceipt {
+                transaction_hash: Felt::from(0x10),
+                actual_fee: FeePayment { amount: Felt::from(0x500), unit: PriceUnit::Wei },
+                messages_sent: vec![MsgToL1 {
+                    from_address: Felt::from(0x100),
+                    to_address: Felt::from_hex_unchecked("0xae0ee0a63a2ce6baeeffe56e7714fb4efe48d419"),
+                    payload: vec![Felt::ONE],
+                }],
+                events: vec![Event { from_address: Felt::from(0x100), keys: vec![Felt::ONE], data: vec![Felt::TWO] }],
+                execution_resources: execution_resources.clone(),
+                execution_result: Default::default(),
+            }
+            .into(),
+            DeployAccountTransactionReceipt {
+                transaction_hash: Felt::from(0x11),
+                actual_fee: FeePayment { amount: Felt::from(0x600), unit: PriceUnit::Wei },
+                messages_sent: vec![],
+                events: vec![],
+                execution_resources,
+                execution_result: Default::default(),
+                contract_address: Felt::from(0x300),
+            }
+            .into(),
+        ];
+
+        let header = Header {
+            global_state_root: Felt::from(0x1234),
+            sequencer_address: Felt::from(0x1),
+            block_timestamp: 1725974819,
+            transaction_count: 2,
+            protocol_version: StarknetVersion::new(0, 13, 2, 0),
+            l1_gas_price: GasPrices {
+                eth_l1_gas_price: 0x3bf1322e5,
+                strk_l1_gas_price: 0x55dfe7f2de82,
+                eth_l1_data_gas_price: 0x3f9ffec0e7,
+                strk_l1_data_gas_price: 0x5b269552db6fa,
+            },
+            ..Default::default()
+        };
+        let block = MadaraMaybePendingBlock {
+            info: MadaraBlockInfo::new(header, vec![Felt::from(0x10), Felt::from(0x11)], Felt::from(0x12)).into(),
+            inner: MadaraBlockInner::new(transactions, receipts),
+        };
+
+        let state_diff = StateDiff {
+            storage_diffs: vec![ContractStorageDiffItem {
+                address: Felt::from(0x100),
+                storage_entries: vec![StorageEntry { key: Felt::ONE, value: Felt::TWO }],
+            }],
+            deployed_contracts: vec![DeployedContractItem {
+                address: Felt::from(0x300),
+                class_hash: Felt::from(0x200),
+            }],
+            nonces: vec![NonceUpdate { contract_address: Felt::from(0x100), nonce: Felt::ONE }],
+            ..Default::default()
+        };
+
+        (block, state_diff)
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_state_update_with_block_round_trip(backend: Arc<MadaraBackend>) {
+        let (block, state_diff) = block_zero();
+        backend.store_block(block.clone(), state_diff.clone(), vec![]).unwrap();
+        let server = start_test_server(backend, None).await;
+
+        #[allow(deprecated)] // Sequencer-specific functions are deprecated. Use it via the Provider trait instead.
+        let res = server
+            .provider
+            .get_state_update_with_block(starknet_providers::sequencer::models::BlockId::Number(0))
+            .await
+            .unwrap();
+
+        assert_eq!(res.state_update.block_hash, Some(Felt::from(0x12)));
+        assert_eq!(res.state_update.new_root, Some(Felt::from(0x1234)));
+        assert_eq!(res.state_update.old_root, Felt::ZERO);
+        assert_eq!(StateDiff::from(res.state_update.state_diff), state_diff);
+
+        assert_eq!(res.block.block_hash, Some(Felt::from(0x12)));
+        assert_eq!(res.block.block_number, Some(0));
+        assert_eq!(res.block.state_root, Some(Felt::from(0x1234)));
+        assert_eq!(res.block.timestamp, 1725974819);
+        assert_eq!(res.block.starknet_version.as_deref(), Some("0.13.2"));
+
+        let (transactions, receipts): (Vec<_>, Vec<_>) = res
+            .block
+            .transactions
+            .into_iter()
+            .zip(res.block.transaction_receipts)
+            .map(|(tx, receipt)| {
+                let receipt = TransactionReceipt::from_provider(receipt, &tx);
+                (Transaction::try_from(tx).unwrap(), receipt)
+            })
+            .unzip();
+        assert_eq!(transactions, block.inner.transactions);
+        assert_eq!(receipts, block.inner.receipts);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_pending_state_update(backend: Arc<MadaraBackend>) {
+        let (block, state_diff) = block_zero();
+        backend.store_block(block, state_diff, vec![]).unwrap();
+        let server = start_test_server(backend, None).await;
+
+        #[allow(deprecated)]
+        let res = server
+            .provider
+            .get_state_update_with_block(starknet_providers::sequencer::models::BlockId::Pending)
+            .await
+            .unwrap();
+
+        assert_eq!(res.state_update.block_hash, None);
+        assert_eq!(res.state_update.old_root, Felt::from(0x1234));
+        assert_eq!(res.block.block_hash, None);
+        assert_eq!(res.block.parent_block_hash, Felt::from(0x12));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_add_transaction_round_trip(backend: Arc<MadaraBackend>) {
+        let add_txs = Arc::new(RecordingProvider::default());
+        let server = start_test_server(backend, Some(Arc::clone(&add_txs) as _)).await;
+
+        let res = server.provider.add_invoke_transaction(invoke_v3()).await.unwrap();
+        assert_eq!(res.transaction_hash, Felt::from(0x10));
+        assert_eq!(*add_txs.invoke.lock().unwrap(), vec![invoke_v3()]);
+
+        let declare = BroadcastedDeclareTransaction::V2(BroadcastedDeclareTransactionV2 {
+            sender_address: Felt::from(0x100),
+            compiled_class_hash: Felt::from(0x400),
+            max_fee: Felt::from(0x1000),
+            signature: vec![Felt::THREE],
+            nonce: Felt::TWO,
+            contract_class: Arc::new(FlattenedSierraClass {
+                sierra_program: vec![Felt::ONE, Felt::from(0x1234)],
+                contract_class_version: "0.1.0".into(),
+                entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+                abi: "[]".into(),
+            }),
+            is_query: false,
+        });
+        let res = server.provider.add_declare_transaction(declare.clone()).await.unwrap();
+        assert_eq!(res.transaction_hash, Felt::from(0x20));
+        assert_eq!(res.class_hash, Felt::from(0x21));
+        assert_eq!(*add_txs.declare.lock().unwrap(), vec![declare]);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_add_transaction_errors(backend: Arc<MadaraBackend>) {
+        let add_txs = Arc::new(RecordingProvider { reject_with: Some(52), ..Default::default() });
+        let server = start_test_server(Arc::clone(&backend), Some(add_txs)).await;
+        assert!(matches!(
+            server.provider.add_invoke_transaction(invoke_v3()).await,
+            Err(ProviderError::StarknetError(StarknetError::InvalidTransactionNonce))
+        ));
+
+        // The endpoint is disabled when no provider is given.
+        let server = start_test_server(backend, None).await;
+        assert!(server.provider.add_invoke_transaction(invoke_v3()).await.is_err());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_add_transaction_too_large(backend: Arc<MadaraBackend>) {
+        let add_txs: Arc<dyn AddTransactionProvider> = Arc::new(RecordingProvider::default());
+        let body = vec![b' '; TEST_MAX_REQUEST_SIZE + 1];
+
+        // Announced through the `Content-Length` header.
+        let req = Request::post("/gateway/add_transaction")
+            .header(hyper::header::CONTENT_LENGTH, body.len())
+            .body(Body::from(body.clone()))
+            .unwrap();
+        let res = handle_request(req, Arc::clone(&backend), Some(Arc::clone(&add_txs)), TEST_MAX_REQUEST_SIZE).await;
+        assert_eq!(res.status(), hyper::StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Without a `Content-Length` header, the body is only read up to the limit.
+        let req = Request::post("/gateway/add_transaction").body(Body::from(body)).unwrap();
+        let res = handle_request(req, backend, Some(add_txs), TEST_MAX_REQUEST_SIZE).await;
+        assert_eq!(res.status(), hyper::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    async fn get_block_json(backend: &Arc<MadaraBackend>) -> serde_json::Value {
+        let req = Request::get("/feeder_gateway/get_block?blockNumber=0").body(Body::empty()).unwrap();
+        let res = handle_request(req, Arc::clone(backend), None, TEST_MAX_REQUEST_SIZE).await;
+        serde_json::from_slice(&hyper::body::to_bytes(res.into_body()).await.unwrap()).unwrap()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_block_l1_transaction_hash(backend: Arc<MadaraBackend>) {
+        let (block, state_diff) = block_zero();
+        backend.store_block(block, state_diff, vec![]).unwrap();
+        backend
+            .store_l1_state_update(&L1StateUpdateRecord {
+                block_number: 0,
+                block_hash: Felt::from(0x12),
+                global_root: Felt::from(0x1234),
+                l1_block_number: 100,
+                l1_block_hash: [0xcd; 32],
+                l1_transaction_hash: [0xab; 32],
+            })
+            .unwrap();
+
+        // The state update was not verified yet.
+        let block = get_block_json(&backend).await;
+        assert_eq!(block["status"], "ACCEPTED_ON_L2");
+        assert!(block.get("l1_transaction_hash").is_none());
+
+        backend.write_last_confirmed_block(0).unwrap();
+        let block = get_block_json(&backend).await;
+        assert_eq!(block["status"], "ACCEPTED_ON_L1");
+        assert_eq!(block["l1_transaction_hash"], format!("0x{}", "ab".repeat(32)));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_not_found_errors(backend: Arc<MadaraBackend>) {
+        let server = start_test_server(backend, None).await;
+
+        assert!(matches!(
+            server.provider.get_block_with_tx_hashes(BlockId::Number(5)).await,
+            Err(ProviderError::StarknetError(StarknetError::BlockNotFound))
+        ));
+        assert!(matches!(
+            server.provider.get_class(BlockId::Tag(BlockTag::Pending), Felt::from(0x1234)).await,
+            Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound))
+        ));
+    }
+}
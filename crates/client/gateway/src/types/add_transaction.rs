@@ -0,0 +1,343 @@
+use std::io::Read;
+use std::sync::Arc;
+
+use base64::Engine;
+use mp_convert::felt_to_u64;
+use mp_transactions::{DataAvailabilityMode as MadaraDaMode, ResourceBoundsMapping as MadaraResourceBoundsMapping};
+use serde::{Deserialize, Serialize};
+use starknet_core::types::{
+    BroadcastedDeclareTransaction, BroadcastedDeclareTransactionV1, BroadcastedDeclareTransactionV2,
+    BroadcastedDeclareTransactionV3, BroadcastedDeployAccountTransaction, BroadcastedDeployAccountTransactionV1,
+    BroadcastedDeployAccountTransactionV3, BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV1,
+    BroadcastedInvokeTransactionV3, CompressedLegacyContractClass, EntryPointsByType, FlattenedSierraClass,
+};
+use starknet_types_core::felt::Felt;
+
+use super::V3Fields;
+
+/// A transaction sent to `gateway/add_transaction`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum UserTransaction {
+    #[serde(rename = "INVOKE_FUNCTION")]
+    Invoke(UserInvokeTransaction),
+    #[serde(rename = "DECLARE")]
+    Declare(UserDeclareTransaction),
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount(UserDeployAccountTransaction),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserInvokeTransaction {
+    pub version: Felt,
+    #[serde(alias = "contract_address")]
+    pub sender_address: Felt,
+    pub calldata: Vec<Felt>,
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    #[serde(default)]
+    pub max_fee: Option<Felt>,
+    #[serde(flatten)]
+    pub v3: Option<V3Fields>,
+    #[serde(default)]
+    pub account_deployment_data: Vec<Felt>,
+    #[serde(default)]
+    pub is_query: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserDeclareTransaction {
+    pub version: Felt,
+    pub sender_address: Felt,
+    pub contract_class: UserContractClass,
+    #[serde(default)]
+    pub compiled_class_hash: Option<Felt>,
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    #[serde(default)]
+    pub max_fee: Option<Felt>,
+    #[serde(flatten)]
+    pub v3: Option<V3Fields>,
+    #[serde(default)]
+    pub account_deployment_data: Vec<Felt>,
+    #[serde(default)]
+    pub is_query: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserDeployAccountTransaction {
+    pub version: Felt,
+    pub class_hash: Felt,
+    pub contract_address_salt: Felt,
+    pub constructor_calldata: Vec<Felt>,
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    #[serde(default)]
+    pub max_fee: Option<Felt>,
+    #[serde(flatten)]
+    pub v3: Option<V3Fields>,
+    #[serde(default)]
+    pub is_query: bool,
+}
+
+/// Classes are sent compressed: the legacy program and the sierra program are gzipped and base64 encoded.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum UserContractClass {
+    Sierra(CompressedSierraClass),
+    Legacy(CompressedLegacyContractClass),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompressedSierraClass {
+    pub sierra_program: String,
+    pub contract_class_version: String,
+    pub entry_points_by_type: EntryPointsByType,
+    pub abi: String,
+}
+
+/// Response of `gateway/add_transaction`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddTransactionResult {
+    pub code: AddTransactionCode,
+    pub transaction_hash: Felt,
+    /// Address of the deployed account, for deploy account transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Felt>,
+    /// Hash of the declared class, for declare transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_hash: Option<Felt>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AddTransactionCode {
+    #[serde(rename = "TRANSACTION_RECEIVED")]
+    TransactionReceived,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UserTransactionError {
+    #[error("Unsupported transaction version {0:#x}")]
+    UnsupportedVersion(Felt),
+    #[error("Query transactions cannot be added")]
+    QueryTransaction,
+    #[error("Missing field {0}")]
+    MissingField(&'static str),
+    #[error("Declare transactions of version {0:#x} expect a {1} class")]
+    WrongClassType(Felt, &'static str),
+    #[error("Invalid compressed sierra program: {0}")]
+    InvalidSierraProgram(String),
+    #[error("Decompressed sierra program is larger than {MAX_DECOMPRESSED_SIERRA_PROGRAM_SIZE} bytes")]
+    SierraProgramTooLarge,
+}
+
+struct V3 {
+    resource_bounds: starknet_core::types::ResourceBoundsMapping,
+    tip: u64,
+    paymaster_data: Vec<Felt>,
+    nonce_data_availability_mode: starknet_core::types::DataAvailabilityMode,
+    fee_data_availability_mode: starknet_core::types::DataAvailabilityMode,
+}
+
+fn v3_fields(v3: Option<V3Fields>) -> Result<V3, UserTransactionError> {
+    let v3 = v3.ok_or(UserTransactionError::MissingField("resource_bounds"))?;
+    Ok(V3 {
+        resource_bounds: MadaraResourceBoundsMapping::from(v3.resource_bounds).into(),
+        tip: v3.tip,
+        paymaster_data: v3.paymaster_data,
+        nonce_data_availability_mode: MadaraDaMode::from(v3.nonce_data_availability_mode).into(),
+        fee_data_availability_mode: MadaraDaMode::from(v3.fee_data_availability_mode).into(),
+    })
+}
+
+/// Query versions (with the `2^128` offset) do not fit and are rejected.
+fn version(version: Felt) -> Result<u64, UserTransactionError> {
+    felt_to_u64(&version).map_err(|_| UserTransactionError::UnsupportedVersion(version))
+}
+
+fn max_fee(max_fee: Option<Felt>) -> Result<Felt, UserTransactionError> {
+    max_fee.ok_or(UserTransactionError::MissingField("max_fee"))
+}
+
+impl TryFrom<UserInvokeTransaction> for BroadcastedInvokeTransaction {
+    type Error = UserTransactionError;
+
+    fn try_from(tx: UserInvokeTransaction) -> Result<Self, Self::Error> {
+        if tx.is_query {
+            return Err(UserTransactionError::QueryTransaction);
+        }
+        match version(tx.version)? {
+            1 => Ok(Self::V1(BroadcastedInvokeTransactionV1 {
+                sender_address: tx.sender_address,
+                calldata: tx.calldata,
+                max_fee: max_fee(tx.max_fee)?,
+                signature: tx.signature,
+                nonce: tx.nonce,
+                is_query: false,
+            })),
+            3 => {
+                let v3 = v3_fields(tx.v3)?;
+                Ok(Self::V3(BroadcastedInvokeTransactionV3 {
+                    sender_address: tx.sender_address,
+                    calldata: tx.calldata,
+                    signature: tx.signature,
+                    nonce: tx.nonce,
+                    resource_bounds: v3.resource_bounds,
+                    tip: v3.tip,
+                    paymaster_data: v3.paymaster_data,
+                    account_deployment_data: tx.account_deployment_data,
+                    nonce_data_availability_mode: v3.nonce_data_availability_mode,
+                    fee_data_availability_mode: v3.fee_data_availability_mode,
+                    is_query: false,
+                }))
+            }
+            _ => Err(UserTransactionError::UnsupportedVersion(tx.version)),
+        }
+    }
+}
+
+impl TryFrom<UserDeclareTransaction> for BroadcastedDeclareTransaction {
+    type Error = UserTransactionError;
+
+    fn try_from(tx: UserDeclareTransaction) -> Result<Self, Self::Error> {
+        if tx.is_query {
+            return Err(UserTransactionError::QueryTransaction);
+        }
+        match (version(tx.version)?, tx.contract_class) {
+            (1, UserContractClass::Legacy(contract_class)) => Ok(Self::V1(BroadcastedDeclareTransactionV1 {
+                sender_address: tx.sender_address,
+                max_fee: max_fee(tx.max_fee)?,
+                signature: tx.signature,
+                nonce: tx.nonce,
+                contract_class: Arc::new(contract_class),
+                is_query: false,
+            })),
+            (2, UserContractClass::Sierra(contract_class)) => Ok(Self::V2(BroadcastedDeclareTransactionV2 {
+                sender_address: tx.sender_address,
+                compiled_class_hash: tx
+                    .compiled_class_hash
+                    .ok_or(UserTransactionError::MissingField("compiled_class_hash"))?,
+                max_fee: max_fee(tx.max_fee)?,
+                signature: tx.signature,
+                nonce: tx.nonce,
+                contract_class: Arc::new(contract_class.decompress()?),
+                is_query: false,
+            })),
+            (3, UserContractClass::Sierra(contract_class)) => {
+                let v3 = v3_fields(tx.v3)?;
+                Ok(Self::V3(BroadcastedDeclareTransactionV3 {
+                    sender_address: tx.sender_address,
+                    compiled_class_hash: tx
+                        .compiled_class_hash
+                        .ok_or(UserTransactionError::MissingField("compiled_class_hash"))?,
+                    signature: tx.signature,
+                    nonce: tx.nonce,
+                    contract_class: Arc::new(contract_class.decompress()?),
+                    resource_bounds: v3.resource_bounds,
+                    tip: v3.tip,
+                    paymaster_data: v3.paymaster_data,
+                    account_deployment_data: tx.account_deployment_data,
+                    nonce_data_availability_mode: v3.nonce_data_availability_mode,
+                    fee_data_availability_mode: v3.fee_data_availability_mode,
+                    is_query: false,
+                }))
+            }
+            (1, _) => Err(UserTransactionError::WrongClassType(tx.version, "legacy")),
+            (2 | 3, _) => Err(UserTransactionError::WrongClassType(tx.version, "sierra")),
+            _ => Err(UserTransactionError::UnsupportedVersion(tx.version)),
+        }
+    }
+}
+
+impl TryFrom<UserDeployAccountTransaction> for BroadcastedDeployAccountTransaction {
+    type Error = UserTransactionError;
+
+    fn try_from(tx: UserDeployAccountTransaction) -> Result<Self, Self::Error> {
+        if tx.is_query {
+            return Err(UserTransactionError::QueryTransaction);
+        }
+        match version(tx.version)? {
+            1 => Ok(Self::V1(BroadcastedDeployAccountTransactionV1 {
+                max_fee: max_fee(tx.max_fee)?,
+                signature: tx.signature,
+                nonce: tx.nonce,
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+                class_hash: tx.class_hash,
+                is_query: false,
+            })),
+            3 => {
+                let v3 = v3_fields(tx.v3)?;
+                Ok(Self::V3(BroadcastedDeployAccountTransactionV3 {
+                    signature: tx.signature,
+                    nonce: tx.nonce,
+                    contract_address_salt: tx.contract_address_salt,
+                    constructor_calldata: tx.constructor_calldata,
+                    class_hash: tx.class_hash,
+                    resource_bounds: v3.resource_bounds,
+                    tip: v3.tip,
+                    paymaster_data: v3.paymaster_data,
+                    nonce_data_availability_mode: v3.nonce_data_availability_mode,
+                    fee_data_availability_mode: v3.fee_data_availability_mode,
+                    is_query: false,
+                }))
+            }
+            _ => Err(UserTransactionError::UnsupportedVersion(tx.version)),
+        }
+    }
+}
+
+/// Upper bound on the size of a decompressed sierra program, so that a small gzip payload cannot expand without limit.
+pub const MAX_DECOMPRESSED_SIERRA_PROGRAM_SIZE: u64 = 32 * 1024 * 1024;
+
+impl CompressedSierraClass {
+    pub fn decompress(self) -> Result<FlattenedSierraClass, UserTransactionError> {
+        let compressed = base64::engine::general_purpose::STANDARD
+            .decode(&self.sierra_program)
+            .map_err(|err| UserTransactionError::InvalidSierraProgram(err.to_string()))?;
+        let mut program = Vec::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .take(MAX_DECOMPRESSED_SIERRA_PROGRAM_SIZE + 1)
+            .read_to_end(&mut program)
+            .map_err(|err| UserTransactionError::InvalidSierraProgram(err.to_string()))?;
+        if program.len() as u64 > MAX_DECOMPRESSED_SIERRA_PROGRAM_SIZE {
+            return Err(UserTransactionError::SierraProgramTooLarge);
+        }
+        let sierra_program = serde_json::from_slice(&program)
+            .map_err(|err| UserTransactionError::InvalidSierraProgram(err.to_string()))?;
+
+        Ok(FlattenedSierraClass {
+            sierra_program,
+            contract_class_version: self.contract_class_version,
+            entry_points_by_type: self.entry_points_by_type,
+            abi: self.abi,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn compressed_class(program: &[u8]) -> CompressedSierraClass {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(program).unwrap();
+        CompressedSierraClass {
+            sierra_program: base64::engine::general_purpose::STANDARD.encode(encoder.finish().unwrap()),
+            contract_class_version: "0.1.0".into(),
+            entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+            abi: "[]".into(),
+        }
+    }
+
+    #[test]
+    fn test_decompress_sierra_program() {
+        let class = compressed_class(br#"["0x1","0x1234"]"#).decompress().unwrap();
+        assert_eq!(class.sierra_program, vec![Felt::ONE, Felt::from(0x1234)]);
+
+        // A few kilobytes of gzip which expand past the limit.
+        let bomb = vec![b' '; MAX_DECOMPRESSED_SIERRA_PROGRAM_SIZE as usize + 1];
+        assert!(matches!(compressed_class(&bomb).decompress(), Err(UserTransactionError::SierraProgramTooLarge)));
+    }
+}
@@ -0,0 +1,173 @@
+use mc_db::l1_db::L1StateUpdateRecord;
+use mc_rpc::versions::v0_8_0::types::L1TxnHash;
+use mp_block::header::{BlockStatus, GasPrices, L1DataAvailabilityMode};
+use mp_block::{MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlockInfo, MadaraPendingBlockInfo};
+use serde::{Deserialize, Serialize};
+use starknet_types_core::felt::Felt;
+
+use super::{hex_int, ConfirmedReceipt, Transaction};
+
+/// A closed or pending block. The pending block has no hash, number or commitments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderBlock {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    pub parent_block_hash: Felt,
+    pub timestamp: u64,
+    pub sequencer_address: Felt,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_root: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_commitment: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_commitment: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt_commitment: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff_commitment: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff_length: Option<u64>,
+    pub status: BlockStatus,
+    /// Hash of the L1 transaction which settled the block. This field is specific to Madara.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_transaction_hash: Option<L1TxnHash>,
+    pub l1_da_mode: L1DaMode,
+    pub l1_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+    pub transactions: Vec<Transaction>,
+    pub transaction_receipts: Vec<ConfirmedReceipt>,
+    pub starknet_version: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum L1DaMode {
+    Calldata,
+    Blob,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourcePrice {
+    #[serde(with = "hex_int")]
+    pub price_in_wei: u128,
+    #[serde(with = "hex_int")]
+    pub price_in_fri: u128,
+}
+
+impl From<L1DataAvailabilityMode> for L1DaMode {
+    fn from(value: L1DataAvailabilityMode) -> Self {
+        match value {
+            L1DataAvailabilityMode::Calldata => Self::Calldata,
+            L1DataAvailabilityMode::Blob => Self::Blob,
+        }
+    }
+}
+
+impl ProviderBlock {
+    /// `l1_last_confirmed_block` is used to tell blocks accepted on L1 apart from blocks only accepted on L2.
+    /// `l1_state_update` is the state update posted on L1 which settled the block, if any: its transaction hash is
+    /// only returned for blocks accepted on L1.
+    pub fn new(
+        info: MadaraMaybePendingBlockInfo,
+        inner: MadaraBlockInner,
+        l1_last_confirmed_block: Option<u64>,
+        l1_state_update: Option<L1StateUpdateRecord>,
+    ) -> Self {
+        match info {
+            MadaraMaybePendingBlockInfo::NotPending(info) => {
+                Self::closed(info, inner, l1_last_confirmed_block, l1_state_update)
+            }
+            MadaraMaybePendingBlockInfo::Pending(info) => Self::pending(info, inner),
+        }
+    }
+
+    fn closed(
+        info: MadaraBlockInfo,
+        inner: MadaraBlockInner,
+        l1_last_confirmed_block: Option<u64>,
+        l1_state_update: Option<L1StateUpdateRecord>,
+    ) -> Self {
+        let header = info.header;
+        let (status, l1_transaction_hash) =
+            if l1_last_confirmed_block.is_some_and(|l1_block_n| header.block_number <= l1_block_n) {
+                (BlockStatus::AcceptedOnL1, l1_state_update.map(|update| L1TxnHash(update.l1_transaction_hash)))
+            } else {
+                (BlockStatus::AcceptedOnL2, None)
+            };
+        let (transactions, transaction_receipts) = transactions_and_receipts(inner);
+
+        Self {
+            block_hash: Some(info.block_hash),
+            block_number: Some(header.block_number),
+            parent_block_hash: header.parent_block_hash,
+            timestamp: header.block_timestamp,
+            sequencer_address: header.sequencer_address,
+            state_root: Some(header.global_state_root),
+            transaction_commitment: Some(header.transaction_commitment),
+            event_commitment: Some(header.event_commitment),
+            receipt_commitment: Some(header.receipt_commitment),
+            state_diff_commitment: Some(header.state_diff_commitment),
+            state_diff_length: Some(header.state_diff_length),
+            status,
+            l1_transaction_hash,
+            l1_da_mode: header.l1_da_mode.into(),
+            l1_gas_price: ResourcePrice::l1_gas(&header.l1_gas_price),
+            l1_data_gas_price: ResourcePrice::l1_data_gas(&header.l1_gas_price),
+            transactions,
+            transaction_receipts,
+            starknet_version: header.protocol_version.to_string(),
+        }
+    }
+
+    fn pending(info: MadaraPendingBlockInfo, inner: MadaraBlockInner) -> Self {
+        let header = info.header;
+        let (transactions, transaction_receipts) = transactions_and_receipts(inner);
+
+        Self {
+            block_hash: None,
+            block_number: None,
+            parent_block_hash: header.parent_block_hash,
+            timestamp: header.block_timestamp,
+            sequencer_address: header.sequencer_address,
+            state_root: None,
+            transaction_commitment: None,
+            event_commitment: None,
+            receipt_commitment: None,
+            state_diff_commitment: None,
+            state_diff_length: None,
+            status: BlockStatus::Pending,
+            l1_transaction_hash: None,
+            l1_da_mode: header.l1_da_mode.into(),
+            l1_gas_price: ResourcePrice::l1_gas(&header.l1_gas_price),
+            l1_data_gas_price: ResourcePrice::l1_data_gas(&header.l1_gas_price),
+            transactions,
+            transaction_receipts,
+            starknet_version: header.protocol_version.to_string(),
+        }
+    }
+}
+
+fn transactions_and_receipts(inner: MadaraBlockInner) -> (Vec<Transaction>, Vec<ConfirmedReceipt>) {
+    inner
+        .transactions
+        .into_iter()
+        .zip(inner.receipts)
+        .enumerate()
+        .map(|(index, (transaction, receipt))| {
+            let provider_receipt = ConfirmedReceipt::new(receipt.clone(), &transaction, index as u64);
+            (Transaction::new(transaction, &receipt), provider_receipt)
+        })
+        .unzip()
+}
+
+impl ResourcePrice {
+    fn l1_gas(prices: &GasPrices) -> Self {
+        Self { price_in_wei: prices.eth_l1_gas_price, price_in_fri: prices.strk_l1_gas_price }
+    }
+
+    fn l1_data_gas(prices: &GasPrices) -> Self {
+        Self { price_in_wei: prices.eth_l1_data_gas_price, price_in_fri: prices.strk_l1_data_gas_price }
+    }
+}
@@ -0,0 +1,77 @@
+//! The sequencer gateway JSON format. It differs from the JSON-RPC one in many places: transaction types, hex encoded
+//! integers, data availability modes as integers, storage diffs as maps...
+
+mod add_transaction;
+mod block;
+mod receipt;
+mod state_update;
+mod transaction;
+
+pub use add_transaction::*;
+pub use block::*;
+pub use receipt::*;
+pub use state_update::*;
+pub use transaction::*;
+
+/// Integers encoded as hex strings.
+pub(crate) mod hex_int {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use std::fmt::LowerHex;
+
+    pub fn serialize<S: Serializer, T: LowerHex>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{value:#x}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: TryFrom<u128>>(deserializer: D) -> Result<T, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let value = u128::from_str_radix(s.trim_start_matches("0x"), 16).map_err(D::Error::custom)?;
+        T::try_from(value).map_err(|_| D::Error::custom(format!("Integer {s} is out of range")))
+    }
+}
+
+/// L1 addresses are encoded on 20 bytes, with the leading zeros.
+pub(crate) mod eth_address {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use starknet_types_core::felt::Felt;
+
+    pub fn serialize<S: Serializer>(value: &Felt, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = value.to_bytes_be();
+        let hex: String = bytes[12..].iter().map(|b| format!("{b:02x}")).collect();
+        serializer.serialize_str(&format!("0x{hex}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Felt, D::Error> {
+        Felt::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use starknet_types_core::felt::Felt;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Encoded {
+        #[serde(with = "super::hex_int")]
+        int: u128,
+        #[serde(with = "super::eth_address")]
+        address: Felt,
+    }
+
+    #[test]
+    fn test_encodings() {
+        let value = Encoded {
+            int: 0x3bf1322e5,
+            address: Felt::from_hex_unchecked("0xae0ee0a63a2ce6baeeffe56e7714fb4efe48d419"),
+        };
+        let json = serde_json::json!({ "int": "0x3bf1322e5", "address": "0xae0ee0a63a2ce6baeeffe56e7714fb4efe48d419" });
+        assert_eq!(serde_json::to_value(&value).unwrap(), json);
+        assert_eq!(serde_json::from_value::<Encoded>(json).unwrap(), value);
+
+        let value = Encoded { int: 0, address: Felt::ONE };
+        assert_eq!(
+            serde_json::to_value(&value).unwrap(),
+            serde_json::json!({ "int": "0x0", "address": "0x0000000000000000000000000000000000000001" })
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use mp_receipt::{
+    DataAvailabilityResources, Event, ExecutionResources as MadaraExecutionResources, ExecutionResult,
+    MsgToL1 as MadaraMsgToL1, TransactionReceipt as MadaraReceipt,
+};
+use mp_transactions::{L1HandlerTransaction, Transaction as MadaraTransaction};
+use serde::{Deserialize, Serialize};
+use starknet_types_core::felt::Felt;
+
+use super::eth_address;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmedReceipt {
+    pub transaction_hash: Felt,
+    pub transaction_index: u64,
+    pub actual_fee: Felt,
+    pub execution_resources: ExecutionResources,
+    pub l2_to_l1_messages: Vec<MsgToL1>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_to_l2_consumed_message: Option<MsgToL2>,
+    pub events: Vec<Event>,
+    pub execution_status: ExecutionStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExecutionStatus {
+    Succeeded,
+    Reverted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionResources {
+    pub n_steps: u64,
+    pub builtin_instance_counter: BuiltinCounters,
+    pub n_memory_holes: u64,
+    pub data_availability: GasVector,
+    pub total_gas_consumed: GasVector,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuiltinCounters {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pedersen_builtin: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range_check_builtin: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bitwise_builtin: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ecdsa_builtin: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ec_op_builtin: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poseidon_builtin: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keccak_builtin: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment_arena_builtin: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasVector {
+    pub l1_gas: u64,
+    pub l1_data_gas: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MsgToL1 {
+    pub from_address: Felt,
+    #[serde(with = "eth_address")]
+    pub to_address: Felt,
+    pub payload: Vec<Felt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MsgToL2 {
+    #[serde(with = "eth_address")]
+    pub from_address: Felt,
+    pub to_address: Felt,
+    pub selector: Felt,
+    pub payload: Vec<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<Felt>,
+}
+
+impl ConfirmedReceipt {
+    /// The transaction is needed to rebuild the consumed L1 message of L1 handler transactions.
+    pub fn new(receipt: MadaraReceipt, transaction: &MadaraTransaction, transaction_index: u64) -> Self {
+        let l1_to_l2_consumed_message = match transaction {
+            MadaraTransaction::L1Handler(tx) => Some(MsgToL2::from(tx)),
+            _ => None,
+        };
+        let (execution_status, revert_error) = match receipt.execution_result() {
+            ExecutionResult::Succeeded => (ExecutionStatus::Succeeded, None),
+            ExecutionResult::Reverted { reason } => (ExecutionStatus::Reverted, Some(reason)),
+        };
+
+        Self {
+            transaction_hash: receipt.transaction_hash(),
+            transaction_index,
+            actual_fee: receipt.actual_fee().amount,
+            execution_resources: execution_resources(&receipt).into(),
+            l2_to_l1_messages: receipt.messages_sent().iter().cloned().map(Into::into).collect(),
+            l1_to_l2_consumed_message,
+            events: receipt.events().to_vec(),
+            execution_status,
+            revert_error,
+        }
+    }
+}
+
+fn execution_resources(receipt: &MadaraReceipt) -> &MadaraExecutionResources {
+    match receipt {
+        MadaraReceipt::Invoke(receipt) => &receipt.execution_resources,
+        MadaraReceipt::L1Handler(receipt) => &receipt.execution_resources,
+        MadaraReceipt::Declare(receipt) => &receipt.execution_resources,
+        MadaraReceipt::Deploy(receipt) => &receipt.execution_resources,
+        MadaraReceipt::DeployAccount(receipt) => &receipt.execution_resources,
+    }
+}
+
+impl From<&MadaraExecutionResources> for ExecutionResources {
+    fn from(resources: &MadaraExecutionResources) -> Self {
+        Self {
+            n_steps: resources.steps,
+            builtin_instance_counter: BuiltinCounters {
+                pedersen_builtin: resources.pedersen_builtin_applications,
+                range_check_builtin: resources.range_check_builtin_applications,
+                bitwise_builtin: resources.bitwise_builtin_applications,
+                ecdsa_builtin: resources.ecdsa_builtin_applications,
+                ec_op_builtin: resources.ec_op_builtin_applications,
+                poseidon_builtin: resources.poseidon_builtin_applications,
+                keccak_builtin: resources.keccak_builtin_applications,
+                segment_arena_builtin: resources.segment_arena_builtin,
+            },
+            n_memory_holes: resources.memory_holes.unwrap_or_default(),
+            data_availability: (&resources.data_availability).into(),
+            total_gas_consumed: (&resources.total_gas_consumed).into(),
+        }
+    }
+}
+
+impl From<&DataAvailabilityResources> for GasVector {
+    fn from(resources: &DataAvailabilityResources) -> Self {
+        Self { l1_gas: resources.l1_gas, l1_data_gas: resources.l1_data_gas }
+    }
+}
+
+impl From<MadaraMsgToL1> for MsgToL1 {
+    fn from(msg: MadaraMsgToL1) -> Self {
+        Self { from_address: msg.from_address, to_address: msg.to_address, payload: msg.payload }
+    }
+}
+
+/// The first calldata element of an L1 handler transaction is the L1 sender, the rest is the message payload.
+impl From<&L1HandlerTransaction> for MsgToL2 {
+    fn from(tx: &L1HandlerTransaction) -> Self {
+        let (from_address, payload) = tx.calldata.split_first().unwrap_or((&Felt::ZERO, &[]));
+        Self {
+            from_address: *from_address,
+            to_address: tx.contract_address,
+            selector: tx.entry_point_selector,
+            payload: payload.to_vec(),
+            nonce: Some(tx.nonce.into()),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+
+use mp_state_update::{DeclaredClassItem, DeployedContractItem, StateDiff, StorageEntry};
+use serde::{Deserialize, Serialize};
+use starknet_types_core::felt::Felt;
+
+use super::ProviderBlock;
+
+/// `block_hash` and `new_root` are missing for the pending block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderStateUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_root: Option<Felt>,
+    pub old_root: Felt,
+    pub state_diff: ProviderStateDiff,
+}
+
+/// Returned by `get_state_update` when `includeBlock=true`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderStateUpdateWithBlock {
+    pub state_update: ProviderStateUpdate,
+    pub block: ProviderBlock,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderStateDiff {
+    pub storage_diffs: BTreeMap<Felt, Vec<StorageEntry>>,
+    pub nonces: BTreeMap<Felt, Felt>,
+    pub deployed_contracts: Vec<DeployedContractItem>,
+    pub old_declared_contracts: Vec<Felt>,
+    pub declared_classes: Vec<DeclaredClassItem>,
+    pub replaced_classes: Vec<ReplacedClass>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplacedClass {
+    pub address: Felt,
+    pub class_hash: Felt,
+}
+
+impl From<StateDiff> for ProviderStateDiff {
+    fn from(state_diff: StateDiff) -> Self {
+        Self {
+            storage_diffs: state_diff
+                .storage_diffs
+                .into_iter()
+                .map(|diff| (diff.address, diff.storage_entries))
+                .collect(),
+            nonces: state_diff.nonces.into_iter().map(|nonce| (nonce.contract_address, nonce.nonce)).collect(),
+            deployed_contracts: state_diff.deployed_contracts,
+            old_declared_contracts: state_diff.deprecated_declared_classes,
+            declared_classes: state_diff.declared_classes,
+            replaced_classes: state_diff
+                .replaced_classes
+                .into_iter()
+                .map(|replaced| ReplacedClass { address: replaced.contract_address, class_hash: replaced.class_hash })
+                .collect(),
+        }
+    }
+}
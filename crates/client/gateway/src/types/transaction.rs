@@ -0,0 +1,381 @@
+use mp_receipt::TransactionReceipt as MadaraReceipt;
+use mp_transactions::{
+    DataAvailabilityMode as MadaraDaMode, DeclareTransaction as MadaraDeclare,
+    DeployAccountTransaction as MadaraDeployAccount, InvokeTransaction as MadaraInvoke,
+    ResourceBounds as MadaraResourceBounds, ResourceBoundsMapping as MadaraResourceBoundsMapping,
+    Transaction as MadaraTransaction,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use starknet_types_core::felt::Felt;
+
+use super::hex_int;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Transaction {
+    #[serde(rename = "INVOKE_FUNCTION")]
+    Invoke(InvokeTransaction),
+    #[serde(rename = "L1_HANDLER")]
+    L1Handler(L1HandlerTransaction),
+    #[serde(rename = "DECLARE")]
+    Declare(DeclareTransaction),
+    #[serde(rename = "DEPLOY")]
+    Deploy(DeployTransaction),
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount(DeployAccountTransaction),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvokeTransaction {
+    pub transaction_hash: Felt,
+    pub version: Felt,
+    /// The called contract for v0 transactions.
+    #[serde(alias = "contract_address")]
+    pub sender_address: Felt,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_point_selector: Option<Felt>,
+    pub calldata: Vec<Felt>,
+    pub signature: Vec<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<Felt>,
+    #[serde(flatten)]
+    pub v3: Option<V3Fields>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_deployment_data: Option<Vec<Felt>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L1HandlerTransaction {
+    pub transaction_hash: Felt,
+    pub version: Felt,
+    pub contract_address: Felt,
+    pub entry_point_selector: Felt,
+    pub nonce: Felt,
+    pub calldata: Vec<Felt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclareTransaction {
+    pub transaction_hash: Felt,
+    pub version: Felt,
+    pub sender_address: Felt,
+    pub class_hash: Felt,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<Felt>,
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<Felt>,
+    #[serde(flatten)]
+    pub v3: Option<V3Fields>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_deployment_data: Option<Vec<Felt>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployTransaction {
+    pub transaction_hash: Felt,
+    pub version: Felt,
+    pub contract_address: Felt,
+    pub contract_address_salt: Felt,
+    pub class_hash: Felt,
+    pub constructor_calldata: Vec<Felt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployAccountTransaction {
+    pub transaction_hash: Felt,
+    pub version: Felt,
+    pub contract_address: Felt,
+    pub contract_address_salt: Felt,
+    pub class_hash: Felt,
+    pub constructor_calldata: Vec<Felt>,
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<Felt>,
+    #[serde(flatten)]
+    pub v3: Option<V3Fields>,
+}
+
+/// Fields of v3 transactions, which pay their fee in STRK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct V3Fields {
+    pub resource_bounds: ResourceBoundsMapping,
+    #[serde(with = "hex_int")]
+    pub tip: u64,
+    pub paymaster_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceBoundsMapping {
+    #[serde(rename = "L1_GAS")]
+    pub l1_gas: ResourceBounds,
+    #[serde(rename = "L2_GAS")]
+    pub l2_gas: ResourceBounds,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceBounds {
+    #[serde(with = "hex_int")]
+    pub max_amount: u64,
+    #[serde(with = "hex_int")]
+    pub max_price_per_unit: u128,
+}
+
+/// Encoded as an integer in the feeder gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataAvailabilityMode {
+    L1,
+    L2,
+}
+
+impl Serialize for DataAvailabilityMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(match self {
+            Self::L1 => 0,
+            Self::L2 => 1,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for DataAvailabilityMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(Self::L1),
+            1 => Ok(Self::L2),
+            other => Err(serde::de::Error::custom(format!("Invalid data availability mode {other}"))),
+        }
+    }
+}
+
+impl From<MadaraDaMode> for DataAvailabilityMode {
+    fn from(value: MadaraDaMode) -> Self {
+        match value {
+            MadaraDaMode::L1 => Self::L1,
+            MadaraDaMode::L2 => Self::L2,
+        }
+    }
+}
+
+impl From<DataAvailabilityMode> for MadaraDaMode {
+    fn from(value: DataAvailabilityMode) -> Self {
+        match value {
+            DataAvailabilityMode::L1 => Self::L1,
+            DataAvailabilityMode::L2 => Self::L2,
+        }
+    }
+}
+
+impl From<MadaraResourceBoundsMapping> for ResourceBoundsMapping {
+    fn from(value: MadaraResourceBoundsMapping) -> Self {
+        Self { l1_gas: value.l1_gas.into(), l2_gas: value.l2_gas.into() }
+    }
+}
+
+impl From<ResourceBoundsMapping> for MadaraResourceBoundsMapping {
+    fn from(value: ResourceBoundsMapping) -> Self {
+        Self { l1_gas: value.l1_gas.into(), l2_gas: value.l2_gas.into() }
+    }
+}
+
+impl From<MadaraResourceBounds> for ResourceBounds {
+    fn from(value: MadaraResourceBounds) -> Self {
+        Self { max_amount: value.max_amount, max_price_per_unit: value.max_price_per_unit }
+    }
+}
+
+impl From<ResourceBounds> for MadaraResourceBounds {
+    fn from(value: ResourceBounds) -> Self {
+        Self { max_amount: value.max_amount, max_price_per_unit: value.max_price_per_unit }
+    }
+}
+
+impl Transaction {
+    /// The receipt is needed for the transaction hash, and the address of deployed contracts.
+    pub fn new(transaction: MadaraTransaction, receipt: &MadaraReceipt) -> Self {
+        let transaction_hash = receipt.transaction_hash();
+        match transaction {
+            MadaraTransaction::Invoke(tx) => Self::Invoke(InvokeTransaction::new(tx, transaction_hash)),
+            MadaraTransaction::L1Handler(tx) => Self::L1Handler(L1HandlerTransaction {
+                transaction_hash,
+                version: tx.version,
+                contract_address: tx.contract_address,
+                entry_point_selector: tx.entry_point_selector,
+                nonce: tx.nonce.into(),
+                calldata: tx.calldata,
+            }),
+            MadaraTransaction::Declare(tx) => Self::Declare(DeclareTransaction::new(tx, transaction_hash)),
+            MadaraTransaction::Deploy(tx) => Self::Deploy(DeployTransaction {
+                transaction_hash,
+                version: tx.version,
+                contract_address: contract_address(receipt),
+                contract_address_salt: tx.contract_address_salt,
+                class_hash: tx.class_hash,
+                constructor_calldata: tx.constructor_calldata,
+            }),
+            MadaraTransaction::DeployAccount(tx) => {
+                Self::DeployAccount(DeployAccountTransaction::new(tx, transaction_hash, contract_address(receipt)))
+            }
+        }
+    }
+}
+
+fn contract_address(receipt: &MadaraReceipt) -> Felt {
+    match receipt {
+        MadaraReceipt::Deploy(receipt) => receipt.contract_address,
+        MadaraReceipt::DeployAccount(receipt) => receipt.contract_address,
+        _ => Felt::ZERO,
+    }
+}
+
+impl InvokeTransaction {
+    fn new(tx: MadaraInvoke, transaction_hash: Felt) -> Self {
+        match tx {
+            MadaraInvoke::V0(tx) => Self {
+                transaction_hash,
+                version: Felt::ZERO,
+                sender_address: tx.contract_address,
+                entry_point_selector: Some(tx.entry_point_selector),
+                calldata: tx.calldata,
+                signature: tx.signature,
+                nonce: None,
+                max_fee: Some(tx.max_fee),
+                v3: None,
+                account_deployment_data: None,
+            },
+            MadaraInvoke::V1(tx) => Self {
+                transaction_hash,
+                version: Felt::ONE,
+                sender_address: tx.sender_address,
+                entry_point_selector: None,
+                calldata: tx.calldata,
+                signature: tx.signature,
+                nonce: Some(tx.nonce),
+                max_fee: Some(tx.max_fee),
+                v3: None,
+                account_deployment_data: None,
+            },
+            MadaraInvoke::V3(tx) => Self {
+                transaction_hash,
+                version: Felt::THREE,
+                sender_address: tx.sender_address,
+                entry_point_selector: None,
+                calldata: tx.calldata,
+                signature: tx.signature,
+                nonce: Some(tx.nonce),
+                max_fee: None,
+                v3: Some(V3Fields {
+                    resource_bounds: tx.resource_bounds.into(),
+                    tip: tx.tip,
+                    paymaster_data: tx.paymaster_data,
+                    nonce_data_availability_mode: tx.nonce_data_availability_mode.into(),
+                    fee_data_availability_mode: tx.fee_data_availability_mode.into(),
+                }),
+                account_deployment_data: Some(tx.account_deployment_data),
+            },
+        }
+    }
+}
+
+impl DeclareTransaction {
+    fn new(tx: MadaraDeclare, transaction_hash: Felt) -> Self {
+        match tx {
+            MadaraDeclare::V0(tx) => Self {
+                transaction_hash,
+                version: Felt::ZERO,
+                sender_address: tx.sender_address,
+                class_hash: tx.class_hash,
+                compiled_class_hash: None,
+                signature: tx.signature,
+                nonce: Felt::ZERO,
+                max_fee: Some(tx.max_fee),
+                v3: None,
+                account_deployment_data: None,
+            },
+            MadaraDeclare::V1(tx) => Self {
+                transaction_hash,
+                version: Felt::ONE,
+                sender_address: tx.sender_address,
+                class_hash: tx.class_hash,
+                compiled_class_hash: None,
+                signature: tx.signature,
+                nonce: tx.nonce,
+                max_fee: Some(tx.max_fee),
+                v3: None,
+                account_deployment_data: None,
+            },
+            MadaraDeclare::V2(tx) => Self {
+                transaction_hash,
+                version: Felt::TWO,
+                sender_address: tx.sender_address,
+                class_hash: tx.class_hash,
+                compiled_class_hash: Some(tx.compiled_class_hash),
+                signature: tx.signature,
+                nonce: tx.nonce,
+                max_fee: Some(tx.max_fee),
+                v3: None,
+                account_deployment_data: None,
+            },
+            MadaraDeclare::V3(tx) => Self {
+                transaction_hash,
+                version: Felt::THREE,
+                sender_address: tx.sender_address,
+                class_hash: tx.class_hash,
+                compiled_class_hash: Some(tx.compiled_class_hash),
+                signature: tx.signature,
+                nonce: tx.nonce,
+                max_fee: None,
+                v3: Some(V3Fields {
+                    resource_bounds: tx.resource_bounds.into(),
+                    tip: tx.tip,
+                    paymaster_data: tx.paymaster_data,
+                    nonce_data_availability_mode: tx.nonce_data_availability_mode.into(),
+                    fee_data_availability_mode: tx.fee_data_availability_mode.into(),
+                }),
+                account_deployment_data: Some(tx.account_deployment_data),
+            },
+        }
+    }
+}
+
+impl DeployAccountTransaction {
+    fn new(tx: MadaraDeployAccount, transaction_hash: Felt, contract_address: Felt) -> Self {
+        match tx {
+            MadaraDeployAccount::V1(tx) => Self {
+                transaction_hash,
+                version: Felt::ONE,
+                contract_address,
+                contract_address_salt: tx.contract_address_salt,
+                class_hash: tx.class_hash,
+                constructor_calldata: tx.constructor_calldata,
+                signature: tx.signature,
+                nonce: tx.nonce,
+                max_fee: Some(tx.max_fee),
+                v3: None,
+            },
+            MadaraDeployAccount::V3(tx) => Self {
+                transaction_hash,
+                version: Felt::THREE,
+                contract_address,
+                contract_address_salt: tx.contract_address_salt,
+                class_hash: tx.class_hash,
+                constructor_calldata: tx.constructor_calldata,
+                signature: tx.signature,
+                nonce: tx.nonce,
+                max_fee: None,
+                v3: Some(V3Fields {
+                    resource_bounds: tx.resource_bounds.into(),
+                    tip: tx.tip,
+                    paymaster_data: tx.paymaster_data,
+                    nonce_data_availability_mode: tx.nonce_data_availability_mode.into(),
+                    fee_data_availability_mode: tx.fee_data_availability_mode.into(),
+                }),
+            },
+        }
+    }
+}
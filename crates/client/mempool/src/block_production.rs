@@ -1,5 +1,6 @@
 // TODO: Move this into its own crate.
 
+use blockifier::blockifier::config::ConcurrencyConfig;
 use blockifier::blockifier::transaction_executor::{TransactionExecutor, VisitedSegmentsMapping};
 use blockifier::bouncer::{Bouncer, BouncerWeights, BuiltinCount};
 use blockifier::state::cached_state::CommitmentStateDiff;
@@ -9,7 +10,7 @@ use blockifier::transaction::transaction_execution::Transaction;
 use mc_block_import::BlockImporter;
 use mc_db::db_block_id::DbBlockId;
 use mc_db::{MadaraBackend, MadaraStorageError};
-use mc_exec::{BlockifierStateAdapter, ExecutionContext};
+use mc_exec::{BlockifierStateAdapter, ContractClassCache, ExecutionContext};
 use mp_block::{BlockId, BlockTag, MadaraPendingBlock};
 use mp_class::ConvertedClass;
 use mp_convert::ToFelt;
@@ -26,7 +27,7 @@ use std::sync::Arc;
 
 use crate::close_block::close_block;
 use crate::header::make_pending_header;
-use crate::{clone_account_tx, L1DataProvider, Mempool, MempoolProvider, MempoolTransaction};
+use crate::{clone_transaction, L1DataProvider, Mempool, MempoolProvider, MempoolTransaction};
 
 /// We always take transactions in batches from the mempool
 const TX_BATCH_SIZE: usize = 128;
@@ -161,6 +162,8 @@ pub struct BlockProductionTask {
     declared_classes: Vec<ConvertedClass>,
     pub(crate) executor: TransactionExecutor<BlockifierStateAdapter>,
     l1_data_provider: Arc<dyn L1DataProvider>,
+    class_cache: Arc<ContractClassCache>,
+    concurrency_config: ConcurrencyConfig,
     current_pending_tick: usize,
 }
 
@@ -175,6 +178,7 @@ impl BlockProductionTask {
         importer: Arc<BlockImporter>,
         mempool: Arc<Mempool>,
         l1_data_provider: Arc<dyn L1DataProvider>,
+        class_cache: Arc<ContractClassCache>,
     ) -> Result<Self, Error> {
         let parent_block_hash = backend
             .get_block_hash(&BlockId::Tag(BlockTag::Latest))?
@@ -189,8 +193,12 @@ impl BlockProductionTask {
         //     l1_gas_price: l1_data_provider.get_gas_prices(),
         //     l1_da_mode: l1_data_provider.get_da_mode(),
         // })?;
-        let mut executor =
-            ExecutionContext::new_in_block(Arc::clone(&backend), &pending_block.info.clone().into())?.tx_executor();
+        let mut executor = ExecutionContext::new_in_block(
+            Arc::clone(&backend),
+            Arc::clone(&class_cache),
+            &pending_block.info.clone().into(),
+        )?
+        .tx_executor();
 
         let bouncer_config = backend.chain_config().bouncer_config.clone();
         executor.bouncer = Bouncer::new(bouncer_config);
@@ -204,17 +212,44 @@ impl BlockProductionTask {
             block: pending_block,
             declared_classes: vec![],
             l1_data_provider,
+            class_cache,
+            concurrency_config: ConcurrencyConfig::default(),
         })
     }
 
+    /// Execute the transactions of each batch concurrently, on `n_workers` threads and by chunks of `chunk_size`
+    /// transactions. Transactions are executed sequentially by default.
+    pub fn with_concurrency(mut self, concurrency_config: ConcurrencyConfig) -> Self {
+        self.executor.config.concurrency_config = concurrency_config.clone();
+        self.concurrency_config = concurrency_config;
+        self
+    }
+
+    /// The L1 sync removes the L1 to L2 messages whose L1 block was reorged out. Their `L1Handler` transactions may
+    /// still be in the mempool, and are dropped here.
+    fn drop_reorged_l1_messages(&self, txs: Vec<MempoolTransaction>) -> Result<Vec<MempoolTransaction>, Error> {
+        let mut kept = Vec::with_capacity(txs.len());
+        for tx in txs {
+            if let Transaction::L1HandlerTransaction(l1_handler) = &tx.tx {
+                if self.backend.get_l1_message(l1_handler.tx.nonce)?.is_none() {
+                    log::warn!("Dropping L1 handler transaction {}: its L1 message was reorged out", tx.tx_hash());
+                    self.mempool.mark_rejected(*tx.tx_hash(), "The L1 message was reorged out of L1".into());
+                    continue;
+                }
+            }
+            kept.push(tx);
+        }
+        Ok(kept)
+    }
+
     fn continue_block(&mut self, bouncer_cap: BouncerWeights) -> Result<StateDiff, Error> {
         self.executor.bouncer.bouncer_config.block_max_capacity = bouncer_cap;
 
         let mut txs_to_process = Vec::with_capacity(TX_BATCH_SIZE);
         self.mempool.take_txs_chunk(&mut txs_to_process, TX_BATCH_SIZE);
+        let txs_to_process = self.drop_reorged_l1_messages(txs_to_process)?;
 
-        let blockifier_txs: Vec<_> =
-            txs_to_process.iter().map(|tx| Transaction::AccountTransaction(clone_account_tx(&tx.tx))).collect();
+        let blockifier_txs: Vec<_> = txs_to_process.iter().map(|tx| clone_transaction(&tx.tx)).collect();
 
         // Execute the transactions.
         let all_results = self.executor.execute_txs(&blockifier_txs);
@@ -248,10 +283,10 @@ impl BlockProductionTask {
                         self.declared_classes.push(class);
                     }
 
-                    self.block.inner.receipts.push(from_blockifier_execution_info(
-                        &execution_info,
-                        &Transaction::AccountTransaction(clone_account_tx(&mempool_tx.tx)),
-                    ));
+                    self.block
+                        .inner
+                        .receipts
+                        .push(from_blockifier_execution_info(&execution_info, &clone_transaction(&mempool_tx.tx)));
                     let converted_tx = TransactionWithHash::from(mempool_tx.tx);
                     self.block.info.tx_hashes.push(converted_tx.hash);
                     self.block.inner.transactions.push(converted_tx.transaction);
@@ -262,6 +297,7 @@ impl BlockProductionTask {
                     // We reject them.
                     // Note that this is a big DoS vector.
                     log::error!("Unsuccessful execution of transaction {}: {err:#}", mempool_tx.tx_hash());
+                    self.mempool.mark_rejected(*mempool_tx.tx_hash(), format!("{err:#}"));
                 }
             }
         }
@@ -357,8 +393,13 @@ impl BlockProductionTask {
         self.block.info.header.parent_block_hash = import_result.block_hash; // fix temp parent block hash for new pending :)
 
         // Prepare for next block.
-        self.executor =
-            ExecutionContext::new_in_block(Arc::clone(&self.backend), &self.block.info.clone().into())?.tx_executor();
+        self.executor = ExecutionContext::new_in_block(
+            Arc::clone(&self.backend),
+            Arc::clone(&self.class_cache),
+            &self.block.info.clone().into(),
+        )?
+        .tx_executor();
+        self.executor.config.concurrency_config = self.concurrency_config.clone();
         self.current_pending_tick = 0;
 
         log::info!("⛏️  Closed block #{} with {} transactions", block_n, n_txs);
@@ -1,6 +1,6 @@
 use crate::L1DataProvider;
-use mp_block::header::PendingHeader;
-use mp_chain_config::ChainConfig;
+use mp_block::header::{GasPrices, PendingHeader};
+use mp_chain_config::{ChainConfig, L1GasPriceConfig};
 use starknet_types_core::felt::Felt;
 use std::time::SystemTime;
 
@@ -17,7 +17,102 @@ pub fn make_pending_header(
             .expect("Current system time is before the UNIX epoch")
             .as_secs(),
         protocol_version: chain_config.latest_protocol_version,
-        l1_gas_price: l1_info.get_gas_prices(),
+        l1_gas_price: l1_gas_prices(&chain_config.l1_gas_price, l1_info.get_gas_prices()),
         l1_da_mode: l1_info.get_da_mode(),
     }
 }
+
+/// Applies the fixed price override, or the margin and the bounds of the chain config to the prices fetched from L1.
+/// The smoothing is done when fetching the prices, as it needs the base fees of every L1 block.
+fn l1_gas_prices(config: &L1GasPriceConfig, prices: GasPrices) -> GasPrices {
+    if let Some(fixed) = &config.fixed {
+        return GasPrices {
+            eth_l1_gas_price: fixed.eth_l1_gas_price,
+            strk_l1_gas_price: fixed.strk_l1_gas_price,
+            eth_l1_data_gas_price: fixed.eth_l1_data_gas_price,
+            strk_l1_data_gas_price: fixed.strk_l1_data_gas_price,
+        };
+    }
+
+    let adjust = |price: u128, min: Option<u128>, max: Option<u128>| {
+        let price = price.saturating_mul(100 + config.margin_percent as u128) / 100;
+        let price = min.map_or(price, |min| price.max(min));
+        max.map_or(price, |max| price.min(max))
+    };
+    GasPrices {
+        eth_l1_gas_price: adjust(prices.eth_l1_gas_price, config.min.eth_l1_gas_price, config.max.eth_l1_gas_price),
+        strk_l1_gas_price: adjust(prices.strk_l1_gas_price, config.min.strk_l1_gas_price, config.max.strk_l1_gas_price),
+        eth_l1_data_gas_price: adjust(
+            prices.eth_l1_data_gas_price,
+            config.min.eth_l1_data_gas_price,
+            config.max.eth_l1_data_gas_price,
+        ),
+        strk_l1_data_gas_price: adjust(
+            prices.strk_l1_data_gas_price,
+            config.min.strk_l1_data_gas_price,
+            config.max.strk_l1_data_gas_price,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mp_chain_config::{L1GasPriceBounds, L1GasPrices};
+
+    fn prices() -> GasPrices {
+        GasPrices {
+            eth_l1_gas_price: 1000,
+            strk_l1_gas_price: 2000,
+            eth_l1_data_gas_price: 10,
+            strk_l1_data_gas_price: 20,
+        }
+    }
+
+    #[test]
+    fn test_default_config_keeps_prices() {
+        assert_eq!(l1_gas_prices(&L1GasPriceConfig::default(), prices()), prices());
+    }
+
+    #[test]
+    fn test_margin_and_bounds() {
+        let config = L1GasPriceConfig {
+            margin_percent: 10,
+            min: L1GasPriceBounds { eth_l1_data_gas_price: Some(50), ..Default::default() },
+            max: L1GasPriceBounds { strk_l1_gas_price: Some(2100), ..Default::default() },
+            ..Default::default()
+        };
+        assert_eq!(
+            l1_gas_prices(&config, prices()),
+            GasPrices {
+                eth_l1_gas_price: 1100,
+                strk_l1_gas_price: 2100,
+                eth_l1_data_gas_price: 50,
+                strk_l1_data_gas_price: 22,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fixed_override() {
+        let config = L1GasPriceConfig {
+            fixed: Some(L1GasPrices {
+                eth_l1_gas_price: 1,
+                strk_l1_gas_price: 2,
+                eth_l1_data_gas_price: 3,
+                strk_l1_data_gas_price: 4,
+            }),
+            margin_percent: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            l1_gas_prices(&config, prices()),
+            GasPrices {
+                eth_l1_gas_price: 1,
+                strk_l1_gas_price: 2,
+                eth_l1_data_gas_price: 3,
+                strk_l1_data_gas_price: 4,
+            }
+        );
+    }
+}
@@ -3,11 +3,15 @@
 //! Insertion and popping should be O(log n).
 //! We also really don't want to poison the lock by panicking.
 //!
-//! TODO: mempool size limits
+//! The number of account transactions is bounded globally and per account, see [`MempoolLimits`]. L1 handler
+//! transactions are not subject to these limits: their fee was paid on L1 and they cannot be spammed.
+//!
 //! TODO(perf): should we box the MempoolTransaction?
 
-use crate::{clone_account_tx, contract_addr, nonce, tx_hash};
+use crate::limits::{can_replace, MempoolLimits};
+use crate::{clone_transaction, contract_addr, nonce, tx_hash};
 use blockifier::transaction::account_transaction::AccountTransaction;
+use blockifier::transaction::transaction_execution::Transaction;
 use mp_class::ConvertedClass;
 use starknet_api::{
     core::{ContractAddress, Nonce},
@@ -24,7 +28,7 @@ pub type ArrivedAtTimestamp = SystemTime;
 
 #[derive(Debug)]
 pub struct MempoolTransaction {
-    pub tx: AccountTransaction,
+    pub tx: Transaction,
     pub arrived_at: ArrivedAtTimestamp,
     pub converted_class: Option<ConvertedClass>,
 }
@@ -32,7 +36,7 @@ pub struct MempoolTransaction {
 impl Clone for MempoolTransaction {
     fn clone(&self) -> Self {
         Self {
-            tx: clone_account_tx(&self.tx),
+            tx: clone_transaction(&self.tx),
             arrived_at: self.arrived_at,
             converted_class: self.converted_class.clone(),
         }
@@ -110,26 +114,18 @@ impl NonceChain {
         debug_assert_eq!(front.0.arrived_at, self.front_arrived_at);
     }
 
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
     /// Returns where in the chain it was inserted.
-    /// When `force` is `true`, this function should never return any error.
+    /// When `force` is `true`, this function should never return any error, and a transaction with the same nonce is
+    /// replaced.
     pub fn insert(
         &mut self,
         mempool_tx: MempoolTransaction,
         force: bool,
     ) -> Result<InsertedPosition, TxInsersionError> {
-        let position = if self.front_arrived_at > mempool_tx.arrived_at {
-            // We are inserting at the front here
-            let former_head_arrived_at = self.front_arrived_at;
-            self.front_arrived_at = mempool_tx.arrived_at;
-            #[cfg(debug_assertions)]
-            {
-                self.front_tx_hash = mempool_tx.tx_hash();
-            }
-            InsertedPosition::Front { former_head_arrived_at }
-        } else {
-            InsertedPosition::Other
-        };
-
         #[cfg(debug_assertions)] // unknown field `front_tx_hash` in release if debug_assert_eq is used
         assert_eq!(self.transactions.first().expect("Getting the first tx").0.tx_hash(), self.front_tx_hash);
 
@@ -139,7 +135,19 @@ impl NonceChain {
             return Err(TxInsersionError::NonceConflict);
         }
 
-        Ok(position)
+        // The front is the transaction with the lowest nonce, which may have been inserted or replaced.
+        let front = &self.transactions.first().expect("Nonce chain should not be empty").0;
+        #[cfg(debug_assertions)]
+        {
+            self.front_tx_hash = front.tx_hash();
+        }
+        if self.front_arrived_at != front.arrived_at {
+            let former_head_arrived_at = self.front_arrived_at;
+            self.front_arrived_at = front.arrived_at;
+            Ok(InsertedPosition::Front { former_head_arrived_at })
+        } else {
+            Ok(InsertedPosition::Other)
+        }
     }
 
     pub fn pop(&mut self) -> (MempoolTransaction, NonceChainNewState) {
@@ -183,29 +191,52 @@ impl PartialOrd for AccountOrderedByTimestamp {
     }
 }
 
-#[derive(Default)]
 /// Invariants:
 /// - Every nonce chain in `nonce_chains` should have a one to one match with `tx_queue`.
 /// - Every [`AccountTransaction::DeployAccount`] transaction should have a one to one match with `deployed_contracts`.
+/// - L1 handler transactions are only ever in `l1_handler_txs`, never in a nonce chain.
+/// - `n_txs` is the number of transactions in all nonce chains.
 /// - See [`NonceChain`] invariants.
 pub struct MempoolInner {
     /// We have one nonce chain per contract address.
     nonce_chains: HashMap<ContractAddress, NonceChain>,
+    /// L1 handler transactions, ordered by L1 message nonce. They are popped before any account transaction.
+    l1_handler_txs: BTreeSet<OrderMempoolTransactionByNonce>,
     /// FCFS queue.
     tx_queue: BTreeSet<AccountOrderedByTimestamp>,
     /// This is used for quickly checking if the contract has been deployed for the same block it is invoked.
     deployed_contracts: HashSet<ContractAddress>,
+    /// Number of account transactions.
+    n_txs: usize,
+    limits: MempoolLimits,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum TxInsersionError {
     #[error("A transaction with this nonce already exists in the transaction pool")]
     NonceConflict,
+    #[error("The fee is too low to replace the transaction with the same nonce in the transaction pool")]
+    ReplacementFeeTooLow,
     #[error("A transaction deploying the same account already exists in the transaction pool")]
     AccountAlreadyDeployed,
+    #[error("The transaction pool is full")]
+    MempoolFull,
+    #[error("This account has too many transactions in the transaction pool")]
+    TooManyTransactionsForAccount,
 }
 
 impl MempoolInner {
+    pub fn new(limits: MempoolLimits) -> Self {
+        Self {
+            nonce_chains: Default::default(),
+            l1_handler_txs: Default::default(),
+            tx_queue: Default::default(),
+            deployed_contracts: Default::default(),
+            n_txs: 0,
+            limits,
+        }
+    }
+
     #[cfg(test)]
     pub fn check_invariants(&self) {
         self.nonce_chains.values().for_each(NonceChain::check_invariants);
@@ -218,48 +249,86 @@ impl MempoolInner {
         debug_assert!(tx_queue.is_empty());
         let mut deployed_contracts = self.deployed_contracts.clone();
         for contract in self.nonce_chains.values().flat_map(|chain| &chain.transactions) {
-            if let AccountTransaction::DeployAccount(tx) = &contract.0.tx {
-                debug_assert!(deployed_contracts.remove(&tx.contract_address))
+            match &contract.0.tx {
+                Transaction::AccountTransaction(AccountTransaction::DeployAccount(tx)) => {
+                    debug_assert!(deployed_contracts.remove(&tx.contract_address))
+                }
+                Transaction::L1HandlerTransaction(_) => panic!("L1 handler transaction in a nonce chain"),
+                _ => {}
             };
         }
         debug_assert!(deployed_contracts.is_empty());
+        debug_assert_eq!(self.n_txs, self.nonce_chains.values().map(NonceChain::len).sum::<usize>());
+        for tx in &self.l1_handler_txs {
+            debug_assert!(matches!(tx.0.tx, Transaction::L1HandlerTransaction(_)));
+        }
     }
 
     /// When `force` is `true`, this function should never return any error.
-    pub fn insert_tx(&mut self, mempool_tx: MempoolTransaction, force: bool) -> Result<(), TxInsersionError> {
+    ///
+    /// When `force` is `false`, a transaction with the same sender and nonce as a transaction already in the mempool
+    /// replaces it if it pays enough more fees. The replacement keeps the place of the replaced transaction in the
+    /// queue. Otherwise, the transaction is rejected if the mempool or the nonce chain of the account is full.
+    /// Returns the hash of the transaction with the same sender and nonce which was replaced by this one, if any.
+    pub fn insert_tx(
+        &mut self,
+        mempool_tx: MempoolTransaction,
+        force: bool,
+    ) -> Result<Option<TransactionHash>, TxInsersionError> {
+        if let Transaction::L1HandlerTransaction(_) = &mempool_tx.tx {
+            return self.insert_l1_handler_tx(mempool_tx, force);
+        }
+
         // Get the nonce chain for the contract
 
         let contract_addr = mempool_tx.contract_address();
-        let arrived_at = mempool_tx.arrived_at;
-
-        let deployed_contract_address =
-            if let AccountTransaction::DeployAccount(tx) = &mempool_tx.tx { Some(tx.contract_address) } else { None };
-
-        if let Some(contract_address) = &deployed_contract_address {
-            if !self.deployed_contracts.insert(*contract_address) && !force {
-                return Err(TxInsersionError::AccountAlreadyDeployed);
+        let mut mempool_tx = OrderMempoolTransactionByNonce(mempool_tx);
+
+        // Transactions are compared by nonce: this looks up the transaction with the same nonce.
+        let replaced = self.nonce_chains.get(&contract_addr).and_then(|chain| chain.transactions.get(&mempool_tx));
+        let replaced_deployed_address = replaced.and_then(|replaced| deployed_contract_address(&replaced.0.tx));
+        match replaced {
+            Some(replaced) if !force => {
+                if !can_replace(&replaced.0.tx, &mempool_tx.0.tx) {
+                    return Err(TxInsersionError::ReplacementFeeTooLow);
+                }
+                mempool_tx.0.arrived_at = replaced.0.arrived_at;
+            }
+            Some(_) => {}
+            None if !force => {
+                if self.n_txs >= self.limits.max_transactions {
+                    return Err(TxInsersionError::MempoolFull);
+                }
+                if self.nonce_chains.get(&contract_addr).map(NonceChain::len).unwrap_or(0)
+                    >= self.limits.max_transactions_per_account
+                {
+                    return Err(TxInsersionError::TooManyTransactionsForAccount);
+                }
+            }
+            None => {}
+        }
+        let replaced_tx_hash = replaced.map(|replaced| replaced.0.tx_hash());
+        let is_replacement = replaced_tx_hash.is_some();
+        let mempool_tx = mempool_tx.0;
+
+        let deployed_address = deployed_contract_address(&mempool_tx.tx);
+        if deployed_address != replaced_deployed_address {
+            if let Some(contract_address) = &deployed_address {
+                if !self.deployed_contracts.insert(*contract_address) && !force {
+                    return Err(TxInsersionError::AccountAlreadyDeployed);
+                }
+            }
+            if let Some(contract_address) = &replaced_deployed_address {
+                let removed = self.deployed_contracts.remove(contract_address);
+                debug_assert!(removed);
             }
         }
 
         match self.nonce_chains.entry(contract_addr) {
             hash_map::Entry::Occupied(mut entry) => {
-                // Handle nonce collision.
-                let position = match entry.get_mut().insert(mempool_tx, force) {
-                    Ok(position) => position,
-                    Err(_nonce_collision) => {
-                        if force {
-                            panic!("Force add should never error")
-                        }
-                        // Rollback the prior mutation.
-                        if let Some(contract_address) = &deployed_contract_address {
-                            if !self.deployed_contracts.remove(contract_address) {
-                                return Err(TxInsersionError::AccountAlreadyDeployed);
-                            }
-                        }
-
-                        return Err(TxInsersionError::NonceConflict);
-                    }
-                };
+                // Nonce collisions were handled above.
+                let position =
+                    entry.get_mut().insert(mempool_tx, force || is_replacement).expect("Force add should never error");
 
                 match position {
                     InsertedPosition::Front { former_head_arrived_at } => {
@@ -268,8 +337,10 @@ impl MempoolInner {
                             .tx_queue
                             .remove(&AccountOrderedByTimestamp { contract_addr, timestamp: former_head_arrived_at });
                         debug_assert!(removed);
-                        let inserted =
-                            self.tx_queue.insert(AccountOrderedByTimestamp { contract_addr, timestamp: arrived_at });
+                        let inserted = self.tx_queue.insert(AccountOrderedByTimestamp {
+                            contract_addr,
+                            timestamp: entry.get().front_arrived_at,
+                        });
                         debug_assert!(inserted);
                     }
                     InsertedPosition::Other => {
@@ -279,6 +350,7 @@ impl MempoolInner {
             }
             hash_map::Entry::Vacant(entry) => {
                 // Insert the new nonce chain
+                let arrived_at = mempool_tx.arrived_at;
                 let nonce_chain = NonceChain::new_with_first_tx(mempool_tx);
                 entry.insert(nonce_chain);
 
@@ -287,7 +359,26 @@ impl MempoolInner {
                 debug_assert!(inserted);
             }
         };
-        Ok(())
+        if !is_replacement {
+            self.n_txs += 1;
+        }
+        Ok(replaced_tx_hash)
+    }
+
+    /// An L1 message can only be in the mempool once: a second transaction with the same L1 nonce is a conflict.
+    fn insert_l1_handler_tx(
+        &mut self,
+        mempool_tx: MempoolTransaction,
+        force: bool,
+    ) -> Result<Option<TransactionHash>, TxInsersionError> {
+        if force {
+            let replaced = self.l1_handler_txs.replace(OrderMempoolTransactionByNonce(mempool_tx));
+            return Ok(replaced.map(|replaced| replaced.0.tx_hash()));
+        }
+        if !self.l1_handler_txs.insert(OrderMempoolTransactionByNonce(mempool_tx)) {
+            return Err(TxInsersionError::NonceConflict);
+        }
+        Ok(None)
     }
 
     pub fn has_deployed_contract(&self, addr: &ContractAddress) -> bool {
@@ -295,6 +386,10 @@ impl MempoolInner {
     }
 
     pub fn pop_next(&mut self) -> Option<MempoolTransaction> {
+        if let Some(tx) = self.l1_handler_txs.pop_first() {
+            return Some(tx.0);
+        }
+
         // Pop tx queue.
         let tx_queue_account = self.tx_queue.pop_first()?; // Bubble up None if the mempool is empty.
 
@@ -319,14 +414,40 @@ impl MempoolInner {
         }
 
         // Update deployed contracts.
-        if let AccountTransaction::DeployAccount(tx) = &mempool_tx.tx {
-            let removed = self.deployed_contracts.remove(&tx.contract_address);
+        if let Some(contract_address) = deployed_contract_address(&mempool_tx.tx) {
+            let removed = self.deployed_contracts.remove(&contract_address);
             debug_assert!(removed);
         }
+        self.n_txs -= 1;
 
         Some(mempool_tx)
     }
 
+    /// Removes the nonce chains whose front transaction arrived more than [`MempoolLimits::max_age`] before `now`.
+    /// The front transaction of a chain blocks all the other ones, so the whole chain is stale.
+    pub fn remove_age_exceeded_txs(&mut self, now: SystemTime) -> Vec<MempoolTransaction> {
+        let cutoff = self.limits.age_cutoff(now);
+        let mut removed_txs = vec![];
+        while let Some(tx_queue_account) = self.tx_queue.first() {
+            if tx_queue_account.timestamp >= cutoff {
+                break;
+            }
+            let contract_addr = tx_queue_account.contract_addr;
+            self.tx_queue.pop_first();
+
+            let nonce_chain = self.nonce_chains.remove(&contract_addr).expect("Nonce chain does not match tx queue");
+            for tx in nonce_chain.transactions {
+                if let Some(contract_address) = deployed_contract_address(&tx.0.tx) {
+                    let removed = self.deployed_contracts.remove(&contract_address);
+                    debug_assert!(removed);
+                }
+                self.n_txs -= 1;
+                removed_txs.push(tx.0);
+            }
+        }
+        removed_txs
+    }
+
     pub fn pop_next_chunk(&mut self, dest: &mut Vec<MempoolTransaction>, n: usize) {
         for _ in 0..n {
             let Some(tx) = self.pop_next() else { break };
@@ -342,24 +463,35 @@ impl MempoolInner {
     }
 }
 
+fn deployed_contract_address(tx: &Transaction) -> Option<ContractAddress> {
+    match tx {
+        Transaction::AccountTransaction(AccountTransaction::DeployAccount(tx)) => Some(tx.contract_address),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use bitvec::{order::Msb0, vec::BitVec};
     use blockifier::{
         execution::contract_class::ClassInfo,
         test_utils::{contracts::FeatureContract, CairoVersion},
-        transaction::transactions::{DeclareTransaction, InvokeTransaction},
+        transaction::transaction_types::TransactionType,
+        transaction::transactions::{DeclareTransaction, InvokeTransaction, L1HandlerTransaction},
     };
     use proptest::prelude::*;
     use proptest_derive::Arbitrary;
     use starknet_api::{
         data_availability::DataAvailabilityMode,
-        transaction::{DeclareTransactionV3, InvokeTransactionV3},
+        transaction::{
+            DeclareTransactionV3, Fee, InvokeTransactionV3, Resource, ResourceBounds, ResourceBoundsMapping, Tip,
+        },
     };
     use starknet_types_core::felt::Felt;
 
     use super::*;
     use std::fmt;
+    use std::time::Duration;
 
     #[derive(PartialEq, Eq, Hash)]
     struct AFelt(Felt);
@@ -387,7 +519,10 @@ mod tests {
             write!(
                 f,
                 "Insert(ty={:?},arrived_at={:?},tx_hash={:?},contract_address={:?},nonce={:?},force={:?})",
-                self.0.tx.tx_type(),
+                match &self.0.tx {
+                    Transaction::AccountTransaction(tx) => tx.tx_type(),
+                    Transaction::L1HandlerTransaction(_) => TransactionType::L1Handler,
+                },
                 self.0.arrived_at,
                 self.0.tx_hash(),
                 self.0.contract_address(),
@@ -406,6 +541,7 @@ mod tests {
                 Declare,
                 DeployAccount,
                 InvokeFunction,
+                L1Handler,
             }
 
             <(TxTy, SystemTime, AFelt, AFelt, u64, bool)>::arbitrary()
@@ -418,7 +554,7 @@ mod tests {
                     let dummy_class_info = ClassInfo::new(&dummy_contract_class.get_class(), 100, 100).unwrap();
 
                     let tx = match ty {
-                        TxTy::Declare => AccountTransaction::Declare(
+                        TxTy::Declare => Transaction::AccountTransaction(AccountTransaction::Declare(
                             DeclareTransaction::new(
                                 starknet_api::transaction::DeclareTransaction::V3(DeclareTransactionV3 {
                                     resource_bounds: Default::default(),
@@ -437,8 +573,8 @@ mod tests {
                                 dummy_class_info,
                             )
                             .unwrap(),
-                        ),
-                        TxTy::DeployAccount => AccountTransaction::Declare(
+                        )),
+                        TxTy::DeployAccount => Transaction::AccountTransaction(AccountTransaction::Declare(
                             DeclareTransaction::new(
                                 starknet_api::transaction::DeclareTransaction::V3(DeclareTransactionV3 {
                                     resource_bounds: Default::default(),
@@ -457,22 +593,33 @@ mod tests {
                                 dummy_class_info,
                             )
                             .unwrap(),
-                        ),
-                        TxTy::InvokeFunction => AccountTransaction::Invoke(InvokeTransaction::new(
-                            starknet_api::transaction::InvokeTransaction::V3(InvokeTransactionV3 {
-                                resource_bounds: Default::default(),
-                                tip: Default::default(),
-                                signature: Default::default(),
+                        )),
+                        TxTy::InvokeFunction => {
+                            Transaction::AccountTransaction(AccountTransaction::Invoke(InvokeTransaction::new(
+                                starknet_api::transaction::InvokeTransaction::V3(InvokeTransactionV3 {
+                                    resource_bounds: Default::default(),
+                                    tip: Default::default(),
+                                    signature: Default::default(),
+                                    nonce,
+                                    sender_address: contract_addr,
+                                    calldata: Default::default(),
+                                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                                    fee_data_availability_mode: DataAvailabilityMode::L1,
+                                    paymaster_data: Default::default(),
+                                    account_deployment_data: Default::default(),
+                                }),
+                                tx_hash,
+                            )))
+                        }
+                        TxTy::L1Handler => Transaction::L1HandlerTransaction(L1HandlerTransaction {
+                            tx: starknet_api::transaction::L1HandlerTransaction {
                                 nonce,
-                                sender_address: contract_addr,
-                                calldata: Default::default(),
-                                nonce_data_availability_mode: DataAvailabilityMode::L1,
-                                fee_data_availability_mode: DataAvailabilityMode::L1,
-                                paymaster_data: Default::default(),
-                                account_deployment_data: Default::default(),
-                            }),
+                                contract_address: contract_addr,
+                                ..Default::default()
+                            },
                             tx_hash,
-                        )),
+                            paid_fee_on_l1: Fee(1),
+                        }),
                     };
 
                     Insert(MempoolTransaction { tx, arrived_at, converted_class: None }, force)
@@ -491,7 +638,7 @@ mod tests {
     struct MempoolInvariantsProblem(Vec<Operation>);
     impl MempoolInvariantsProblem {
         fn check(&self) {
-            let mut mempool = MempoolInner::default();
+            let mut mempool = MempoolInner::new(MempoolLimits::for_testing());
             mempool.check_invariants();
 
             let mut inserted = HashSet::new();
@@ -537,4 +684,172 @@ mod tests {
             pb.check();
         }
     }
+
+    fn l1_handler_tx(nonce: u64) -> MempoolTransaction {
+        MempoolTransaction {
+            tx: Transaction::L1HandlerTransaction(L1HandlerTransaction {
+                tx: starknet_api::transaction::L1HandlerTransaction {
+                    nonce: Nonce(Felt::from(nonce)),
+                    ..Default::default()
+                },
+                tx_hash: TransactionHash(Felt::from(nonce)),
+                paid_fee_on_l1: Fee(1),
+            }),
+            arrived_at: SystemTime::now(),
+            converted_class: None,
+        }
+    }
+
+    #[test]
+    fn test_l1_handler_txs_by_nonce() {
+        let mut mempool = MempoolInner::new(MempoolLimits::for_testing());
+        mempool.insert_tx(l1_handler_tx(2), false).unwrap();
+        mempool.insert_tx(l1_handler_tx(1), false).unwrap();
+        assert!(matches!(mempool.insert_tx(l1_handler_tx(2), false), Err(TxInsersionError::NonceConflict)));
+        mempool.check_invariants();
+
+        assert_eq!(mempool.pop_next().unwrap().nonce(), Nonce(Felt::from(1)));
+        assert_eq!(mempool.pop_next().unwrap().nonce(), Nonce(Felt::from(2)));
+        assert!(mempool.pop_next().is_none());
+    }
+
+    fn invoke_tx(sender: u64, nonce: u64, tip: u64, arrived_at: SystemTime) -> MempoolTransaction {
+        invoke_tx_with_l1_gas(sender, nonce, tip, ResourceBounds::default(), arrived_at)
+    }
+
+    fn invoke_tx_with_l1_gas(
+        sender: u64,
+        nonce: u64,
+        tip: u64,
+        l1_gas: ResourceBounds,
+        arrived_at: SystemTime,
+    ) -> MempoolTransaction {
+        let resource_bounds =
+            ResourceBoundsMapping::try_from(vec![(Resource::L1Gas, l1_gas), (Resource::L2Gas, Default::default())])
+                .unwrap();
+        let tx_hash = Felt::from(sender * 1_000_000 + nonce * 1_000 + tip)
+            + Felt::from(l1_gas.max_amount) * Felt::TWO.pow(64u32)
+            + Felt::from(l1_gas.max_price_per_unit) * Felt::TWO.pow(128u32);
+        MempoolTransaction {
+            tx: Transaction::AccountTransaction(AccountTransaction::Invoke(InvokeTransaction::new(
+                starknet_api::transaction::InvokeTransaction::V3(InvokeTransactionV3 {
+                    resource_bounds,
+                    tip: Tip(tip),
+                    signature: Default::default(),
+                    nonce: Nonce(Felt::from(nonce)),
+                    sender_address: ContractAddress::try_from(Felt::from(sender)).unwrap(),
+                    calldata: Default::default(),
+                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                    fee_data_availability_mode: DataAvailabilityMode::L1,
+                    paymaster_data: Default::default(),
+                    account_deployment_data: Default::default(),
+                }),
+                TransactionHash(tx_hash),
+            ))),
+            arrived_at,
+            converted_class: None,
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits =
+            MempoolLimits { max_transactions: 3, max_transactions_per_account: 2, ..MempoolLimits::for_testing() };
+        let mut mempool = MempoolInner::new(limits);
+        let now = SystemTime::now();
+
+        mempool.insert_tx(invoke_tx(1, 0, 0, now), false).unwrap();
+        mempool.insert_tx(invoke_tx(1, 1, 0, now), false).unwrap();
+        assert!(matches!(
+            mempool.insert_tx(invoke_tx(1, 2, 0, now), false),
+            Err(TxInsersionError::TooManyTransactionsForAccount)
+        ));
+        mempool.insert_tx(invoke_tx(2, 0, 0, now), false).unwrap();
+        assert!(matches!(mempool.insert_tx(invoke_tx(3, 0, 0, now), false), Err(TxInsersionError::MempoolFull)));
+        // L1 handler transactions and forced insertions are not limited.
+        mempool.insert_tx(l1_handler_tx(0), false).unwrap();
+        mempool.insert_tx(invoke_tx(3, 0, 0, now), true).unwrap();
+        mempool.check_invariants();
+
+        mempool.pop_next().unwrap();
+        mempool.pop_next().unwrap();
+        mempool.pop_next().unwrap();
+        mempool.insert_tx(invoke_tx(4, 0, 0, now), false).unwrap();
+        mempool.check_invariants();
+    }
+
+    #[test]
+    fn test_replace_by_fee() {
+        let mut mempool = MempoolInner::new(MempoolLimits::for_testing());
+        let first = SystemTime::now();
+        let second = first + Duration::from_secs(1);
+
+        mempool.insert_tx(invoke_tx(1, 0, 100, first), false).unwrap();
+        mempool.insert_tx(invoke_tx(2, 0, 100, second), false).unwrap();
+        assert!(matches!(
+            mempool.insert_tx(invoke_tx(1, 0, 109, second), false),
+            Err(TxInsersionError::ReplacementFeeTooLow)
+        ));
+        assert_eq!(
+            mempool.insert_tx(invoke_tx(1, 0, 110, second), false).unwrap(),
+            Some(invoke_tx(1, 0, 100, first).tx_hash())
+        );
+        mempool.check_invariants();
+
+        // The replacement keeps the place of the replaced transaction.
+        let tx = mempool.pop_next().unwrap();
+        assert_eq!(tx.tx_hash(), invoke_tx(1, 0, 110, second).tx_hash());
+        assert_eq!(tx.arrived_at, first);
+        assert_eq!(mempool.pop_next().unwrap().tx_hash(), invoke_tx(2, 0, 100, second).tx_hash());
+        assert!(mempool.pop_next().is_none());
+        mempool.check_invariants();
+    }
+
+    #[test]
+    fn test_replace_by_fee_per_gas_unit() {
+        let mut mempool = MempoolInner::new(MempoolLimits::for_testing());
+        let now = SystemTime::now();
+        let l1_gas = ResourceBounds { max_amount: 1_000, max_price_per_unit: 100 };
+
+        mempool.insert_tx(invoke_tx_with_l1_gas(1, 0, 0, l1_gas, now), false).unwrap();
+        // Bidding for more gas at the same price does not pay more.
+        let more_gas = ResourceBounds { max_amount: 100_000, ..l1_gas };
+        assert!(matches!(
+            mempool.insert_tx(invoke_tx_with_l1_gas(1, 0, 0, more_gas, now), false),
+            Err(TxInsersionError::ReplacementFeeTooLow)
+        ));
+        // The tip is paid per unit of gas too.
+        assert!(matches!(
+            mempool.insert_tx(invoke_tx_with_l1_gas(1, 0, 9, l1_gas, now), false),
+            Err(TxInsersionError::ReplacementFeeTooLow)
+        ));
+        assert_eq!(
+            mempool.insert_tx(invoke_tx_with_l1_gas(1, 0, 10, l1_gas, now), false).unwrap(),
+            Some(invoke_tx_with_l1_gas(1, 0, 0, l1_gas, now).tx_hash())
+        );
+        mempool.check_invariants();
+    }
+
+    #[test]
+    fn test_remove_age_exceeded_txs() {
+        let limits = MempoolLimits { max_age: Duration::from_secs(60), ..MempoolLimits::for_testing() };
+        let mut mempool = MempoolInner::new(limits);
+        let now = SystemTime::now();
+        let old = now - Duration::from_secs(120);
+
+        mempool.insert_tx(invoke_tx(1, 0, 0, old), false).unwrap();
+        mempool.insert_tx(invoke_tx(1, 1, 0, now), false).unwrap();
+        mempool.insert_tx(invoke_tx(2, 0, 0, now), false).unwrap();
+        mempool.insert_tx(l1_handler_tx(0), false).unwrap();
+
+        // The whole nonce chain of the first account is removed.
+        let removed = mempool.remove_age_exceeded_txs(now);
+        assert_eq!(removed.len(), 2);
+        assert!(removed.iter().all(|tx| tx.contract_address() == ContractAddress::try_from(Felt::ONE).unwrap()));
+        mempool.check_invariants();
+
+        assert!(matches!(mempool.pop_next().unwrap().tx, Transaction::L1HandlerTransaction(_)));
+        assert_eq!(mempool.pop_next().unwrap().tx_hash(), invoke_tx(2, 0, 0, now).tx_hash());
+        assert!(mempool.pop_next().is_none());
+    }
 }
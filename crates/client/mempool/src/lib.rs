@@ -4,18 +4,20 @@ use blockifier::transaction::transaction_execution::Transaction;
 use blockifier::transaction::transactions::DeclareTransaction;
 use blockifier::transaction::transactions::DeployAccountTransaction;
 use blockifier::transaction::transactions::InvokeTransaction;
+use blockifier::transaction::transactions::L1HandlerTransaction;
 use header::make_pending_header;
 use inner::MempoolInner;
 use mc_db::db_block_id::DbBlockId;
 use mc_db::MadaraBackend;
 use mc_db::MadaraStorageError;
-use mc_exec::ExecutionContext;
+use mc_exec::{ContractClassCache, ExecutionContext};
 use mp_block::BlockId;
 use mp_block::BlockTag;
 use mp_block::MadaraPendingBlockInfo;
 use mp_class::ConvertedClass;
 use mp_transactions::broadcasted_to_blockifier;
 use mp_transactions::BroadcastedToBlockifierError;
+use mp_transactions::TransactionWithHash;
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::transaction::TransactionHash;
 use starknet_core::types::BroadcastedDeclareTransaction;
@@ -33,12 +35,16 @@ pub use inner::{ArrivedAtTimestamp, MempoolTransaction};
 #[cfg(any(test, feature = "testing"))]
 pub use l1::MockL1DataProvider;
 pub use l1::{GasPriceProvider, L1DataProvider};
+pub use limits::MempoolLimits;
+pub use tx_status::{SubmittedTransaction, SubmittedTransactionStatus, TransactionStatusTable};
 
 pub mod block_production;
 mod close_block;
 pub mod header;
 mod inner;
 mod l1;
+mod limits;
+mod tx_status;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -55,7 +61,7 @@ pub enum Error {
 }
 impl Error {
     pub fn is_internal(&self) -> bool {
-        !matches!(self, Error::Validation(_))
+        !matches!(self, Error::Validation(_) | Error::InnerMempool(_))
     }
 }
 
@@ -67,25 +73,71 @@ pub trait MempoolProvider: Send + Sync {
         &self,
         tx: BroadcastedDeployAccountTransaction,
     ) -> Result<DeployAccountTransactionResult, Error>;
+    fn accept_l1_handler_tx(&self, tx: L1HandlerTransaction) -> Result<L1HandlerTransactionResult, Error>;
     fn take_txs_chunk(&self, dest: &mut Vec<MempoolTransaction>, n: usize);
     fn take_tx(&self) -> Option<MempoolTransaction>;
     fn re_add_txs(&self, txs: Vec<MempoolTransaction>);
+    /// Called when a transaction taken from the mempool failed to execute and will not be included in a block.
+    fn mark_rejected(&self, tx_hash: Felt, reason: String);
+    /// Status of a transaction submitted to the mempool which is not in a block yet.
+    fn get_submitted_transaction(&self, tx_hash: &Felt) -> Option<SubmittedTransaction>;
+    /// Hashes of the submitted transactions whose status changed, see [`TransactionStatusTable::subscribe`].
+    fn subscribe_submitted_transactions(&self) -> tokio::sync::broadcast::Receiver<Felt>;
     fn chain_id(&self) -> Felt;
 }
 
+/// Result of adding an L1 handler transaction to the mempool, mirroring the results of the other transaction types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L1HandlerTransactionResult {
+    pub transaction_hash: Felt,
+}
+
 pub struct Mempool {
     backend: Arc<MadaraBackend>,
     l1_data_provider: Arc<dyn L1DataProvider>,
+    class_cache: Arc<ContractClassCache>,
     inner: RwLock<MempoolInner>,
+    statuses: TransactionStatusTable,
 }
 
 impl Mempool {
-    pub fn new(backend: Arc<MadaraBackend>, l1_data_provider: Arc<dyn L1DataProvider>) -> Self {
-        Mempool { backend, l1_data_provider, inner: Default::default() }
+    pub fn new(
+        backend: Arc<MadaraBackend>,
+        l1_data_provider: Arc<dyn L1DataProvider>,
+        class_cache: Arc<ContractClassCache>,
+    ) -> Self {
+        let limits = MempoolLimits::new(backend.chain_config());
+        // Keep the status of transactions for a while after they leave the mempool.
+        let statuses = TransactionStatusTable::new(limits.max_transactions.saturating_mul(2));
+        Mempool { backend, l1_data_provider, class_cache, inner: RwLock::new(MempoolInner::new(limits)), statuses }
+    }
+
+    /// Stale transactions are removed lazily, whenever the mempool is locked for writing.
+    fn remove_age_exceeded_txs(&self, inner: &mut MempoolInner) {
+        let removed = inner.remove_age_exceeded_txs(ArrivedAtTimestamp::now());
+        if !removed.is_empty() {
+            log::debug!("Removed {} transactions which stayed in the mempool for too long", removed.len());
+        }
+        for tx in removed {
+            self.statuses.set_dropped(*tx.tx_hash(), "Transaction stayed in the mempool for too long".into());
+        }
+    }
+
+    /// Records a transaction which was just inserted in the mempool, and the transaction it replaced if any.
+    fn on_tx_inserted(&self, tx: Transaction, replaced: Option<TransactionHash>) {
+        if let Some(replaced) = replaced {
+            self.statuses
+                .set_dropped(*replaced, "Transaction was replaced by a transaction paying a higher fee".into());
+        }
+        let TransactionWithHash { transaction, hash } = TransactionWithHash::from(tx);
+        self.statuses.set_received(hash, Some(transaction));
     }
 
     fn accept_tx(&self, tx: Transaction, converted_class: Option<ConvertedClass>) -> Result<(), Error> {
-        let Transaction::AccountTransaction(tx) = tx else { panic!("L1HandlerTransaction not supported yet") };
+        let tx = match tx {
+            Transaction::AccountTransaction(tx) => tx,
+            Transaction::L1HandlerTransaction(tx) => return self.accept_l1_handler(tx),
+        };
 
         // The timestamp *does not* take the transaction validation time into account.
         let arrived_at = ArrivedAtTimestamp::now();
@@ -121,21 +173,43 @@ impl Mempool {
         };
 
         // Perform validations
-        let exec_context = ExecutionContext::new_in_block(Arc::clone(&self.backend), &pending_block_info)?;
+        let exec_context = ExecutionContext::new_in_block(
+            Arc::clone(&self.backend),
+            Arc::clone(&self.class_cache),
+            &pending_block_info,
+        )?;
         let mut validator = exec_context.tx_validator();
         validator.perform_validations(clone_account_tx(&tx), deploy_account_tx_hash.is_some())?;
 
         if !is_only_query(&tx) {
             // Finally, add it to the nonce chain for the account nonce
             let force = false;
-            self.inner
-                .write()
-                .expect("Poisoned lock")
-                .insert_tx(MempoolTransaction { tx, arrived_at, converted_class }, force)?
+            let tx = Transaction::AccountTransaction(tx);
+            let status_tx = clone_transaction(&tx);
+            let mut inner = self.inner.write().expect("Poisoned lock");
+            self.remove_age_exceeded_txs(&mut inner);
+            let replaced = inner.insert_tx(MempoolTransaction { tx, arrived_at, converted_class }, force)?;
+            self.on_tx_inserted(status_tx, replaced);
         }
 
         Ok(())
     }
+
+    /// L1 handler transactions are not validated: they have no signature and their fee was already paid on L1.
+    /// They are ordered by their L1 message nonce, and a nonce can only be in the mempool once.
+    fn accept_l1_handler(&self, tx: L1HandlerTransaction) -> Result<(), Error> {
+        let arrived_at = ArrivedAtTimestamp::now();
+        let force = false;
+        let tx = Transaction::L1HandlerTransaction(tx);
+        let status_tx = clone_transaction(&tx);
+        let replaced = self
+            .inner
+            .write()
+            .expect("Poisoned lock")
+            .insert_tx(MempoolTransaction { tx, arrived_at, converted_class: None }, force)?;
+        self.on_tx_inserted(status_tx, replaced);
+        Ok(())
+    }
 }
 
 pub fn transaction_hash(tx: &Transaction) -> Felt {
@@ -209,13 +283,21 @@ impl MempoolProvider for Mempool {
         Ok(res)
     }
 
+    fn accept_l1_handler_tx(&self, tx: L1HandlerTransaction) -> Result<L1HandlerTransactionResult, Error> {
+        let res = L1HandlerTransactionResult { transaction_hash: *tx.tx_hash };
+        self.accept_tx(Transaction::L1HandlerTransaction(tx), None)?;
+        Ok(res)
+    }
+
     fn take_txs_chunk(&self, dest: &mut Vec<MempoolTransaction>, n: usize) {
         let mut inner = self.inner.write().expect("Poisoned lock");
+        self.remove_age_exceeded_txs(&mut inner);
         inner.pop_next_chunk(dest, n)
     }
 
     fn take_tx(&self) -> Option<MempoolTransaction> {
         let mut inner = self.inner.write().expect("Poisoned lock");
+        self.remove_age_exceeded_txs(&mut inner);
         inner.pop_next()
     }
 
@@ -224,6 +306,18 @@ impl MempoolProvider for Mempool {
         inner.re_add_txs(txs)
     }
 
+    fn mark_rejected(&self, tx_hash: Felt, reason: String) {
+        self.statuses.set_rejected(tx_hash, reason)
+    }
+
+    fn get_submitted_transaction(&self, tx_hash: &Felt) -> Option<SubmittedTransaction> {
+        self.statuses.get(tx_hash)
+    }
+
+    fn subscribe_submitted_transactions(&self) -> tokio::sync::broadcast::Receiver<Felt> {
+        self.statuses.subscribe()
+    }
+
     fn chain_id(&self) -> Felt {
         Felt::from_bytes_be_slice(format!("{}", self.backend.chain_config().chain_id).as_bytes())
     }
@@ -237,27 +331,43 @@ pub(crate) fn is_only_query(tx: &AccountTransaction) -> bool {
     }
 }
 
-pub(crate) fn contract_addr(tx: &AccountTransaction) -> ContractAddress {
+pub(crate) fn contract_addr(tx: &Transaction) -> ContractAddress {
     match tx {
-        AccountTransaction::Declare(tx) => tx.tx.sender_address(),
-        AccountTransaction::DeployAccount(tx) => tx.contract_address,
-        AccountTransaction::Invoke(tx) => tx.tx.sender_address(),
+        Transaction::AccountTransaction(AccountTransaction::Declare(tx)) => tx.tx.sender_address(),
+        Transaction::AccountTransaction(AccountTransaction::DeployAccount(tx)) => tx.contract_address,
+        Transaction::AccountTransaction(AccountTransaction::Invoke(tx)) => tx.tx.sender_address(),
+        Transaction::L1HandlerTransaction(tx) => tx.tx.contract_address,
     }
 }
 
-pub(crate) fn nonce(tx: &AccountTransaction) -> Nonce {
+/// For L1 handler transactions, this is the nonce of the L1 message.
+pub(crate) fn nonce(tx: &Transaction) -> Nonce {
     match tx {
-        AccountTransaction::Declare(tx) => tx.tx.nonce(),
-        AccountTransaction::DeployAccount(tx) => tx.tx.nonce(),
-        AccountTransaction::Invoke(tx) => tx.tx.nonce(),
+        Transaction::AccountTransaction(AccountTransaction::Declare(tx)) => tx.tx.nonce(),
+        Transaction::AccountTransaction(AccountTransaction::DeployAccount(tx)) => tx.tx.nonce(),
+        Transaction::AccountTransaction(AccountTransaction::Invoke(tx)) => tx.tx.nonce(),
+        Transaction::L1HandlerTransaction(tx) => tx.tx.nonce,
     }
 }
 
-pub(crate) fn tx_hash(tx: &AccountTransaction) -> TransactionHash {
+pub(crate) fn tx_hash(tx: &Transaction) -> TransactionHash {
     match tx {
-        AccountTransaction::Declare(tx) => tx.tx_hash,
-        AccountTransaction::DeployAccount(tx) => tx.tx_hash,
-        AccountTransaction::Invoke(tx) => tx.tx_hash,
+        Transaction::AccountTransaction(AccountTransaction::Declare(tx)) => tx.tx_hash,
+        Transaction::AccountTransaction(AccountTransaction::DeployAccount(tx)) => tx.tx_hash,
+        Transaction::AccountTransaction(AccountTransaction::Invoke(tx)) => tx.tx_hash,
+        Transaction::L1HandlerTransaction(tx) => tx.tx_hash,
+    }
+}
+
+// Transaction does not implement Clone, for the same reason as AccountTransaction below.
+pub(crate) fn clone_transaction(tx: &Transaction) -> Transaction {
+    match tx {
+        Transaction::AccountTransaction(tx) => Transaction::AccountTransaction(clone_account_tx(tx)),
+        Transaction::L1HandlerTransaction(tx) => Transaction::L1HandlerTransaction(L1HandlerTransaction {
+            tx: tx.tx.clone(),
+            tx_hash: tx.tx_hash,
+            paid_fee_on_l1: tx.paid_fee_on_l1,
+        }),
     }
 }
 
@@ -0,0 +1,62 @@
+use blockifier::transaction::objects::{FeeType, TransactionInfo, TransactionInfoCreator};
+use blockifier::transaction::transaction_execution::Transaction;
+use mp_chain_config::ChainConfig;
+use std::time::{Duration, SystemTime};
+
+/// A transaction can only replace a transaction with the same nonce if it pays at least this much more, in percent.
+const REPLACEMENT_FEE_BUMP_PERCENT: u128 = 10;
+
+#[derive(Debug, Clone)]
+pub struct MempoolLimits {
+    /// Maximum number of account transactions. L1 handler transactions do not count towards this limit.
+    pub max_transactions: usize,
+    pub max_transactions_per_account: usize,
+    pub max_age: Duration,
+}
+
+impl MempoolLimits {
+    pub fn new(chain_config: &ChainConfig) -> Self {
+        Self {
+            max_transactions: chain_config.mempool_tx_limit,
+            max_transactions_per_account: chain_config.mempool_tx_limit_per_account,
+            max_age: chain_config.mempool_tx_max_age,
+        }
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub fn for_testing() -> Self {
+        Self {
+            max_transactions: 10_000,
+            max_transactions_per_account: 10_000,
+            max_age: Duration::from_secs(60 * 60 * 24 * 365),
+        }
+    }
+
+    /// Transactions which arrived before the returned timestamp are too old.
+    pub(crate) fn age_cutoff(&self, now: SystemTime) -> SystemTime {
+        now.checked_sub(self.max_age).unwrap_or(SystemTime::UNIX_EPOCH)
+    }
+}
+
+/// What a transaction bids for its inclusion, and in which token. This is the maximum price per unit of L1 gas plus
+/// the tip, which is also paid per unit, for transactions with resource bounds, and the maximum fee otherwise. Bidding
+/// for more gas does not mean paying more for it.
+fn fee_bid(tx: &Transaction) -> (FeeType, u128) {
+    match tx.create_tx_info() {
+        TransactionInfo::Current(info) => {
+            let max_price_per_unit =
+                info.l1_resource_bounds().map(|bounds| bounds.max_price_per_unit).unwrap_or_default();
+            (FeeType::Strk, max_price_per_unit.saturating_add(info.tip.0.into()))
+        }
+        TransactionInfo::Deprecated(info) => (FeeType::Eth, info.max_fee.0),
+    }
+}
+
+/// Whether `new` pays enough to replace `previous`, a transaction with the same sender and nonce. Fees paid in
+/// different tokens cannot be compared, and such transactions never replace each other.
+pub(crate) fn can_replace(previous: &Transaction, new: &Transaction) -> bool {
+    let (previous_fee_type, previous_fee) = fee_bid(previous);
+    let (new_fee_type, new_fee) = fee_bid(new);
+    let min_fee = previous_fee.saturating_add(previous_fee.saturating_mul(REPLACEMENT_FEE_BUMP_PERCENT) / 100);
+    previous_fee_type == new_fee_type && new_fee > previous_fee && new_fee >= min_fee
+}
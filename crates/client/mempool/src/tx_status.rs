@@ -0,0 +1,113 @@
+use lru::LruCache;
+use mp_transactions::Transaction;
+use starknet_types_core::felt::Felt;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Status of a transaction which was submitted to this node, but is not in a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmittedTransactionStatus {
+    /// Waiting to be included in a block.
+    Received,
+    /// The transaction failed and will never be included in a block.
+    Rejected { reason: String },
+    /// The transaction was removed from the mempool before being executed.
+    Dropped { reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmittedTransaction {
+    pub status: SubmittedTransactionStatus,
+    /// The transaction is only kept while it is waiting to be included in a block.
+    pub transaction: Option<Transaction>,
+}
+
+/// Statuses of the last submitted transactions, by transaction hash. Transactions which made it into a block are
+/// found in the db instead, and their entry here is stale: the db should always be checked first.
+pub struct TransactionStatusTable {
+    entries: Mutex<LruCache<Felt, SubmittedTransaction>>,
+    /// See [`TransactionStatusTable::subscribe`].
+    updates: broadcast::Sender<Felt>,
+}
+
+impl TransactionStatusTable {
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+    /// Number of status updates kept for slow subscribers.
+    const UPDATES_CAPACITY: usize = 1024;
+
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self { entries: Mutex::new(LruCache::new(capacity)), updates: broadcast::channel(Self::UPDATES_CAPACITY).0 }
+    }
+
+    /// Subscribe to the status changes. The hash of the transaction is sent every time its status is set, once the new
+    /// status can be read with [`TransactionStatusTable::get`].
+    pub fn subscribe(&self) -> broadcast::Receiver<Felt> {
+        self.updates.subscribe()
+    }
+
+    pub fn get(&self, tx_hash: &Felt) -> Option<SubmittedTransaction> {
+        self.entries.lock().expect("Poisoned lock").get(tx_hash).cloned()
+    }
+
+    pub fn set_received(&self, tx_hash: Felt, transaction: Option<Transaction>) {
+        self.set(tx_hash, SubmittedTransaction { status: SubmittedTransactionStatus::Received, transaction });
+    }
+
+    pub fn set_rejected(&self, tx_hash: Felt, reason: String) {
+        self.set(
+            tx_hash,
+            SubmittedTransaction { status: SubmittedTransactionStatus::Rejected { reason }, transaction: None },
+        );
+    }
+
+    pub fn set_dropped(&self, tx_hash: Felt, reason: String) {
+        self.set(
+            tx_hash,
+            SubmittedTransaction { status: SubmittedTransactionStatus::Dropped { reason }, transaction: None },
+        );
+    }
+
+    fn set(&self, tx_hash: Felt, submitted: SubmittedTransaction) {
+        self.entries.lock().expect("Poisoned lock").put(tx_hash, submitted);
+        // An error only means that there are no subscribers.
+        let _ = self.updates.send(tx_hash);
+    }
+}
+
+impl Default for TransactionStatusTable {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_status_table() {
+        let table = TransactionStatusTable::new(2);
+        let mut updates = table.subscribe();
+        table.set_received(Felt::ONE, Some(Transaction::L1Handler(Default::default())));
+        table.set_received(Felt::TWO, None);
+        table.set_rejected(Felt::ONE, "Out of gas".into());
+
+        assert_eq!(
+            table.get(&Felt::ONE),
+            Some(SubmittedTransaction {
+                status: SubmittedTransactionStatus::Rejected { reason: "Out of gas".into() },
+                transaction: None
+            })
+        );
+        assert_eq!(updates.try_recv(), Ok(Felt::ONE));
+        assert_eq!(updates.try_recv(), Ok(Felt::TWO));
+        assert_eq!(updates.try_recv(), Ok(Felt::ONE));
+
+        // The least recently used entry is evicted.
+        table.set_dropped(Felt::THREE, "Too old".into());
+        assert_eq!(table.get(&Felt::TWO), None);
+        assert!(table.get(&Felt::ONE).is_some());
+    }
+}
@@ -34,6 +34,11 @@ impl MetricsRegistry {
     pub fn is_enabled(&self) -> bool {
         self.0.is_some()
     }
+
+    /// A registry in which metrics are never exposed, for tests.
+    pub fn dummy() -> Self {
+        Self(None)
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -2,3 +2,10 @@
 pub const MAX_EVENTS_KEYS: usize = 100;
 /// Maximum number of events that can be fetched in a single chunk for the `get_events` RPC.
 pub const MAX_EVENTS_CHUNK_SIZE: usize = 1000;
+/// Maximum number of keys (classes, contracts and storage slots) that can be proven in a single `get_storage_proof`
+/// RPC call.
+pub const MAX_STORAGE_PROOF_KEYS_BY_QUERY: usize = 100;
+/// Maximum number of blocks a websocket subscription can start in the past.
+pub const MAX_BLOCKS_BACK_BY_SUBSCRIPTION: u64 = 1024;
+/// Maximum number of sender addresses that can be passed to the `subscribe_pending_transactions` RPC.
+pub const MAX_ADDRESSES_IN_FILTER: usize = 1024;
@@ -76,12 +76,20 @@ pub enum StarknetRpcApiError {
     UnsupportedContractClassVersion,
     #[error("An unexpected error occurred")]
     ErrUnexpectedError { data: String },
+    #[error("Invalid subscription id")]
+    InvalidSubscriptionId,
+    #[error("Too many addresses in filter sender_address filter")]
+    TooManyAddressesInFilter,
+    #[error("Cannot go back more than 1024 blocks")]
+    TooManyBlocksBack,
     #[error("Internal server error")]
     InternalServerError,
     #[error("Unimplemented method")]
     UnimplementedMethod,
     #[error("Too many storage keys requested")]
     ProofLimitExceeded,
+    #[error("Block state has been pruned")]
+    BlockPruned { earliest: u64 },
 }
 
 impl From<&StarknetRpcApiError> for i32 {
@@ -116,9 +124,13 @@ impl From<&StarknetRpcApiError> for i32 {
             StarknetRpcApiError::UnsupportedTxnVersion => 61,
             StarknetRpcApiError::UnsupportedContractClassVersion => 62,
             StarknetRpcApiError::ErrUnexpectedError { .. } => 63,
+            StarknetRpcApiError::InvalidSubscriptionId => 66,
+            StarknetRpcApiError::TooManyAddressesInFilter => 67,
+            StarknetRpcApiError::TooManyBlocksBack => 68,
             StarknetRpcApiError::InternalServerError => 500,
             StarknetRpcApiError::UnimplementedMethod => 501,
             StarknetRpcApiError::ProofLimitExceeded => 10000,
+            StarknetRpcApiError::BlockPruned { .. } => 10001,
         }
     }
 }
@@ -131,6 +143,9 @@ impl StarknetRpcApiError {
                 "transaction_index": tx_index,
                 "execution_error": error,
             })),
+            StarknetRpcApiError::BlockPruned { earliest } => Some(json!({
+                "earliest_available_block": earliest,
+            })),
             _ => None,
         }
     }
@@ -197,8 +212,11 @@ impl From<StarknetError> for StarknetRpcApiError {
 }
 
 impl From<MadaraStorageError> for StarknetRpcApiError {
-    fn from(_: MadaraStorageError) -> Self {
-        StarknetRpcApiError::ErrUnexpectedError { data: "DB error".to_string() }
+    fn from(err: MadaraStorageError) -> Self {
+        match err {
+            MadaraStorageError::BlockPruned { earliest, .. } => StarknetRpcApiError::BlockPruned { earliest },
+            _ => StarknetRpcApiError::ErrUnexpectedError { data: "DB error".to_string() },
+        }
     }
 }
 
@@ -18,12 +18,14 @@ use std::sync::Arc;
 
 use mc_db::db_block_id::DbBlockIdResolvable;
 use mc_db::MadaraBackend;
+use mc_exec::ContractClassCache;
 use mp_block::{MadaraMaybePendingBlock, MadaraMaybePendingBlockInfo};
 use mp_chain_config::{ChainConfig, RpcVersion};
 use mp_convert::ToFelt;
 
 use errors::{StarknetRpcApiError, StarknetRpcResult};
 use providers::AddTransactionProvider;
+use utils::subscription::Subscriptions;
 use utils::ResultExt;
 
 /// A Starknet RPC server for Madara
@@ -32,6 +34,8 @@ pub struct Starknet {
     backend: Arc<MadaraBackend>,
     chain_config: Arc<ChainConfig>,
     pub(crate) add_transaction_provider: Arc<dyn AddTransactionProvider>,
+    pub(crate) ws_subscriptions: Arc<Subscriptions>,
+    pub(crate) class_cache: Arc<ContractClassCache>,
 }
 
 impl Starknet {
@@ -39,8 +43,9 @@ impl Starknet {
         backend: Arc<MadaraBackend>,
         chain_config: Arc<ChainConfig>,
         add_transaction_provider: Arc<dyn AddTransactionProvider>,
+        class_cache: Arc<ContractClassCache>,
     ) -> Self {
-        Self { backend, add_transaction_provider, chain_config }
+        Self { backend, add_transaction_provider, chain_config, ws_subscriptions: Default::default(), class_cache }
     }
 
     pub fn clone_backend(&self) -> Arc<MadaraBackend> {
@@ -71,6 +76,16 @@ impl Starknet {
             .ok_or(StarknetRpcApiError::BlockNotFound)
     }
 
+    /// Returns [`StarknetRpcApiError::BlockPruned`] if the state of this block has been pruned from the database.
+    pub fn check_state_available(&self, block_id: &impl DbBlockIdResolvable) -> StarknetRpcResult<()> {
+        // The pending block is always built on top of the latest state.
+        let Some(block_n) = self.backend.get_block_n(block_id).or_internal_server_error("Error getting block n")?
+        else {
+            return Ok(());
+        };
+        Ok(self.backend.check_not_pruned(block_n)?)
+    }
+
     pub fn chain_id(&self) -> Felt {
         self.chain_config.chain_id.clone().to_felt()
     }
@@ -93,6 +108,9 @@ impl Starknet {
 }
 
 /// Returns the RpcModule merged with all the supported RPC versions.
+///
+/// The [`versions::v0_8_0`] module is not mounted yet: execution only carries the l1 gas resource bound, so the
+/// blocks, transactions, receipts and fee estimates it returns are not v0.8.0 compliant.
 pub fn versioned_rpc_api(starknet: &Starknet, read: bool, write: bool, trace: bool) -> anyhow::Result<RpcModule<()>> {
     let mut rpc_api = RpcModule::new(());
 
@@ -1,21 +1,101 @@
+use std::future::Future;
+use std::time::Duration;
+
 use jsonrpsee::core::{async_trait, RpcResult};
+use mc_mempool::{SubmittedTransaction, TransactionStatusTable};
+use mc_metrics::{CounterVec, MetricsRegistry, Opts, PrometheusError, U64};
 use starknet_core::types::{
     BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction,
     DeclareTransactionResult, DeployAccountTransactionResult, InvokeTransactionResult,
 };
 use starknet_providers::{Provider, ProviderError};
+use starknet_types_core::felt::Felt;
+use tokio::sync::broadcast;
 
 use crate::{bail_internal_server_error, errors::StarknetRpcApiError};
 
 use super::AddTransactionProvider;
 
+/// Only failures to reach the upstream are retried: a transaction it rejected is never sent again.
+#[derive(Clone, Debug)]
+pub struct ForwardRetryConfig {
+    pub max_retries: u32,
+    /// Doubled after every failed attempt.
+    pub initial_backoff: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct ForwardMetrics {
+    forwarded_transactions: CounterVec<U64>,
+}
+
+impl ForwardMetrics {
+    pub fn register(registry: &MetricsRegistry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            forwarded_transactions: registry.register(CounterVec::new(
+                Opts::new(
+                    "madara_forwarded_transactions",
+                    "Number of transactions forwarded to the sequencer. The result is `accepted`, `rejected` by the \
+                     sequencer, or `failed` when the sequencer could not be reached",
+                ),
+                &["tx_type", "result"],
+            )?)?,
+        })
+    }
+
+    fn record(&self, tx_type: &str, result: &str) {
+        self.forwarded_transactions.with_label_values(&[tx_type, result]).inc();
+    }
+}
+
 pub struct ForwardToProvider<P: Provider + Send + Sync> {
     provider: P,
+    retry: ForwardRetryConfig,
+    metrics: ForwardMetrics,
+    /// Forwarded transactions accepted by the sequencer, until they show up in a block synced by this node.
+    submitted: TransactionStatusTable,
 }
 
 impl<P: Provider + Send + Sync> ForwardToProvider<P> {
-    pub fn new(provider: P) -> Self {
-        Self { provider }
+    pub fn new(provider: P, retry: ForwardRetryConfig, metrics: ForwardMetrics) -> Self {
+        Self { provider, retry, metrics, submitted: TransactionStatusTable::default() }
+    }
+
+    /// Calls `send` until the sequencer answers or we run out of retries.
+    async fn forward<T, Fut>(&self, tx_type: &'static str, send: impl Fn() -> Fut) -> RpcResult<T>
+    where
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        let mut backoff = self.retry.initial_backoff;
+        let mut attempt = 0;
+        let res = loop {
+            match send().await {
+                Err(err @ (ProviderError::RateLimited | ProviderError::Other(_)))
+                    if attempt < self.retry.max_retries =>
+                {
+                    log::debug!("Forwarding {tx_type} transaction failed, retrying in {backoff:?}: {err}");
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                res => break res,
+            }
+        };
+
+        match res {
+            Ok(response) => {
+                self.metrics.record(tx_type, "accepted");
+                Ok(response)
+            }
+            Err(ProviderError::StarknetError(e)) => {
+                self.metrics.record(tx_type, "rejected");
+                Err(StarknetRpcApiError::from(e).into())
+            }
+            Err(e) => {
+                self.metrics.record(tx_type, "failed");
+                bail_internal_server_error!("Failed to add {tx_type} transaction to sequencer: {e}")
+            }
+        }
     }
 }
 
@@ -25,28 +105,23 @@ impl<P: Provider + Send + Sync> AddTransactionProvider for ForwardToProvider<P>
         &self,
         declare_transaction: BroadcastedDeclareTransaction,
     ) -> RpcResult<DeclareTransactionResult> {
-        let sequencer_response = match self.provider.add_declare_transaction(declare_transaction).await {
-            Ok(response) => response,
-            Err(ProviderError::StarknetError(e)) => {
-                return Err(StarknetRpcApiError::from(e).into());
-            }
-            Err(e) => bail_internal_server_error!("Failed to add declare transaction to sequencer: {e}"),
-        };
+        let sequencer_response =
+            self.forward("declare", || self.provider.add_declare_transaction(declare_transaction.clone())).await?;
 
+        self.submitted.set_received(sequencer_response.transaction_hash, None);
         Ok(sequencer_response)
     }
     async fn add_deploy_account_transaction(
         &self,
         deploy_account_transaction: BroadcastedDeployAccountTransaction,
     ) -> RpcResult<DeployAccountTransactionResult> {
-        let sequencer_response = match self.provider.add_deploy_account_transaction(deploy_account_transaction).await {
-            Ok(response) => response,
-            Err(ProviderError::StarknetError(e)) => {
-                return Err(StarknetRpcApiError::from(e).into());
-            }
-            Err(e) => bail_internal_server_error!("Failed to add deploy account transaction to sequencer: {e}"),
-        };
+        let sequencer_response = self
+            .forward("deploy_account", || {
+                self.provider.add_deploy_account_transaction(deploy_account_transaction.clone())
+            })
+            .await?;
 
+        self.submitted.set_received(sequencer_response.transaction_hash, None);
         Ok(sequencer_response)
     }
 
@@ -54,14 +129,106 @@ impl<P: Provider + Send + Sync> AddTransactionProvider for ForwardToProvider<P>
         &self,
         invoke_transaction: BroadcastedInvokeTransaction,
     ) -> RpcResult<InvokeTransactionResult> {
-        let sequencer_response = match self.provider.add_invoke_transaction(invoke_transaction).await {
-            Ok(response) => response,
-            Err(ProviderError::StarknetError(e)) => {
-                return Err(StarknetRpcApiError::from(e).into());
-            }
-            Err(e) => bail_internal_server_error!("Failed to add invoke transaction to sequencer: {e}"),
-        };
+        let sequencer_response =
+            self.forward("invoke", || self.provider.add_invoke_transaction(invoke_transaction.clone())).await?;
 
+        self.submitted.set_received(sequencer_response.transaction_hash, None);
         Ok(sequencer_response)
     }
+
+    fn get_submitted_transaction(&self, tx_hash: &Felt) -> Option<SubmittedTransaction> {
+        self.submitted.get(tx_hash)
+    }
+
+    fn subscribe_submitted_transactions(&self) -> broadcast::Receiver<Felt> {
+        self.submitted.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use starknet_core::types::BroadcastedInvokeTransactionV1;
+    use starknet_providers::jsonrpc::HttpTransport;
+    use starknet_providers::JsonRpcClient;
+
+    fn forward_to(server: &MockServer, max_retries: u32) -> ForwardToProvider<JsonRpcClient<HttpTransport>> {
+        let transport = HttpTransport::new(server.url("/").parse::<url::Url>().unwrap())
+            .with_header("x-api-key".into(), "secret".into());
+        ForwardToProvider::new(
+            JsonRpcClient::new(transport),
+            ForwardRetryConfig { max_retries, initial_backoff: Duration::from_millis(1) },
+            ForwardMetrics::register(&MetricsRegistry::dummy()).unwrap(),
+        )
+    }
+
+    fn invoke() -> BroadcastedInvokeTransaction {
+        BroadcastedInvokeTransaction::V1(BroadcastedInvokeTransactionV1 {
+            sender_address: Felt::from(0x100),
+            calldata: vec![Felt::ONE],
+            max_fee: Felt::from(0x1000),
+            signature: vec![],
+            nonce: Felt::ZERO,
+            is_query: false,
+        })
+    }
+
+    fn count(forward: &ForwardToProvider<JsonRpcClient<HttpTransport>>, result: &str) -> u64 {
+        forward.metrics.forwarded_transactions.with_label_values(&["invoke", result]).get()
+    }
+
+    #[tokio::test]
+    async fn test_forward_accepted() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST).header("x-api-key", "secret").body_contains("starknet_addInvokeTransaction");
+            then.status(200).json_body(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "transaction_hash": "0x10" }
+            }));
+        });
+        let forward = forward_to(&server, 2);
+
+        let res = forward.add_invoke_transaction(invoke()).await.unwrap();
+        assert_eq!(res.transaction_hash, Felt::from(0x10));
+        assert!(forward.get_submitted_transaction(&Felt::from(0x10)).is_some());
+        mock.assert_hits(1);
+        assert_eq!(count(&forward, "accepted"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_forward_rejected_is_not_retried() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST);
+            then.status(200).json_body(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": 52, "message": "Invalid transaction nonce" }
+            }));
+        });
+        let forward = forward_to(&server, 2);
+
+        let err = forward.add_invoke_transaction(invoke()).await.unwrap_err();
+        assert_eq!(err.code(), 52);
+        mock.assert_hits(1);
+        assert_eq!(count(&forward, "rejected"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_forward_unreachable_is_retried() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST);
+            then.status(503);
+        });
+        let forward = forward_to(&server, 2);
+
+        let err = forward.add_invoke_transaction(invoke()).await.unwrap_err();
+        assert_eq!(err.code(), 500);
+        mock.assert_hits(3);
+        assert_eq!(count(&forward, "failed"), 1);
+    }
 }
@@ -3,11 +3,14 @@ use crate::{errors::StarknetRpcApiError, utils::display_internal_server_error};
 use jsonrpsee::core::{async_trait, RpcResult};
 use mc_mempool::Mempool;
 use mc_mempool::MempoolProvider;
+use mc_mempool::SubmittedTransaction;
 use starknet_core::types::{
     BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction,
     DeclareTransactionResult, DeployAccountTransactionResult, InvokeTransactionResult,
 };
+use starknet_types_core::felt::Felt;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 
 /// This [`AddTransactionProvider`] adds the received transactions to a mempool.
 pub struct MempoolAddTxProvider {
@@ -49,4 +52,10 @@ impl AddTransactionProvider for MempoolAddTxProvider {
     ) -> RpcResult<InvokeTransactionResult> {
         Ok(self.mempool.accept_invoke_tx(invoke_transaction).map_err(make_err)?)
     }
+    fn get_submitted_transaction(&self, tx_hash: &Felt) -> Option<SubmittedTransaction> {
+        self.mempool.get_submitted_transaction(tx_hash)
+    }
+    fn subscribe_submitted_transactions(&self) -> broadcast::Receiver<Felt> {
+        self.mempool.subscribe_submitted_transactions()
+    }
 }
@@ -5,10 +5,13 @@ pub use forward_to_provider::*;
 pub use mempool::*;
 
 use jsonrpsee::core::{async_trait, RpcResult};
+use mc_mempool::SubmittedTransaction;
 use starknet_core::types::{
     BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction,
     DeclareTransactionResult, DeployAccountTransactionResult, InvokeTransactionResult,
 };
+use starknet_types_core::felt::Felt;
+use tokio::sync::broadcast;
 
 #[async_trait]
 pub trait AddTransactionProvider: Send + Sync {
@@ -26,4 +29,11 @@ pub trait AddTransactionProvider: Send + Sync {
         &self,
         invoke_transaction: BroadcastedInvokeTransaction,
     ) -> RpcResult<InvokeTransactionResult>;
+
+    /// Status of a transaction submitted through this provider which is not in a block yet. Transactions are only
+    /// remembered for a while, and this returns `None` for unknown transactions.
+    fn get_submitted_transaction(&self, tx_hash: &Felt) -> Option<SubmittedTransaction>;
+
+    /// Hashes of the submitted transactions whose status changed, for instance when a transaction is rejected.
+    fn subscribe_submitted_transactions(&self) -> broadcast::Receiver<Felt>;
 }
@@ -1,5 +1,6 @@
 use jsonrpsee::core::{async_trait, RpcResult};
 use mc_db::MadaraBackend;
+use mc_exec::ContractClassCache;
 use mp_block::{
     header::{GasPrices, L1DataAvailabilityMode, PendingHeader},
     Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock, MadaraMaybePendingBlockInfo,
@@ -25,7 +26,10 @@ use std::sync::Arc;
 use crate::{providers::AddTransactionProvider, Starknet};
 
 #[cfg(test)]
-pub struct TestTransactionProvider;
+#[derive(Default)]
+pub struct TestTransactionProvider {
+    pub submitted: mc_mempool::TransactionStatusTable,
+}
 
 #[cfg(test)]
 #[async_trait]
@@ -48,13 +52,24 @@ impl AddTransactionProvider for TestTransactionProvider {
     ) -> RpcResult<InvokeTransactionResult> {
         unimplemented!()
     }
+    fn get_submitted_transaction(&self, tx_hash: &Felt) -> Option<mc_mempool::SubmittedTransaction> {
+        self.submitted.get(tx_hash)
+    }
+    fn subscribe_submitted_transactions(&self) -> tokio::sync::broadcast::Receiver<Felt> {
+        self.submitted.subscribe()
+    }
 }
 
 #[fixture]
 pub fn rpc_test_setup(_set_workdir: ()) -> (Arc<MadaraBackend>, Starknet) {
     let chain_config = Arc::new(ChainConfig::test_config().unwrap());
     let backend = MadaraBackend::open_for_testing(chain_config.clone());
-    let rpc = Starknet::new(backend.clone(), chain_config.clone(), Arc::new(TestTransactionProvider));
+    let rpc = Starknet::new(
+        backend.clone(),
+        chain_config.clone(),
+        Arc::new(TestTransactionProvider::default()),
+        Arc::new(ContractClassCache::for_testing()),
+    );
     (backend, rpc)
 }
 
@@ -1,4 +1,5 @@
 pub(crate) mod block;
+pub(crate) mod subscription;
 pub(crate) mod transaction;
 
 use std::fmt;
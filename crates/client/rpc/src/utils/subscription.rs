@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use jsonrpsee::core::StringError;
+use jsonrpsee::types::SubscriptionId;
+use jsonrpsee::{SubscriptionMessage, SubscriptionSink};
+use mc_db::db_block_id::DbBlockId;
+use mc_db::notifications::{BlockNotification, RevertedBlocks};
+use mp_block::MadaraBlock;
+use serde::Serialize;
+use starknet_core::types::{BlockId, BlockTag};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, oneshot};
+
+use crate::constants::MAX_BLOCKS_BACK_BY_SUBSCRIPTION;
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::utils::ResultExt;
+use crate::Starknet;
+
+/// Websocket subscriptions which are still running.
+///
+/// The spec has a single `starknet_unsubscribe` method for every kind of subscription, while jsonrpsee registers one
+/// unsubscribe method per subscription kind. Subscriptions register themselves here so that they can be closed by
+/// `starknet_unsubscribe`. Subscription ids are random strings, which prevents closing the subscriptions of another
+/// connection.
+#[derive(Default)]
+pub struct Subscriptions {
+    active: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+impl Subscriptions {
+    /// Registers a subscription. The returned handle resolves when the subscription is closed through
+    /// [`Subscriptions::unsubscribe`], and removes the subscription from the registry when dropped.
+    pub fn register(self: &Arc<Self>, id: &SubscriptionId) -> SubscriptionHandle {
+        let id = subscription_id_key(id);
+        let (sender, receiver) = oneshot::channel();
+        self.active.lock().expect("Poisoned lock").insert(id.clone(), sender);
+        SubscriptionHandle { subscriptions: Arc::clone(self), id, unsubscribed: receiver }
+    }
+
+    /// Returns `false` if there is no active subscription with this id.
+    pub fn unsubscribe(&self, id: &str) -> bool {
+        match self.active.lock().expect("Poisoned lock").remove(id) {
+            // The subscription may have ended on its own in the meantime, which is fine.
+            Some(sender) => {
+                let _ = sender.send(());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub struct SubscriptionHandle {
+    subscriptions: Arc<Subscriptions>,
+    id: String,
+    unsubscribed: oneshot::Receiver<()>,
+}
+
+impl SubscriptionHandle {
+    /// Completes when the subscription is closed through [`Subscriptions::unsubscribe`].
+    pub async fn unsubscribed(&mut self) {
+        let _ = (&mut self.unsubscribed).await;
+    }
+}
+
+impl Drop for SubscriptionHandle {
+    fn drop(&mut self) {
+        self.subscriptions.active.lock().expect("Poisoned lock").remove(&self.id);
+    }
+}
+
+/// Sent by the subscriptions which follow the chain when blocks they notified about are reverted.
+pub const REORG_NOTIFICATION: &str = "starknet_subscriptionReorg";
+
+/// Sends a notification with the given method name. Notifications are named after the kind of update they carry
+/// (`starknet_subscriptionReorg`...) rather than after the subscription method, which is what jsonrpsee does by default.
+pub async fn send_notification(
+    sink: &SubscriptionSink,
+    method: &str,
+    result: &impl Serialize,
+) -> Result<(), StringError> {
+    sink.send(SubscriptionMessage::new(method, sink.subscription_id(), result)?).await?;
+    Ok(())
+}
+
+/// Block from which a subscription starts, given the `block_id` parameter of the subscription. Defaults to the latest
+/// block.
+pub fn starting_block_n(starknet: &Starknet, block_id: Option<BlockId>) -> StarknetRpcResult<u64> {
+    let Some(latest_block_n) =
+        starknet.backend.get_latest_block_n().or_internal_server_error("Error getting latest block")?
+    else {
+        // The subscription will start at the genesis block, once it is imported.
+        return Ok(0);
+    };
+    let block_n = match block_id {
+        None | Some(BlockId::Tag(BlockTag::Latest)) => latest_block_n,
+        Some(BlockId::Tag(BlockTag::Pending)) => latest_block_n + 1,
+        Some(block_id) => starknet.get_block_n(&block_id)?,
+    };
+    if latest_block_n.saturating_sub(block_n) > MAX_BLOCKS_BACK_BY_SUBSCRIPTION {
+        return Err(StarknetRpcApiError::TooManyBlocksBack);
+    }
+    Ok(block_n)
+}
+
+pub enum ChainUpdate {
+    Block(Arc<MadaraBlock>),
+    Reorg(RevertedBlocks),
+}
+
+/// Walks through the closed blocks of the chain, starting from a past block and then following the new blocks as they
+/// are imported. Blocks are yielded once, in order, unless they are reverted: in that case the reorg is yielded and
+/// the chain is followed again from the first reverted block.
+pub struct ChainFollower {
+    notifications: broadcast::Receiver<BlockNotification>,
+    next_block_n: u64,
+    /// Whether blocks need to be read from the db, either because the follower started in the past or because it
+    /// lagged behind the notifications.
+    catching_up: bool,
+}
+
+impl ChainFollower {
+    pub fn new(starknet: &Starknet, next_block_n: u64) -> Self {
+        // The notifications are subscribed to before reading the db, so that no block is missed in-between.
+        Self { notifications: starknet.backend.subscribe_block_notifications(), next_block_n, catching_up: true }
+    }
+
+    /// Returns `None` when no more blocks will be imported.
+    pub async fn next(&mut self, starknet: &Starknet) -> StarknetRpcResult<Option<ChainUpdate>> {
+        loop {
+            if self.catching_up {
+                if let Some(block) = self.next_block_from_db(starknet)? {
+                    self.next_block_n += 1;
+                    return Ok(Some(ChainUpdate::Block(Arc::new(block))));
+                }
+                self.catching_up = false;
+            }
+
+            match self.notifications.recv().await {
+                Ok(BlockNotification::Closed(block)) => {
+                    let block_n = block.info.header.block_number;
+                    if block_n == self.next_block_n {
+                        self.next_block_n += 1;
+                        return Ok(Some(ChainUpdate::Block(block)));
+                    }
+                    // Blocks before `next_block_n` were already read from the db.
+                    self.catching_up = block_n > self.next_block_n;
+                }
+                Ok(BlockNotification::Reverted(reverted)) if reverted.starting_block_number < self.next_block_n => {
+                    self.next_block_n = reverted.starting_block_number;
+                    return Ok(Some(ChainUpdate::Reorg(reverted)));
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    log::debug!("Chain follower lagged behind by {skipped} notifications, catching up from the db");
+                    self.catching_up = true;
+                }
+                Err(RecvError::Closed) => return Ok(None),
+            }
+        }
+    }
+
+    fn next_block_from_db(&self, starknet: &Starknet) -> StarknetRpcResult<Option<MadaraBlock>> {
+        let Some(block) = starknet
+            .backend
+            .get_block(&DbBlockId::BlockN(self.next_block_n))
+            .or_internal_server_error("Error getting block from storage")?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(MadaraBlock::try_from(block).or_internal_server_error("Closed block is pending")?))
+    }
+}
+
+fn subscription_id_key(id: &SubscriptionId) -> String {
+    match id {
+        SubscriptionId::Num(n) => n.to_string(),
+        SubscriptionId::Str(s) => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unsubscribe() {
+        let subscriptions = Arc::new(Subscriptions::default());
+        let mut handle = subscriptions.register(&SubscriptionId::Str("abc".into()));
+
+        assert!(!subscriptions.unsubscribe("def"));
+        assert!(subscriptions.unsubscribe("abc"));
+        handle.unsubscribed().await;
+        assert!(!subscriptions.unsubscribe("abc"));
+
+        // Subscriptions which ended on their own are removed from the registry.
+        drop(subscriptions.register(&SubscriptionId::Num(1)));
+        assert!(!subscriptions.unsubscribe("1"));
+    }
+}
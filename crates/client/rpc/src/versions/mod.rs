@@ -1 +1,2 @@
 pub mod v0_7_1;
+pub mod v0_8_0;
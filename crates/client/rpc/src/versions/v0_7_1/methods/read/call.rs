@@ -32,8 +32,10 @@ use crate::Starknet;
 /// * `BLOCK_NOT_FOUND` - If the specified block does not exist in the blockchain.
 pub fn call(starknet: &Starknet, request: FunctionCall, block_id: BlockId) -> StarknetRpcResult<Vec<Felt>> {
     let block_info = starknet.get_block_info(&block_id)?;
+    starknet.check_state_available(&block_id)?;
 
-    let exec_context = ExecutionContext::new_in_block(Arc::clone(&starknet.backend), &block_info)?;
+    let exec_context =
+        ExecutionContext::new_in_block(Arc::clone(&starknet.backend), Arc::clone(&starknet.class_cache), &block_info)?;
 
     if block_info.protocol_version() < &FALLBACK_TO_SEQUENCER_WHEN_VERSION_BELOW {
         return Err(StarknetRpcApiError::UnsupportedTxnVersion);
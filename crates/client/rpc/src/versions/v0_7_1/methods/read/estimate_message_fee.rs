@@ -35,12 +35,14 @@ pub async fn estimate_message_fee(
     block_id: BlockId,
 ) -> StarknetRpcResult<FeeEstimate> {
     let block_info = starknet.get_block_info(&block_id)?;
+    starknet.check_state_available(&block_id)?;
 
     if block_info.protocol_version() < &FALLBACK_TO_SEQUENCER_WHEN_VERSION_BELOW {
         return Err(StarknetRpcApiError::UnsupportedTxnVersion);
     }
 
-    let exec_context = ExecutionContext::new_in_block(Arc::clone(&starknet.backend), &block_info)?;
+    let exec_context =
+        ExecutionContext::new_in_block(Arc::clone(&starknet.backend), Arc::clone(&starknet.class_cache), &block_info)?;
 
     let transaction = convert_message_into_transaction(message, starknet.chain_id());
     let execution_result = exec_context
@@ -33,6 +33,7 @@ pub fn get_class_at(
         .resolve_block_id(&block_id)
         .or_internal_server_error("Error resolving block id")?
         .ok_or(StarknetRpcApiError::BlockNotFound)?;
+    starknet.check_state_available(&resolved_block_id)?;
 
     let class_hash = starknet
         .backend
@@ -24,6 +24,7 @@ pub fn get_class_hash_at(starknet: &Starknet, block_id: BlockId, contract_addres
     if !block_exists {
         return Err(StarknetRpcApiError::BlockNotFound);
     }
+    starknet.check_state_available(&block_id)?;
 
     let class_hash = starknet
         .backend
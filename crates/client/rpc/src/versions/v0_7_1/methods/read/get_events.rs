@@ -4,6 +4,7 @@ use starknet_core::types::{BlockId, BlockTag, EmittedEvent, EventFilterWithPage,
 use crate::constants::{MAX_EVENTS_CHUNK_SIZE, MAX_EVENTS_KEYS};
 use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
 use crate::types::ContinuationToken;
+use crate::utils::ResultExt;
 use crate::Starknet;
 
 /// Returns all events matching the given filter.
@@ -55,7 +56,20 @@ pub async fn get_events(starknet: &Starknet, filter: EventFilterWithPage) -> Sta
     let from_block = continuation_token.block_n;
     let mut filtered_events: Vec<EmittedEvent> = Vec::new();
 
-    for current_block in from_block..=to_block {
+    // Use the event index to skip the blocks that cannot contain matching events. The block of the continuation token
+    // is always visited, as the token needs to be checked against it.
+    let candidate_blocks = starknet
+        .backend
+        .get_events_candidate_blocks(from_address, &keys, from_block, to_block.min(latest_block))
+        .or_internal_server_error("Error getting event candidate blocks")?;
+    let pending_block = (to_block > latest_block).then_some(latest_block + 1);
+    let blocks = std::iter::once(from_block)
+        .chain(candidate_blocks.into_iter().chain(pending_block).filter(|block_n| *block_n > from_block));
+
+    for current_block in blocks {
+        if current_block > to_block {
+            break;
+        }
         let (_pending, block) = if current_block <= latest_block {
             (false, starknet.get_block(&BlockId::Number(current_block))?)
         } else {
@@ -94,7 +108,7 @@ pub async fn get_events(starknet: &Starknet, filter: EventFilterWithPage) -> Sta
 }
 
 #[inline]
-fn event_match_filter(event: &EmittedEvent, address: Option<Felt>, keys: &[Vec<Felt>]) -> bool {
+pub(crate) fn event_match_filter(event: &EmittedEvent, address: Option<Felt>, keys: &[Vec<Felt>]) -> bool {
     let match_from_address = address.map_or(true, |addr| addr == event.from_address);
     let match_keys = keys
         .iter()
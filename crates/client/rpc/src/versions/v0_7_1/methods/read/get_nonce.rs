@@ -29,6 +29,7 @@ pub fn get_nonce(starknet: &Starknet, block_id: BlockId, contract_address: Felt)
     if !block_exists {
         return Err(StarknetRpcApiError::BlockNotFound);
     }
+    starknet.check_state_available(&block_id)?;
 
     if !starknet
         .backend
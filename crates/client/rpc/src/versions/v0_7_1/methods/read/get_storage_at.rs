@@ -44,6 +44,7 @@ pub fn get_storage_at(
     if !block_exists {
         return Err(StarknetRpcApiError::BlockNotFound);
     }
+    starknet.check_state_available(&block_id)?;
 
     // Check if contract exists
     starknet
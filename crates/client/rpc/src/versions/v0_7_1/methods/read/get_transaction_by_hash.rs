@@ -20,8 +20,8 @@ use crate::Starknet;
 /// Returns information about the requested transaction, including its status, sender,
 /// recipient, and other transaction details. The information is encapsulated in a `Transaction`
 /// type, which is a combination of the `TXN` schema and additional properties, such as the
-/// `transaction_hash`. In case the specified transaction hash is not found, returns a
-/// `StarknetRpcApiError` with `TXN_HASH_NOT_FOUND`.
+/// `transaction_hash`. Transactions which were submitted to this node but are not in a block yet are returned too. In
+/// case the specified transaction hash is not found, returns a `StarknetRpcApiError` with `TXN_HASH_NOT_FOUND`.
 ///
 /// ### Errors
 ///
@@ -32,11 +32,19 @@ use crate::Starknet;
 /// - `TOO_MANY_KEYS_IN_FILTER` if there are too many keys in the filter, which may exceed the
 ///   system's capacity.
 pub fn get_transaction_by_hash(starknet: &Starknet, transaction_hash: Felt) -> StarknetRpcResult<Transaction> {
-    let (block, tx_index) = starknet
+    let Some((block, tx_index)) = starknet
         .backend
         .find_tx_hash_block(&transaction_hash)
         .or_internal_server_error("Error getting block from tx hash")?
-        .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
+    else {
+        // Transactions waiting in the mempool are known too.
+        return starknet
+            .add_transaction_provider
+            .get_submitted_transaction(&transaction_hash)
+            .and_then(|submitted| submitted.transaction)
+            .map(|transaction| transaction.to_core(transaction_hash))
+            .ok_or(StarknetRpcApiError::TxnHashNotFound);
+    };
     let transaction = block
         .inner
         .transactions
@@ -48,8 +56,9 @@ pub fn get_transaction_by_hash(starknet: &Starknet, transaction_hash: Felt) -> S
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters};
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters, TestTransactionProvider};
     use rstest::rstest;
+    use std::sync::Arc;
 
     #[rstest]
     fn test_get_transaction_by_hash(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
@@ -75,4 +84,25 @@ mod tests {
         let does_not_exist = Felt::from_hex_unchecked("0x7128638126378");
         assert_eq!(get_transaction_by_hash(&rpc, does_not_exist), Err(StarknetRpcApiError::TxnHashNotFound));
     }
+
+    #[rstest]
+    fn test_get_transaction_by_hash_submitted(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (_, mut rpc) = sample_chain_for_block_getters;
+        let provider = Arc::new(TestTransactionProvider::default());
+        rpc.add_transaction_provider = provider.clone();
+
+        let tx = mp_transactions::Transaction::L1Handler(mp_transactions::L1HandlerTransaction {
+            nonce: 3,
+            contract_address: Felt::from(0x4343),
+            entry_point_selector: Felt::from(0x1212),
+            calldata: vec![Felt::ONE],
+            ..Default::default()
+        });
+        let (received, rejected) = (Felt::from(0x1001), Felt::from(0x1002));
+        provider.submitted.set_received(received, Some(tx.clone()));
+        provider.submitted.set_rejected(rejected, "Out of gas".into());
+
+        assert_eq!(get_transaction_by_hash(&rpc, received).unwrap(), tx.to_core(received));
+        assert_eq!(get_transaction_by_hash(&rpc, rejected), Err(StarknetRpcApiError::TxnHashNotFound));
+    }
 }
@@ -1,3 +1,4 @@
+use mc_mempool::SubmittedTransactionStatus;
 use mp_block::MadaraMaybePendingBlockInfo;
 use mp_receipt::ExecutionResult;
 use starknet_core::types::{Felt, TransactionExecutionStatus, TransactionStatus};
@@ -25,13 +26,23 @@ use crate::Starknet;
 ///   - `execution_status`: The execution status of the transaction, providing details on the
 ///     execution outcome if the transaction has been processed.
 pub fn get_transaction_status(starknet: &Starknet, transaction_hash: Felt) -> StarknetRpcResult<TransactionStatus> {
-    let (block, tx_index) = starknet
+    let Some((block, tx_index)) = starknet
         .backend
         .find_tx_hash_block(&transaction_hash)
         .or_internal_server_error("Error find tx hash block info from db")?
-        .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
-
-    // Note: we don't support TransactionStatus::Received and TransactionStatus::Rejected yet.
+    else {
+        // The transaction may not have made it into a block yet.
+        let submitted = starknet
+            .add_transaction_provider
+            .get_submitted_transaction(&transaction_hash)
+            .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
+        return Ok(match submitted.status {
+            SubmittedTransactionStatus::Received => TransactionStatus::Received,
+            SubmittedTransactionStatus::Rejected { .. } | SubmittedTransactionStatus::Dropped { .. } => {
+                TransactionStatus::Rejected
+            }
+        });
+    };
 
     let tx_receipt = block.inner.receipts.get(tx_index.0 as usize).ok_or(StarknetRpcApiError::TxnHashNotFound)?;
 
@@ -55,8 +66,9 @@ pub fn get_transaction_status(starknet: &Starknet, transaction_hash: Felt) -> St
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters};
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters, TestTransactionProvider};
     use rstest::rstest;
+    use std::sync::Arc;
 
     #[rstest]
     fn test_get_transaction_status(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
@@ -94,4 +106,26 @@ mod tests {
         let does_not_exist = Felt::from_hex_unchecked("0x7128638126378");
         assert_eq!(get_transaction_status(&rpc, does_not_exist), Err(StarknetRpcApiError::TxnHashNotFound));
     }
+
+    #[rstest]
+    fn test_get_transaction_status_submitted(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (SampleChainForBlockGetters { tx_hashes, .. }, mut rpc) = sample_chain_for_block_getters;
+        let provider = Arc::new(TestTransactionProvider::default());
+        rpc.add_transaction_provider = provider.clone();
+
+        let (received, rejected, dropped) = (Felt::from(0x1001), Felt::from(0x1002), Felt::from(0x1003));
+        provider.submitted.set_received(received, None);
+        provider.submitted.set_rejected(rejected, "Out of gas".into());
+        provider.submitted.set_dropped(dropped, "Replaced".into());
+        // Transactions in a block are found in the db, whatever their last known status.
+        provider.submitted.set_received(tx_hashes[0], None);
+
+        assert_eq!(get_transaction_status(&rpc, received).unwrap(), TransactionStatus::Received);
+        assert_eq!(get_transaction_status(&rpc, rejected).unwrap(), TransactionStatus::Rejected);
+        assert_eq!(get_transaction_status(&rpc, dropped).unwrap(), TransactionStatus::Rejected);
+        assert_eq!(
+            get_transaction_status(&rpc, tx_hashes[0]).unwrap(),
+            TransactionStatus::AcceptedOnL1(TransactionExecutionStatus::Succeeded)
+        );
+    }
 }
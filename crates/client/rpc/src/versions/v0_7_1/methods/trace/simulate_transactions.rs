@@ -14,12 +14,14 @@ pub async fn simulate_transactions(
     simulation_flags: Vec<SimulationFlag>,
 ) -> StarknetRpcResult<Vec<SimulatedTransaction>> {
     let block_info = starknet.get_block_info(&block_id)?;
+    starknet.check_state_available(&block_id)?;
     let starknet_version = *block_info.protocol_version();
 
     if starknet_version < FALLBACK_TO_SEQUENCER_WHEN_VERSION_BELOW {
         return Err(StarknetRpcApiError::UnsupportedTxnVersion);
     }
-    let exec_context = ExecutionContext::new_in_block(Arc::clone(&starknet.backend), &block_info)?;
+    let exec_context =
+        ExecutionContext::new_in_block(Arc::clone(&starknet.backend), Arc::clone(&starknet.class_cache), &block_info)?;
 
     let charge_fee = !simulation_flags.contains(&SimulationFlag::SkipFeeCharge);
     let validate = !simulation_flags.contains(&SimulationFlag::SkipValidate);
@@ -14,12 +14,14 @@ pub async fn trace_block_transactions(
     block_id: BlockId,
 ) -> StarknetRpcResult<Vec<TransactionTraceWithHash>> {
     let block = starknet.get_block(&block_id)?;
+    starknet.check_state_available(&block_id)?;
 
     if block.info.protocol_version() < &FALLBACK_TO_SEQUENCER_WHEN_VERSION_BELOW {
         return Err(StarknetRpcApiError::UnsupportedTxnVersion);
     }
 
-    let exec_context = ExecutionContext::new_in_block(Arc::clone(&starknet.backend), &block.info)?;
+    let exec_context =
+        ExecutionContext::new_in_block(Arc::clone(&starknet.backend), Arc::clone(&starknet.class_cache), &block.info)?;
 
     let transactions: Vec<_> = block
         .inner
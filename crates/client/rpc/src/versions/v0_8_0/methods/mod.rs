@@ -0,0 +1,4 @@
+pub mod read;
+pub mod trace;
+pub mod write;
+pub mod ws;
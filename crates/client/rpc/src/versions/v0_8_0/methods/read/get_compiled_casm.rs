@@ -0,0 +1,95 @@
+use mp_block::{BlockId, BlockTag};
+use mp_class::ClassInfo;
+use starknet_types_core::felt::Felt;
+
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::utils::{OptionExt, ResultExt};
+use crate::Starknet;
+
+/// Get the CASM code resulting from the compilation of a Sierra class.
+///
+/// ### Arguments
+///
+/// * `class_hash` - The hash of the Sierra class.
+///
+/// ### Returns
+///
+/// The compiled class, as produced by the Sierra to CASM compiler.
+///
+/// ### Errors
+///
+/// * `CLASS_HASH_NOT_FOUND` - If the class does not exist, or is a legacy (Cairo 0) class which has no Sierra
+///   representation to compile.
+pub fn get_compiled_casm(starknet: &Starknet, class_hash: Felt) -> StarknetRpcResult<serde_json::Value> {
+    let block_id = BlockId::Tag(BlockTag::Pending);
+    let class_info = starknet
+        .backend
+        .get_class_info(&block_id, &class_hash)
+        .or_internal_server_error("Error getting contract class info")?
+        .ok_or(StarknetRpcApiError::ClassHashNotFound)?;
+
+    let ClassInfo::Sierra(info) = class_info else {
+        return Err(StarknetRpcApiError::ClassHashNotFound);
+    };
+
+    let compiled = starknet
+        .backend
+        .get_sierra_compiled(&block_id, &info.compiled_class_hash)
+        .or_internal_server_error("Error getting compiled class")?
+        .ok_or_internal_server_error("Compiled class not found for a declared Sierra class")?;
+
+    serde_json::from_str(compiled.as_ref()).or_internal_server_error("Error parsing compiled class")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::rpc_test_setup;
+    use mc_db::MadaraBackend;
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+    use mp_class::{
+        CompiledSierra, ConvertedClass, EntryPointsByType, FlattenedSierraClass, SierraClassInfo, SierraConvertedClass,
+    };
+    use mp_state_update::StateDiff;
+    use rstest::rstest;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[rstest]
+    fn test_get_compiled_casm(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+
+        let class_hash = Felt::from_hex_unchecked("0x1234");
+        let casm =
+            json!({ "prime": "0x800000000000011000000000000000000000000000000000000000000000001", "bytecode": [] });
+        let compiled: CompiledSierra = serde_json::from_value(json!(casm.to_string())).unwrap();
+        let sierra_class = FlattenedSierraClass {
+            sierra_program: vec![],
+            contract_class_version: "0.1.0".into(),
+            entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+            abi: String::new(),
+        };
+        let converted_class = ConvertedClass::Sierra(SierraConvertedClass {
+            class_hash,
+            info: SierraClassInfo {
+                contract_class: Arc::new(sierra_class),
+                compiled_class_hash: Felt::from_hex_unchecked("0x5678"),
+            },
+            compiled: Arc::new(compiled),
+        });
+
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraBlockInfo::new(Header::default(), vec![], Felt::ONE).into(),
+                    inner: MadaraBlockInner::new(vec![], vec![]),
+                },
+                StateDiff::default(),
+                vec![converted_class],
+            )
+            .unwrap();
+
+        assert_eq!(get_compiled_casm(&rpc, class_hash).unwrap(), casm);
+        assert_eq!(get_compiled_casm(&rpc, Felt::ONE), Err(StarknetRpcApiError::ClassHashNotFound));
+    }
+}
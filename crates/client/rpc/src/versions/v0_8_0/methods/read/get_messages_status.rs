@@ -0,0 +1,132 @@
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::utils::ResultExt;
+use crate::versions::v0_8_0::methods::read::get_transaction_status::get_transaction_status;
+use crate::versions::v0_8_0::types::{L1TxnHash, MessageStatus};
+use crate::Starknet;
+
+/// Get the status of the L2 transactions resulting from the L1 to L2 messages sent by an L1 transaction.
+///
+/// ### Arguments
+///
+/// * `transaction_hash` - The hash of the L1 transaction that sent the messages.
+///
+/// ### Returns
+///
+/// The hash and status of the `L1Handler` transaction of every message sent by the L1 transaction, in the order the
+/// messages were sent. The status is the one of [`get_transaction_status`]: transactions which are not in a block yet
+/// have their mempool status. Messages cancelled on L1 never result in an L2 transaction and are not returned, nor are
+/// the messages which were not submitted to the mempool again yet after a restart.
+///
+/// Only sequencers process the L1 to L2 messages. Full nodes have no record of them, and always answer
+/// `TXN_HASH_NOT_FOUND`.
+///
+/// ### Errors
+///
+/// * `TXN_HASH_NOT_FOUND` - If no message sent by this L1 transaction was processed by this node.
+pub fn get_messages_status(starknet: &Starknet, transaction_hash: L1TxnHash) -> StarknetRpcResult<Vec<MessageStatus>> {
+    let nonces = starknet
+        .backend
+        .get_l1_tx_messaging_nonces(&transaction_hash.0)
+        .or_internal_server_error("Error getting the messages of an L1 transaction")?;
+
+    let mut statuses = Vec::with_capacity(nonces.len());
+    for nonce in nonces {
+        let Some(message) = starknet
+            .backend
+            .get_l1_message(nonce)
+            .or_internal_server_error("Error getting the L1 handler transaction of a message")?
+        else {
+            continue;
+        };
+        let l2_tx_hash = message.tx_hash;
+
+        let status = match get_transaction_status(starknet, l2_tx_hash) {
+            Ok(status) => MessageStatus {
+                transaction_hash: l2_tx_hash,
+                finality_status: status.finality_status,
+                execution_status: status.execution_status,
+                failure_reason: status.failure_reason,
+            },
+            // The L1 handler transaction was not submitted to the mempool again yet after a restart.
+            Err(StarknetRpcApiError::TxnHashNotFound) => continue,
+            Err(err) => return Err(err),
+        };
+        statuses.push(status);
+    }
+
+    if statuses.is_empty() {
+        return Err(StarknetRpcApiError::TxnHashNotFound);
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters, TestTransactionProvider};
+    use crate::versions::v0_8_0::types::TxnStatus;
+    use mc_db::l1_db::L1MessageRecord;
+    use rstest::rstest;
+    use starknet_api::core::Nonce;
+    use starknet_core::types::TransactionExecutionStatus;
+    use starknet_types_core::felt::Felt;
+    use std::sync::Arc;
+
+    #[rstest]
+    fn test_get_messages_status(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (SampleChainForBlockGetters { tx_hashes, .. }, mut rpc) = sample_chain_for_block_getters;
+        let provider = Arc::new(TestTransactionProvider::default());
+        rpc.add_transaction_provider = provider.clone();
+        let l1_tx_hash = L1TxnHash([1; 32]);
+
+        // One message was included in a block, the next one is still in the mempool, the next one was rejected by the
+        // mempool and the last one was not submitted to the mempool again yet after a restart.
+        let (in_mempool, rejected, not_submitted) = (Felt::from(0xabcdef), Felt::from(0xabcdf0), Felt::from(0xabcdf1));
+        provider.submitted.set_received(in_mempool, None);
+        provider.submitted.set_rejected(rejected, "Invalid nonce".into());
+        let messages = [
+            (Felt::ONE, tx_hashes[0]),
+            (Felt::TWO, in_mempool),
+            (Felt::THREE, rejected),
+            (Felt::from(4), not_submitted),
+        ];
+        for (nonce, l2_tx_hash) in messages {
+            rpc.backend
+                .store_l1_message(&L1MessageRecord {
+                    nonce: Nonce(nonce),
+                    l1_block_number: 1,
+                    l1_block_hash: [1; 32],
+                    log_index: 0,
+                    l1_transaction_hash: l1_tx_hash.0,
+                    tx_hash: l2_tx_hash,
+                })
+                .unwrap();
+            rpc.backend.add_l1_tx_messaging_nonce(&l1_tx_hash.0, Nonce(nonce)).unwrap();
+        }
+
+        assert_eq!(
+            get_messages_status(&rpc, l1_tx_hash).unwrap(),
+            vec![
+                MessageStatus {
+                    transaction_hash: tx_hashes[0],
+                    finality_status: TxnStatus::AcceptedOnL1,
+                    execution_status: Some(TransactionExecutionStatus::Succeeded),
+                    failure_reason: None,
+                },
+                MessageStatus {
+                    transaction_hash: in_mempool,
+                    finality_status: TxnStatus::Received,
+                    execution_status: None,
+                    failure_reason: None,
+                },
+                MessageStatus {
+                    transaction_hash: rejected,
+                    finality_status: TxnStatus::Rejected,
+                    execution_status: None,
+                    failure_reason: Some("Invalid nonce".into()),
+                },
+            ]
+        );
+        assert_eq!(get_messages_status(&rpc, L1TxnHash([2; 32])), Err(StarknetRpcApiError::TxnHashNotFound));
+    }
+}
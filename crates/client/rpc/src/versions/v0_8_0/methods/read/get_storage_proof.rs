@@ -0,0 +1,288 @@
+use std::collections::HashSet;
+
+use bitvec::order::Msb0;
+use bitvec::slice::BitSlice;
+use bitvec::view::{AsBits, BitView};
+use bonsai_trie::id::BasicId;
+use bonsai_trie::{BonsaiStorage, ProofNode};
+use mc_db::bonsai_db::BonsaiDb;
+use mc_db::db_block_id::DbBlockId;
+use mc_db::{bonsai_identifier, MadaraStorageError};
+use starknet_core::types::BlockId;
+use starknet_types_core::felt::Felt;
+use starknet_types_core::hash::StarkHash;
+
+use crate::constants::MAX_STORAGE_PROOF_KEYS_BY_QUERY;
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::types::{
+    ContractLeafData, ContractStorageKeysItem, ContractsProof, GetStorageProofResult, GlobalRoots, MerkleNode,
+    NodeHashToNodeMappingItem,
+};
+use crate::utils::{OptionExt, ResultExt};
+use crate::Starknet;
+
+/// Get merkle proofs for classes, contracts and contract storage slots.
+///
+/// Proofs are given against the global tries at the requested block. Proofs for different keys of the same trie
+/// share most of their nodes, which are only returned once: every node is given along with its hash, and a proof is
+/// checked by walking the nodes from the root down to the requested key.
+///
+/// ### Arguments
+///
+/// * `block_id` - The hash of the requested block, or number (height) of the requested block, or a block tag. The
+///   pending block is proven against the state of the latest block.
+/// * `class_hashes` - Class hashes to prove in the class trie.
+/// * `contract_addresses` - Contract addresses to prove in the contract trie.
+/// * `contracts_storage_keys` - Storage keys to prove in the storage trie of each contract.
+///
+/// ### Returns
+///
+/// The proof nodes for every trie, the leaf data (nonce, class hash and storage root) of the requested contracts and the global
+/// roots of the requested block.
+///
+/// ### Errors
+///
+/// * `BLOCK_NOT_FOUND` - If the specified block does not exist.
+/// * `PROOF_LIMIT_EXCEEDED` - If more than [`MAX_STORAGE_PROOF_KEYS_BY_QUERY`] keys are requested in total.
+/// * `BLOCK_PRUNED` - If the tries cannot be reverted to the requested block anymore.
+pub fn get_storage_proof(
+    starknet: &Starknet,
+    block_id: BlockId,
+    class_hashes: Option<Vec<Felt>>,
+    contract_addresses: Option<Vec<Felt>>,
+    contracts_storage_keys: Option<Vec<ContractStorageKeysItem>>,
+) -> StarknetRpcResult<GetStorageProofResult> {
+    let class_hashes = class_hashes.unwrap_or_default();
+    let contract_addresses = contract_addresses.unwrap_or_default();
+    let contracts_storage_keys = contracts_storage_keys.unwrap_or_default();
+
+    let n_keys = class_hashes.len()
+        + contract_addresses.len()
+        + contracts_storage_keys.iter().map(|item| item.storage_keys.len()).sum::<usize>();
+    if n_keys > MAX_STORAGE_PROOF_KEYS_BY_QUERY {
+        return Err(StarknetRpcApiError::ProofLimitExceeded);
+    }
+
+    // The pending block has no tries of its own.
+    let block_n = match starknet.backend.resolve_block_id(&block_id).or_internal_server_error("Resolving block id")? {
+        Some(DbBlockId::BlockN(block_n)) => block_n,
+        Some(DbBlockId::Pending) => starknet.current_block_number()?,
+        None => return Err(StarknetRpcApiError::BlockNotFound),
+    };
+    let block_id = mp_block::BlockId::Number(block_n);
+    starknet.check_state_available(&block_id)?;
+
+    let block_info = starknet.get_block_info(&block_id)?;
+    let block_hash = block_info.as_nonpending().ok_or_internal_server_error("Block should not be pending")?.block_hash;
+
+    let contract_trie = open_trie(starknet.backend.contract_trie_at(block_n))?;
+    let contract_storage_trie = open_trie(starknet.backend.contract_storage_trie_at(block_n))?;
+    let class_trie = open_trie(starknet.backend.class_trie_at(block_n))?;
+
+    let classes_proof = make_trie_proof(&class_trie, bonsai_identifier::CLASS, &class_hashes)?;
+
+    let nodes = make_trie_proof(&contract_trie, bonsai_identifier::CONTRACT, &contract_addresses)?;
+    let contract_leaves_data = contract_addresses
+        .iter()
+        .map(|contract_address| {
+            let nonce = starknet
+                .backend
+                .get_contract_nonce_at(&block_id, contract_address)
+                .or_internal_server_error("Error getting contract nonce")?
+                .unwrap_or(Felt::ZERO);
+            let class_hash = starknet
+                .backend
+                .get_contract_class_hash_at(&block_id, contract_address)
+                .or_internal_server_error("Error getting contract class hash")?
+                .unwrap_or(Felt::ZERO);
+            let storage_root = contract_storage_trie
+                .root_hash(&contract_address.to_bytes_be())
+                .map_err(MadaraStorageError::from)
+                .or_internal_server_error("Error getting contract storage root")?;
+            Ok(ContractLeafData { nonce, class_hash, storage_root })
+        })
+        .collect::<StarknetRpcResult<_>>()?;
+
+    let contracts_storage_proofs = contracts_storage_keys
+        .iter()
+        .map(|ContractStorageKeysItem { contract_address, storage_keys }| {
+            make_trie_proof(&contract_storage_trie, &contract_address.to_bytes_be(), storage_keys)
+        })
+        .collect::<StarknetRpcResult<_>>()?;
+
+    let contracts_tree_root = contract_trie
+        .root_hash(bonsai_identifier::CONTRACT)
+        .map_err(MadaraStorageError::from)
+        .or_internal_server_error("Error getting contract trie root")?;
+    let classes_tree_root = class_trie
+        .root_hash(bonsai_identifier::CLASS)
+        .map_err(MadaraStorageError::from)
+        .or_internal_server_error("Error getting class trie root")?;
+
+    Ok(GetStorageProofResult {
+        classes_proof,
+        contracts_proof: ContractsProof { nodes, contract_leaves_data },
+        contracts_storage_proofs,
+        global_roots: GlobalRoots { contracts_tree_root, classes_tree_root, block_hash },
+    })
+}
+
+fn open_trie<T>(res: Result<T, MadaraStorageError>) -> StarknetRpcResult<T> {
+    match res {
+        Err(err @ MadaraStorageError::BlockPruned { .. }) => Err(err.into()),
+        res => res.or_internal_server_error("Error opening trie view"),
+    }
+}
+
+/// Proof nodes of all the `keys` in a trie, deduplicated.
+fn make_trie_proof<H: StarkHash + Send + Sync>(
+    trie: &BonsaiStorage<BasicId, BonsaiDb<'_>, H>,
+    identifier: &[u8],
+    keys: &[Felt],
+) -> StarknetRpcResult<Vec<NodeHashToNodeMappingItem>> {
+    let mut seen = HashSet::new();
+    let mut nodes = vec![];
+    for key in keys {
+        let bytes = key.to_bytes_be();
+        let proof = trie
+            .get_proof(identifier, &bytes.as_bits::<Msb0>()[5..])
+            .map_err(MadaraStorageError::from)
+            .or_internal_server_error("Error getting trie proof")?;
+
+        for node in proof {
+            let node_hash = node.hash::<H>();
+            if seen.insert(node_hash) {
+                nodes.push(NodeHashToNodeMappingItem { node_hash, node: merkle_node(node) });
+            }
+        }
+    }
+    Ok(nodes)
+}
+
+fn merkle_node(node: ProofNode) -> MerkleNode {
+    match node {
+        ProofNode::Binary { left, right } => MerkleNode::Binary { left, right },
+        ProofNode::Edge { child, path } => {
+            MerkleNode::Edge { path: path_to_felt(&path.0), length: path.0.len(), child }
+        }
+    }
+}
+
+fn path_to_felt(path: &BitSlice<u8, Msb0>) -> Felt {
+    let mut bytes = [0u8; 32];
+    bytes.view_bits_mut::<Msb0>()[256 - path.len()..].copy_from_bitslice(path);
+    Felt::from_bytes_be(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{sample_chain_for_state_updates, SampleChainForStateUpdates};
+    use bitvec::vec::BitVec;
+    use rstest::rstest;
+    use starknet_core::types::BlockTag;
+    use starknet_types_core::hash::{Pedersen, Poseidon};
+
+    fn key_bits(key: &Felt) -> BitVec<u8, Msb0> {
+        key.to_bytes_be().as_bits::<Msb0>()[5..].to_owned()
+    }
+
+    /// Walks the proof from the root down to `key`, checking the node hashes along the way, and returns the leaf.
+    fn verify_proof<H: StarkHash>(root: Felt, nodes: &[NodeHashToNodeMappingItem], key: &Felt) -> Felt {
+        let bits = key_bits(key);
+        let mut hash = root;
+        let mut depth = 0;
+        while depth < bits.len() {
+            let item = nodes.iter().find(|item| item.node_hash == hash).expect("Missing proof node");
+            match item.node {
+                MerkleNode::Binary { left, right } => {
+                    assert_eq!(H::hash(&left, &right), hash);
+                    hash = if bits[depth] { right } else { left };
+                    depth += 1;
+                }
+                MerkleNode::Edge { path, length, child } => {
+                    assert_eq!(H::hash(&child, &path) + Felt::from(length), hash);
+                    assert_eq!(path, path_to_felt(&bits[depth..depth + length]));
+                    hash = child;
+                    depth += length;
+                }
+            }
+        }
+        hash
+    }
+
+    #[rstest]
+    fn test_get_storage_proof(sample_chain_for_state_updates: (SampleChainForStateUpdates, Starknet)) {
+        let (SampleChainForStateUpdates { contracts, keys, values, class_hashes, block_hashes, .. }, rpc) =
+            sample_chain_for_state_updates;
+
+        let leaf_hashes = [Felt::from(11), Felt::from(22)];
+        {
+            let mut contract_storage_trie = rpc.backend.contract_storage_trie();
+            contract_storage_trie.insert(&contracts[0].to_bytes_be(), &key_bits(&keys[0]), &values[0]).unwrap();
+            contract_storage_trie.insert(&contracts[0].to_bytes_be(), &key_bits(&keys[1]), &values[1]).unwrap();
+            contract_storage_trie.commit(BasicId::new(2)).unwrap();
+            let mut contract_trie = rpc.backend.contract_trie();
+            contract_trie.insert(bonsai_identifier::CONTRACT, &key_bits(&contracts[0]), &leaf_hashes[0]).unwrap();
+            contract_trie.insert(bonsai_identifier::CONTRACT, &key_bits(&contracts[1]), &leaf_hashes[1]).unwrap();
+            contract_trie.commit(BasicId::new(2)).unwrap();
+            let mut class_trie = rpc.backend.class_trie();
+            class_trie.insert(bonsai_identifier::CLASS, &key_bits(&class_hashes[0]), &Felt::ONE).unwrap();
+            class_trie.commit(BasicId::new(2)).unwrap();
+        }
+
+        let res = get_storage_proof(
+            &rpc,
+            BlockId::Tag(BlockTag::Latest),
+            Some(vec![class_hashes[0]]),
+            Some(contracts[..2].to_vec()),
+            Some(vec![ContractStorageKeysItem { contract_address: contracts[0], storage_keys: keys[..2].to_vec() }]),
+        )
+        .unwrap();
+
+        assert_eq!(res.global_roots.block_hash, block_hashes[2]);
+        let roots = &res.global_roots;
+        assert_eq!(verify_proof::<Poseidon>(roots.classes_tree_root, &res.classes_proof, &class_hashes[0]), Felt::ONE);
+        for (contract, leaf_hash) in contracts.iter().zip(leaf_hashes) {
+            assert_eq!(
+                verify_proof::<Pedersen>(roots.contracts_tree_root, &res.contracts_proof.nodes, contract),
+                leaf_hash
+            );
+        }
+
+        let storage_root = rpc.backend.contract_storage_trie().root_hash(&contracts[0].to_bytes_be()).unwrap();
+        assert_eq!(res.contracts_storage_proofs.len(), 1);
+        for (key, value) in keys.iter().zip(&values).take(2) {
+            assert_eq!(verify_proof::<Pedersen>(storage_root, &res.contracts_storage_proofs[0], key), *value);
+        }
+
+        let block_id = mp_block::BlockId::Number(2);
+        let expected_leaves_data: Vec<_> = contracts[..2]
+            .iter()
+            .map(|contract| ContractLeafData {
+                nonce: rpc.backend.get_contract_nonce_at(&block_id, contract).unwrap().unwrap_or(Felt::ZERO),
+                class_hash: rpc.backend.get_contract_class_hash_at(&block_id, contract).unwrap().unwrap_or(Felt::ZERO),
+                storage_root: rpc.backend.contract_storage_trie().root_hash(&contract.to_bytes_be()).unwrap(),
+            })
+            .collect();
+        assert_eq!(res.contracts_proof.contract_leaves_data, expected_leaves_data);
+        assert_eq!(res.contracts_proof.contract_leaves_data[0].storage_root, storage_root);
+        assert_eq!(res.contracts_proof.contract_leaves_data[1].storage_root, Felt::ZERO);
+    }
+
+    #[rstest]
+    fn test_get_storage_proof_limit(sample_chain_for_state_updates: (SampleChainForStateUpdates, Starknet)) {
+        let (_, rpc) = sample_chain_for_state_updates;
+
+        let keys: Vec<Felt> = (0..MAX_STORAGE_PROOF_KEYS_BY_QUERY as u64).map(Felt::from).collect();
+        assert_eq!(
+            get_storage_proof(&rpc, BlockId::Tag(BlockTag::Latest), Some(keys.clone()), Some(vec![Felt::ONE]), None),
+            Err(StarknetRpcApiError::ProofLimitExceeded)
+        );
+        assert!(get_storage_proof(&rpc, BlockId::Tag(BlockTag::Latest), Some(keys), None, None).is_ok());
+        assert_eq!(
+            get_storage_proof(&rpc, BlockId::Number(3), None, None, None),
+            Err(StarknetRpcApiError::BlockNotFound)
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use mc_mempool::SubmittedTransactionStatus;
+use mp_block::MadaraMaybePendingBlockInfo;
+use mp_receipt::ExecutionResult;
+use starknet_core::types::TransactionExecutionStatus;
+use starknet_types_core::felt::Felt;
+
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::utils::ResultExt;
+use crate::versions::v0_8_0::types::{TxnFinalityAndExecutionStatus, TxnStatus};
+use crate::Starknet;
+
+/// Gets the Transaction Status, Including Mempool Status, Execution Details and Failure Reason
+///
+/// Same as the v0.7.1 method, except that the revert reason of reverted transactions is returned along with their
+/// status.
+///
+/// ### Arguments
+///
+/// * `transaction_hash` - The hash of the transaction for which the status is requested.
+///
+/// ### Returns
+///
+/// * `finality_status` - Whether the transaction has been accepted on L2 or L1.
+/// * `execution_status` - Whether the transaction succeeded or was reverted.
+/// * `failure_reason` - The revert reason, for reverted transactions. For rejected transactions, why they will never be
+///   included in a block.
+pub fn get_transaction_status(
+    starknet: &Starknet,
+    transaction_hash: Felt,
+) -> StarknetRpcResult<TxnFinalityAndExecutionStatus> {
+    let Some((block, tx_index)) = starknet
+        .backend
+        .find_tx_hash_block(&transaction_hash)
+        .or_internal_server_error("Error find tx hash block info from db")?
+    else {
+        let submitted = starknet
+            .add_transaction_provider
+            .get_submitted_transaction(&transaction_hash)
+            .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
+        let (finality_status, failure_reason) = match submitted.status {
+            SubmittedTransactionStatus::Received => (TxnStatus::Received, None),
+            SubmittedTransactionStatus::Rejected { reason } | SubmittedTransactionStatus::Dropped { reason } => {
+                (TxnStatus::Rejected, Some(reason))
+            }
+        };
+        return Ok(TxnFinalityAndExecutionStatus { finality_status, execution_status: None, failure_reason });
+    };
+
+    let tx_receipt = block.inner.receipts.get(tx_index.0 as usize).ok_or(StarknetRpcApiError::TxnHashNotFound)?;
+
+    let (execution_status, failure_reason) = match tx_receipt.execution_result() {
+        ExecutionResult::Reverted { reason } => (TransactionExecutionStatus::Reverted, Some(reason)),
+        ExecutionResult::Succeeded => (TransactionExecutionStatus::Succeeded, None),
+    };
+
+    let finality_status = match block.info {
+        MadaraMaybePendingBlockInfo::NotPending(block)
+            if block.header.block_number <= starknet.get_l1_last_confirmed_block()? =>
+        {
+            TxnStatus::AcceptedOnL1
+        }
+        _ => TxnStatus::AcceptedOnL2,
+    };
+
+    Ok(TxnFinalityAndExecutionStatus { finality_status, execution_status: Some(execution_status), failure_reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters, TestTransactionProvider};
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    #[rstest]
+    fn test_get_transaction_status(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (SampleChainForBlockGetters { tx_hashes, .. }, rpc) = sample_chain_for_block_getters;
+
+        assert_eq!(
+            get_transaction_status(&rpc, tx_hashes[0]).unwrap(),
+            TxnFinalityAndExecutionStatus {
+                finality_status: TxnStatus::AcceptedOnL1,
+                execution_status: Some(TransactionExecutionStatus::Succeeded),
+                failure_reason: None,
+            }
+        );
+        assert_eq!(
+            get_transaction_status(&rpc, tx_hashes[2]).unwrap(),
+            TxnFinalityAndExecutionStatus {
+                finality_status: TxnStatus::AcceptedOnL2,
+                execution_status: Some(TransactionExecutionStatus::Reverted),
+                failure_reason: Some("too bad".into()),
+            }
+        );
+
+        let does_not_exist = Felt::from_hex_unchecked("0x7128638126378");
+        assert_eq!(get_transaction_status(&rpc, does_not_exist), Err(StarknetRpcApiError::TxnHashNotFound));
+    }
+
+    #[rstest]
+    fn test_get_transaction_status_submitted(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (_, mut rpc) = sample_chain_for_block_getters;
+        let provider = Arc::new(TestTransactionProvider::default());
+        rpc.add_transaction_provider = provider.clone();
+
+        let (received, rejected) = (Felt::from(0x1001), Felt::from(0x1002));
+        provider.submitted.set_received(received, None);
+        provider.submitted.set_rejected(rejected, "Out of gas".into());
+
+        assert_eq!(
+            get_transaction_status(&rpc, received).unwrap(),
+            TxnFinalityAndExecutionStatus {
+                finality_status: TxnStatus::Received,
+                execution_status: None,
+                failure_reason: None
+            }
+        );
+        assert_eq!(
+            get_transaction_status(&rpc, rejected).unwrap(),
+            TxnFinalityAndExecutionStatus {
+                finality_status: TxnStatus::Rejected,
+                execution_status: None,
+                failure_reason: Some("Out of gas".into()),
+            }
+        );
+    }
+}
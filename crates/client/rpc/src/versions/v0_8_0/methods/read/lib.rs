@@ -0,0 +1,147 @@
+use jsonrpsee::core::{async_trait, RpcResult};
+use mp_chain_config::RpcVersion;
+use starknet_core::types::{
+    BlockHashAndNumber, BlockId, BroadcastedTransaction, ContractClass, EventFilterWithPage, EventsPage, FunctionCall,
+    MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, MaybePendingStateUpdate,
+    MsgFromL1, SimulationFlagForEstimateFee, SyncStatusType, Transaction, TransactionReceiptWithBlockInfo,
+};
+use starknet_types_core::felt::Felt;
+
+use super::get_compiled_casm::*;
+use super::get_messages_status::*;
+use super::get_storage_proof::*;
+use super::get_transaction_status::*;
+use crate::types::{ContractStorageKeysItem, GetStorageProofResult};
+use crate::versions::v0_7_1::methods::read::{
+    block_hash_and_number::*, call::*, estimate_fee::*, estimate_message_fee::*, get_block_transaction_count::*,
+    get_block_with_receipts::*, get_block_with_tx_hashes::*, get_block_with_txs::*, get_class::*, get_class_at::*,
+    get_class_hash_at::*, get_events::*, get_nonce::*, get_state_update::*, get_storage_at::*,
+    get_transaction_by_block_id_and_index::*, get_transaction_by_hash::*, get_transaction_receipt::*, syncing::*,
+};
+use crate::versions::v0_8_0::types::{
+    BroadcastedTxn, FeeEstimate, L1TxnHash, MessageStatus, TxnFinalityAndExecutionStatus,
+};
+use crate::versions::v0_8_0::StarknetReadRpcApiV0_8_0Server;
+use crate::Starknet;
+
+#[async_trait]
+impl StarknetReadRpcApiV0_8_0Server for Starknet {
+    /// Blocks, transactions, receipts and traces are still returned in their v0.7.1 shape on this endpoint, so it does
+    /// not advertise v0.8.0 yet.
+    fn spec_version(&self) -> RpcResult<String> {
+        Ok(RpcVersion::RPC_VERSION_0_7_1.to_string())
+    }
+
+    fn block_number(&self) -> RpcResult<u64> {
+        Ok(self.current_block_number()?)
+    }
+
+    fn block_hash_and_number(&self) -> RpcResult<BlockHashAndNumber> {
+        Ok(block_hash_and_number(self)?)
+    }
+
+    fn call(&self, request: FunctionCall, block_id: BlockId) -> RpcResult<Vec<Felt>> {
+        Ok(call(self, request, block_id)?)
+    }
+
+    fn chain_id(&self) -> RpcResult<Felt> {
+        Ok(self.chain_id())
+    }
+
+    fn get_block_transaction_count(&self, block_id: BlockId) -> RpcResult<u128> {
+        Ok(get_block_transaction_count(self, block_id)?)
+    }
+
+    async fn estimate_fee(
+        &self,
+        request: Vec<BroadcastedTxn<BroadcastedTransaction>>,
+        simulation_flags: Vec<SimulationFlagForEstimateFee>,
+        block_id: BlockId,
+    ) -> RpcResult<Vec<FeeEstimate>> {
+        let request = request.into_iter().map(|tx| tx.tx).collect();
+        let fee_estimates = estimate_fee(self, request, simulation_flags, block_id).await?;
+        Ok(fee_estimates.into_iter().map(Into::into).collect())
+    }
+
+    async fn estimate_message_fee(&self, message: MsgFromL1, block_id: BlockId) -> RpcResult<FeeEstimate> {
+        Ok(estimate_message_fee(self, message, block_id).await?.into())
+    }
+
+    async fn get_block_with_receipts(&self, block_id: BlockId) -> RpcResult<MaybePendingBlockWithReceipts> {
+        Ok(get_block_with_receipts(self, block_id)?)
+    }
+
+    fn get_block_with_tx_hashes(&self, block_id: BlockId) -> RpcResult<MaybePendingBlockWithTxHashes> {
+        Ok(get_block_with_tx_hashes(self, block_id)?)
+    }
+
+    fn get_block_with_txs(&self, block_id: BlockId) -> RpcResult<MaybePendingBlockWithTxs> {
+        get_block_with_txs(self, block_id)
+    }
+
+    fn get_class_at(&self, block_id: BlockId, contract_address: Felt) -> RpcResult<ContractClass> {
+        Ok(get_class_at(self, block_id, contract_address)?)
+    }
+
+    fn get_class_hash_at(&self, block_id: BlockId, contract_address: Felt) -> RpcResult<Felt> {
+        Ok(get_class_hash_at(self, block_id, contract_address)?)
+    }
+
+    fn get_class(&self, block_id: BlockId, class_hash: Felt) -> RpcResult<ContractClass> {
+        Ok(get_class(self, block_id, class_hash)?)
+    }
+
+    fn get_compiled_casm(&self, class_hash: Felt) -> RpcResult<serde_json::Value> {
+        Ok(get_compiled_casm(self, class_hash)?)
+    }
+
+    async fn get_events(&self, filter: EventFilterWithPage) -> RpcResult<EventsPage> {
+        Ok(get_events(self, filter).await?)
+    }
+
+    fn get_messages_status(&self, transaction_hash: L1TxnHash) -> RpcResult<Vec<MessageStatus>> {
+        Ok(get_messages_status(self, transaction_hash)?)
+    }
+
+    fn get_nonce(&self, block_id: BlockId, contract_address: Felt) -> RpcResult<Felt> {
+        Ok(get_nonce(self, block_id, contract_address)?)
+    }
+
+    fn get_storage_at(&self, contract_address: Felt, key: Felt, block_id: BlockId) -> RpcResult<Felt> {
+        Ok(get_storage_at(self, contract_address, key, block_id)?)
+    }
+
+    fn get_storage_proof(
+        &self,
+        block_id: BlockId,
+        class_hashes: Option<Vec<Felt>>,
+        contract_addresses: Option<Vec<Felt>>,
+        contracts_storage_keys: Option<Vec<ContractStorageKeysItem>>,
+    ) -> RpcResult<GetStorageProofResult> {
+        Ok(get_storage_proof(self, block_id, class_hashes, contract_addresses, contracts_storage_keys)?)
+    }
+
+    fn get_transaction_by_block_id_and_index(&self, block_id: BlockId, index: u64) -> RpcResult<Transaction> {
+        Ok(get_transaction_by_block_id_and_index(self, block_id, index)?)
+    }
+
+    fn get_transaction_by_hash(&self, transaction_hash: Felt) -> RpcResult<Transaction> {
+        Ok(get_transaction_by_hash(self, transaction_hash)?)
+    }
+
+    async fn get_transaction_receipt(&self, transaction_hash: Felt) -> RpcResult<TransactionReceiptWithBlockInfo> {
+        Ok(get_transaction_receipt(self, transaction_hash)?)
+    }
+
+    fn get_transaction_status(&self, transaction_hash: Felt) -> RpcResult<TxnFinalityAndExecutionStatus> {
+        Ok(get_transaction_status(self, transaction_hash)?)
+    }
+
+    async fn syncing(&self) -> RpcResult<SyncStatusType> {
+        Ok(syncing(self).await?)
+    }
+
+    fn get_state_update(&self, block_id: BlockId) -> RpcResult<MaybePendingStateUpdate> {
+        Ok(get_state_update(self, block_id)?)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod get_compiled_casm;
+pub mod get_messages_status;
+pub mod get_storage_proof;
+pub mod get_transaction_status;
+pub mod lib;
@@ -0,0 +1,31 @@
+use jsonrpsee::core::{async_trait, RpcResult};
+use starknet_core::types::{
+    BlockId, BroadcastedTransaction, Felt, SimulatedTransaction, SimulationFlag, TransactionTraceWithHash,
+};
+
+use crate::versions::v0_7_1::methods::trace::simulate_transactions::simulate_transactions;
+use crate::versions::v0_7_1::methods::trace::trace_block_transactions::trace_block_transactions;
+use crate::versions::v0_7_1::methods::trace::trace_transaction::trace_transaction;
+use crate::versions::v0_8_0::types::BroadcastedTxn;
+use crate::{versions::v0_8_0::StarknetTraceRpcApiV0_8_0Server, Starknet};
+
+#[async_trait]
+impl StarknetTraceRpcApiV0_8_0Server for Starknet {
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTxn<BroadcastedTransaction>>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        let transactions = transactions.into_iter().map(|tx| tx.tx).collect();
+        Ok(simulate_transactions(self, block_id, transactions, simulation_flags).await?)
+    }
+
+    async fn trace_block_transactions(&self, block_id: BlockId) -> RpcResult<Vec<TransactionTraceWithHash>> {
+        Ok(trace_block_transactions(self, block_id).await?)
+    }
+
+    async fn trace_transaction(&self, transaction_hash: Felt) -> RpcResult<TransactionTraceWithHash> {
+        Ok(trace_transaction(self, transaction_hash).await?)
+    }
+}
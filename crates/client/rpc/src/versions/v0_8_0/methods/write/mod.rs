@@ -0,0 +1,60 @@
+use jsonrpsee::core::{async_trait, RpcResult};
+use starknet_core::types::{
+    BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction,
+    DeclareTransactionResult, DeployAccountTransactionResult, InvokeTransactionResult,
+};
+
+use crate::versions::v0_8_0::types::BroadcastedTxn;
+use crate::{versions::v0_8_0::StarknetWriteRpcApiV0_8_0Server, Starknet};
+
+#[async_trait]
+impl StarknetWriteRpcApiV0_8_0Server for Starknet {
+    /// Submit a new declare transaction to be added to the chain
+    ///
+    /// # Arguments
+    ///
+    /// * `declare_transaction` - the declare transaction to be added to the chain
+    ///
+    /// # Returns
+    ///
+    /// * `declare_transaction_result` - the result of the declare transaction
+    async fn add_declare_transaction(
+        &self,
+        declare_transaction: BroadcastedTxn<BroadcastedDeclareTransaction>,
+    ) -> RpcResult<DeclareTransactionResult> {
+        Ok(self.add_transaction_provider.add_declare_transaction(declare_transaction.tx).await?)
+    }
+
+    /// Add an Deploy Account Transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `deploy account transaction` - <https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/#deploy_account_transaction>
+    ///
+    /// # Returns
+    ///
+    /// * `transaction_hash` - transaction hash corresponding to the invocation
+    /// * `contract_address` - address of the deployed contract account
+    async fn add_deploy_account_transaction(
+        &self,
+        deploy_account_transaction: BroadcastedTxn<BroadcastedDeployAccountTransaction>,
+    ) -> RpcResult<DeployAccountTransactionResult> {
+        Ok(self.add_transaction_provider.add_deploy_account_transaction(deploy_account_transaction.tx).await?)
+    }
+
+    /// Add an Invoke Transaction to invoke a contract function
+    ///
+    /// # Arguments
+    ///
+    /// * `invoke tx` - <https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/#invoke_transaction>
+    ///
+    /// # Returns
+    ///
+    /// * `transaction_hash` - transaction hash corresponding to the invocation
+    async fn add_invoke_transaction(
+        &self,
+        invoke_transaction: BroadcastedTxn<BroadcastedInvokeTransaction>,
+    ) -> RpcResult<InvokeTransactionResult> {
+        Ok(self.add_transaction_provider.add_invoke_transaction(invoke_transaction.tx).await?)
+    }
+}
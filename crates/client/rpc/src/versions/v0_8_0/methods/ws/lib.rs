@@ -0,0 +1,86 @@
+use jsonrpsee::core::{async_trait, RpcResult, SubscriptionResult};
+use jsonrpsee::PendingSubscriptionSink;
+use starknet_core::types::BlockId;
+use starknet_types_core::felt::Felt;
+
+use super::subscribe_events::*;
+use super::subscribe_new_heads::*;
+use super::subscribe_pending_transactions::*;
+use super::subscribe_transaction_status::*;
+use crate::errors::StarknetRpcApiError;
+use crate::versions::v0_8_0::StarknetWsRpcApiV0_8_0Server;
+use crate::Starknet;
+
+#[async_trait]
+impl StarknetWsRpcApiV0_8_0Server for Starknet {
+    async fn subscribe_new_heads(
+        &self,
+        pending: PendingSubscriptionSink,
+        block_id: Option<BlockId>,
+    ) -> SubscriptionResult {
+        subscribe_new_heads(self, pending, block_id).await
+    }
+
+    async fn subscribe_events(
+        &self,
+        pending: PendingSubscriptionSink,
+        from_address: Option<Felt>,
+        keys: Option<Vec<Vec<Felt>>>,
+        block_id: Option<BlockId>,
+    ) -> SubscriptionResult {
+        subscribe_events(self, pending, from_address, keys, block_id).await
+    }
+
+    async fn subscribe_transaction_status(
+        &self,
+        pending: PendingSubscriptionSink,
+        transaction_hash: Felt,
+    ) -> SubscriptionResult {
+        subscribe_transaction_status(self, pending, transaction_hash).await
+    }
+
+    async fn subscribe_pending_transactions(
+        &self,
+        pending: PendingSubscriptionSink,
+        transaction_details: Option<bool>,
+        sender_address: Option<Vec<Felt>>,
+    ) -> SubscriptionResult {
+        subscribe_pending_transactions(self, pending, transaction_details, sender_address).await
+    }
+
+    fn unsubscribe(&self, subscription_id: String) -> RpcResult<bool> {
+        if self.ws_subscriptions.unsubscribe(&subscription_id) {
+            Ok(true)
+        } else {
+            Err(StarknetRpcApiError::InvalidSubscriptionId.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters};
+    use jsonrpsee::core::server::MethodsError;
+    use jsonrpsee::types::SubscriptionId;
+    use rstest::rstest;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_unsubscribe(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (_, rpc) = sample_chain_for_block_getters;
+        let module = StarknetWsRpcApiV0_8_0Server::into_rpc(rpc);
+
+        let sub = module.subscribe_unbounded("starknet_V0_8_0_subscribeNewHeads", [None::<BlockId>]).await.unwrap();
+        let id = match sub.subscription_id() {
+            SubscriptionId::Num(n) => n.to_string(),
+            SubscriptionId::Str(s) => s.to_string(),
+        };
+
+        assert!(module.call::<_, bool>("starknet_V0_8_0_unsubscribe", [&id]).await.unwrap());
+        let Err(MethodsError::JsonRpc(err)) = module.call::<_, bool>("starknet_V0_8_0_unsubscribe", [&id]).await else {
+            panic!("Expected the subscription to be closed")
+        };
+        assert_eq!(err, StarknetRpcApiError::InvalidSubscriptionId.into());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod lib;
+pub mod subscribe_events;
+pub mod subscribe_new_heads;
+pub mod subscribe_pending_transactions;
+pub mod subscribe_transaction_status;
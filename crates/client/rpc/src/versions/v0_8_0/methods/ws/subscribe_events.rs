@@ -0,0 +1,184 @@
+use jsonrpsee::core::SubscriptionResult;
+use jsonrpsee::PendingSubscriptionSink;
+use starknet_core::types::{BlockId, EmittedEvent};
+use starknet_types_core::felt::Felt;
+
+use crate::constants::MAX_EVENTS_KEYS;
+use crate::errors::StarknetRpcApiError;
+use crate::utils::subscription::{send_notification, starting_block_n, ChainFollower, ChainUpdate, REORG_NOTIFICATION};
+use crate::versions::v0_7_1::methods::read::get_events::event_match_filter;
+use crate::versions::v0_8_0::types::ReorgData;
+use crate::Starknet;
+
+const EVENTS_NOTIFICATION: &str = "starknet_subscriptionEvents";
+
+/// Subscribe to the events emitted in new blocks.
+///
+/// ### Arguments
+///
+/// * `from_address` - Only send the events emitted by this contract.
+/// * `keys` - Only send the events matching these keys, with the same semantics as `starknet_getEvents`.
+/// * `block_id` - The block from which events are sent, at most 1024 blocks in the past. Defaults to the latest
+///   block.
+///
+/// ### Notifications
+///
+/// * `starknet_subscriptionEvents` - Every matching event, in the order in which they were emitted.
+/// * `starknet_subscriptionReorg` - Blocks whose events were sent and are no longer part of the chain.
+///
+/// ### Errors
+///
+/// * `TOO_MANY_KEYS_IN_FILTER` - If more than 100 keys are given.
+/// * `TOO_MANY_BLOCKS_BACK` - If `block_id` is more than 1024 blocks in the past.
+/// * `BLOCK_NOT_FOUND` - If `block_id` does not exist.
+pub async fn subscribe_events(
+    starknet: &Starknet,
+    pending: PendingSubscriptionSink,
+    from_address: Option<Felt>,
+    keys: Option<Vec<Vec<Felt>>>,
+    block_id: Option<BlockId>,
+) -> SubscriptionResult {
+    let keys = keys.unwrap_or_default();
+    if keys.len() > MAX_EVENTS_KEYS {
+        pending.reject(StarknetRpcApiError::TooManyKeysInFilter).await;
+        return Ok(());
+    }
+    let mut follower = match starting_block_n(starknet, block_id) {
+        Ok(block_n) => ChainFollower::new(starknet, block_n),
+        Err(err) => {
+            pending.reject(err).await;
+            return Ok(());
+        }
+    };
+
+    let sink = pending.accept().await?;
+    let mut subscription = starknet.ws_subscriptions.register(&sink.subscription_id());
+
+    loop {
+        let update = tokio::select! {
+            _ = sink.closed() => break,
+            _ = subscription.unsubscribed() => break,
+            update = follower.next(starknet) => update?,
+        };
+
+        match update {
+            Some(ChainUpdate::Block(block)) => {
+                let block_hash = Some(block.info.block_hash);
+                let block_number = Some(block.info.header.block_number);
+                let events = block.inner.receipts.iter().flat_map(|receipt| {
+                    receipt.events().iter().map(move |event| EmittedEvent {
+                        from_address: event.from_address,
+                        keys: event.keys.clone(),
+                        data: event.data.clone(),
+                        block_hash,
+                        block_number,
+                        transaction_hash: receipt.transaction_hash(),
+                    })
+                });
+                for event in events.filter(|event| event_match_filter(event, from_address, &keys)) {
+                    send_notification(&sink, EVENTS_NOTIFICATION, &event).await?;
+                }
+            }
+            Some(ChainUpdate::Reorg(reverted)) => {
+                send_notification(&sink, REORG_NOTIFICATION, &ReorgData::from(&reverted)).await?
+            }
+            None => break,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::rpc_test_setup;
+    use crate::versions::v0_8_0::StarknetWsRpcApiV0_8_0Server;
+    use mc_db::MadaraBackend;
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+    use mp_receipt::{Event, InvokeTransactionReceipt, TransactionReceipt};
+    use mp_state_update::StateDiff;
+    use mp_transactions::{InvokeTransaction, InvokeTransactionV0, Transaction};
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    fn store_block_with_events(backend: &MadaraBackend, block_number: u64, events: Vec<Event>) {
+        let tx_hash = Felt::from(block_number);
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraBlockInfo::new(
+                        Header { block_number, ..Default::default() },
+                        vec![tx_hash],
+                        Felt::from(block_number + 100),
+                    )
+                    .into(),
+                    inner: MadaraBlockInner::new(
+                        vec![Transaction::Invoke(InvokeTransaction::V0(InvokeTransactionV0::default()))],
+                        vec![TransactionReceipt::Invoke(InvokeTransactionReceipt {
+                            transaction_hash: tx_hash,
+                            events,
+                            ..Default::default()
+                        })],
+                    ),
+                },
+                StateDiff::default(),
+                vec![],
+            )
+            .unwrap();
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_subscribe_events(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let event = |from_address: u64, key: u64| Event {
+            from_address: Felt::from(from_address),
+            keys: vec![Felt::from(key)],
+            data: vec![Felt::from(from_address + key)],
+        };
+        store_block_with_events(&backend, 0, vec![event(1, 10), event(2, 10), event(1, 11)]);
+
+        let module = StarknetWsRpcApiV0_8_0Server::into_rpc(rpc);
+        let mut sub = module
+            .subscribe_unbounded(
+                "starknet_V0_8_0_subscribeEvents",
+                (Some(Felt::from(1)), Some(vec![vec![Felt::from(10), Felt::from(12)]]), Some(BlockId::Number(0))),
+            )
+            .await
+            .unwrap();
+
+        let (emitted, _) = sub.next::<EmittedEvent>().await.unwrap().unwrap();
+        assert_eq!(
+            emitted,
+            EmittedEvent {
+                from_address: Felt::from(1),
+                keys: vec![Felt::from(10)],
+                data: vec![Felt::from(11)],
+                block_hash: Some(Felt::from(100)),
+                block_number: Some(0),
+                transaction_hash: Felt::from(0),
+            }
+        );
+
+        store_block_with_events(&backend, 1, vec![event(2, 12), event(1, 12)]);
+        let (emitted, _) = sub.next::<EmittedEvent>().await.unwrap().unwrap();
+        assert_eq!(
+            (emitted.from_address, emitted.keys, emitted.block_number),
+            (Felt::from(1), vec![Felt::from(12)], Some(1))
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_subscribe_events_too_many_keys(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (_, rpc) = rpc_test_setup;
+        let module = StarknetWsRpcApiV0_8_0Server::into_rpc(rpc);
+
+        let keys = vec![vec![Felt::ONE]; MAX_EVENTS_KEYS + 1];
+        assert!(module
+            .subscribe_unbounded("starknet_V0_8_0_subscribeEvents", (None::<Felt>, Some(keys)))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use jsonrpsee::core::SubscriptionResult;
+use jsonrpsee::PendingSubscriptionSink;
+use starknet_core::types::BlockId;
+
+use crate::utils::subscription::{send_notification, starting_block_n, ChainFollower, ChainUpdate, REORG_NOTIFICATION};
+use crate::versions::v0_8_0::types::{BlockHeader, ReorgData};
+use crate::Starknet;
+
+const NEW_HEADS_NOTIFICATION: &str = "starknet_subscriptionNewHeads";
+
+/// Subscribe to the headers of new blocks.
+///
+/// ### Arguments
+///
+/// * `block_id` - The block from which headers are sent, at most 1024 blocks in the past. Defaults to the latest
+///   block.
+///
+/// ### Notifications
+///
+/// * `starknet_subscriptionNewHeads` - The header of every block, in order.
+/// * `starknet_subscriptionReorg` - Blocks which were sent and are no longer part of the chain. The headers of the
+///   blocks replacing them follow.
+///
+/// ### Errors
+///
+/// * `TOO_MANY_BLOCKS_BACK` - If `block_id` is more than 1024 blocks in the past.
+/// * `BLOCK_NOT_FOUND` - If `block_id` does not exist.
+pub async fn subscribe_new_heads(
+    starknet: &Starknet,
+    pending: PendingSubscriptionSink,
+    block_id: Option<BlockId>,
+) -> SubscriptionResult {
+    let mut follower = match starting_block_n(starknet, block_id) {
+        Ok(block_n) => ChainFollower::new(starknet, block_n),
+        Err(err) => {
+            pending.reject(err).await;
+            return Ok(());
+        }
+    };
+
+    let sink = pending.accept().await?;
+    let mut subscription = starknet.ws_subscriptions.register(&sink.subscription_id());
+
+    loop {
+        let update = tokio::select! {
+            _ = sink.closed() => break,
+            _ = subscription.unsubscribed() => break,
+            update = follower.next(starknet) => update?,
+        };
+
+        match update {
+            Some(ChainUpdate::Block(block)) => {
+                send_notification(&sink, NEW_HEADS_NOTIFICATION, &BlockHeader::from(&block.info)).await?
+            }
+            Some(ChainUpdate::Reorg(reverted)) => {
+                send_notification(&sink, REORG_NOTIFICATION, &ReorgData::from(&reverted)).await?
+            }
+            None => break,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::StarknetRpcApiError;
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters};
+    use crate::versions::v0_8_0::StarknetWsRpcApiV0_8_0Server;
+    use jsonrpsee::core::server::MethodsError;
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+    use mp_state_update::StateDiff;
+    use rstest::rstest;
+    use starknet_types_core::felt::Felt;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_subscribe_new_heads(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (SampleChainForBlockGetters { block_hashes, .. }, rpc) = sample_chain_for_block_getters;
+        let backend = rpc.clone_backend();
+        let module = StarknetWsRpcApiV0_8_0Server::into_rpc(rpc);
+
+        let mut sub =
+            module.subscribe_unbounded("starknet_V0_8_0_subscribeNewHeads", [BlockId::Number(1)]).await.unwrap();
+
+        // Past blocks are sent first.
+        let (header, _) = sub.next::<BlockHeader>().await.unwrap().unwrap();
+        assert_eq!((header.block_number, header.block_hash), (1, block_hashes[1]));
+        let (header, _) = sub.next::<BlockHeader>().await.unwrap().unwrap();
+        assert_eq!((header.block_number, header.block_hash), (2, block_hashes[2]));
+
+        // Then the new blocks, as they are imported.
+        let block_hash = Felt::from_hex_unchecked("0x1234");
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraBlockInfo::new(
+                        Header { parent_block_hash: block_hashes[2], block_number: 3, ..Default::default() },
+                        vec![],
+                        block_hash,
+                    )
+                    .into(),
+                    inner: MadaraBlockInner::new(vec![], vec![]),
+                },
+                StateDiff::default(),
+                vec![],
+            )
+            .unwrap();
+        let (header, _) = sub.next::<BlockHeader>().await.unwrap().unwrap();
+        assert_eq!((header.block_number, header.block_hash, header.parent_hash), (3, block_hash, block_hashes[2]));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_subscribe_new_heads_errors(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (_, rpc) = sample_chain_for_block_getters;
+        let module = StarknetWsRpcApiV0_8_0Server::into_rpc(rpc);
+
+        let Err(MethodsError::JsonRpc(err)) =
+            module.subscribe_unbounded("starknet_V0_8_0_subscribeNewHeads", [BlockId::Number(10)]).await
+        else {
+            panic!("Expected the subscription to be rejected")
+        };
+        assert_eq!(err, StarknetRpcApiError::BlockNotFound.into());
+    }
+}
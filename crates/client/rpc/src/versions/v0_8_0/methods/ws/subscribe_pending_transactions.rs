@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+
+use jsonrpsee::core::SubscriptionResult;
+use jsonrpsee::PendingSubscriptionSink;
+use mc_db::db_block_id::DbBlockId;
+use mc_db::notifications::BlockNotification;
+use mp_block::MadaraBlockInner;
+use mp_transactions::Transaction;
+use starknet_types_core::felt::Felt;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::constants::MAX_ADDRESSES_IN_FILTER;
+use crate::errors::StarknetRpcApiError;
+use crate::utils::subscription::send_notification;
+use crate::utils::ResultExt;
+use crate::versions::v0_8_0::types::PendingTxn;
+use crate::Starknet;
+
+const PENDING_TRANSACTIONS_NOTIFICATION: &str = "starknet_subscriptionPendingTransactions";
+
+/// Subscribe to the transactions added to the pending block.
+///
+/// Transactions which were already in the pending block when subscribing are not sent.
+///
+/// ### Arguments
+///
+/// * `transaction_details` - Send the full transactions instead of their hashes.
+/// * `sender_address` - Only send the transactions of these senders. Deploy and deploy account transactions have no
+///   sender, and are never sent when this filter is set.
+///
+/// ### Notifications
+///
+/// * `starknet_subscriptionPendingTransactions` - Every new pending transaction.
+///
+/// ### Errors
+///
+/// * `TOO_MANY_ADDRESSES_IN_FILTER` - If more than 1024 sender addresses are given.
+pub async fn subscribe_pending_transactions(
+    starknet: &Starknet,
+    pending: PendingSubscriptionSink,
+    transaction_details: Option<bool>,
+    sender_address: Option<Vec<Felt>>,
+) -> SubscriptionResult {
+    let transaction_details = transaction_details.unwrap_or_default();
+    let sender_address: HashSet<Felt> = sender_address.unwrap_or_default().into_iter().collect();
+    if sender_address.len() > MAX_ADDRESSES_IN_FILTER {
+        pending.reject(StarknetRpcApiError::TooManyAddressesInFilter).await;
+        return Ok(());
+    }
+
+    let mut notifications = starknet.backend.subscribe_block_notifications();
+    // Hashes of the transactions of the current pending block which were already seen.
+    let mut seen: HashSet<Felt> = match starknet
+        .backend
+        .get_block_info(&DbBlockId::Pending)
+        .or_internal_server_error("Error getting pending block info")
+    {
+        Ok(info) => info.map(|info| info.tx_hashes().iter().copied().collect()).unwrap_or_default(),
+        Err(err) => {
+            pending.reject(err).await;
+            return Ok(());
+        }
+    };
+
+    let sink = pending.accept().await?;
+    let mut subscription = starknet.ws_subscriptions.register(&sink.subscription_id());
+
+    loop {
+        let notification = tokio::select! {
+            _ = sink.closed() => break,
+            _ = subscription.unsubscribed() => break,
+            notification = notifications.recv() => notification,
+        };
+
+        // Transactions can be included in a closed block without ever being seen in a pending block, they are sent
+        // before moving on to the next pending block.
+        let (block_txs, tx_hashes, is_closed) = match &notification {
+            Ok(BlockNotification::Pending(block)) => (&block.inner, &block.info.tx_hashes, false),
+            Ok(BlockNotification::Closed(block)) => (&block.inner, &block.info.tx_hashes, true),
+            Ok(BlockNotification::Reverted(_) | BlockNotification::L1Confirmed(_)) => continue,
+            Err(RecvError::Lagged(skipped)) => {
+                log::debug!("Pending transactions subscription lagged behind by {skipped} notifications");
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        for (tx, &tx_hash) in new_transactions(block_txs, tx_hashes, &mut seen) {
+            let matches_sender =
+                sender_address.is_empty() || sender(tx).is_some_and(|sender| sender_address.contains(sender));
+            if !matches_sender {
+                continue;
+            }
+            let message = if transaction_details {
+                PendingTxn::Full(tx.clone().to_core(tx_hash))
+            } else {
+                PendingTxn::Hash(tx_hash)
+            };
+            send_notification(&sink, PENDING_TRANSACTIONS_NOTIFICATION, &message).await?;
+        }
+
+        if is_closed {
+            seen.clear();
+        }
+    }
+
+    Ok(())
+}
+
+fn new_transactions<'a>(
+    block: &'a MadaraBlockInner,
+    tx_hashes: &'a [Felt],
+    seen: &'a mut HashSet<Felt>,
+) -> impl Iterator<Item = (&'a Transaction, &'a Felt)> + 'a {
+    block.transactions.iter().zip(tx_hashes).filter(move |(_, tx_hash)| seen.insert(**tx_hash))
+}
+
+fn sender(tx: &Transaction) -> Option<&Felt> {
+    match tx {
+        Transaction::Invoke(tx) => Some(tx.sender_address()),
+        Transaction::Declare(tx) => Some(tx.sender_address()),
+        Transaction::L1Handler(tx) => Some(&tx.contract_address),
+        Transaction::Deploy(_) | Transaction::DeployAccount(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters};
+    use crate::versions::v0_8_0::StarknetWsRpcApiV0_8_0Server;
+    use mp_block::header::PendingHeader;
+    use mp_block::{MadaraMaybePendingBlock, MadaraPendingBlockInfo};
+    use mp_receipt::{InvokeTransactionReceipt, TransactionReceipt};
+    use mp_state_update::StateDiff;
+    use mp_transactions::{InvokeTransaction, InvokeTransactionV1};
+    use rstest::rstest;
+
+    fn invoke(sender_address: Felt) -> Transaction {
+        Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 { sender_address, ..Default::default() }))
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_subscribe_pending_transactions(
+        sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet),
+    ) {
+        let (SampleChainForBlockGetters { tx_hashes, block_hashes, .. }, rpc) = sample_chain_for_block_getters;
+        let backend = rpc.clone_backend();
+        let module = StarknetWsRpcApiV0_8_0Server::into_rpc(rpc);
+
+        let mut sub = module
+            .subscribe_unbounded(
+                "starknet_V0_8_0_subscribePendingTransactions",
+                (false, vec![Felt::from_hex_unchecked("0x434b3"), Felt::ONE]),
+            )
+            .await
+            .unwrap();
+
+        // The transaction which was already pending is not sent, nor the transaction from another sender.
+        let pending_txs = [
+            (tx_hashes[3], backend.get_block(&DbBlockId::Pending).unwrap().unwrap().inner.transactions[0].clone()),
+            (Felt::from(10), invoke(Felt::TWO)),
+            (Felt::from(11), invoke(Felt::ONE)),
+        ];
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraPendingBlockInfo::new(
+                        PendingHeader { parent_block_hash: block_hashes[2], ..Default::default() },
+                        pending_txs.iter().map(|(hash, _)| *hash).collect(),
+                    )
+                    .into(),
+                    inner: MadaraBlockInner::new(
+                        pending_txs.iter().map(|(_, tx)| tx.clone()).collect(),
+                        pending_txs
+                            .iter()
+                            .map(|(hash, _)| {
+                                TransactionReceipt::Invoke(InvokeTransactionReceipt {
+                                    transaction_hash: *hash,
+                                    ..Default::default()
+                                })
+                            })
+                            .collect(),
+                    ),
+                },
+                StateDiff::default(),
+                vec![],
+            )
+            .unwrap();
+
+        let (tx, _) = sub.next::<PendingTxn>().await.unwrap().unwrap();
+        assert_eq!(tx, PendingTxn::Hash(Felt::from(11)));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_subscribe_pending_transactions_too_many_addresses(
+        sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet),
+    ) {
+        let (_, rpc) = sample_chain_for_block_getters;
+        let module = StarknetWsRpcApiV0_8_0Server::into_rpc(rpc);
+
+        let addresses: Vec<Felt> = (0..=MAX_ADDRESSES_IN_FILTER as u64).map(Felt::from).collect();
+        assert!(module
+            .subscribe_unbounded("starknet_V0_8_0_subscribePendingTransactions", (false, addresses))
+            .await
+            .is_err());
+    }
+}
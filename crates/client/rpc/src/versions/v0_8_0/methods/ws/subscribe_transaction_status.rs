@@ -0,0 +1,175 @@
+use jsonrpsee::core::SubscriptionResult;
+use jsonrpsee::PendingSubscriptionSink;
+use starknet_types_core::felt::Felt;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::errors::StarknetRpcApiError;
+use crate::utils::subscription::send_notification;
+use crate::versions::v0_8_0::methods::read::get_transaction_status::get_transaction_status;
+use crate::versions::v0_8_0::types::{NewTransactionStatus, TxnStatus};
+use crate::Starknet;
+
+const TRANSACTION_STATUS_NOTIFICATION: &str = "starknet_subscriptionTransactionStatus";
+
+/// Subscribe to the status updates of a transaction.
+///
+/// The status is checked again every time the chain is updated, when the last block confirmed on L1 changes, and when
+/// the status of the transaction changes in the mempool. Nothing is sent while the transaction is unknown to the node,
+/// and the subscription ends once the transaction is accepted on L1 or rejected.
+///
+/// ### Arguments
+///
+/// * `transaction_hash` - The hash of the transaction to follow.
+///
+/// ### Notifications
+///
+/// * `starknet_subscriptionTransactionStatus` - The current status of the transaction, and then every new status.
+pub async fn subscribe_transaction_status(
+    starknet: &Starknet,
+    pending: PendingSubscriptionSink,
+    transaction_hash: Felt,
+) -> SubscriptionResult {
+    let mut notifications = starknet.backend.subscribe_block_notifications();
+    let mut submitted = starknet.add_transaction_provider.subscribe_submitted_transactions();
+    let sink = pending.accept().await?;
+    let mut subscription = starknet.ws_subscriptions.register(&sink.subscription_id());
+
+    let mut last_status = None;
+    'subscription: loop {
+        let status = match get_transaction_status(starknet, transaction_hash) {
+            Ok(status) => Some(status),
+            Err(StarknetRpcApiError::TxnHashNotFound) => None,
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(status) = status.filter(|status| last_status.as_ref() != Some(status)) {
+            send_notification(
+                &sink,
+                TRANSACTION_STATUS_NOTIFICATION,
+                &NewTransactionStatus { transaction_hash, status: status.clone() },
+            )
+            .await?;
+            if matches!(status.finality_status, TxnStatus::AcceptedOnL1 | TxnStatus::Rejected) {
+                break;
+            }
+            last_status = Some(status);
+        }
+
+        // Wait for an update which can change the status. Lagging is fine, the status is read again from the db.
+        loop {
+            tokio::select! {
+                _ = sink.closed() => break 'subscription,
+                _ = subscription.unsubscribed() => break 'subscription,
+                notification = notifications.recv() => match notification {
+                    Ok(_) | Err(RecvError::Lagged(_)) => break,
+                    Err(RecvError::Closed) => break 'subscription,
+                },
+                update = submitted.recv() => match update {
+                    Ok(hash) if hash != transaction_hash => {}
+                    Ok(_) | Err(RecvError::Lagged(_)) => break,
+                    Err(RecvError::Closed) => break 'subscription,
+                },
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters, TestTransactionProvider};
+    use crate::versions::v0_8_0::types::TxnFinalityAndExecutionStatus;
+    use crate::versions::v0_8_0::StarknetWsRpcApiV0_8_0Server;
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+    use mp_receipt::{InvokeTransactionReceipt, TransactionReceipt};
+    use mp_state_update::StateDiff;
+    use mp_transactions::{InvokeTransaction, InvokeTransactionV0, Transaction};
+    use rstest::rstest;
+    use starknet_core::types::TransactionExecutionStatus;
+    use std::sync::Arc;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_subscribe_transaction_status(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (SampleChainForBlockGetters { block_hashes, .. }, rpc) = sample_chain_for_block_getters;
+        let backend = rpc.clone_backend();
+        let module = StarknetWsRpcApiV0_8_0Server::into_rpc(rpc);
+
+        let tx_hash = Felt::from_hex_unchecked("0xabcdef");
+        let mut sub =
+            module.subscribe_unbounded("starknet_V0_8_0_subscribeTransactionStatus", [tx_hash]).await.unwrap();
+
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraBlockInfo::new(
+                        Header { parent_block_hash: block_hashes[2], block_number: 3, ..Default::default() },
+                        vec![tx_hash],
+                        Felt::from_hex_unchecked("0x1234"),
+                    )
+                    .into(),
+                    inner: MadaraBlockInner::new(
+                        vec![Transaction::Invoke(InvokeTransaction::V0(InvokeTransactionV0::default()))],
+                        vec![TransactionReceipt::Invoke(InvokeTransactionReceipt {
+                            transaction_hash: tx_hash,
+                            ..Default::default()
+                        })],
+                    ),
+                },
+                StateDiff::default(),
+                vec![],
+            )
+            .unwrap();
+
+        let (status, _) = sub.next::<NewTransactionStatus>().await.unwrap().unwrap();
+        assert_eq!(
+            status,
+            NewTransactionStatus {
+                transaction_hash: tx_hash,
+                status: TxnFinalityAndExecutionStatus {
+                    finality_status: TxnStatus::AcceptedOnL2,
+                    execution_status: Some(TransactionExecutionStatus::Succeeded),
+                    failure_reason: None,
+                },
+            }
+        );
+
+        backend.write_last_confirmed_block(3).unwrap();
+        let (status, _) = sub.next::<NewTransactionStatus>().await.unwrap().unwrap();
+        assert_eq!(status.status.finality_status, TxnStatus::AcceptedOnL1);
+        assert_eq!(status.status.execution_status, Some(TransactionExecutionStatus::Succeeded));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_subscribe_transaction_status_rejected(
+        sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet),
+    ) {
+        let (_, mut rpc) = sample_chain_for_block_getters;
+        let provider = Arc::new(TestTransactionProvider::default());
+        rpc.add_transaction_provider = provider.clone();
+        let module = StarknetWsRpcApiV0_8_0Server::into_rpc(rpc);
+
+        let tx_hash = Felt::from_hex_unchecked("0xabcdef");
+        provider.submitted.set_received(tx_hash, None);
+        let mut sub =
+            module.subscribe_unbounded("starknet_V0_8_0_subscribeTransactionStatus", [tx_hash]).await.unwrap();
+
+        let (status, _) = sub.next::<NewTransactionStatus>().await.unwrap().unwrap();
+        assert_eq!(status.status.finality_status, TxnStatus::Received);
+
+        // Updates of other transactions are ignored.
+        provider.submitted.set_rejected(Felt::ONE, "Out of gas".into());
+        provider.submitted.set_rejected(tx_hash, "Out of gas".into());
+        let (status, _) = sub.next::<NewTransactionStatus>().await.unwrap().unwrap();
+        assert_eq!(
+            status.status,
+            TxnFinalityAndExecutionStatus {
+                finality_status: TxnStatus::Rejected,
+                execution_status: None,
+                failure_reason: Some("Out of gas".into()),
+            }
+        );
+    }
+}
@@ -0,0 +1,5 @@
+pub mod api;
+pub mod methods;
+pub mod types;
+
+pub use api::*;
@@ -0,0 +1,286 @@
+//! Types that changed shape in the v0.8 spec. Everything else is shared with v0.7.1 and comes from `starknet_core`.
+
+use std::fmt;
+
+use mc_db::notifications::RevertedBlocks;
+use mp_block::MadaraBlockInfo;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use starknet_core::types::{
+    L1DataAvailabilityMode, PriceUnit, ResourceBounds, ResourcePrice, Transaction, TransactionExecutionStatus,
+};
+use starknet_types_core::felt::Felt;
+
+/// Resource bounds of v3 transactions. The v0.8 spec adds a bound on L1 data gas.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceBoundsMapping {
+    pub l1_gas: ResourceBounds,
+    pub l1_data_gas: ResourceBounds,
+    pub l2_gas: ResourceBounds,
+}
+
+/// A transaction broadcasted with the v0.8 spec.
+///
+/// The execution engine does not know about the `l1_data_gas` resource bound of v3 transactions yet: data gas is still
+/// charged against the `l1_gas` bound. Rather than silently dropping a bound set by the user, transactions with a
+/// non-zero `l1_data_gas` bound are rejected. The rest of the transaction is parsed as a v0.7.1 transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BroadcastedTxn<T> {
+    pub tx: T,
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for BroadcastedTxn<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        if let Some(bounds) = value.get_mut("resource_bounds") {
+            let mapping: ResourceBoundsMapping = serde_json::from_value(bounds.clone()).map_err(D::Error::custom)?;
+            if mapping.l1_data_gas != (ResourceBounds { max_amount: 0, max_price_per_unit: 0 }) {
+                return Err(D::Error::custom(
+                    "Non-zero l1_data_gas resource bounds are not supported yet: data gas is charged against the \
+                     l1_gas bound",
+                ));
+            }
+            if let Some(bounds) = bounds.as_object_mut() {
+                bounds.remove("l1_data_gas");
+            }
+        }
+        let tx = serde_json::from_value(value).map_err(D::Error::custom)?;
+        Ok(Self { tx })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    pub l1_gas_consumed: Felt,
+    pub l1_gas_price: Felt,
+    pub l2_gas_consumed: Felt,
+    pub l2_gas_price: Felt,
+    pub l1_data_gas_consumed: Felt,
+    pub l1_data_gas_price: Felt,
+    pub overall_fee: Felt,
+    pub unit: PriceUnit,
+}
+
+impl From<starknet_core::types::FeeEstimate> for FeeEstimate {
+    /// L2 gas is not metered by the execution engine yet, all of the execution is charged as L1 gas.
+    fn from(fee: starknet_core::types::FeeEstimate) -> Self {
+        Self {
+            l1_gas_consumed: fee.gas_consumed,
+            l1_gas_price: fee.gas_price,
+            l2_gas_consumed: Felt::ZERO,
+            l2_gas_price: Felt::ZERO,
+            l1_data_gas_consumed: fee.data_gas_consumed,
+            l1_data_gas_price: fee.data_gas_price,
+            overall_fee: fee.overall_fee,
+            unit: fee.unit,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TxnStatus {
+    Received,
+    Rejected,
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
+/// Transaction status, with the revert reason of reverted transactions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxnFinalityAndExecutionStatus {
+    pub finality_status: TxnStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+/// Status of the L2 transaction resulting from an L1 to L2 message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageStatus {
+    pub transaction_hash: Felt,
+    pub finality_status: TxnStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+/// Hash of an L1 transaction. It does not always fit in a [`Felt`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct L1TxnHash(pub [u8; 32]);
+
+impl fmt::Display for L1TxnHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+
+impl Serialize for L1TxnHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for L1TxnHash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let s = String::deserialize(deserializer)?;
+        let digits = s.strip_prefix("0x").ok_or_else(|| D::Error::custom("Expected a 0x-prefixed hex string"))?;
+        if digits.is_empty() || digits.len() > 64 || !digits.is_ascii() {
+            return Err(D::Error::custom("Expected at most 32 bytes of hex"));
+        }
+
+        // Left-pad to 64 digits, then parse the digits two by two.
+        let padded = format!("{digits:0>64}");
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&padded[2 * i..2 * i + 2], 16).map_err(D::Error::custom)?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+/// Header of a block, as sent to the `subscribeNewHeads` subscribers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHeader {
+    pub block_hash: Felt,
+    pub parent_hash: Felt,
+    pub block_number: u64,
+    pub new_root: Felt,
+    pub timestamp: u64,
+    pub sequencer_address: Felt,
+    pub l1_gas_price: ResourcePrice,
+    pub l2_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+    pub l1_da_mode: L1DataAvailabilityMode,
+    pub starknet_version: String,
+}
+
+impl From<&MadaraBlockInfo> for BlockHeader {
+    /// L2 gas is not priced yet, see [`FeeEstimate`].
+    fn from(block: &MadaraBlockInfo) -> Self {
+        Self {
+            block_hash: block.block_hash,
+            parent_hash: block.header.parent_block_hash,
+            block_number: block.header.block_number,
+            new_root: block.header.global_state_root,
+            timestamp: block.header.block_timestamp,
+            sequencer_address: block.header.sequencer_address,
+            l1_gas_price: block.header.l1_gas_price.l1_gas_price(),
+            l2_gas_price: ResourcePrice { price_in_fri: Felt::ZERO, price_in_wei: Felt::ZERO },
+            l1_data_gas_price: block.header.l1_gas_price.l1_data_gas_price(),
+            l1_da_mode: block.header.l1_da_mode.into(),
+            starknet_version: block.header.protocol_version.to_string(),
+        }
+    }
+}
+
+/// Sent to the `subscribeTransactionStatus` subscribers each time the status of the transaction changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewTransactionStatus {
+    pub transaction_hash: Felt,
+    pub status: TxnFinalityAndExecutionStatus,
+}
+
+/// Sent to the `subscribePendingTransactions` subscribers, depending on whether they asked for the transaction details.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PendingTxn {
+    Hash(Felt),
+    Full(Transaction),
+}
+
+/// Blocks removed from the chain by a reorg, sent to the `subscribeNewHeads` and `subscribeEvents` subscribers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReorgData {
+    pub starting_block_hash: Felt,
+    pub starting_block_number: u64,
+    pub ending_block_hash: Felt,
+    pub ending_block_number: u64,
+}
+
+impl From<&RevertedBlocks> for ReorgData {
+    fn from(reverted: &RevertedBlocks) -> Self {
+        Self {
+            starting_block_hash: reverted.starting_block_hash,
+            starting_block_number: reverted.starting_block_number,
+            ending_block_hash: reverted.ending_block_hash,
+            ending_block_number: reverted.ending_block_number,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use starknet_core::types::BroadcastedInvokeTransaction;
+
+    #[test]
+    fn test_l1_txn_hash_serde() {
+        let hash: L1TxnHash = serde_json::from_value(json!("0x1a2b")).unwrap();
+        let mut expected = [0u8; 32];
+        expected[30..].copy_from_slice(&[0x1a, 0x2b]);
+        assert_eq!(hash, L1TxnHash(expected));
+        assert_eq!(serde_json::to_value(hash).unwrap(), json!(format!("0x{}1a2b", "0".repeat(60))));
+
+        assert!(serde_json::from_value::<L1TxnHash>(json!("1a2b")).is_err());
+        assert!(serde_json::from_value::<L1TxnHash>(json!(format!("0x{}", "1".repeat(65)))).is_err());
+        assert!(serde_json::from_value::<L1TxnHash>(json!("0xzz")).is_err());
+    }
+
+    #[test]
+    fn test_broadcasted_txn_l1_data_gas() {
+        let bounds = |amount: &str| json!({ "max_amount": amount, "max_price_per_unit": amount });
+        let tx = |l1_data_gas: &str| {
+            json!({
+                "type": "INVOKE",
+                "version": "0x3",
+                "sender_address": "0x1",
+                "calldata": [],
+                "signature": [],
+                "nonce": "0x0",
+                "resource_bounds": {
+                    "l1_gas": bounds("0x10"),
+                    "l1_data_gas": bounds(l1_data_gas),
+                    "l2_gas": bounds("0x0"),
+                },
+                "tip": "0x0",
+                "paymaster_data": [],
+                "account_deployment_data": [],
+                "nonce_data_availability_mode": "L1",
+                "fee_data_availability_mode": "L1",
+            })
+        };
+
+        let parsed: BroadcastedTxn<BroadcastedInvokeTransaction> = serde_json::from_value(tx("0x0")).unwrap();
+        let BroadcastedInvokeTransaction::V3(parsed) = parsed.tx else { panic!("Expected a v3 transaction") };
+        assert_eq!(parsed.resource_bounds.l1_gas, ResourceBounds { max_amount: 0x10, max_price_per_unit: 0x10 });
+
+        let err = serde_json::from_value::<BroadcastedTxn<BroadcastedInvokeTransaction>>(tx("0x20")).unwrap_err();
+        assert!(err.to_string().contains("l1_data_gas"), "{err}");
+    }
+
+    #[test]
+    fn test_fee_estimate_from_v0_7() {
+        let fee = starknet_core::types::FeeEstimate {
+            gas_consumed: Felt::from(10),
+            gas_price: Felt::from(2),
+            data_gas_consumed: Felt::from(3),
+            data_gas_price: Felt::from(4),
+            overall_fee: Felt::from(32),
+            unit: PriceUnit::Fri,
+        };
+        let fee = FeeEstimate::from(fee);
+        assert_eq!(fee.l1_gas_consumed, Felt::from(10));
+        assert_eq!(fee.l2_gas_consumed, Felt::ZERO);
+        assert_eq!(fee.l1_data_gas_price, Felt::from(4));
+        assert_eq!(fee.overall_fee, Felt::from(32));
+    }
+}
@@ -12,10 +12,11 @@ use mp_class::class_update::{ClassUpdate, LegacyClassUpdate, SierraClassUpdate};
 use mp_class::MISSED_CLASS_HASHES;
 use mp_convert::{felt_to_u128, ToFelt};
 use mp_receipt::TransactionReceipt;
+use mp_state_update::StateDiff;
 use mp_transactions::{Transaction, MAIN_CHAIN_ID};
 use mp_utils::{stopwatch_end, wait_or_graceful_shutdown, PerfStopwatch};
 use starknet_api::core::ChainId;
-use starknet_core::types::{ContractClass, MaybePendingBlockWithReceipts, StarknetError};
+use starknet_core::types::{ContractClass, MaybePendingBlockWithReceipts, ResourcePrice, StarknetError};
 use starknet_providers::{Provider, ProviderError, SequencerGatewayProvider};
 use starknet_types_core::felt::Felt;
 use url::Url;
@@ -24,8 +25,8 @@ use crate::l2::L2SyncError;
 
 use super::FetchError;
 
-const MAX_RETRY: u32 = 15;
-const BASE_DELAY: Duration = Duration::from_secs(1);
+pub(crate) const MAX_RETRY: u32 = 15;
+pub(crate) const BASE_DELAY: Duration = Duration::from_secs(1);
 
 /// The configuration of the worker responsible for fetching new blocks and state updates from the
 /// feeder.
@@ -47,6 +48,8 @@ pub struct FetchConfig {
     pub sync_polling_interval: Option<Duration>,
     /// Number of blocks to sync (for testing purposes).
     pub n_blocks_to_sync: Option<u64>,
+    /// Fetch the blocks from this Starknet JSON-RPC endpoint instead of the feeder gateway.
+    pub json_rpc: Option<Url>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -116,7 +119,8 @@ pub async fn fetch_pending_block_and_updates(
         MaybePendingBlockWithReceipts::PendingBlock(block) => block,
     };
 
-    let class_update = fetch_class_updates(backend, &state_update, block_id, provider).await?;
+    let state_diff = state_update.state_diff.into();
+    let class_update = fetch_class_updates(backend, &state_diff, block_id, provider).await?;
 
     stopwatch_end!(sw, "fetching {:?}: {:?}", block_id);
 
@@ -129,17 +133,10 @@ pub async fn fetch_pending_block_and_updates(
             sequencer_address: block.sequencer_address,
             block_timestamp: block.timestamp,
             protocol_version: block.starknet_version.parse().context("Invalid starknet version")?,
-            l1_gas_price: GasPrices {
-                eth_l1_gas_price: felt_to_u128(&block.l1_gas_price.price_in_wei).context("Converting prices")?,
-                strk_l1_gas_price: felt_to_u128(&block.l1_gas_price.price_in_fri).context("Converting prices")?,
-                eth_l1_data_gas_price: felt_to_u128(&block.l1_data_gas_price.price_in_wei)
-                    .context("Converting prices")?,
-                strk_l1_data_gas_price: felt_to_u128(&block.l1_data_gas_price.price_in_fri)
-                    .context("Converting prices")?,
-            },
+            l1_gas_price: gas_prices(&block.l1_gas_price, &block.l1_data_gas_price)?,
             l1_da_mode: block.l1_da_mode.into(),
         },
-        state_diff: state_update.state_diff.into(),
+        state_diff,
         transactions,
         receipts,
         declared_classes: class_update.into_iter().map(Into::into).collect(),
@@ -157,7 +154,8 @@ pub async fn fetch_block_and_updates(
     let (state_update, block) =
         retry(|| fetch_state_update_with_block(provider, block_id), MAX_RETRY, BASE_DELAY).await?;
 
-    let class_update = fetch_class_updates(backend, &state_update, block_id, provider).await?;
+    let state_diff = state_update.state_diff.into();
+    let class_update = fetch_class_updates(backend, &state_diff, block_id, provider).await?;
 
     stopwatch_end!(sw, "fetching {:?}: {:?}", block_id);
 
@@ -209,7 +207,7 @@ pub async fn fetch_block_and_updates(
             },
             l1_da_mode: block.l1_da_mode.into(),
         },
-        state_diff: state_update.state_diff.into(),
+        state_diff,
         receipts: block
             .transaction_receipts
             .into_iter()
@@ -227,7 +225,7 @@ pub async fn fetch_block_and_updates(
     })
 }
 
-async fn retry<F, Fut, T>(mut f: F, max_retries: u32, base_delay: Duration) -> Result<T, ProviderError>
+pub(crate) async fn retry<F, Fut, T>(mut f: F, max_retries: u32, base_delay: Duration) -> Result<T, ProviderError>
 where
     F: FnMut() -> Fut,
     Fut: std::future::Future<Output = Result<T, ProviderError>>,
@@ -273,12 +271,22 @@ async fn fetch_state_update_with_block(
     Ok((state_update_with_block.state_update, state_update_with_block.block))
 }
 
-/// retrieves class updates from Starknet sequencer
-async fn fetch_class_updates(
+/// Converts the gas prices of a block in the JSON-RPC format.
+pub(crate) fn gas_prices(l1_gas_price: &ResourcePrice, l1_data_gas_price: &ResourcePrice) -> anyhow::Result<GasPrices> {
+    Ok(GasPrices {
+        eth_l1_gas_price: felt_to_u128(&l1_gas_price.price_in_wei).context("Converting prices")?,
+        strk_l1_gas_price: felt_to_u128(&l1_gas_price.price_in_fri).context("Converting prices")?,
+        eth_l1_data_gas_price: felt_to_u128(&l1_data_gas_price.price_in_wei).context("Converting prices")?,
+        strk_l1_data_gas_price: felt_to_u128(&l1_data_gas_price.price_in_fri).context("Converting prices")?,
+    })
+}
+
+/// retrieves the classes declared in a state diff, from the sequencer or a JSON-RPC node
+pub(crate) async fn fetch_class_updates<P: Provider + Sync>(
     backend: &MadaraBackend,
-    state_update: &starknet_providers::sequencer::models::StateUpdate,
+    state_diff: &StateDiff,
     block_id: FetchBlockId,
-    provider: &SequencerGatewayProvider,
+    provider: &P,
 ) -> anyhow::Result<Vec<ClassUpdate>> {
     let chain_id: Felt = backend.chain_config().chain_id.to_felt();
 
@@ -288,11 +296,10 @@ async fn fetch_class_updates(
         let block_number = block_id.block_n().unwrap(); // Safe to unwrap because of the condition above
         MISSED_CLASS_HASHES.get(&block_number).cloned().unwrap_or_default()
     } else {
-        state_update.state_diff.old_declared_contracts.clone()
+        state_diff.deprecated_declared_classes.clone()
     };
 
-    let sierra_classes: Vec<_> = state_update
-        .state_diff
+    let sierra_classes: Vec<_> = state_diff
         .declared_classes
         .iter()
         .map(|declared_class| (declared_class.class_hash, &declared_class.compiled_class_hash))
@@ -335,10 +342,10 @@ async fn fetch_class_updates(
 
 /// Downloads a class definition from the Starknet sequencer. Note that because
 /// of the current type hell we decided to deal with raw JSON data instead of starknet-providers `DeployedContract`.
-async fn fetch_class(
+async fn fetch_class<P: Provider + Sync>(
     class_hash: Felt,
     block_id: FetchBlockId,
-    provider: &SequencerGatewayProvider,
+    provider: &P,
 ) -> Result<(Felt, ContractClass), ProviderError> {
     let contract_class = provider.get_class(starknet_core::types::BlockId::from(block_id), class_hash).await?;
     Ok((class_hash, contract_class))
@@ -663,9 +670,10 @@ mod test_l2_fetchers {
             .await
             .expect("Failed to fetch state update with block");
 
-        let class_updates = fetch_class_updates(&ctx.backend, &state_update, FetchBlockId::BlockN(5), &ctx.provider)
-            .await
-            .expect("Failed to fetch class updates");
+        let class_updates =
+            fetch_class_updates(&ctx.backend, &state_update.state_diff.into(), FetchBlockId::BlockN(5), &*ctx.provider)
+                .await
+                .expect("Failed to fetch class updates");
 
         assert!(!class_updates.is_empty(), "Should have fetched at least one class update");
 
@@ -688,7 +696,9 @@ mod test_l2_fetchers {
         let (state_update, _block) =
             fetch_state_update_with_block(&ctx.provider, FetchBlockId::BlockN(5)).await.unwrap();
         ctx.mock_class_hash_not_found("0x40fe2533528521fc49a8ad8440f8a1780c50337a94d0fce43756015fa816a8a".to_string());
-        let result = fetch_class_updates(&ctx.backend, &state_update, FetchBlockId::BlockN(5), &ctx.provider).await;
+        let result =
+            fetch_class_updates(&ctx.backend, &state_update.state_diff.into(), FetchBlockId::BlockN(5), &*ctx.provider)
+                .await;
 
         assert!(
             matches!(
@@ -718,7 +728,7 @@ mod test_l2_fetchers {
         ctx.mock_class_hash("cairo/target/dev/madara_contracts_TestContract.contract_class.json");
 
         let (fetched_hash, _contract_class) =
-            fetch_class(class_hash, FetchBlockId::BlockN(5), &ctx.provider).await.expect("Failed to fetch class");
+            fetch_class(class_hash, FetchBlockId::BlockN(5), &*ctx.provider).await.expect("Failed to fetch class");
 
         assert_eq!(fetched_hash, class_hash, "Fetched class hash should match the requested one");
     }
@@ -736,7 +746,7 @@ mod test_l2_fetchers {
         let class_hash = felt!("0x1234");
         ctx.mock_class_hash_not_found("0x1234".to_string());
 
-        let result = fetch_class(class_hash, FetchBlockId::BlockN(5), &ctx.provider).await;
+        let result = fetch_class(class_hash, FetchBlockId::BlockN(5), &*ctx.provider).await;
 
         assert!(
             matches!(result, Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound))),
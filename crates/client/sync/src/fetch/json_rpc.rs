@@ -0,0 +1,319 @@
+//! Fetches blocks from the JSON-RPC endpoint of another node (Madara, Juno, Pathfinder...) using `getBlockWithReceipts`,
+//! `getStateUpdate` and `getClass`. This is how a node bootstraps from a sequencer which does not expose a feeder
+//! gateway, such as a private appchain.
+use anyhow::Context;
+use mc_block_import::{UnverifiedCommitments, UnverifiedFullBlock, UnverifiedHeader, UnverifiedPendingFullBlock};
+use mc_db::MadaraBackend;
+use mp_block::header::GasPrices;
+use mp_chain_config::StarknetVersion;
+use mp_convert::felt_to_u128;
+use mp_receipt::{DataAvailabilityResources, PriceUnit, TransactionReceipt};
+use mp_state_update::StateDiff;
+use mp_utils::{stopwatch_end, PerfStopwatch};
+use starknet_core::types::{BlockId, MaybePendingBlockWithReceipts, MaybePendingStateUpdate, TransactionWithReceipt};
+use starknet_providers::jsonrpc::{JsonRpcClient, JsonRpcTransport};
+use starknet_providers::{Provider, ProviderError};
+use starknet_types_core::felt::Felt;
+
+use super::fetchers::{fetch_class_updates, gas_prices, retry, FetchBlockId, BASE_DELAY, MAX_RETRY};
+use super::source::{fetch_block_hash, BlockSource};
+use super::FetchError;
+
+#[async_trait::async_trait]
+impl<T> BlockSource for JsonRpcClient<T>
+where
+    T: JsonRpcTransport + Send + Sync + 'static,
+{
+    async fn fetch_block(&self, backend: &MadaraBackend, block_n: u64) -> Result<UnverifiedFullBlock, FetchError> {
+        let block_id = FetchBlockId::BlockN(block_n);
+
+        let sw = PerfStopwatch::new();
+        let (block, state_update) =
+            retry(|| fetch_block_with_state_update(self, block_id), MAX_RETRY, BASE_DELAY).await?;
+
+        let (MaybePendingBlockWithReceipts::Block(block), MaybePendingStateUpdate::Update(state_update)) =
+            (block, state_update)
+        else {
+            return Err(anyhow::anyhow!("Fetched a closed block, got a pending one").into());
+        };
+        // The two requests are not atomic: the block may have been reorged in between.
+        if block.block_hash != state_update.block_hash {
+            return Err(anyhow::anyhow!(
+                "Block #{block_n} has hash {:#x}, but its state update is for block {:#x}",
+                block.block_hash,
+                state_update.block_hash
+            )
+            .into());
+        }
+
+        let state_diff: StateDiff = state_update.state_diff.into();
+        let class_update = fetch_class_updates(backend, &state_diff, block_id, self).await?;
+
+        stopwatch_end!(sw, "fetching {:?}: {:?}", block_id);
+
+        let l1_gas_price = gas_prices(&block.l1_gas_price, &block.l1_data_gas_price)?;
+        let (transactions, receipts) = split_transactions(block.transactions, &l1_gas_price)?;
+
+        Ok(UnverifiedFullBlock {
+            unverified_block_number: Some(block.block_number),
+            header: UnverifiedHeader {
+                parent_block_hash: Some(block.parent_hash),
+                sequencer_address: block.sequencer_address,
+                block_timestamp: block.timestamp,
+                protocol_version: protocol_version(&block.starknet_version, block.block_number)?,
+                l1_gas_price,
+                l1_da_mode: block.l1_da_mode.into(),
+            },
+            state_diff,
+            transactions,
+            receipts,
+            declared_classes: class_update.into_iter().map(Into::into).collect(),
+            // Same as the feeder gateway: the other commitments are not returned by the node.
+            commitments: UnverifiedCommitments {
+                global_state_root: Some(block.new_root),
+                block_hash: Some(block.block_hash),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// The pending block and its state update are fetched in two requests, and may not match when the pending block
+    /// changes in between. Import will fail in that case, and the next poll will fix it.
+    async fn fetch_pending_block(
+        &self,
+        backend: &MadaraBackend,
+    ) -> Result<Option<UnverifiedPendingFullBlock>, FetchError> {
+        let block_id = FetchBlockId::Pending;
+
+        let sw = PerfStopwatch::new();
+        let (block, state_update) =
+            retry(|| fetch_block_with_state_update(self, block_id), MAX_RETRY, BASE_DELAY).await?;
+
+        let (MaybePendingBlockWithReceipts::PendingBlock(block), MaybePendingStateUpdate::PendingUpdate(state_update)) =
+            (block, state_update)
+        else {
+            // Some nodes return the latest block when there is no pending block.
+            log::debug!("Fetched the pending block, got a closed one");
+            return Ok(None);
+        };
+
+        let state_diff: StateDiff = state_update.state_diff.into();
+        let class_update = fetch_class_updates(backend, &state_diff, block_id, self).await?;
+
+        stopwatch_end!(sw, "fetching {:?}: {:?}", block_id);
+
+        let l1_gas_price = gas_prices(&block.l1_gas_price, &block.l1_data_gas_price)?;
+        let (transactions, receipts) = split_transactions(block.transactions, &l1_gas_price)?;
+
+        Ok(Some(UnverifiedPendingFullBlock {
+            header: UnverifiedHeader {
+                parent_block_hash: Some(block.parent_hash),
+                sequencer_address: block.sequencer_address,
+                block_timestamp: block.timestamp,
+                protocol_version: block.starknet_version.parse().context("Invalid starknet version")?,
+                l1_gas_price,
+                l1_da_mode: block.l1_da_mode.into(),
+            },
+            state_diff,
+            transactions,
+            receipts,
+            declared_classes: class_update.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn block_hash(&self, block_n: u64) -> Result<Option<Felt>, ProviderError> {
+        fetch_block_hash(self, block_n).await
+    }
+}
+
+async fn fetch_block_with_state_update<P: Provider + Sync>(
+    provider: &P,
+    block_id: FetchBlockId,
+) -> Result<(MaybePendingBlockWithReceipts, MaybePendingStateUpdate), ProviderError> {
+    let block_id = BlockId::from(block_id);
+    futures::try_join!(provider.get_block_with_receipts(block_id), provider.get_state_update(block_id))
+}
+
+fn split_transactions(
+    transactions: Vec<TransactionWithReceipt>,
+    gas_prices: &GasPrices,
+) -> anyhow::Result<(Vec<mp_transactions::Transaction>, Vec<TransactionReceipt>)> {
+    let transactions = transactions
+        .into_iter()
+        .map(|t| Ok((t.transaction.into(), receipt_from_rpc(t.receipt.into(), gas_prices)?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(transactions.into_iter().unzip())
+}
+
+/// JSON-RPC receipts only have the gas consumed for data availability, but the receipt commitment of Starknet 0.13.2+
+/// blocks covers the total gas consumed by the transaction. It is recovered from the fee: the data gas is only
+/// consumed by data availability, and the rest of the fee pays for the L1 gas.
+fn receipt_from_rpc(mut receipt: TransactionReceipt, gas_prices: &GasPrices) -> anyhow::Result<TransactionReceipt> {
+    let (gas_price, data_gas_price) = match receipt.actual_fee().unit {
+        PriceUnit::Wei => (gas_prices.eth_l1_gas_price, gas_prices.eth_l1_data_gas_price),
+        PriceUnit::Fri => (gas_prices.strk_l1_gas_price, gas_prices.strk_l1_data_gas_price),
+    };
+    let fee = felt_to_u128(&receipt.actual_fee().amount).context("Converting the fee")?;
+    let l1_data_gas = receipt.data_availability().l1_data_gas;
+    let data_gas_fee = data_gas_price.saturating_mul(l1_data_gas.into());
+    let l1_gas = fee.saturating_sub(data_gas_fee).checked_div(gas_price).unwrap_or_default();
+    *receipt.total_gas_consumed_mut() =
+        DataAvailabilityResources { l1_gas: l1_gas.try_into().context("Converting the L1 gas")?, l1_data_gas };
+    Ok(receipt)
+}
+
+/// Nodes return an empty version for the blocks which predate versioning.
+fn protocol_version(starknet_version: &str, block_n: u64) -> anyhow::Result<StarknetVersion> {
+    if starknet_version.is_empty() {
+        return StarknetVersion::try_from_mainnet_block_number(block_n)
+            .context("Unable to determine the Starknet version");
+    }
+    starknet_version.parse().context("Invalid starknet version")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::gateway::test_setup;
+    use httpmock::{Method::POST, MockServer};
+    use mp_block::header::L1DataAvailabilityMode;
+    use mp_receipt::{ExecutionResources, FeePayment, InvokeTransactionReceipt};
+    use rstest::*;
+    use serde_json::json;
+    use starknet_core::types::StarknetError;
+    use starknet_providers::jsonrpc::HttpTransport;
+    use std::sync::Arc;
+    use url::Url;
+
+    fn mock_block(server: &MockServer, block_n: u64) {
+        server.mock(|when, then| {
+            when.method(POST)
+                .body_contains("starknet_getBlockWithReceipts")
+                .body_contains(format!("\"block_number\":{block_n}"));
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "status": "ACCEPTED_ON_L2",
+                    "block_hash": "0x5",
+                    "parent_hash": "0x4",
+                    "block_number": block_n,
+                    "new_root": "0x1234",
+                    "timestamp": 1725950824,
+                    "sequencer_address": "0x1",
+                    "l1_gas_price": { "price_in_fri": "0x10", "price_in_wei": "0x11" },
+                    "l1_data_gas_price": { "price_in_fri": "0x12", "price_in_wei": "0x13" },
+                    "l1_da_mode": "BLOB",
+                    "starknet_version": "0.13.2",
+                    "transactions": []
+                }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .body_contains("starknet_getStateUpdate")
+                .body_contains(format!("\"block_number\":{block_n}"));
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "block_hash": "0x5",
+                    "new_root": "0x1234",
+                    "old_root": "0x1233",
+                    "state_diff": {
+                        "storage_diffs": [
+                            { "address": "0x100", "storage_entries": [{ "key": "0x1", "value": "0x2" }] }
+                        ],
+                        "deprecated_declared_classes": [],
+                        "declared_classes": [],
+                        "deployed_contracts": [],
+                        "replaced_classes": [],
+                        "nonces": []
+                    }
+                }
+            }));
+        });
+    }
+
+    fn mock_block_not_found(server: &MockServer) {
+        server.mock(|when, then| {
+            when.method(POST);
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": 24, "message": "Block not found" }
+            }));
+        });
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_json_rpc_fetch_block(test_setup: Arc<MadaraBackend>) {
+        let server = MockServer::start();
+        let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(&server.base_url()).unwrap()));
+        mock_block(&server, 5);
+
+        let block = provider.fetch_block(&test_setup, 5).await.unwrap();
+        assert_eq!(block.unverified_block_number, Some(5));
+        assert_eq!(
+            block.header,
+            UnverifiedHeader {
+                parent_block_hash: Some(Felt::from(4)),
+                sequencer_address: Felt::ONE,
+                block_timestamp: 1725950824,
+                protocol_version: StarknetVersion::new(0, 13, 2, 0),
+                l1_gas_price: GasPrices {
+                    eth_l1_gas_price: 0x11,
+                    strk_l1_gas_price: 0x10,
+                    eth_l1_data_gas_price: 0x13,
+                    strk_l1_data_gas_price: 0x12,
+                },
+                l1_da_mode: L1DataAvailabilityMode::Blob,
+            }
+        );
+        assert_eq!(block.commitments.block_hash, Some(Felt::from(5)));
+        assert_eq!(block.commitments.global_state_root, Some(Felt::from(0x1234)));
+        assert_eq!(block.state_diff.storage_diffs.len(), 1);
+        assert!(block.transactions.is_empty());
+        assert!(block.declared_classes.is_empty());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_json_rpc_fetch_block_not_found(test_setup: Arc<MadaraBackend>) {
+        let server = MockServer::start();
+        let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(&server.base_url()).unwrap()));
+        mock_block_not_found(&server);
+
+        assert!(matches!(
+            provider.fetch_block(&test_setup, 6).await,
+            Err(FetchError::Provider(ProviderError::StarknetError(StarknetError::BlockNotFound)))
+        ));
+    }
+
+    #[test]
+    fn test_receipt_total_gas_consumed() {
+        let gas_prices = GasPrices {
+            eth_l1_gas_price: 0x11,
+            strk_l1_gas_price: 0x10,
+            eth_l1_data_gas_price: 0x13,
+            strk_l1_data_gas_price: 0x12,
+        };
+        let receipt = |amount: u64, unit: PriceUnit| {
+            TransactionReceipt::Invoke(InvokeTransactionReceipt {
+                actual_fee: FeePayment { amount: Felt::from(amount), unit },
+                execution_resources: ExecutionResources {
+                    data_availability: DataAvailabilityResources { l1_gas: 0, l1_data_gas: 128 },
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        };
+
+        // 1000 L1 gas and 128 L1 data gas.
+        let got = receipt_from_rpc(receipt(1000 * 0x10 + 128 * 0x12, PriceUnit::Fri), &gas_prices).unwrap();
+        assert_eq!(got.total_gas_consumed(), &DataAvailabilityResources { l1_gas: 1000, l1_data_gas: 128 });
+        let got = receipt_from_rpc(receipt(1000 * 0x11 + 128 * 0x13, PriceUnit::Wei), &gas_prices).unwrap();
+        assert_eq!(got.total_gas_consumed(), &DataAvailabilityResources { l1_gas: 1000, l1_data_gas: 128 });
+    }
+}
@@ -6,12 +6,14 @@ use mc_block_import::UnverifiedFullBlock;
 use mc_db::MadaraBackend;
 use mp_utils::{channel_wait_or_graceful_shutdown, wait_or_graceful_shutdown};
 use starknet_core::types::StarknetError;
-use starknet_providers::{ProviderError, SequencerGatewayProvider};
+use starknet_providers::ProviderError;
 use tokio::sync::{mpsc, oneshot};
 
-use crate::fetch::fetchers::fetch_block_and_updates;
+use crate::fetch::source::BlockSource;
 
 pub mod fetchers;
+pub mod json_rpc;
+pub mod source;
 
 #[allow(clippy::too_many_arguments)]
 pub async fn l2_fetch_task(
@@ -19,7 +21,7 @@ pub async fn l2_fetch_task(
     first_block: u64,
     n_blocks_to_sync: Option<u64>,
     fetch_stream_sender: mpsc::Sender<UnverifiedFullBlock>,
-    provider: Arc<SequencerGatewayProvider>,
+    provider: Arc<dyn BlockSource>,
     sync_polling_interval: Option<Duration>,
     once_caught_up_callback: oneshot::Sender<()>,
 ) -> anyhow::Result<()> {
@@ -32,7 +34,7 @@ pub async fn l2_fetch_task(
         // Fetch blocks and updates in parallel one time before looping
         let fetch_stream = (first_block..).take(n_blocks_to_sync.unwrap_or(u64::MAX) as _).map(|block_n| {
             let provider = Arc::clone(&provider);
-            async move { (block_n, fetch_block_and_updates(backend, block_n, &provider).await) }
+            async move { (block_n, provider.fetch_block(backend, block_n).await) }
         });
 
         // Have 10 fetches in parallel at once, using futures Buffered
@@ -64,7 +66,7 @@ pub async fn l2_fetch_task(
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
         while wait_or_graceful_shutdown(interval.tick()).await.is_some() {
             loop {
-                match fetch_block_and_updates(backend, next_block, &provider).await {
+                match provider.fetch_block(backend, next_block).await {
                     Err(FetchError::Provider(ProviderError::StarknetError(StarknetError::BlockNotFound))) => {
                         break;
                     }
@@ -0,0 +1,55 @@
+//! Where the blocks are fetched from: the feeder gateway of a Starknet sequencer, or the JSON-RPC endpoint of another
+//! full node (see [`super::json_rpc`]).
+use mc_block_import::{UnverifiedFullBlock, UnverifiedPendingFullBlock};
+use mc_db::MadaraBackend;
+use starknet_core::types::{BlockId, MaybePendingBlockWithTxHashes};
+use starknet_providers::{Provider, ProviderError, SequencerGatewayProvider};
+use starknet_types_core::felt::Felt;
+
+use super::fetchers::{fetch_block_and_updates, fetch_pending_block_and_updates};
+use super::FetchError;
+
+#[async_trait::async_trait]
+pub trait BlockSource: Send + Sync {
+    /// Fetches a closed block with its state diff and declared classes. Returns a
+    /// [`starknet_core::types::StarknetError::BlockNotFound`] provider error when the block does not exist yet.
+    async fn fetch_block(&self, backend: &MadaraBackend, block_n: u64) -> Result<UnverifiedFullBlock, FetchError>;
+
+    /// Fetches the pending block. Returns [`None`] when there is no pending block.
+    async fn fetch_pending_block(
+        &self,
+        backend: &MadaraBackend,
+    ) -> Result<Option<UnverifiedPendingFullBlock>, FetchError>;
+
+    /// Hash of a closed block, used to find the common ancestor with the local chain after a reorg.
+    async fn block_hash(&self, block_n: u64) -> Result<Option<Felt>, ProviderError>;
+}
+
+/// Hash of a closed block, using the `getBlockWithTxHashes` method available both on the gateway and JSON-RPC.
+pub(crate) async fn fetch_block_hash<P: Provider + Sync>(
+    provider: &P,
+    block_n: u64,
+) -> Result<Option<Felt>, ProviderError> {
+    match provider.get_block_with_tx_hashes(BlockId::Number(block_n)).await? {
+        MaybePendingBlockWithTxHashes::Block(block) => Ok(Some(block.block_hash)),
+        MaybePendingBlockWithTxHashes::PendingBlock(_) => Ok(None),
+    }
+}
+
+#[async_trait::async_trait]
+impl BlockSource for SequencerGatewayProvider {
+    async fn fetch_block(&self, backend: &MadaraBackend, block_n: u64) -> Result<UnverifiedFullBlock, FetchError> {
+        fetch_block_and_updates(backend, block_n, self).await
+    }
+
+    async fn fetch_pending_block(
+        &self,
+        backend: &MadaraBackend,
+    ) -> Result<Option<UnverifiedPendingFullBlock>, FetchError> {
+        fetch_pending_block_and_updates(backend, self).await
+    }
+
+    async fn block_hash(&self, block_n: u64) -> Result<Option<Felt>, ProviderError> {
+        fetch_block_hash(self, block_n).await
+    }
+}
@@ -1,22 +1,24 @@
 //! Contains the code required to sync data from the feeder efficiently.
-use crate::fetch::fetchers::fetch_pending_block_and_updates;
 use crate::fetch::l2_fetch_task;
+use crate::fetch::source::BlockSource;
 use crate::metrics::block_metrics::BlockMetrics;
 use crate::utils::trim_hash;
 use anyhow::Context;
 use futures::{stream, StreamExt};
 use mc_block_import::{
-    BlockImportResult, BlockImporter, BlockValidationContext, PreValidatedBlock, UnverifiedFullBlock,
+    BlockImportError, BlockImportResult, BlockImporter, BlockValidationContext, PreValidatedBlock, UnverifiedFullBlock,
 };
 use mc_db::db_metrics::DbMetrics;
 use mc_db::MadaraBackend;
 use mc_db::MadaraStorageError;
+use mc_db::MAX_REORG_DEPTH;
+use mc_exec::ContractClassCache;
 use mc_telemetry::{TelemetryHandle, VerbosityLevel};
-use mp_block::Header;
+use mp_block::{BlockId, Header};
 use mp_utils::{channel_wait_or_graceful_shutdown, stopwatch_end, wait_or_graceful_shutdown, PerfStopwatch};
 use num_traits::FromPrimitive;
 use starknet_api::core::ChainId;
-use starknet_providers::{ProviderError, SequencerGatewayProvider};
+use starknet_providers::ProviderError;
 use starknet_types_core::felt::Felt;
 use std::pin::pin;
 use std::sync::{Arc, Mutex};
@@ -36,6 +38,8 @@ pub enum L2SyncError {
     BlockImport(#[from] mc_block_import::BlockImportError),
     #[error("Unexpected class type for class hash {class_hash:#x}")]
     UnexpectedClassType { class_hash: Felt },
+    #[error("Chain reorganization detected: block #{block_n} does not build on top of the local chain")]
+    Reorg { block_n: u64 },
 }
 
 /// Contains the latest Starknet verified state on L2
@@ -52,6 +56,8 @@ async fn l2_verify_and_apply_task(
     mut updates_receiver: mpsc::Receiver<PreValidatedBlock>,
     block_import: Arc<BlockImporter>,
     validation: BlockValidationContext,
+    // Re-execute the blocks using this class cache before importing them.
+    verify_execution: Option<Arc<ContractClassCache>>,
     backup_every_n_blocks: Option<u64>,
     block_metrics: BlockMetrics,
     db_metrics: DbMetrics,
@@ -60,7 +66,23 @@ async fn l2_verify_and_apply_task(
     telemetry: TelemetryHandle,
 ) -> anyhow::Result<()> {
     while let Some(block) = channel_wait_or_graceful_shutdown(pin!(updates_receiver.recv())).await {
-        let BlockImportResult { header, block_hash } = block_import.verify_apply(block, validation.clone()).await?;
+        let block_n = block.unverified_block_number;
+        let block = match &verify_execution {
+            Some(class_cache) => block_import.re_execute(block, Arc::clone(class_cache)).await?,
+            None => block,
+        };
+        let BlockImportResult { header, block_hash } = match block_import.verify_apply(block, validation.clone()).await
+        {
+            Err(BlockImportError::ParentHash { got, expected }) => {
+                log::warn!("Parent hash mismatch: expected {expected:#x}, got {got:#x}");
+                let block_n = match block_n {
+                    Some(block_n) => block_n,
+                    None => backend.get_latest_block_n()?.map(|n| n + 1).unwrap_or(0),
+                };
+                return Err(L2SyncError::Reorg { block_n }.into());
+            }
+            res => res?,
+        };
 
         update_sync_metrics(
             header.block_number,
@@ -154,7 +176,7 @@ async fn l2_pending_block_task(
     block_import: Arc<BlockImporter>,
     validation: BlockValidationContext,
     sync_finished_cb: oneshot::Receiver<()>,
-    provider: Arc<SequencerGatewayProvider>,
+    provider: Arc<dyn BlockSource>,
     pending_block_poll_interval: Duration,
 ) -> anyhow::Result<()> {
     // clear pending status
@@ -176,8 +198,7 @@ async fn l2_pending_block_task(
     while wait_or_graceful_shutdown(interval.tick()).await.is_some() {
         log::debug!("getting pending block...");
 
-        let block =
-            fetch_pending_block_and_updates(&backend, &provider).await.context("Getting pending block from FGW")?;
+        let block = provider.fetch_pending_block(&backend).await.context("Getting pending block")?;
 
         let Some(block) = block else {
             // No pending block.
@@ -208,25 +229,23 @@ pub struct L2SyncConfig {
     pub backup_every_n_blocks: Option<u64>,
     pub pending_block_poll_interval: Duration,
     pub ignore_block_order: bool,
+    pub verify_execution: bool,
 }
 
-/// Spawns workers to fetch blocks and state updates from the feeder.
+/// Spawns workers to fetch blocks and state updates from the block source.
 #[allow(clippy::too_many_arguments)]
 pub async fn sync(
     backend: &Arc<MadaraBackend>,
-    provider: SequencerGatewayProvider,
+    provider: Arc<dyn BlockSource>,
     config: L2SyncConfig,
     block_metrics: BlockMetrics,
     db_metrics: DbMetrics,
     starting_block: u64,
     chain_id: ChainId,
     telemetry: TelemetryHandle,
+    class_cache: Arc<ContractClassCache>,
 ) -> anyhow::Result<()> {
-    let (fetch_stream_sender, fetch_stream_receiver) = mpsc::channel(8);
-    let (block_conv_sender, block_conv_receiver) = mpsc::channel(4);
-    let provider = Arc::new(provider);
     let sync_timer = Arc::new(Mutex::new(None));
-    let (once_caught_up_cb_sender, once_caught_up_cb_receiver) = oneshot::channel();
 
     // [Fetch task] ==new blocks and updates=> [Block conversion task] ======> [Verification and apply
     // task]
@@ -246,48 +265,95 @@ pub async fn sync(
         ignore_block_order: config.ignore_block_order,
     };
 
-    let mut join_set = JoinSet::new();
-    join_set.spawn(l2_fetch_task(
-        Arc::clone(backend),
-        config.first_block,
-        config.n_blocks_to_sync,
-        fetch_stream_sender,
-        Arc::clone(&provider),
-        config.sync_polling_interval,
-        once_caught_up_cb_sender,
-    ));
-    join_set.spawn(l2_block_conversion_task(
-        fetch_stream_receiver,
-        block_conv_sender,
-        Arc::clone(&block_importer),
-        validation.clone(),
-    ));
-    join_set.spawn(l2_verify_and_apply_task(
-        Arc::clone(backend),
-        block_conv_receiver,
-        Arc::clone(&block_importer),
-        validation.clone(),
-        config.backup_every_n_blocks,
-        block_metrics,
-        db_metrics,
-        starting_block,
-        Arc::clone(&sync_timer),
-        telemetry,
-    ));
-    join_set.spawn(l2_pending_block_task(
-        Arc::clone(backend),
-        Arc::clone(&block_importer),
-        validation.clone(),
-        once_caught_up_cb_receiver,
-        provider,
-        config.pending_block_poll_interval,
-    ));
-
-    while let Some(res) = join_set.join_next().await {
-        res.context("task was dropped")??;
+    let mut first_block = config.first_block;
+    loop {
+        let (fetch_stream_sender, fetch_stream_receiver) = mpsc::channel(8);
+        let (block_conv_sender, block_conv_receiver) = mpsc::channel(4);
+        let (once_caught_up_cb_sender, once_caught_up_cb_receiver) = oneshot::channel();
+        let n_blocks_to_sync = config.n_blocks_to_sync.map(|n| (config.first_block + n).saturating_sub(first_block));
+
+        let mut join_set = JoinSet::new();
+        join_set.spawn(l2_fetch_task(
+            Arc::clone(backend),
+            first_block,
+            n_blocks_to_sync,
+            fetch_stream_sender,
+            Arc::clone(&provider),
+            config.sync_polling_interval,
+            once_caught_up_cb_sender,
+        ));
+        join_set.spawn(l2_block_conversion_task(
+            fetch_stream_receiver,
+            block_conv_sender,
+            Arc::clone(&block_importer),
+            validation.clone(),
+        ));
+        join_set.spawn(l2_verify_and_apply_task(
+            Arc::clone(backend),
+            block_conv_receiver,
+            Arc::clone(&block_importer),
+            validation.clone(),
+            config.verify_execution.then(|| Arc::clone(&class_cache)),
+            config.backup_every_n_blocks,
+            block_metrics.clone(),
+            db_metrics.clone(),
+            starting_block,
+            Arc::clone(&sync_timer),
+            telemetry.clone(),
+        ));
+        join_set.spawn(l2_pending_block_task(
+            Arc::clone(backend),
+            Arc::clone(&block_importer),
+            validation.clone(),
+            once_caught_up_cb_receiver,
+            Arc::clone(&provider),
+            config.pending_block_poll_interval,
+        ));
+
+        let mut reorg = None;
+        while let Some(res) = join_set.join_next().await {
+            match res.context("task was dropped")? {
+                Ok(()) => {}
+                Err(err) => match err.downcast_ref::<L2SyncError>() {
+                    Some(L2SyncError::Reorg { block_n }) => {
+                        reorg = Some(*block_n);
+                        break;
+                    }
+                    _ => return Err(err),
+                },
+            }
+        }
+
+        let Some(block_n) = reorg else { return Ok(()) };
+        // Dropping the join set aborts the remaining tasks of this pipeline.
+        drop(join_set);
+
+        let common_ancestor = find_common_ancestor(backend, provider.as_ref(), block_n).await?;
+        log::warn!("🔀 Reorg detected at block #{block_n}, reverting to common ancestor #{common_ancestor}");
+        backend.revert_to(common_ancestor).context("Reverting the chain to the common ancestor")?;
+        first_block = common_ancestor + 1;
     }
+}
 
-    Ok(())
+/// Walks back from the parent of `block_n` until a block is found whose hash matches the one in the block source.
+async fn find_common_ancestor(
+    backend: &MadaraBackend,
+    provider: &dyn BlockSource,
+    block_n: u64,
+) -> Result<u64, L2SyncError> {
+    let lowest = block_n.saturating_sub(MAX_REORG_DEPTH + 1);
+    for n in (lowest..block_n).rev() {
+        let Some(local_hash) = backend.get_block_hash(&BlockId::Number(n))? else { continue };
+        let Some(remote_hash) = provider.block_hash(n).await? else { continue };
+        if local_hash == remote_hash {
+            return Ok(n);
+        }
+    }
+    Err(L2SyncError::Db(MadaraStorageError::RevertTooDeep {
+        target: lowest,
+        latest: block_n.saturating_sub(1),
+        max_depth: MAX_REORG_DEPTH,
+    }))
 }
 
 async fn update_sync_metrics(
@@ -2,9 +2,12 @@ use crate::l2::L2SyncConfig;
 use crate::metrics::block_metrics::BlockMetrics;
 use anyhow::Context;
 use fetch::fetchers::FetchConfig;
+use fetch::source::BlockSource;
 use mc_db::{db_metrics::DbMetrics, MadaraBackend};
+use mc_exec::ContractClassCache;
 use mc_telemetry::TelemetryHandle;
 use mp_convert::ToFelt;
+use starknet_providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet_providers::SequencerGatewayProvider;
 use std::{sync::Arc, time::Duration};
 
@@ -24,6 +27,8 @@ pub async fn sync(
     db_metrics: DbMetrics,
     telemetry: TelemetryHandle,
     pending_block_poll_interval: Duration,
+    verify_execution: bool,
+    class_cache: Arc<ContractClassCache>,
 ) -> anyhow::Result<()> {
     let (starting_block, ignore_block_order) = if let Some(starting_block) = starting_block {
         log::warn!("⚠️  Forcing unordered state. This will most probably break your database.");
@@ -41,14 +46,22 @@ pub async fn sync(
 
     log::info!("⛓️  Starting L2 sync from block {}", starting_block);
 
-    let provider = SequencerGatewayProvider::new(
-        fetch_config.gateway.clone(),
-        fetch_config.feeder_gateway.clone(),
-        fetch_config.chain_id.to_felt(),
-    );
-    let provider = match &fetch_config.api_key {
-        Some(api_key) => provider.with_header("X-Throttling-Bypass".to_string(), api_key.clone()),
-        None => provider,
+    let provider: Arc<dyn BlockSource> = match &fetch_config.json_rpc {
+        Some(url) => {
+            log::info!("🌐 Fetching blocks from the JSON-RPC node at {url}");
+            Arc::new(JsonRpcClient::new(HttpTransport::new(url.clone())))
+        }
+        None => {
+            let provider = SequencerGatewayProvider::new(
+                fetch_config.gateway.clone(),
+                fetch_config.feeder_gateway.clone(),
+                fetch_config.chain_id.to_felt(),
+            );
+            match &fetch_config.api_key {
+                Some(api_key) => Arc::new(provider.with_header("X-Throttling-Bypass".to_string(), api_key.clone())),
+                None => Arc::new(provider),
+            }
+        }
     };
 
     l2::sync(
@@ -62,12 +75,14 @@ pub async fn sync(
             backup_every_n_blocks,
             pending_block_poll_interval,
             ignore_block_order,
+            verify_execution,
         },
         block_metrics,
         db_metrics,
         starting_block,
         backend.chain_config().chain_id.clone(),
         telemetry,
+        class_cache,
     )
     .await?;
 
@@ -1,3 +1,6 @@
+use blockifier::blockifier::config::ConcurrencyConfig;
+use std::num::NonZeroUsize;
+
 /// Parameters used to config block production.
 #[derive(Clone, Debug, clap::Parser)]
 pub struct BlockProductionParams {
@@ -18,4 +21,28 @@ pub struct BlockProductionParams {
     /// Create this number of contracts in the genesis block for the devnet configuration.
     #[arg(long, default_value_t = 10)]
     pub devnet_contracts: u64,
+
+    /// Execute the transactions of a block concurrently, using this number of worker threads.
+    ///
+    /// This is disabled by default: transactions are executed one after the other.
+    #[arg(long, value_name = "WORKERS")]
+    pub block_production_concurrency: Option<NonZeroUsize>,
+
+    /// Number of transactions executed at once by the concurrent workers.
+    /// Only used when `--block-production-concurrency` is set.
+    #[arg(long, value_name = "SIZE", default_value_t = NonZeroUsize::new(64).unwrap())]
+    pub block_production_chunk_size: NonZeroUsize,
+}
+
+impl BlockProductionParams {
+    pub fn concurrency_config(&self) -> ConcurrencyConfig {
+        match self.block_production_concurrency {
+            Some(n_workers) => ConcurrencyConfig {
+                enabled: true,
+                n_workers: n_workers.get(),
+                chunk_size: self.block_production_chunk_size.get(),
+            },
+            None => ConcurrencyConfig::default(),
+        }
+    }
 }
@@ -30,8 +30,16 @@ pub struct ChainConfigOverrideParams {
     pub sequencer_address: Option<String>,
     #[arg(long, requires = "chain_config_override", value_name = "OVERRIDED MAX NONCE VALIDATION FOR SKIP")]
     pub max_nonce_for_validation_skip: Option<u64>,
+    #[arg(long, requires = "chain_config_override", value_name = "OVERRIDED MEMPOOL TX LIMIT")]
+    pub mempool_tx_limit: Option<usize>,
+    #[arg(long, requires = "chain_config_override", value_name = "OVERRIDED MEMPOOL TX LIMIT PER ACCOUNT")]
+    pub mempool_tx_limit_per_account: Option<usize>,
+    #[arg(long, requires = "chain_config_override", value_name = "OVERRIDED MEMPOOL TX MAX AGE")]
+    pub mempool_tx_max_age: Option<u64>,
     #[arg(long, requires = "chain_config_override", value_name = "OVERRIDED ETH CORE CONTRACT")]
     pub eth_core_contract_address: Option<String>,
+    #[arg(long, requires = "chain_config_override", value_name = "OVERRIDED ETH CORE CONTRACT DEPLOYMENT BLOCK")]
+    pub eth_core_contract_deployment_block: Option<u64>,
 }
 
 impl ChainConfigOverrideParams {
@@ -75,11 +83,27 @@ impl ChainConfigOverrideParams {
             chain_config.max_nonce_for_validation_skip = max_nonce;
         }
 
+        if let Some(limit) = params.mempool_tx_limit {
+            chain_config.mempool_tx_limit = limit;
+        }
+
+        if let Some(limit) = params.mempool_tx_limit_per_account {
+            chain_config.mempool_tx_limit_per_account = limit;
+        }
+
+        if let Some(max_age) = params.mempool_tx_max_age {
+            chain_config.mempool_tx_max_age = Duration::from_secs(max_age);
+        }
+
         if let Some(address) = params.eth_core_contract_address {
             chain_config.eth_core_contract_address =
                 H160::from_str(address.as_str()).expect("failed to parse core contract");
         }
 
+        if let Some(block_number) = params.eth_core_contract_deployment_block {
+            chain_config.eth_core_contract_deployment_block = block_number;
+        }
+
         chain_config
     }
 }
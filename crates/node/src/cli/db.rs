@@ -1,5 +1,9 @@
 use std::path::PathBuf;
 
+use mc_db::pruning::PruningMode;
+use mc_db::{TrieLogConfig, MAX_REORG_DEPTH};
+use mc_exec::ContractClassCache;
+
 #[derive(Clone, Debug, clap::Args)]
 pub struct DbParams {
     /// The path where madara will store the database. You should probably change it.
@@ -13,4 +17,39 @@ pub struct DbParams {
     /// Restore the database at startup from the latest backup version. Use it with `--backup-dir <PATH>`
     #[clap(long)]
     pub restore_from_latest_backup: bool,
+
+    /// State history pruning mode. Use `archive` to keep the state of every block, or a number of blocks to only keep
+    /// the historical state of the last N blocks. State queries on older blocks will be rejected.
+    #[clap(long, default_value_t = PruningMode::Archive, value_name = "archive|N")]
+    pub pruning: PruningMode,
+
+    /// Number of blocks for which the global tries history is kept. This allows the node to revert the tries on
+    /// reorgs, and to open the tries at a past block to serve historical storage proofs. Cannot be lower than 64.
+    #[clap(long, default_value_t = MAX_REORG_DEPTH, value_name = "NUMBER OF BLOCKS")]
+    pub max_saved_trie_logs: u64,
+
+    /// Maximum number of in-memory snapshots of the global tries. Opening the tries at a past block starts from the
+    /// closest snapshot after it, which speeds up historical storage proofs. Snapshots use memory for every trie entry
+    /// written since their block. Use 0 to disable them.
+    #[clap(long, default_value_t = 0, value_name = "NUMBER OF SNAPSHOTS")]
+    pub max_saved_snapshots: usize,
+
+    /// Number of blocks between two snapshots of the global tries. Cannot be 0.
+    #[clap(long, default_value_t = MAX_REORG_DEPTH, value_name = "NUMBER OF BLOCKS")]
+    pub snapshot_interval: u64,
+
+    /// Maximum number of contract classes kept in memory, ready to be executed. Parsing classes is expensive, this
+    /// speeds up calls, tracing, fee estimation and block production on frequently used contracts.
+    #[clap(long, default_value_t = ContractClassCache::DEFAULT_CAPACITY, value_name = "NUMBER OF CLASSES")]
+    pub class_cache_size: usize,
+}
+
+impl DbParams {
+    pub fn trie_log_config(&self) -> TrieLogConfig {
+        TrieLogConfig {
+            max_saved_trie_logs: self.max_saved_trie_logs,
+            max_saved_snapshots: self.max_saved_snapshots,
+            snapshot_interval: self.snapshot_interval,
+        }
+    }
 }
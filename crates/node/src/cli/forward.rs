@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use mc_rpc::providers::ForwardRetryConfig;
+use url::Url;
+
+/// Parameters used to config where a full node forwards the transactions it receives through the write RPC methods.
+/// By default, transactions are sent to the gateway of the network, or to the JSON-RPC endpoint set with
+/// `--sync-from-rpc`.
+#[derive(Clone, Debug, clap::Args)]
+pub struct ForwardParams {
+    /// Forward transactions to this sequencer gateway, for example `https://sequencer.example.com/gateway`.
+    #[arg(long, value_name = "URL", conflicts_with = "forward_rpc")]
+    pub forward_gateway: Option<Url>,
+
+    /// Forward transactions to this JSON-RPC endpoint.
+    #[arg(long, value_name = "URL")]
+    pub forward_rpc: Option<Url>,
+
+    /// API key sent along with every forwarded transaction. When forwarding to the gateway of the network, this
+    /// defaults to `--gateway-key`.
+    #[arg(long, value_name = "API KEY")]
+    pub forward_api_key: Option<String>,
+
+    /// Name of the header holding the API key.
+    #[arg(long, value_name = "HEADER", default_value = "X-Throttling-Bypass")]
+    pub forward_api_key_header: String,
+
+    /// Number of times a transaction is sent again when the upstream cannot be reached or rate limits us.
+    #[arg(long, value_name = "NUMBER", default_value = "3")]
+    pub forward_max_retries: u32,
+
+    /// Delay before the first retry, in milliseconds. It is doubled after every failed attempt.
+    #[arg(long, value_name = "MILLISECONDS", default_value = "500")]
+    pub forward_retry_backoff: u64,
+}
+
+impl ForwardParams {
+    pub fn retry_config(&self) -> ForwardRetryConfig {
+        ForwardRetryConfig {
+            max_retries: self.forward_max_retries,
+            initial_backoff: Duration::from_millis(self.forward_retry_backoff),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use clap::Args;
+
+/// Parameters used to config the gateway and feeder gateway server.
+#[derive(Debug, Clone, Args)]
+pub struct GatewayParams {
+    /// Enable the feeder gateway server, serving blocks, state updates and classes in the Starknet sequencer format.
+    #[arg(long, alias = "feeder-gateway")]
+    pub feeder_gateway_enable: bool,
+    /// Enable the `gateway/add_transaction` endpoint, so that full nodes can forward their transactions to this
+    /// sequencer. Only available when producing blocks.
+    #[arg(long)]
+    pub gateway_enable: bool,
+    /// Listen on all network interfaces. This usually means the gateway will be accessible externally.
+    #[arg(long)]
+    pub gateway_external: bool,
+    /// The port used by the gateway server.
+    #[arg(long, value_name = "PORT", default_value = "8080")]
+    pub gateway_port: u16,
+    /// Reject `gateway/add_transaction` requests larger than this size, in megabytes.
+    #[arg(long, value_name = "MEGABYTES", default_value = "15")]
+    pub gateway_max_request_size: u32,
+}
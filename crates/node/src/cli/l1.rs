@@ -1,5 +1,8 @@
+use alloy::primitives::Address;
+use mc_eth::oracle::EthStrkRate;
 use url::Url;
 const DEFAULT_GAS_PRICE_POLL_MS: u64 = 10_000;
+const DEFAULT_L1_EVENTS_POLL_MS: u64 = 6_000;
 
 fn parse_url(s: &str) -> Result<Url, url::ParseError> {
     s.parse()
@@ -15,6 +18,16 @@ pub struct L1SyncParams {
     #[clap(long, value_parser = parse_url, value_name = "ETHEREUM RPC URL")]
     pub l1_endpoint: Option<Url>,
 
+    /// Number of L1 blocks on top of an L1 block before the state updates and L1 to L2 messages it contains are
+    /// processed. By default, they are processed once their L1 block is finalized. Shallower L1 reorgs never affect
+    /// processed events, and deeper ones are detected and rolled back.
+    #[clap(long, value_name = "BLOCKS")]
+    pub l1_confirmations: Option<u64>,
+
+    /// Time in milliseconds between two polls of the L1 for state updates and L1 to L2 messages.
+    #[clap(long, default_value_t = DEFAULT_L1_EVENTS_POLL_MS)]
+    pub l1_events_poll_ms: u64,
+
     /// Disable the gas price sync service. The sync service is responsible to fetch the fee history from the ethereum.
     #[clap(long, alias = "no-gas-price-sync")]
     pub gas_price_sync_disabled: bool,
@@ -22,4 +35,25 @@ pub struct L1SyncParams {
     /// Time in milliseconds in which the gas price worker will fetch the gas price.
     #[clap(long, default_value_t = DEFAULT_GAS_PRICE_POLL_MS, alias = "gas-price-poll")]
     pub gas_price_poll_ms: u64,
+
+    /// Fixed ETH/STRK rate used to compute the L1 gas prices in STRK, for example `2500.5` STRK for one ETH.
+    #[clap(long, value_name = "RATE", conflicts_with_all = ["strk_price_feed_url", "strk_price_oracle_address"])]
+    pub strk_per_eth: Option<EthStrkRate>,
+
+    /// HTTP price feed returning the ETH/STRK rate, in the format of the Pragma offchain API.
+    #[clap(long, value_parser = parse_url, value_name = "URL", conflicts_with = "strk_price_oracle_address")]
+    pub strk_price_feed_url: Option<Url>,
+
+    /// API key of the ETH/STRK price feed.
+    #[clap(long, value_name = "API KEY", requires = "strk_price_feed_url")]
+    pub strk_price_feed_api_key: Option<String>,
+
+    /// Address of a Chainlink-compatible ETH/STRK aggregator contract on L1.
+    #[clap(long, value_name = "ADDRESS")]
+    pub strk_price_oracle_address: Option<Address>,
+
+    /// Maximum age of the ETH/STRK rate, in seconds. The STRK gas prices keep their last value while the rate is older
+    /// than this.
+    #[clap(long, default_value = "3600", value_name = "SECONDS")]
+    pub strk_price_max_age: u64,
 }
@@ -1,9 +1,12 @@
 pub mod block_production;
 pub mod chain_config_overrides;
 pub mod db;
+pub mod forward;
+pub mod gateway;
 pub mod l1;
 pub mod prometheus;
 pub mod rpc;
+pub mod settlement;
 pub mod sync;
 pub mod telemetry;
 
@@ -11,8 +14,11 @@ use crate::cli::l1::L1SyncParams;
 pub use block_production::*;
 pub use chain_config_overrides::*;
 pub use db::*;
+pub use forward::*;
+pub use gateway::*;
 pub use prometheus::*;
 pub use rpc::*;
+pub use settlement::*;
 pub use sync::*;
 pub use telemetry::*;
 
@@ -55,10 +61,22 @@ pub struct RunCmd {
     #[clap(flatten)]
     pub rpc_params: RpcParams,
 
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub gateway_params: GatewayParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub forward_params: ForwardParams,
+
     #[allow(missing_docs)]
     #[clap(flatten)]
     pub block_production_params: BlockProductionParams,
 
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub settlement_params: SettlementParams,
+
     /// Enable authority mode: the node will run as a sequencer and try and produce its own blocks.
     #[arg(long)]
     pub authority: bool,
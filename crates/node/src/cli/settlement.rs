@@ -0,0 +1,38 @@
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use clap::Args;
+
+/// Parameters used to settle the produced blocks on L1.
+#[derive(Debug, Clone, Args)]
+pub struct SettlementParams {
+    /// Send the state update of every produced block to the `updateState` function of the L1 core contract. No proof
+    /// is sent: the core contract must accept the state updates of this sequencer as they are, as development core
+    /// contracts do. Only available when producing blocks, and requires `--l1-endpoint`.
+    #[arg(long, requires = "settlement_private_key")]
+    pub settlement: bool,
+
+    /// Core contract the state updates are sent to. Defaults to the core contract of the chain config.
+    #[arg(long, value_name = "ADDRESS")]
+    pub settlement_core_contract: Option<Address>,
+
+    /// Private key of the L1 account sending the state updates.
+    #[arg(long, value_name = "HEX")]
+    pub settlement_private_key: Option<PrivateKeySigner>,
+
+    /// Interval between two checks for new blocks to settle, in milliseconds.
+    #[arg(long, value_name = "MILLISECONDS", default_value = "6000")]
+    pub settlement_poll_ms: u64,
+
+    /// A state update which is still not in an L1 block after this time, in seconds, is sent again with higher fees.
+    #[arg(long, value_name = "SECONDS", default_value = "300")]
+    pub settlement_resubmit_after: u64,
+
+    /// Time to wait before sending a state update which failed or reverted again, in seconds. It doubles after every
+    /// failure.
+    #[arg(long, value_name = "SECONDS", default_value = "60")]
+    pub settlement_retry_backoff: u64,
+
+    /// Settlement stops with an error once the state update of a block failed this many times in a row.
+    #[arg(long, value_name = "NUMBER", default_value = "5")]
+    pub settlement_max_attempts: u32,
+}
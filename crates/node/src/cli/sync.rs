@@ -2,6 +2,7 @@ use crate::cli::NetworkType;
 use mc_sync::fetch::fetchers::FetchConfig;
 use starknet_api::core::ChainId;
 use std::time::Duration;
+use url::Url;
 
 #[derive(Clone, Debug, clap::Args)]
 pub struct SyncParams {
@@ -24,6 +25,16 @@ pub struct SyncParams {
     #[clap(long)]
     pub disable_root: bool,
 
+    /// Re-execute the transactions of every synced block, and check that the resulting receipts and state diff match
+    /// the ones from the gateway. This is much slower, and the node stops at the first mismatch.
+    #[clap(long)]
+    pub verify_execution: bool,
+
+    /// Sync from the JSON-RPC endpoint of another node (Madara, Juno, Pathfinder...) instead of the feeder gateway.
+    /// Transactions received by this node are forwarded to that endpoint too.
+    #[clap(long, value_name = "URL")]
+    pub sync_from_rpc: Option<Url>,
+
     /// Gateway api key to avoid rate limiting (optional).
     #[clap(long, value_name = "API KEY")]
     pub gateway_key: Option<String>,
@@ -71,6 +82,7 @@ impl SyncParams {
             api_key: self.gateway_key.clone(),
             sync_polling_interval: polling,
             n_blocks_to_sync: self.n_blocks_to_sync,
+            json_rpc: self.sync_from_rpc.clone(),
         }
     }
 }
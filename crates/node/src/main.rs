@@ -13,18 +13,20 @@ use clap::Parser;
 use mc_block_import::BlockImporter;
 
 use mc_db::DatabaseService;
+use mc_exec::{ClassCacheMetrics, ContractClassCache};
 use mc_mempool::{GasPriceProvider, L1DataProvider, Mempool};
 use mc_metrics::MetricsService;
-use mc_rpc::providers::{AddTransactionProvider, ForwardToProvider, MempoolAddTxProvider};
+use mc_rpc::providers::{AddTransactionProvider, ForwardMetrics, ForwardToProvider, MempoolAddTxProvider};
 use mc_telemetry::{SysInfo, TelemetryService};
 use mp_convert::ToFelt;
 use mp_utils::service::{Service, ServiceGroup};
 
+use starknet_providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet_providers::SequencerGatewayProvider;
 
 use cli::{NetworkType, RunCmd};
-use service::L1SyncService;
-use service::{BlockProductionService, RpcService, SyncService};
+use service::{BlockProductionService, GatewayService, RpcService, SyncService};
+use service::{L1SettlementService, L1SyncService};
 
 const GREET_IMPL_NAME: &str = "Madara";
 const GREET_SUPPORT_URL: &str = "https://github.com/madara-alliance/madara/issues";
@@ -72,12 +74,20 @@ async fn main() -> anyhow::Result<()> {
         run_cmd.db_params.backup_dir.clone(),
         run_cmd.db_params.restore_from_latest_backup,
         Arc::clone(&chain_config),
+        run_cmd.db_params.pruning,
+        run_cmd.db_params.trie_log_config(),
     )
     .await
     .context("Initializing db service")?;
 
     let importer = Arc::new(BlockImporter::new(Arc::clone(db_service.backend())));
 
+    // Shared by everything executing transactions: rpc, mempool validation, block production and sync.
+    let class_cache = Arc::new(ContractClassCache::new(
+        run_cmd.db_params.class_cache_size,
+        ClassCacheMetrics::register(&prometheus_service.registry()).context("Registering class cache metrics")?,
+    ));
+
     let l1_gas_setter = GasPriceProvider::new();
     let l1_data_provider: Arc<dyn L1DataProvider> = Arc::new(l1_gas_setter.clone());
     if run_cmd.block_production_params.devnet {
@@ -85,6 +95,15 @@ async fn main() -> anyhow::Result<()> {
         run_cmd.l1_sync_params.gas_price_sync_disabled = true;
     }
 
+    // The mempool is only used when producing blocks. L1 to L2 messages are pushed into it by the l1 sync service.
+    let mempool = run_cmd.is_authority().then(|| {
+        Arc::new(Mempool::new(
+            Arc::clone(db_service.backend()),
+            Arc::clone(&l1_data_provider),
+            Arc::clone(&class_cache),
+        ))
+    });
+
     let l1_service = L1SyncService::new(
         &run_cmd.l1_sync_params,
         &db_service,
@@ -93,65 +112,113 @@ async fn main() -> anyhow::Result<()> {
         chain_config.chain_id.clone(),
         chain_config.eth_core_contract_address,
         run_cmd.is_authority(),
+        mempool.clone(),
     )
     .await
     .context("Initializing the l1 sync service")?;
 
     // Block provider startup.
     // `rpc_add_txs_method_provider` is a trait object that tells the RPC task where to put the transactions when using the Write endpoints.
-    let (block_provider_service, rpc_add_txs_method_provider): (_, Arc<dyn AddTransactionProvider>) =
-        match run_cmd.is_authority() {
-            // Block production service. (authority)
-            true => {
-                let mempool = Arc::new(Mempool::new(Arc::clone(db_service.backend()), Arc::clone(&l1_data_provider)));
-
-                let block_production_service = BlockProductionService::new(
-                    &run_cmd.block_production_params,
-                    &db_service,
-                    Arc::clone(&mempool),
-                    importer,
-                    Arc::clone(&l1_data_provider),
-                    prometheus_service.registry(),
-                    telemetry_service.new_handle(),
-                )?;
-
-                (ServiceGroup::default().with(block_production_service), Arc::new(MempoolAddTxProvider::new(mempool)))
-            }
-            // Block sync service. (full node)
-            false => {
-                // Feeder gateway sync service.
-                let sync_service = SyncService::new(
-                    &run_cmd.sync_params,
-                    Arc::clone(&chain_config),
-                    run_cmd.network,
-                    &db_service,
-                    prometheus_service.registry(),
-                    telemetry_service.new_handle(),
-                )
-                .await
-                .context("Initializing sync service")?;
-
-                (
-                    ServiceGroup::default().with(sync_service),
-                    // TODO(rate-limit): we may get rate limited with this unconfigured provider?
-                    Arc::new(ForwardToProvider::new(SequencerGatewayProvider::new(
-                        run_cmd.network.gateway(),
+    let (block_provider_service, rpc_add_txs_method_provider): (_, Arc<dyn AddTransactionProvider>) = match mempool {
+        // Block production service. (authority)
+        Some(mempool) => {
+            let block_production_service = BlockProductionService::new(
+                &run_cmd.block_production_params,
+                &db_service,
+                Arc::clone(&mempool),
+                importer,
+                Arc::clone(&l1_data_provider),
+                Arc::clone(&class_cache),
+                prometheus_service.registry(),
+                telemetry_service.new_handle(),
+            )?;
+
+            (ServiceGroup::default().with(block_production_service), Arc::new(MempoolAddTxProvider::new(mempool)))
+        }
+        // Block sync service. (full node)
+        None => {
+            // Feeder gateway sync service.
+            let sync_service = SyncService::new(
+                &run_cmd.sync_params,
+                Arc::clone(&chain_config),
+                run_cmd.network,
+                &db_service,
+                prometheus_service.registry(),
+                telemetry_service.new_handle(),
+                Arc::clone(&class_cache),
+            )
+            .await
+            .context("Initializing sync service")?;
+
+            let forward_params = &run_cmd.forward_params;
+            let forward_metrics = ForwardMetrics::register(&prometheus_service.registry())
+                .context("Registering transaction forwarding metrics")?;
+            let retry = forward_params.retry_config();
+            let header = forward_params.forward_api_key_header.clone();
+            // A custom gateway takes precedence over the node we sync from.
+            let forward_rpc = match &forward_params.forward_gateway {
+                Some(_) => forward_params.forward_rpc.as_ref(),
+                None => forward_params.forward_rpc.as_ref().or(run_cmd.sync_params.sync_from_rpc.as_ref()),
+            };
+            let forward_to: Arc<dyn AddTransactionProvider> = match forward_rpc {
+                Some(url) => {
+                    let mut transport = HttpTransport::new(url.clone());
+                    if let Some(api_key) = &forward_params.forward_api_key {
+                        transport = transport.with_header(header, api_key.clone());
+                    }
+                    Arc::new(ForwardToProvider::new(JsonRpcClient::new(transport), retry, forward_metrics))
+                }
+                None => {
+                    // The `--gateway-key` of the network gateway must not be sent to a custom gateway.
+                    let (gateway, api_key) = match &forward_params.forward_gateway {
+                        Some(url) => (url.clone(), forward_params.forward_api_key.clone()),
+                        None => (
+                            run_cmd.network.gateway(),
+                            forward_params.forward_api_key.clone().or(run_cmd.sync_params.gateway_key.clone()),
+                        ),
+                    };
+                    let mut provider = SequencerGatewayProvider::new(
+                        gateway,
                         run_cmd.network.feeder_gateway(),
                         chain_config.chain_id.to_felt(),
-                    ))),
-                )
-            }
-        };
+                    );
+                    if let Some(api_key) = api_key {
+                        provider = provider.with_header(header, api_key);
+                    }
+                    Arc::new(ForwardToProvider::new(provider, retry, forward_metrics))
+                }
+            };
+
+            (ServiceGroup::default().with(sync_service), forward_to)
+        }
+    };
 
     let rpc_service = RpcService::new(
         &run_cmd.rpc_params,
         &db_service,
         Arc::clone(&chain_config),
         prometheus_service.registry(),
-        rpc_add_txs_method_provider,
+        Arc::clone(&rpc_add_txs_method_provider),
+        class_cache,
     )
     .context("Initializing rpc service")?;
 
+    // Full nodes forward their transactions upstream, only a sequencer can accept them through the gateway.
+    anyhow::ensure!(
+        !run_cmd.gateway_params.gateway_enable || run_cmd.is_authority(),
+        "The gateway add_transaction endpoint can only be enabled when producing blocks (--authority or --devnet)"
+    );
+    let gateway_service = GatewayService::new(&run_cmd.gateway_params, &db_service, rpc_add_txs_method_provider);
+
+    let settlement_service = L1SettlementService::new(
+        &run_cmd.settlement_params,
+        &db_service,
+        run_cmd.l1_sync_params.l1_endpoint.as_ref(),
+        chain_config.eth_core_contract_address,
+        run_cmd.is_authority(),
+    )
+    .context("Initializing the l1 settlement service")?;
+
     telemetry_service.send_connected(&node_name, node_version, &chain_config.chain_name, &sys_info);
 
     let app = ServiceGroup::default()
@@ -159,6 +226,8 @@ async fn main() -> anyhow::Result<()> {
         .with(l1_service)
         .with(block_provider_service)
         .with(rpc_service)
+        .with(gateway_service)
+        .with(settlement_service)
         .with(telemetry_service)
         .with(prometheus_service);
 
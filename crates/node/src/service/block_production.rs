@@ -1,9 +1,11 @@
 use std::{io::Write, sync::Arc};
 
 use anyhow::Context;
+use blockifier::blockifier::config::ConcurrencyConfig;
 use mc_block_import::{BlockImporter, BlockValidationContext};
 use mc_db::{DatabaseService, MadaraBackend};
 use mc_devnet::{ChainGenesisDescription, DevnetKeys};
+use mc_exec::ContractClassCache;
 use mc_mempool::{block_production::BlockProductionTask, L1DataProvider, Mempool};
 use mc_metrics::MetricsRegistry;
 use mc_telemetry::TelemetryHandle;
@@ -17,6 +19,8 @@ struct StartParams {
     block_import: Arc<BlockImporter>,
     mempool: Arc<Mempool>,
     l1_data_provider: Arc<dyn L1DataProvider>,
+    class_cache: Arc<ContractClassCache>,
+    concurrency_config: ConcurrencyConfig,
     is_devnet: bool,
     n_devnet_contracts: u64,
 }
@@ -32,6 +36,7 @@ impl BlockProductionService {
         mempool: Arc<mc_mempool::Mempool>,
         block_import: Arc<BlockImporter>,
         l1_data_provider: Arc<dyn L1DataProvider>,
+        class_cache: Arc<ContractClassCache>,
         _metrics_handle: MetricsRegistry,
         _telemetry: TelemetryHandle,
     ) -> anyhow::Result<Self> {
@@ -43,6 +48,8 @@ impl BlockProductionService {
             start: Some(StartParams {
                 backend: Arc::clone(db_service.backend()),
                 l1_data_provider,
+                class_cache,
+                concurrency_config: config.concurrency_config(),
                 mempool,
                 block_import,
                 n_devnet_contracts: config.devnet_contracts,
@@ -60,8 +67,16 @@ impl Service for BlockProductionService {
         if !self.enabled {
             return Ok(());
         }
-        let StartParams { backend, l1_data_provider, mempool, is_devnet, n_devnet_contracts, block_import } =
-            self.start.take().expect("Service already started");
+        let StartParams {
+            backend,
+            l1_data_provider,
+            class_cache,
+            concurrency_config,
+            mempool,
+            is_devnet,
+            n_devnet_contracts,
+            block_import,
+        } = self.start.take().expect("Service already started");
 
         if is_devnet {
             // DEVNET: we the genesis block for the devnet if not deployed, otherwise we only print the devnet keys.
@@ -105,7 +120,10 @@ impl Service for BlockProductionService {
         }
 
         join_set.spawn(async move {
-            BlockProductionTask::new(backend, block_import, mempool, l1_data_provider)?.block_production_task().await?;
+            BlockProductionTask::new(backend, block_import, mempool, l1_data_provider, class_cache)?
+                .with_concurrency(concurrency_config)
+                .block_production_task()
+                .await?;
             Ok(())
         });
 
@@ -0,0 +1,61 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use mc_db::{DatabaseService, MadaraBackend};
+use mc_rpc::providers::AddTransactionProvider;
+use mp_utils::{service::Service, StopHandle};
+use tokio::{sync::oneshot, task::JoinSet};
+
+use crate::cli::GatewayParams;
+
+pub struct GatewayService {
+    config: GatewayParams,
+    backend: Arc<MadaraBackend>,
+    add_transaction_provider: Option<Arc<dyn AddTransactionProvider>>,
+    stop_handle: StopHandle,
+}
+
+impl GatewayService {
+    /// `add_transaction_provider` is only used when `--gateway-enable` is set.
+    pub fn new(
+        config: &GatewayParams,
+        db: &DatabaseService,
+        add_transaction_provider: Arc<dyn AddTransactionProvider>,
+    ) -> Self {
+        Self {
+            config: config.clone(),
+            backend: Arc::clone(db.backend()),
+            add_transaction_provider: config.gateway_enable.then_some(add_transaction_provider),
+            stop_handle: Default::default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Service for GatewayService {
+    async fn start(&mut self, join_set: &mut JoinSet<anyhow::Result<()>>) -> anyhow::Result<()> {
+        if !self.config.feeder_gateway_enable && !self.config.gateway_enable {
+            return Ok(());
+        }
+
+        let listen_addr = if self.config.gateway_external {
+            Ipv4Addr::UNSPECIFIED // listen on 0.0.0.0
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+        let addr = SocketAddr::new(listen_addr.into(), self.config.gateway_port);
+
+        let (stop_send, stop_recv) = oneshot::channel();
+        self.stop_handle = StopHandle::new(Some(stop_send));
+
+        join_set.spawn(mc_gateway::start_server(
+            Arc::clone(&self.backend),
+            self.add_transaction_provider.clone(),
+            self.config.gateway_max_request_size as usize * 1024 * 1024,
+            addr,
+            stop_recv,
+        ));
+
+        Ok(())
+    }
+}
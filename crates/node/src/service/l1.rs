@@ -2,11 +2,11 @@ use crate::cli::l1::L1SyncParams;
 use alloy::primitives::Address;
 use anyhow::Context;
 use mc_db::{DatabaseService, MadaraBackend};
-use mc_eth::client::{EthereumClient, L1BlockMetrics};
-use mc_mempool::GasPriceProvider;
+use mc_eth::client::{EthereumClient, L1BlockMetrics, L1EventsConfig};
+use mc_eth::oracle::{FixedRateOracle, HttpOracle, L1ContractOracle, Oracle};
+use mc_mempool::{GasPriceProvider, Mempool};
 use mc_metrics::MetricsRegistry;
 use mp_block::H160;
-use mp_convert::ToFelt;
 use mp_utils::service::Service;
 use starknet_api::core::ChainId;
 use std::sync::Arc;
@@ -17,13 +17,17 @@ use tokio::task::JoinSet;
 pub struct L1SyncService {
     db_backend: Arc<MadaraBackend>,
     eth_client: Option<EthereumClient>,
+    events_config: L1EventsConfig,
     l1_gas_provider: GasPriceProvider,
+    oracle: Option<Arc<dyn Oracle>>,
     chain_id: ChainId,
     gas_price_sync_disabled: bool,
     gas_price_poll_ms: Duration,
+    mempool: Option<Arc<Mempool>>,
 }
 
 impl L1SyncService {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         config: &L1SyncParams,
         db: &DatabaseService,
@@ -32,6 +36,7 @@ impl L1SyncService {
         chain_id: ChainId,
         l1_core_address: H160,
         authority: bool,
+        mempool: Option<Arc<Mempool>>,
     ) -> anyhow::Result<Self> {
         let eth_client = if !config.sync_l1_disabled {
             if let Some(l1_rpc_url) = &config.l1_endpoint {
@@ -55,32 +60,79 @@ impl L1SyncService {
         let gas_price_sync_enabled = authority && !config.gas_price_sync_disabled;
         let gas_price_poll_ms = Duration::from_secs(config.gas_price_poll_ms);
 
+        let oracle = Self::strk_oracle(config, eth_client.as_ref())?;
+
         if gas_price_sync_enabled {
             let eth_client = eth_client
                 .clone()
                 .context("L1 gas prices require the ethereum service to be enabled. Either disable gas prices syncing using `--no-gas-price-sync`, or remove the `--no-l1-sync` argument.")?;
+            if oracle.is_none() {
+                log::warn!("⚠️  No ETH/STRK rate configured: STRK gas prices will not be updated. Use `--strk-per-eth`, `--strk-price-feed-url` or `--strk-price-oracle-address` to set one.");
+            }
             // running at-least once before the block production service
             log::info!("⏳ Getting initial L1 gas prices");
-            mc_eth::l1_gas_price::gas_price_worker_once(&eth_client, l1_gas_provider.clone(), gas_price_poll_ms)
-                .await
-                .context("Getting initial ethereum gas prices")?;
+            mc_eth::l1_gas_price::gas_price_worker_once(
+                &eth_client,
+                l1_gas_provider.clone(),
+                oracle.as_deref(),
+                db.backend().chain_config().l1_gas_price.ema_blocks,
+                gas_price_poll_ms,
+            )
+            .await
+            .context("Getting initial ethereum gas prices")?;
         }
 
+        let events_config = L1EventsConfig {
+            confirmations: config.l1_confirmations,
+            poll_interval: Duration::from_millis(config.l1_events_poll_ms),
+        };
+
         Ok(Self {
             db_backend: Arc::clone(db.backend()),
             eth_client,
+            events_config,
             l1_gas_provider,
+            oracle,
             chain_id,
             gas_price_sync_disabled: !gas_price_sync_enabled,
             gas_price_poll_ms,
+            mempool,
         })
     }
+
+    fn strk_oracle(
+        config: &L1SyncParams,
+        eth_client: Option<&EthereumClient>,
+    ) -> anyhow::Result<Option<Arc<dyn Oracle>>> {
+        let max_age = Duration::from_secs(config.strk_price_max_age);
+        if let Some(rate) = config.strk_per_eth {
+            return Ok(Some(Arc::new(FixedRateOracle(rate))));
+        }
+        if let Some(url) = &config.strk_price_feed_url {
+            return Ok(Some(Arc::new(HttpOracle::new(url.clone(), config.strk_price_feed_api_key.clone(), max_age))));
+        }
+        if let Some(address) = config.strk_price_oracle_address {
+            let eth_client =
+                eth_client.context("The L1 ETH/STRK oracle requires the ethereum service to be enabled")?;
+            return Ok(Some(Arc::new(L1ContractOracle::new((*eth_client.provider).clone(), address, max_age))));
+        }
+        Ok(None)
+    }
 }
 
 #[async_trait::async_trait]
 impl Service for L1SyncService {
     async fn start(&mut self, join_set: &mut JoinSet<anyhow::Result<()>>) -> anyhow::Result<()> {
-        let L1SyncService { l1_gas_provider, chain_id, gas_price_sync_disabled, gas_price_poll_ms, .. } = self.clone();
+        let L1SyncService {
+            events_config,
+            l1_gas_provider,
+            oracle,
+            chain_id,
+            gas_price_sync_disabled,
+            gas_price_poll_ms,
+            mempool,
+            ..
+        } = self.clone();
 
         if let Some(eth_client) = self.eth_client.take() {
             // enabled
@@ -90,10 +142,13 @@ impl Service for L1SyncService {
                 mc_eth::sync::l1_sync_worker(
                     &db_backend,
                     &eth_client,
-                    chain_id.to_felt(),
+                    &events_config,
+                    &chain_id,
                     l1_gas_provider,
+                    oracle,
                     gas_price_sync_disabled,
                     gas_price_poll_ms,
+                    mempool,
                 )
                 .await
             });
@@ -1,9 +1,13 @@
 mod block_production;
+mod gateway;
 mod l1;
 mod rpc;
+mod settlement;
 mod sync;
 
 pub use block_production::BlockProductionService;
+pub use gateway::GatewayService;
 pub use l1::L1SyncService;
 pub use rpc::RpcService;
+pub use settlement::L1SettlementService;
 pub use sync::SyncService;
@@ -4,6 +4,7 @@ use jsonrpsee::server::ServerHandle;
 use tokio::task::JoinSet;
 
 use mc_db::DatabaseService;
+use mc_exec::ContractClassCache;
 use mc_metrics::MetricsRegistry;
 use mc_rpc::{providers::AddTransactionProvider, versioned_rpc_api, Starknet};
 use mp_chain_config::ChainConfig;
@@ -29,6 +30,7 @@ impl RpcService {
         chain_config: Arc<ChainConfig>,
         metrics_handle: MetricsRegistry,
         add_txs_method_provider: Arc<dyn AddTransactionProvider>,
+        class_cache: Arc<ContractClassCache>,
     ) -> anyhow::Result<Self> {
         if config.rpc_disabled {
             return Ok(Self { server_config: None, server_handle: None });
@@ -47,7 +49,8 @@ impl RpcService {
             }
         };
         let (read, write, trace) = (rpcs, rpcs, rpcs);
-        let starknet = Starknet::new(Arc::clone(db.backend()), chain_config.clone(), add_txs_method_provider);
+        let starknet =
+            Starknet::new(Arc::clone(db.backend()), chain_config.clone(), add_txs_method_provider, class_cache);
         let metrics = RpcMetrics::register(&metrics_handle)?;
 
         Ok(Self {
@@ -65,6 +68,7 @@ impl RpcService {
                 rate_limit: config.rpc_rate_limit,
                 rate_limit_whitelisted_ips: config.rpc_rate_limit_whitelisted_ips.clone(),
                 rate_limit_trust_proxy_headers: config.rpc_rate_limit_trust_proxy_headers,
+                backend: Arc::clone(db.backend()),
             }),
             server_handle: None,
         })
@@ -14,6 +14,7 @@ use governor::state::{InMemoryState, NotKeyed};
 use governor::{Jitter, Quota, RateLimiter};
 use hyper::{Body, Response};
 use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::server::ws;
 use jsonrpsee::types::{ErrorObject, Request};
 use jsonrpsee::MethodResponse;
 use serde_json::{json, Value};
@@ -226,7 +227,15 @@ where
         let mut inner = self.inner.clone();
 
         Box::pin(async move {
-            match add_rpc_version_to_method(&mut req).await {
+            // Websocket upgrade requests have no body: the version is added to the methods of the messages sent over
+            // the connection by [`WsVersionMiddleware`]. The version still needs to be checked before upgrading.
+            let res = if ws::is_upgrade_request(&req) {
+                RpcVersion::from_request_path(req.uri().path()).map(|_| ()).map_err(Into::into)
+            } else {
+                add_rpc_version_to_method(&mut req).await
+            };
+
+            match res {
                 Ok(()) => inner.call(req).await,
                 Err(e) => {
                     let error = match e {
@@ -282,3 +291,45 @@ async fn add_rpc_version_to_method(req: &mut hyper::Request<Body>) -> Result<(),
 
     Ok(())
 }
+
+/// Adds the RPC version to the method names of the messages received on a websocket connection. This is the
+/// counterpart of [`VersionMiddleware`], which only sees the http upgrade request of websocket connections.
+#[derive(Clone)]
+pub struct WsVersionMiddlewareLayer {
+    version: RpcVersion,
+}
+
+impl WsVersionMiddlewareLayer {
+    pub fn new(version: RpcVersion) -> Self {
+        Self { version }
+    }
+}
+
+impl<S> Layer<S> for WsVersionMiddlewareLayer {
+    type Service = WsVersionMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        WsVersionMiddleware { inner, version: self.version }
+    }
+}
+
+#[derive(Clone)]
+pub struct WsVersionMiddleware<S> {
+    inner: S,
+    version: RpcVersion,
+}
+
+impl<'a, S> RpcServiceT<'a> for WsVersionMiddleware<S>
+where
+    S: RpcServiceT<'a>,
+{
+    type Future = S::Future;
+
+    fn call(&self, mut req: Request<'a>) -> Self::Future {
+        let method = req.method_name();
+        let new_method =
+            format!("starknet_{}_{}", self.version.name(), method.strip_prefix("starknet_").unwrap_or(method));
+        req.method = new_method.into();
+        self.inner.call(req)
+    }
+}
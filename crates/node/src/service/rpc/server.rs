@@ -5,6 +5,7 @@ use std::convert::Infallible;
 use std::net::{IpAddr, SocketAddr};
 use std::num::NonZeroU32;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Context;
@@ -24,9 +25,13 @@ use tokio::task::JoinSet;
 use tower::Service;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 
+use mc_db::l1_db::L1VerificationStatus;
+use mc_db::MadaraBackend;
 use mp_utils::wait_or_graceful_shutdown;
 
-use super::middleware::{Metrics, MiddlewareLayer, RpcMetrics, VersionMiddlewareLayer};
+use mp_chain_config::RpcVersion;
+
+use super::middleware::{Metrics, MiddlewareLayer, RpcMetrics, VersionMiddlewareLayer, WsVersionMiddlewareLayer};
 
 const MEGABYTE: u32 = 1024 * 1024;
 
@@ -50,6 +55,8 @@ pub struct ServerConfig {
     pub rate_limit_whitelisted_ips: Vec<IpNetwork>,
     /// Trust proxy headers for rate limiting.
     pub rate_limit_trust_proxy_headers: bool,
+    /// Used to report the L1 verification status on `/health`.
+    pub backend: Arc<MadaraBackend>,
 }
 
 #[derive(Debug, Clone)]
@@ -58,6 +65,7 @@ struct PerConnection<RpcMiddleware, HttpMiddleware> {
     stop_handle: StopHandle,
     metrics: RpcMetrics,
     service_builder: TowerServiceBuilder<RpcMiddleware, HttpMiddleware>,
+    backend: Arc<MadaraBackend>,
 }
 
 /// Start RPC server listening on given address.
@@ -79,6 +87,7 @@ pub async fn start_server(
         rate_limit,
         rate_limit_whitelisted_ips,
         rate_limit_trust_proxy_headers,
+        backend,
     } = config;
 
     let std_listener = TcpListener::bind(addr)
@@ -117,6 +126,7 @@ pub async fn start_server(
         service_builder: builder.to_service_builder(),
         metrics,
         stop_handle: stop_handle.clone(),
+        backend,
     };
 
     let make_service = make_service_fn(move |addr: &AddrStream| {
@@ -144,7 +154,7 @@ pub async fn start_server(
                     rate_limit
                 };
 
-                let PerConnection { service_builder, metrics, stop_handle, methods } = cfg.clone();
+                let PerConnection { service_builder, metrics, stop_handle, methods, backend } = cfg.clone();
 
                 let is_websocket = ws::is_upgrade_request(&req);
                 let transport_label = if is_websocket { "ws" } else { "http" };
@@ -156,13 +166,22 @@ pub async fn start_server(
                         .with_rate_limit_per_minute(rate_limit),
                 };
 
-                let rpc_middleware = RpcServiceBuilder::new().layer(middleware_layer.clone());
+                // The http upgrade request of a websocket connection is checked by the `VersionMiddleware`, a bad
+                // version here means that the connection will be refused anyway.
+                let ws_version_layer = if is_websocket {
+                    RpcVersion::from_request_path(req.uri().path()).ok().map(WsVersionMiddlewareLayer::new)
+                } else {
+                    None
+                };
+
+                let rpc_middleware =
+                    RpcServiceBuilder::new().option_layer(ws_version_layer).layer(middleware_layer.clone());
 
                 let mut svc = service_builder.set_rpc_middleware(rpc_middleware).build(methods, stop_handle);
 
                 async move {
                     if req.uri().path() == "/health" {
-                        Ok(Response::builder().status(StatusCode::OK).body(Body::from("OK"))?)
+                        Ok(health_response(&backend)?)
                     } else {
                         if is_websocket {
                             let on_disconnect = svc.on_session_closed();
@@ -204,6 +223,16 @@ pub async fn start_server(
     Ok(server_handle)
 }
 
+/// The node is unhealthy when the chain it follows diverges from the state updates posted on L1.
+fn health_response(backend: &MadaraBackend) -> Result<Response<Body>, hyper::http::Error> {
+    match backend.get_l1_verification_status() {
+        status @ L1VerificationStatus::Diverged { .. } => {
+            Response::builder().status(StatusCode::SERVICE_UNAVAILABLE).body(Body::from(status.to_string()))
+        }
+        _ => Response::builder().status(StatusCode::OK).body(Body::from("OK")),
+    }
+}
+
 const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
 const X_REAL_IP: HeaderName = HeaderName::from_static("x-real-ip");
 const FORWARDED: HeaderName = HeaderName::from_static("forwarded");
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy::primitives::Address;
+use anyhow::Context;
+use mc_db::{DatabaseService, MadaraBackend};
+use mc_eth::settlement::SettlementConfig;
+use mp_block::H160;
+use mp_utils::service::Service;
+use tokio::task::JoinSet;
+use url::Url;
+
+use crate::cli::SettlementParams;
+
+pub struct L1SettlementService {
+    backend: Arc<MadaraBackend>,
+    config: Option<SettlementConfig>,
+}
+
+impl L1SettlementService {
+    pub fn new(
+        config: &SettlementParams,
+        db: &DatabaseService,
+        l1_endpoint: Option<&Url>,
+        l1_core_address: H160,
+        authority: bool,
+    ) -> anyhow::Result<Self> {
+        let config = if config.settlement {
+            anyhow::ensure!(authority, "Settlement is only available when producing blocks (--authority or --devnet)");
+            let l1_endpoint =
+                l1_endpoint.context("Settlement requires an Ethereum endpoint: use --l1-endpoint <RPC URL>")?;
+            Some(SettlementConfig {
+                l1_endpoint: l1_endpoint.clone(),
+                core_contract: config
+                    .settlement_core_contract
+                    .unwrap_or_else(|| Address::from_slice(l1_core_address.as_bytes())),
+                signer: config
+                    .settlement_private_key
+                    .clone()
+                    .context("Settlement requires --settlement-private-key")?,
+                poll_interval: Duration::from_millis(config.settlement_poll_ms),
+                resubmit_after: Duration::from_secs(config.settlement_resubmit_after),
+                retry_backoff: Duration::from_secs(config.settlement_retry_backoff),
+                max_attempts: config.settlement_max_attempts,
+            })
+        } else {
+            None
+        };
+
+        Ok(Self { backend: Arc::clone(db.backend()), config })
+    }
+}
+
+#[async_trait::async_trait]
+impl Service for L1SettlementService {
+    async fn start(&mut self, join_set: &mut JoinSet<anyhow::Result<()>>) -> anyhow::Result<()> {
+        if let Some(config) = self.config.take() {
+            let backend = Arc::clone(&self.backend);
+            join_set.spawn(async move { mc_eth::settlement::settlement_worker(&backend, &config).await });
+        }
+
+        Ok(())
+    }
+}
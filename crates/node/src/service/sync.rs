@@ -2,6 +2,7 @@ use crate::cli::{NetworkType, SyncParams};
 use anyhow::Context;
 use mc_db::db_metrics::DbMetrics;
 use mc_db::{DatabaseService, MadaraBackend};
+use mc_exec::ContractClassCache;
 use mc_metrics::MetricsRegistry;
 use mc_sync::fetch::fetchers::FetchConfig;
 use mc_sync::metrics::block_metrics::BlockMetrics;
@@ -23,6 +24,8 @@ pub struct SyncService {
     start_params: Option<TelemetryHandle>,
     disabled: bool,
     pending_block_poll_interval: Duration,
+    verify_execution: bool,
+    class_cache: Arc<ContractClassCache>,
 }
 
 impl SyncService {
@@ -33,6 +36,7 @@ impl SyncService {
         db: &DatabaseService,
         metrics_handle: MetricsRegistry,
         telemetry: TelemetryHandle,
+        class_cache: Arc<ContractClassCache>,
     ) -> anyhow::Result<Self> {
         let block_metrics = BlockMetrics::register(&metrics_handle)?;
         let db_metrics = DbMetrics::register(&metrics_handle)?;
@@ -48,6 +52,8 @@ impl SyncService {
             start_params: Some(telemetry),
             disabled: config.sync_disabled,
             pending_block_poll_interval: Duration::from_secs(config.pending_block_poll_interval),
+            verify_execution: config.verify_execution,
+            class_cache,
         })
     }
 }
@@ -65,6 +71,8 @@ impl Service for SyncService {
             block_metrics,
             db_metrics,
             pending_block_poll_interval,
+            verify_execution,
+            class_cache,
             ..
         } = self.clone();
         let telemetry = self.start_params.take().context("Service already started")?;
@@ -81,6 +89,8 @@ impl Service for SyncService {
                 db_metrics,
                 telemetry,
                 pending_block_poll_interval,
+                verify_execution,
+                class_cache,
             )
             .await
         });
@@ -1,4 +1,4 @@
-use crate::StarknetVersion;
+use crate::{L1GasPriceConfig, StarknetVersion};
 use anyhow::{bail, Context};
 use blockifier::bouncer::BouncerWeights;
 use blockifier::{bouncer::BouncerConfig, versioned_constants::VersionedConstants};
@@ -118,6 +118,18 @@ where
     Ok(Duration::from_secs(duration))
 }
 
+fn default_mempool_tx_limit() -> usize {
+    10_000
+}
+
+fn default_mempool_tx_limit_per_account() -> usize {
+    256
+}
+
+fn default_mempool_tx_max_age() -> Duration {
+    Duration::from_secs(60 * 60)
+}
+
 // TODO: this is workaround because BouncerConfig doesn't derive Deserialize in blockifier
 pub fn deserialize_bouncer_config<'de, D>(deserializer: D) -> Result<BouncerConfig, D::Error>
 where
@@ -169,8 +181,31 @@ pub struct ChainConfig {
     /// This number is the maximum nonce the invoke tx can have to qualify for the validation skip.
     pub max_nonce_for_validation_skip: u64,
 
+    /// Only used when mempool is enabled.
+    /// Maximum number of account transactions in the mempool. New transactions are rejected once it is reached.
+    #[serde(default = "default_mempool_tx_limit")]
+    pub mempool_tx_limit: usize,
+    /// Only used when mempool is enabled.
+    /// Maximum number of transactions a single account can have in the mempool.
+    #[serde(default = "default_mempool_tx_limit_per_account")]
+    pub mempool_tx_limit_per_account: usize,
+    /// Only used when mempool is enabled.
+    /// Transactions of an account are evicted from the mempool when its oldest transaction has been waiting for longer
+    /// than this.
+    #[serde(default = "default_mempool_tx_max_age", deserialize_with = "deserialize_duration")]
+    pub mempool_tx_max_age: Duration,
+
+    /// Only used for block production.
+    /// Smoothing, bounds and overrides applied to the L1 gas prices put in block headers.
+    #[serde(default)]
+    pub l1_gas_price: L1GasPriceConfig,
+
     /// The Starknet core contract address for the L1 watcher.
     pub eth_core_contract_address: H160,
+    /// L1 block the core contract was deployed in, or any block before it. When nothing was synced from L1 yet, the L1
+    /// watcher scans the L1 events from this block instead of the genesis block.
+    #[serde(default)]
+    pub eth_core_contract_deployment_block: u64,
 }
 
 impl Default for ChainConfig {
@@ -419,12 +454,24 @@ mod tests {
 
         assert_eq!(chain_config.sequencer_address, ContractAddress::try_from(Felt::from_str("0x0").unwrap()).unwrap());
         assert_eq!(chain_config.max_nonce_for_validation_skip, 2);
+        assert_eq!(chain_config.mempool_tx_limit, 10_000);
+        assert_eq!(chain_config.mempool_tx_limit_per_account, 256);
+        assert_eq!(chain_config.mempool_tx_max_age, Duration::from_secs(3600));
         assert_eq!(
             chain_config.eth_core_contract_address,
             H160::from_str("0xc662c410C0ECf747543f5bA90660f6ABeBD9C8c4").unwrap()
         );
     }
 
+    #[rstest]
+    #[case("mainnet")]
+    #[case("sepolia")]
+    #[case("integration-sepolia")]
+    fn test_preset_core_contract_deployment_block(_set_workdir: (), #[case] preset: &str) {
+        let chain_config = ChainConfig::from_preset(preset).expect("failed to get cfg");
+        assert_ne!(chain_config.eth_core_contract_deployment_block, 0);
+    }
+
     #[rstest]
     fn test_exec_constants(_set_workdir: ()) {
         let chain_config = ChainConfig {
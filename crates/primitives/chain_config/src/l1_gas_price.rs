@@ -0,0 +1,70 @@
+use serde::Deserialize;
+
+/// How the L1 gas prices of produced blocks are derived from the prices fetched from L1. Only used for block
+/// production.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct L1GasPriceConfig {
+    /// Replaces the prices fetched from L1, for devnets and testnets. The other settings are ignored when it is set.
+    #[serde(default)]
+    pub fixed: Option<L1GasPrices>,
+    /// Smooth the L1 base fees with an exponential moving average over this many L1 blocks, at most 300. When it is
+    /// not set, the gas price is the base fee of the latest L1 block and the data gas price is the average blob base
+    /// fee of the last 300 L1 blocks.
+    #[serde(default)]
+    pub ema_blocks: Option<u64>,
+    /// Added to every price, in percent.
+    #[serde(default)]
+    pub margin_percent: u64,
+    /// Applied after the margin.
+    #[serde(default)]
+    pub min: L1GasPriceBounds,
+    /// Applied after the margin.
+    #[serde(default)]
+    pub max: L1GasPriceBounds,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct L1GasPrices {
+    pub eth_l1_gas_price: u128,
+    pub strk_l1_gas_price: u128,
+    pub eth_l1_data_gas_price: u128,
+    pub strk_l1_data_gas_price: u128,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct L1GasPriceBounds {
+    pub eth_l1_gas_price: Option<u128>,
+    pub strk_l1_gas_price: Option<u128>,
+    pub eth_l1_data_gas_price: Option<u128>,
+    pub strk_l1_data_gas_price: Option<u128>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize() {
+        let config: L1GasPriceConfig = serde_yaml::from_str(
+            "
+ema_blocks: 20
+margin_percent: 10
+max:
+  eth_l1_gas_price: 100000000000
+",
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            L1GasPriceConfig {
+                ema_blocks: Some(20),
+                margin_percent: 10,
+                max: L1GasPriceBounds { eth_l1_gas_price: Some(100_000_000_000), ..Default::default() },
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -1,7 +1,9 @@
 mod chain_config;
+mod l1_gas_price;
 mod rpc_version;
 mod starknet_version;
 
 pub use chain_config::*;
+pub use l1_gas_price::*;
 pub use rpc_version::*;
 pub use starknet_version::*;
@@ -70,6 +70,7 @@ impl RpcVersion {
     }
 
     pub const RPC_VERSION_0_7_1: RpcVersion = RpcVersion([0, 7, 1]);
+    pub const RPC_VERSION_0_8_0: RpcVersion = RpcVersion([0, 8, 0]);
     pub const RPC_VERSION_LATEST: RpcVersion = Self::RPC_VERSION_0_7_1;
 }
 
@@ -158,6 +159,12 @@ mod tests {
         assert_eq!(RpcVersion::from_request_path("/rpc/v0_7_1").unwrap(), RpcVersion::RPC_VERSION_0_7_1);
     }
 
+    #[test]
+    fn test_from_request_path_v0_8_not_served() {
+        assert_eq!(RpcVersion::from_request_path("/rpc/v0_8_0"), Err(RpcVersionError::UnsupportedVersion));
+        assert_eq!(RpcVersion::from_request_path("/rpc/v0_8"), Err(RpcVersionError::UnsupportedVersion));
+    }
+
     #[test]
     fn test_from_request_path_empty() {
         assert_eq!(RpcVersion::from_request_path("").unwrap(), RpcVersion::RPC_VERSION_LATEST);
@@ -25,12 +25,17 @@ pub enum ClassCompilationError {
 }
 
 impl CompressedLegacyContractClass {
-    fn serialize_to_json(&self) -> Result<String, ClassCompilationError> {
+    /// The program is stored gzip compressed, as in the JSON-RPC format.
+    pub fn decompress_program(&self) -> Result<serde_json::Value, ClassCompilationError> {
         let mut decompressor = flate2::read::GzDecoder::new(Cursor::new(&self.program));
         let mut program = Vec::new();
         decompressor.read_to_end(&mut program)?;
 
-        let mut program: serde_json::Value = serde_json::from_slice(&program)?;
+        Ok(serde_json::from_slice(&program)?)
+    }
+
+    fn serialize_to_json(&self) -> Result<String, ClassCompilationError> {
+        let mut program = self.decompress_program()?;
 
         let program_object = program.as_object_mut().ok_or(ClassCompilationError::ProgramIsNotAnObject)?;
 
@@ -2,13 +2,16 @@ use blockifier::transaction::{
     account_transaction::AccountTransaction,
     objects::{FeeType, GasVector, HasRelatedFeeType, TransactionExecutionInfo},
     transaction_execution::Transaction,
+    transactions::L1HandlerTransaction,
 };
 use cairo_vm::types::builtin_name::BuiltinName;
+use mp_convert::felt_to_u64;
 use starknet_types_core::felt::Felt;
 
 use crate::{
     DataAvailabilityResources, DeclareTransactionReceipt, DeployAccountTransactionReceipt, Event, ExecutionResources,
-    ExecutionResult, FeePayment, InvokeTransactionReceipt, MsgToL1, PriceUnit, TransactionReceipt,
+    ExecutionResult, FeePayment, InvokeTransactionReceipt, L1HandlerTransactionReceipt, MsgToL1, PriceUnit,
+    TransactionReceipt,
 };
 
 fn blockifier_tx_fee_type(tx: &Transaction) -> FeeType {
@@ -28,6 +31,19 @@ fn blockifier_tx_hash(tx: &Transaction) -> Felt {
     }
 }
 
+/// Hash of the L1 to L2 message consumed by this transaction. The first calldata element is the L1 sender.
+fn l1_handler_message_hash(tx: &L1HandlerTransaction) -> Felt {
+    let (from_address, payload) = tx.tx.calldata.0.split_first().unwrap_or((&Felt::ZERO, &[]));
+    let msg_to_l2 = starknet_core::types::MsgToL2 {
+        from_address: (*from_address).try_into().unwrap_or(Felt::ZERO.try_into().unwrap()),
+        to_address: tx.tx.contract_address.into(),
+        selector: tx.tx.entry_point_selector.0,
+        payload: payload.to_vec(),
+        nonce: felt_to_u64(&tx.tx.nonce.0).unwrap_or_default(),
+    };
+    msg_to_l2.hash().try_into().unwrap_or_default()
+}
+
 pub fn from_blockifier_execution_info(res: &TransactionExecutionInfo, tx: &Transaction) -> TransactionReceipt {
     let price_unit = match blockifier_tx_fee_type(tx) {
         FeeType::Eth => PriceUnit::Wei,
@@ -121,16 +137,15 @@ pub fn from_blockifier_execution_info(res: &TransactionExecutionInfo, tx: &Trans
                 execution_result,
             })
         }
-        Transaction::L1HandlerTransaction(_tx) => unimplemented!("l1 handler tx"),
-        // Transaction::L1HandlerTransactionv(tx) => TransactionReceipt::L1Handler(L1HandlerTransactionReceipt {
-        //     transaction_hash,
-        //     actual_fee,
-        //     messages_sent,
-        //     events,
-        //     execution_resources,
-        //     execution_result,
-        //     message_hash: todo!(),
-        // }),
+        Transaction::L1HandlerTransaction(tx) => TransactionReceipt::L1Handler(L1HandlerTransactionReceipt {
+            message_hash: l1_handler_message_hash(tx),
+            transaction_hash,
+            actual_fee,
+            messages_sent,
+            events,
+            execution_resources,
+            execution_result,
+        }),
     }
 }
 
@@ -90,6 +90,16 @@ impl TransactionReceipt {
         }
     }
 
+    pub fn total_gas_consumed_mut(&mut self) -> &mut DataAvailabilityResources {
+        match self {
+            TransactionReceipt::Invoke(receipt) => &mut receipt.execution_resources.total_gas_consumed,
+            TransactionReceipt::L1Handler(receipt) => &mut receipt.execution_resources.total_gas_consumed,
+            TransactionReceipt::Declare(receipt) => &mut receipt.execution_resources.total_gas_consumed,
+            TransactionReceipt::Deploy(receipt) => &mut receipt.execution_resources.total_gas_consumed,
+            TransactionReceipt::DeployAccount(receipt) => &mut receipt.execution_resources.total_gas_consumed,
+        }
+    }
+
     pub fn messages_sent(&self) -> &[MsgToL1] {
         match self {
             TransactionReceipt::Invoke(receipt) => &receipt.messages_sent,
@@ -0,0 +1,496 @@
+//! Data availability encoding of state diffs, in the format posted on L1 by Starknet since v0.13.1.
+//!
+//! ```text
+//! number of updated contracts
+//! for each contract, by address:
+//!     contract address
+//!     class flag << 128 | new nonce << 64 | number of storage updates
+//!     new class hash, when the class flag is set
+//!     storage key, storage value (for each storage update)
+//! number of declared classes
+//! for each declared class: class hash, compiled class hash
+//! ```
+//!
+//! The class flag is set when the contract was deployed or its class was replaced. Classes declared with Cairo 0 are
+//! not part of the data availability.
+//!
+//! When posted in EIP-4844 blobs, the felts are split into chunks of [`BLOB_LEN`] elements, each chunk being the
+//! coefficients of a polynomial over the BLS12-381 scalar field. A blob holds the evaluations of this polynomial at
+//! the [`BLOB_LEN`]-th roots of unity, in bit-reversed order.
+
+use std::collections::BTreeMap;
+
+use num_bigint::BigUint;
+use starknet_types_core::felt::Felt;
+
+use crate::{ContractStorageDiffItem, DeclaredClassItem, DeployedContractItem, NonceUpdate, StateDiff, StorageEntry};
+
+/// Number of field elements in a blob.
+pub const BLOB_LEN: usize = 4096;
+/// Size of a blob, in bytes.
+pub const BYTES_PER_BLOB: usize = 32 * BLOB_LEN;
+
+/// Modulus of the BLS12-381 scalar field.
+const BLS_MODULUS: &str = "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+/// Primitive [`BLOB_LEN`]-th root of unity of the BLS12-381 scalar field used by EIP-4844.
+const BLOB_ROOT_OF_UNITY: &str = "564c0a11a0f704f4fc3e8acfe0f8245f0ad1347b378fbf96e206da11a5d36306";
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DaError {
+    #[error("Nonce {nonce:#x} of contract {contract_address:#x} does not fit in 64 bits")]
+    NonceTooLarge { contract_address: Felt, nonce: Felt },
+    #[error("Invalid header {header:#x} for contract {contract_address:#x}")]
+    InvalidContractHeader { contract_address: Felt, header: Felt },
+    #[error("Invalid number of elements {0:#x}")]
+    InvalidLength(Felt),
+    #[error("Data availability ends unexpectedly")]
+    UnexpectedEnd,
+    #[error("Data availability has non-zero values after the state diff")]
+    TrailingData,
+    #[error("Blob of {0} bytes, expected {BYTES_PER_BLOB}")]
+    InvalidBlobSize(usize),
+    #[error("Element {index} of blob {blob_index} is not in the BLS12-381 scalar field")]
+    InvalidBlobElement { blob_index: usize, index: usize },
+    #[error("Value {index} of blob {blob_index} is not a felt")]
+    NotAFelt { blob_index: usize, index: usize },
+}
+
+#[derive(Default)]
+struct ContractUpdate {
+    nonce: Felt,
+    class_hash: Option<Felt>,
+    storage: BTreeMap<Felt, Felt>,
+}
+
+/// Encodes a state diff in the Starknet data availability format.
+///
+/// The data availability contains the nonce of every updated contract, even when it did not change. Contracts which
+/// are not in `state_diff.nonces` are encoded with a zero nonce: callers should add the current nonces of the
+/// contracts with storage or class updates beforehand.
+pub fn encode_state_diff(state_diff: &StateDiff) -> Result<Vec<Felt>, DaError> {
+    let mut contracts: BTreeMap<Felt, ContractUpdate> = BTreeMap::new();
+    for diff in &state_diff.storage_diffs {
+        let contract = contracts.entry(diff.address).or_default();
+        contract.storage.extend(diff.storage_entries.iter().map(|entry| (entry.key, entry.value)));
+    }
+    for nonce in &state_diff.nonces {
+        contracts.entry(nonce.contract_address).or_default().nonce = nonce.nonce;
+    }
+    for deployed in &state_diff.deployed_contracts {
+        contracts.entry(deployed.address).or_default().class_hash = Some(deployed.class_hash);
+    }
+    for replaced in &state_diff.replaced_classes {
+        contracts.entry(replaced.contract_address).or_default().class_hash = Some(replaced.class_hash);
+    }
+
+    let mut declared_classes: Vec<_> =
+        state_diff.declared_classes.iter().map(|class| (class.class_hash, class.compiled_class_hash)).collect();
+    declared_classes.sort();
+
+    let mut felts = Vec::with_capacity(2 + 3 * contracts.len() + 2 * declared_classes.len());
+    felts.push(Felt::from(contracts.len() as u64));
+    for (contract_address, contract) in contracts {
+        let nonce: u64 = contract
+            .nonce
+            .try_into()
+            .map_err(|_| DaError::NonceTooLarge { contract_address, nonce: contract.nonce })?;
+        let class_flag = contract.class_hash.is_some() as u128;
+        let info = (Felt::from(class_flag) * Felt::TWO.pow(64u32) + Felt::from(nonce)) * Felt::TWO.pow(64u32)
+            + Felt::from(contract.storage.len() as u64);
+
+        felts.push(contract_address);
+        felts.push(info);
+        felts.extend(contract.class_hash);
+        felts.extend(contract.storage.into_iter().flat_map(|(key, value)| [key, value]));
+    }
+    felts.push(Felt::from(declared_classes.len() as u64));
+    felts.extend(
+        declared_classes.into_iter().flat_map(|(class_hash, compiled_class_hash)| [class_hash, compiled_class_hash]),
+    );
+
+    Ok(felts)
+}
+
+/// Decodes a state diff from the Starknet data availability format. Trailing zeros, such as the padding of the last
+/// blob, are ignored.
+///
+/// The data availability does not tell deployed contracts from replaced classes: contracts with a new class are
+/// decoded as deployed contracts. It also contains the nonce of every updated contract, which are all decoded as
+/// nonce updates.
+pub fn decode_state_diff(felts: &[Felt]) -> Result<StateDiff, DaError> {
+    let mut felts = felts.iter().copied();
+    let mut next = || felts.next().ok_or(DaError::UnexpectedEnd);
+    let mut state_diff = StateDiff::default();
+
+    let n_contracts = decode_length(next()?)?;
+    for _ in 0..n_contracts {
+        let contract_address = next()?;
+        let header = next()?;
+        let bytes = header.to_bytes_be();
+        if bytes[..15].iter().any(|byte| *byte != 0) || bytes[15] > 1 {
+            return Err(DaError::InvalidContractHeader { contract_address, header });
+        }
+        let nonce = u64::from_be_bytes(bytes[16..24].try_into().expect("Slice of 8 bytes"));
+        let n_storage_updates = u64::from_be_bytes(bytes[24..].try_into().expect("Slice of 8 bytes"));
+
+        if bytes[15] == 1 {
+            state_diff.deployed_contracts.push(DeployedContractItem { address: contract_address, class_hash: next()? });
+        }
+        state_diff.nonces.push(NonceUpdate { contract_address, nonce: Felt::from(nonce) });
+        let storage_entries = (0..n_storage_updates)
+            .map(|_| Ok(StorageEntry { key: next()?, value: next()? }))
+            .collect::<Result<Vec<_>, DaError>>()?;
+        if !storage_entries.is_empty() {
+            state_diff.storage_diffs.push(ContractStorageDiffItem { address: contract_address, storage_entries });
+        }
+    }
+
+    let n_declared_classes = decode_length(next()?)?;
+    for _ in 0..n_declared_classes {
+        state_diff.declared_classes.push(DeclaredClassItem { class_hash: next()?, compiled_class_hash: next()? });
+    }
+
+    if felts.any(|felt| felt != Felt::ZERO) {
+        return Err(DaError::TrailingData);
+    }
+    Ok(state_diff)
+}
+
+fn decode_length(felt: Felt) -> Result<u64, DaError> {
+    felt.try_into().map_err(|_| DaError::InvalidLength(felt))
+}
+
+/// Encodes a state diff into blobs.
+pub fn state_diff_to_blobs(state_diff: &StateDiff) -> Result<Vec<Vec<u8>>, DaError> {
+    Ok(felts_to_blobs(&encode_state_diff(state_diff)?))
+}
+
+/// Decodes a state diff from the blobs it was posted in, in order.
+pub fn state_diff_from_blobs(blobs: &[impl AsRef<[u8]>]) -> Result<StateDiff, DaError> {
+    decode_state_diff(&blobs_to_felts(blobs)?)
+}
+
+/// Encodes felts into blobs of [`BYTES_PER_BLOB`] bytes. The last blob is padded with zeros.
+pub fn felts_to_blobs(felts: &[Felt]) -> Vec<Vec<u8>> {
+    let modulus = parse_hex(BLS_MODULUS);
+    let root = parse_hex(BLOB_ROOT_OF_UNITY);
+
+    felts
+        .chunks(BLOB_LEN)
+        .map(|chunk| {
+            let mut values: Vec<BigUint> =
+                chunk.iter().map(|felt| BigUint::from_bytes_be(&felt.to_bytes_be())).collect();
+            values.resize(BLOB_LEN, BigUint::default());
+            fft(&mut values, &root, &modulus);
+            bit_reverse_permutation(&mut values);
+            values.iter().flat_map(to_bytes_32).collect()
+        })
+        .collect()
+}
+
+/// Decodes the felts posted in blobs, in order. The padding of the last blob is returned as zeros.
+pub fn blobs_to_felts(blobs: &[impl AsRef<[u8]>]) -> Result<Vec<Felt>, DaError> {
+    let modulus = parse_hex(BLS_MODULUS);
+    let inverse_root = parse_hex(BLOB_ROOT_OF_UNITY).modpow(&BigUint::from(BLOB_LEN - 1), &modulus);
+    let inverse_len = BigUint::from(BLOB_LEN).modpow(&(&modulus - 2u32), &modulus);
+
+    let mut felts = Vec::with_capacity(blobs.len() * BLOB_LEN);
+    for (blob_index, blob) in blobs.iter().enumerate() {
+        let blob = blob.as_ref();
+        if blob.len() != BYTES_PER_BLOB {
+            return Err(DaError::InvalidBlobSize(blob.len()));
+        }
+        let mut values = blob
+            .chunks(32)
+            .enumerate()
+            .map(|(index, bytes)| {
+                let value = BigUint::from_bytes_be(bytes);
+                if value < modulus {
+                    Ok(value)
+                } else {
+                    Err(DaError::InvalidBlobElement { blob_index, index })
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        bit_reverse_permutation(&mut values);
+        fft(&mut values, &inverse_root, &modulus);
+
+        for (index, value) in values.into_iter().enumerate() {
+            let bytes = to_bytes_32(&(value * &inverse_len % &modulus));
+            let felt = Felt::from_bytes_be(&bytes);
+            if felt.to_bytes_be() != bytes {
+                return Err(DaError::NotAFelt { blob_index, index });
+            }
+            felts.push(felt);
+        }
+    }
+    Ok(felts)
+}
+
+/// Evaluates in place the polynomial with coefficients `values` at the powers of `root`, a primitive
+/// `values.len()`-th root of unity.
+fn fft(values: &mut [BigUint], root: &BigUint, modulus: &BigUint) {
+    let n = values.len();
+    bit_reverse_permutation(values);
+
+    let mut len = 2;
+    while len <= n {
+        let step = root.modpow(&BigUint::from(n / len), modulus);
+        for chunk in values.chunks_mut(len) {
+            let (low, high) = chunk.split_at_mut(len / 2);
+            let mut twiddle = BigUint::from(1u32);
+            for (a, b) in low.iter_mut().zip(high) {
+                let t = &*b * &twiddle % modulus;
+                *b = (&*a + modulus - &t) % modulus;
+                *a = (&*a + &t) % modulus;
+                twiddle = twiddle * &step % modulus;
+            }
+        }
+        len *= 2;
+    }
+}
+
+fn bit_reverse_permutation<T>(values: &mut [T]) {
+    let bits = values.len().trailing_zeros();
+    for i in 0..values.len() {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> BigUint {
+    BigUint::parse_bytes(hex.as_bytes(), 16).expect("Valid hex constant")
+}
+
+fn to_bytes_32(value: &BigUint) -> [u8; 32] {
+    let bytes = value.to_bytes_be();
+    let mut res = [0u8; 32];
+    res[32 - bytes.len()..].copy_from_slice(&bytes);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::dummy_state_diff;
+    use crate::ReplacedClassItem;
+    use proptest::prelude::*;
+
+    /// Blob of the state diff of [`dummy_state_diff`], computed by evaluating its polynomial at every root of unity
+    /// without an FFT.
+    const DUMMY_STATE_DIFF_BLOB: &str = include_str!("../resources/dummy_state_diff_blob.txt");
+
+    fn dummy_state_diff_blob() -> Vec<u8> {
+        let hex = DUMMY_STATE_DIFF_BLOB.trim();
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_encode_state_diff() {
+        let felts = encode_state_diff(&dummy_state_diff()).unwrap();
+
+        let two_64 = Felt::TWO.pow(64u32);
+        let class_flag = Felt::TWO.pow(128u32);
+        #[rustfmt::skip]
+        let expected = vec![
+            Felt::from(8),
+            // storage updates only
+            Felt::from(1), Felt::from(2), Felt::from(2), Felt::from(3), Felt::from(4), Felt::from(5),
+            Felt::from(6), Felt::from(2), Felt::from(7), Felt::from(8), Felt::from(9), Felt::from(10),
+            // deployed
+            Felt::from(17), class_flag, Felt::from(18),
+            Felt::from(19), class_flag, Felt::from(20),
+            // class replaced
+            Felt::from(21), class_flag, Felt::from(22),
+            Felt::from(23), class_flag, Felt::from(24),
+            // nonce updates only
+            Felt::from(25), Felt::from(26) * two_64,
+            Felt::from(27), Felt::from(28) * two_64,
+            Felt::from(2),
+            Felt::from(13), Felt::from(14),
+            Felt::from(15), Felt::from(16),
+        ];
+        assert_eq!(felts, expected);
+    }
+
+    #[test]
+    fn test_encode_nonce_too_large() {
+        let state_diff = StateDiff {
+            nonces: vec![NonceUpdate { contract_address: Felt::ONE, nonce: Felt::TWO.pow(64u32) }],
+            ..Default::default()
+        };
+        assert_eq!(
+            encode_state_diff(&state_diff),
+            Err(DaError::NonceTooLarge { contract_address: Felt::ONE, nonce: Felt::TWO.pow(64u32) })
+        );
+    }
+
+    #[test]
+    fn test_decode_state_diff() {
+        let felts = encode_state_diff(&dummy_state_diff()).unwrap();
+        let mut state_diff = decode_state_diff(&felts).unwrap();
+        state_diff.sort();
+
+        let mut expected = dummy_state_diff();
+        // Cairo 0 classes are not in the data availability, and replaced classes are decoded as deployed contracts
+        expected.deprecated_declared_classes = vec![];
+        expected.deployed_contracts.extend(expected.replaced_classes.drain(..).map(|replaced| DeployedContractItem {
+            address: replaced.contract_address,
+            class_hash: replaced.class_hash,
+        }));
+        // Every updated contract has a nonce
+        expected.nonces.extend(
+            [1, 6, 17, 19, 21, 23]
+                .map(|address| NonceUpdate { contract_address: Felt::from(address), nonce: Felt::ZERO }),
+        );
+        expected.sort();
+        assert_eq!(state_diff, expected);
+    }
+
+    #[test]
+    fn test_decode_state_diff_errors() {
+        let felts = encode_state_diff(&dummy_state_diff()).unwrap();
+
+        assert_eq!(decode_state_diff(&felts[..felts.len() - 1]), Err(DaError::UnexpectedEnd));
+        assert_eq!(
+            decode_state_diff(&[felts.as_slice(), &[Felt::ZERO, Felt::ONE]].concat()),
+            Err(DaError::TrailingData)
+        );
+        assert_eq!(decode_state_diff(&[Felt::MAX]), Err(DaError::InvalidLength(Felt::MAX)));
+
+        let header = Felt::TWO.pow(129u32);
+        assert_eq!(
+            decode_state_diff(&[Felt::ONE, Felt::ONE, header]),
+            Err(DaError::InvalidContractHeader { contract_address: Felt::ONE, header })
+        );
+    }
+
+    #[test]
+    fn test_state_diff_to_blobs() {
+        let blobs = state_diff_to_blobs(&dummy_state_diff()).unwrap();
+        assert_eq!(blobs, vec![dummy_state_diff_blob()]);
+    }
+
+    #[test]
+    fn test_state_diff_from_blobs() {
+        let state_diff = state_diff_from_blobs(&[dummy_state_diff_blob()]).unwrap();
+        let felts = encode_state_diff(&dummy_state_diff()).unwrap();
+        assert_eq!(state_diff, decode_state_diff(&felts).unwrap());
+    }
+
+    /// Known-answer tests against the blobs of Starknet mainnet state updates. Each `<name>.blobs.txt` file of
+    /// `resources/mainnet` holds the blobs of one state update, one hex string per line, and `<name>.state_diff.json`
+    /// holds the state diff they decode to. See `resources/mainnet/README.md` to add one.
+    #[test]
+    #[ignore = "no mainnet blob has been checked in to resources/mainnet yet"]
+    fn test_mainnet_blobs() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/mainnet");
+        let mut n_fixtures = 0;
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()?.strip_suffix(".blobs.txt")) else {
+                continue;
+            };
+            let blobs: Vec<Vec<u8>> = std::fs::read_to_string(&path)
+                .unwrap()
+                .lines()
+                .map(|line| line.trim().trim_start_matches("0x"))
+                .filter(|hex| !hex.is_empty())
+                .map(|hex| (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect())
+                .collect();
+            let expected = std::fs::read_to_string(dir.join(format!("{name}.state_diff.json"))).unwrap();
+            let mut expected: StateDiff = serde_json::from_str(&expected).unwrap();
+
+            let mut state_diff = state_diff_from_blobs(&blobs).unwrap();
+            state_diff.sort();
+            expected.sort();
+            assert_eq!(state_diff, expected, "State diff of the {name} mainnet blobs");
+            n_fixtures += 1;
+        }
+        assert!(n_fixtures > 0, "No mainnet blob in {}", dir.display());
+    }
+
+    #[test]
+    fn test_blobs_to_felts_errors() {
+        assert_eq!(blobs_to_felts(&[vec![0u8; BYTES_PER_BLOB - 1]]), Err(DaError::InvalidBlobSize(BYTES_PER_BLOB - 1)));
+
+        let mut blob = vec![0u8; BYTES_PER_BLOB];
+        blob[32..64].copy_from_slice(&parse_hex(BLS_MODULUS).to_bytes_be());
+        assert_eq!(blobs_to_felts(&[blob]), Err(DaError::InvalidBlobElement { blob_index: 0, index: 1 }));
+
+        // Constant polynomial equal to the largest element of the BLS12-381 scalar field, which is not a felt
+        let blob: Vec<u8> =
+            std::iter::repeat(to_bytes_32(&(parse_hex(BLS_MODULUS) - 1u32))).take(BLOB_LEN).flatten().collect();
+        assert_eq!(blobs_to_felts(&[blob]), Err(DaError::NotAFelt { blob_index: 0, index: 0 }));
+    }
+
+    fn any_felt() -> impl Strategy<Value = Felt> {
+        any::<[u8; 32]>().prop_map(|bytes| Felt::from_bytes_be(&bytes))
+    }
+
+    prop_compose! {
+        fn any_state_diff()(
+            storage_diffs in prop::collection::vec(
+                (any_felt(), prop::collection::vec((any_felt(), any_felt()), 0..4)),
+                0..4,
+            ),
+            declared_classes in prop::collection::vec((any_felt(), any_felt()), 0..4),
+            deployed_contracts in prop::collection::vec((any_felt(), any_felt()), 0..4),
+            replaced_classes in prop::collection::vec((any_felt(), any_felt()), 0..4),
+            nonces in prop::collection::vec((any_felt(), any::<u64>()), 0..4),
+        ) -> StateDiff {
+            StateDiff {
+                storage_diffs: storage_diffs
+                    .into_iter()
+                    .map(|(address, entries)| ContractStorageDiffItem {
+                        address,
+                        storage_entries: entries.into_iter().map(|(key, value)| StorageEntry { key, value }).collect(),
+                    })
+                    .collect(),
+                deprecated_declared_classes: vec![],
+                declared_classes: declared_classes
+                    .into_iter()
+                    .map(|(class_hash, compiled_class_hash)| DeclaredClassItem { class_hash, compiled_class_hash })
+                    .collect(),
+                deployed_contracts: deployed_contracts
+                    .into_iter()
+                    .map(|(address, class_hash)| DeployedContractItem { address, class_hash })
+                    .collect(),
+                replaced_classes: replaced_classes
+                    .into_iter()
+                    .map(|(contract_address, class_hash)| ReplacedClassItem { contract_address, class_hash })
+                    .collect(),
+                nonces: nonces
+                    .into_iter()
+                    .map(|(contract_address, nonce)| NonceUpdate { contract_address, nonce: Felt::from(nonce) })
+                    .collect(),
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_state_diff_round_trip(state_diff in any_state_diff()) {
+            let felts = encode_state_diff(&state_diff).unwrap();
+            let decoded = decode_state_diff(&felts).unwrap();
+            prop_assert_eq!(encode_state_diff(&decoded).unwrap(), felts);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(10))] // the FFT over big integers is slow in debug builds
+        #[test]
+        fn proptest_blobs_round_trip(felts in prop::collection::vec(any_felt(), 0..2 * BLOB_LEN)) {
+            let blobs = felts_to_blobs(&felts);
+            prop_assert_eq!(blobs.len(), felts.len().div_ceil(BLOB_LEN));
+            prop_assert!(blobs.iter().all(|blob| blob.len() == BYTES_PER_BLOB));
+
+            let mut decoded = blobs_to_felts(&blobs).unwrap();
+            prop_assert!(decoded[felts.len()..].iter().all(|felt| *felt == Felt::ZERO));
+            decoded.truncate(felts.len());
+            prop_assert_eq!(decoded, felts);
+        }
+    }
+}
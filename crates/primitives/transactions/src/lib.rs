@@ -398,6 +398,15 @@ impl DeclareTransaction {
             DeclareTransaction::V3(tx) => &tx.sender_address,
         }
     }
+
+    pub fn class_hash(&self) -> &Felt {
+        match self {
+            DeclareTransaction::V0(tx) => &tx.class_hash,
+            DeclareTransaction::V1(tx) => &tx.class_hash,
+            DeclareTransaction::V2(tx) => &tx.class_hash,
+            DeclareTransaction::V3(tx) => &tx.class_hash,
+        }
+    }
     pub fn signature(&self) -> &[Felt] {
         match self {
             DeclareTransaction::V0(tx) => &tx.signature,
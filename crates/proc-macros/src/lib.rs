@@ -27,6 +27,9 @@
 //!     fn block_number(&self) -> anyhow::Result<u64>;
 //! }
 //! ```
+//!
+//! Subscriptions get the same treatment: both the `name` and the `unsubscribe` method names of a
+//! `#[subscription(...)]` attribute are prefixed with the version id.
 
 use proc_macro::TokenStream;
 use proc_macro2::Span;
@@ -78,6 +81,33 @@ fn version_method_name(attr: &Attribute, version: &str) -> syn::Result<Attribute
     Ok(new_attr)
 }
 
+fn version_subscription_names(attr: &Attribute, version: &str) -> syn::Result<Attribute> {
+    let mut name = None;
+    let mut unsubscribe = None;
+    let mut item: Option<syn::Type> = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(format!("{version}_{}", meta.value()?.parse::<LitStr>()?.value()));
+        } else if meta.path.is_ident("unsubscribe") {
+            unsubscribe = Some(format!("{version}_{}", meta.value()?.parse::<LitStr>()?.value()));
+        } else if meta.path.is_ident("item") {
+            item = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("Unsupported subscription argument"));
+        }
+        Ok(())
+    })?;
+
+    let missing = |arg: &str| syn::Error::new_spanned(attr, format!("Subscription is missing the `{arg}` argument"));
+    let name = name.ok_or_else(|| missing("name"))?;
+    let unsubscribe = unsubscribe.ok_or_else(|| missing("unsubscribe"))?;
+    let item = item.ok_or_else(|| missing("item"))?;
+
+    let mut new_attr = attr.clone();
+    new_attr.meta = syn::parse_quote!(subscription(name = #name, unsubscribe = #unsubscribe, item = #item));
+    Ok(new_attr)
+}
+
 #[proc_macro_attribute]
 pub fn versioned_starknet_rpc(attr: TokenStream, input: TokenStream) -> TokenStream {
     let VersionedRpcAttr { version } = parse_macro_input!(attr as VersionedRpcAttr);
@@ -88,17 +118,23 @@ pub fn versioned_starknet_rpc(attr: TokenStream, input: TokenStream) -> TokenStr
 
     for item in &mut item_trait.items {
         if let TraitItem::Fn(method) = item {
-            method.attrs = method
+            let attrs = method
                 .attrs
                 .iter()
                 .filter_map(|attr| {
                     if attr.path().is_ident("method") {
-                        version_method_name(attr, &version).ok()
+                        version_method_name(attr, &version).ok().map(Ok)
+                    } else if attr.path().is_ident("subscription") {
+                        Some(version_subscription_names(attr, &version))
                     } else {
-                        Some(attr.clone())
+                        Some(Ok(attr.clone()))
                     }
                 })
-                .collect();
+                .collect::<syn::Result<Vec<_>>>();
+            match attrs {
+                Ok(attrs) => method.attrs = attrs,
+                Err(err) => return err.to_compile_error().into(),
+            }
         }
     }
 
@@ -52,6 +52,10 @@ impl MadaraCmd {
         self.json_rpc.get_or_insert_with(|| JsonRpcClient::new(HttpTransport::new(self.rpc_url.clone())))
     }
 
+    pub fn rpc_url(&self) -> &Url {
+        &self.rpc_url
+    }
+
     pub fn db_dir(&self) -> &Path {
         self.tempdir.path()
     }
@@ -241,3 +245,92 @@ async fn madara_can_sync_a_few_blocks(_set_workdir: ()) {
         }
     );
 }
+
+#[rstest]
+#[tokio::test]
+async fn madara_can_sync_from_another_madara_rpc(_set_workdir: ()) {
+    use mc_devnet::ChainGenesisDescription;
+    use starknet::accounts::{Account, ExecutionEncoding, SingleOwnerAccount};
+    use starknet::signers::LocalWallet;
+    use starknet_core::types::{BlockId, Call, Felt, ReceiptBlock};
+    use starknet_core::utils::get_selector_from_name;
+
+    const ERC20_STRK_CONTRACT_ADDRESS: Felt =
+        Felt::from_hex_unchecked("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d");
+
+    let _ = env_logger::builder().is_test(true).try_init();
+    let mut sequencer = MadaraCmdBuilder::new()
+        .args([
+            "--devnet",
+            "--network",
+            "devnet",
+            "--no-l1-sync",
+            "--preset=test",
+            "--chain-config-override",
+            "--block-time",
+            "1",
+        ])
+        .run();
+    sequencer.wait_for_ready().await;
+    sequencer.wait_for_sync_to(1).await;
+
+    // The devnet keys are deterministic, so the accounts deployed by the sequencer can be rebuilt here.
+    let contracts = ChainGenesisDescription::base_config().unwrap().add_devnet_contracts(10).unwrap();
+    let chain_id = sequencer.json_rpc().chain_id().await.unwrap();
+    let account = SingleOwnerAccount::new(
+        JsonRpcClient::new(HttpTransport::new(sequencer.rpc_url().clone())),
+        LocalWallet::from(contracts.0[0].secret.clone()),
+        contracts.0[0].address,
+        chain_id,
+        ExecutionEncoding::New,
+    );
+
+    // Sync blocks with transactions, so that the transaction and receipt commitments are checked too.
+    let mut tx_hashes = vec![];
+    for nonce in 0..3u64 {
+        let call = Call {
+            to: ERC20_STRK_CONTRACT_ADDRESS,
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![contracts.0[1].address, (1_000 + nonce).into(), Felt::ZERO],
+        };
+        let res = account.execute_v3(vec![call]).nonce(nonce.into()).gas(60000).gas_price(10000).send().await.unwrap();
+        tx_hashes.push(res.transaction_hash);
+    }
+
+    let rpc = sequencer.json_rpc();
+    let mut last_block_n = 0;
+    for tx_hash in tx_hashes {
+        wait_for_cond(
+            || async {
+                match rpc.get_transaction_receipt(tx_hash).await?.block {
+                    ReceiptBlock::Block { .. } => Ok(()),
+                    ReceiptBlock::Pending => bail!("Transaction {tx_hash:#x} is still pending"),
+                }
+            },
+            Duration::from_millis(1000),
+        )
+        .await;
+        if let ReceiptBlock::Block { block_number, .. } = rpc.get_transaction_receipt(tx_hash).await.unwrap().block {
+            last_block_n = last_block_n.max(block_number);
+        }
+    }
+
+    let sequencer_rpc = sequencer.rpc_url().join("rpc/v0_7_1/").unwrap();
+    let mut full_node = MadaraCmdBuilder::new()
+        .args(["--no-l1-sync", "--preset=test", "--sync-from-rpc", sequencer_rpc.as_str()])
+        .run();
+    full_node.wait_for_ready().await;
+    full_node.wait_for_sync_to(last_block_n).await;
+
+    for block_n in 0..=last_block_n {
+        let block_id = BlockId::Number(block_n);
+        assert_eq!(
+            full_node.json_rpc().get_block_with_receipts(block_id).await.unwrap(),
+            sequencer.json_rpc().get_block_with_receipts(block_id).await.unwrap(),
+        );
+        assert_eq!(
+            full_node.json_rpc().get_state_update(block_id).await.unwrap(),
+            sequencer.json_rpc().get_state_update(block_id).await.unwrap(),
+        );
+    }
+}